@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+// External aligner invocation. The aligner is described by a command template in which `{input}`
+// and `{output}` stand for the unaligned and aligned FASTA files; if the template has no
+// `{output}`, the tool's standard output is written to the output file instead.
+
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+};
+
+use crate::errors::TermalError;
+
+pub const DEFAULT_ALIGNER_TEMPLATE: &str =
+    "mafft --maxiterate 1000 --localpair --treeout --reorder {input}";
+
+pub struct AlignerCommand {
+    program: PathBuf,
+    args: Vec<String>,
+    output: PathBuf,
+    stdout_to_output: bool,
+}
+
+impl AlignerCommand {
+    /// Builds the command from `template`. A bare `mafft` program is looked up in `mafft_bin_dir`
+    /// if one is configured.
+    pub fn from_template(
+        template: &str,
+        input: &Path,
+        output: &Path,
+        mafft_bin_dir: Option<&Path>,
+    ) -> Result<Self, TermalError> {
+        if !template.contains("{input}") {
+            return Err(TermalError::Format(String::from(
+                "Aligner command must contain {input}",
+            )));
+        }
+        let mut tokens = template.split_whitespace();
+        let program = tokens
+            .next()
+            .ok_or_else(|| TermalError::Format(String::from("Empty aligner command")))?;
+        let program = match mafft_bin_dir {
+            Some(dir) if program == "mafft" => dir.join("mafft"),
+            _ => PathBuf::from(program),
+        };
+        let input_str = input.to_string_lossy();
+        let output_str = output.to_string_lossy();
+        let args: Vec<String> = tokens
+            .map(|tok| {
+                tok.replace("{input}", &input_str)
+                    .replace("{output}", &output_str)
+            })
+            .collect();
+        Ok(Self {
+            program,
+            args,
+            output: output.to_path_buf(),
+            stdout_to_output: !template.contains("{output}"),
+        })
+    }
+
+    pub fn is_mafft(&self) -> bool {
+        self.program.file_name().and_then(|name| name.to_str()) == Some("mafft")
+    }
+
+    /// The command line as it will be executed (for display).
+    pub fn command_line(&self) -> String {
+        let mut line = self.program.to_string_lossy().to_string();
+        for arg in &self.args {
+            line.push(' ');
+            line.push_str(arg);
+        }
+        if self.stdout_to_output {
+            line.push_str(&format!(" > {}", self.output.display()));
+        }
+        line
+    }
+
+    pub fn run(&self, stderr: Stdio) -> Result<(), TermalError> {
+        let status = self
+            .spawn(stderr)?
+            .wait()
+            .map_err(|e| TermalError::Format(format!("Failed to run {}: {}", self.name(), e)))?;
+        self.check_status(status)
+    }
+
+    /// Starts the aligner without waiting for it.
+    pub fn spawn(&self, stderr: Stdio) -> Result<Child, TermalError> {
+        let stdout = if self.stdout_to_output {
+            Stdio::from(File::create(&self.output)?)
+        } else {
+            Stdio::null()
+        };
+        Command::new(&self.program)
+            .args(&self.args)
+            .stdout(stdout)
+            .stderr(stderr)
+            .spawn()
+            .map_err(|e| TermalError::Format(format!("Failed to run {}: {}", self.name(), e)))
+    }
+
+    pub fn check_status(&self, status: ExitStatus) -> Result<(), TermalError> {
+        if status.success() {
+            Ok(())
+        } else {
+            Err(TermalError::Format(format!("{} failed", self.name())))
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.program.to_string_lossy().to_string()
+    }
+}
+
+/// Guide tree written next to the input file, as `mafft --treeout` does.
+pub fn tree_sidecar_path(input: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.tree", input.display()))
+}
+
+// Alignment cache: aligned FASTA (and guide tree, if any) of previously aligned inputs, stored as
+// <key>.fa / <key>.tree under $XDG_CACHE_HOME/termal (or ~/.cache/termal).
+
+/// Cache key for an input file's contents aligned with a given command template (FNV-1a, so that
+/// keys are stable across builds).
+pub fn alignment_cache_key(input: &[u8], template: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.iter().chain([0u8].iter()).chain(template.as_bytes()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn alignment_cache_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("termal"))
+}
+
+/// Paths of the cached alignment and tree for `key`, if an alignment is cached.
+pub fn cached_alignment(key: &str) -> Option<(PathBuf, Option<PathBuf>)> {
+    let dir = alignment_cache_dir()?;
+    let fasta = dir.join(format!("{}.fa", key));
+    if !fasta.is_file() {
+        return None;
+    }
+    let tree = dir.join(format!("{}.tree", key));
+    Some((fasta, tree.is_file().then_some(tree)))
+}
+
+pub fn store_cached_alignment(key: &str, aligned: &Path, tree: Option<&str>) -> io::Result<()> {
+    let dir = alignment_cache_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No cache directory"))?;
+    fs::create_dir_all(&dir)?;
+    if let Some(tree) = tree {
+        fs::write(dir.join(format!("{}.tree", key)), tree)?;
+    }
+    // Written last, as its presence marks the entry as complete.
+    fs::copy(aligned, dir.join(format!("{}.fa", key)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_substitutes_paths() {
+        let cmd = AlignerCommand::from_template(
+            "muscle -align {input} -output {output}",
+            Path::new("in.fa"),
+            Path::new("out.fa"),
+            Some(Path::new("/opt/mafft/bin")),
+        )
+        .unwrap();
+        assert_eq!(cmd.command_line(), "muscle -align in.fa -output out.fa");
+        assert!(!cmd.is_mafft());
+    }
+
+    #[test]
+    fn default_template_uses_mafft_bin_dir_and_stdout() {
+        let cmd = AlignerCommand::from_template(
+            DEFAULT_ALIGNER_TEMPLATE,
+            Path::new("in.fa"),
+            Path::new("out.fa"),
+            Some(Path::new("/opt/mafft/bin")),
+        )
+        .unwrap();
+        assert!(cmd.is_mafft());
+        assert_eq!(
+            cmd.command_line(),
+            "/opt/mafft/bin/mafft --maxiterate 1000 --localpair --treeout --reorder in.fa > out.fa"
+        );
+    }
+
+    #[test]
+    fn cache_key_depends_on_input_and_template() {
+        let key = alignment_cache_key(b">s1\nACGT\n", DEFAULT_ALIGNER_TEMPLATE);
+        assert_eq!(key.len(), 16);
+        assert_eq!(
+            key,
+            alignment_cache_key(b">s1\nACGT\n", DEFAULT_ALIGNER_TEMPLATE)
+        );
+        assert_ne!(
+            key,
+            alignment_cache_key(b">s1\nACGA\n", DEFAULT_ALIGNER_TEMPLATE)
+        );
+        assert_ne!(
+            key,
+            alignment_cache_key(b">s1\nACGT\n", "muscle -align {input}")
+        );
+    }
+
+    #[test]
+    fn template_requires_input() {
+        assert!(AlignerCommand::from_template(
+            "muscle",
+            Path::new("in.fa"),
+            Path::new("out.fa"),
+            None
+        )
+        .is_err());
+    }
+}
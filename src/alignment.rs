@@ -2,6 +2,22 @@
 // Copyright (c) 2025 Thomas Junier
 // Modifications (c) 2026 Peter Carlton
 
+//! Multiple sequence alignments and their statistics, independent of the UI.
+//!
+//! An [`Alignment`] is built with [`Alignment::from_file`] or [`Alignment::from_vecs`]; its
+//! sequences, consensus and per-column and per-sequence metrics are read through accessors.
+//!
+//! ```
+//! use msafara::alignment::Alignment;
+//!
+//! let aln = Alignment::from_vecs(
+//!     vec![String::from("a"), String::from("b")],
+//!     vec![String::from("AC-T"), String::from("AGGT")],
+//! );
+//! assert_eq!((aln.num_seq(), aln.aln_len()), (2, 4));
+//! assert_eq!(aln.densities(), &[1.0, 1.0, 0.5, 1.0]);
+//! ```
+
 mod permutation;
 
 use std::collections::HashMap;
@@ -9,21 +25,49 @@ use std::collections::HashMap;
 use itertools::Itertools;
 
 use crate::seq::file::SeqFile;
+use crate::vec_f64_aux::{normalize, ones_complement, product};
+
+use crate::alignment::SeqType::{Nucleic, Protein, Rna};
+
+/// Minimum fraction of sequences sharing a column's most frequent residue for the column to count
+/// as conserved (upper-case in the consensus).
+pub const DEFAULT_CONSENSUS_THRESHOLD: f64 = 0.8;
 
-use crate::alignment::SeqType::{Nucleic, Protein};
+/// Minimum number of consecutive conserved columns that make a conserved block.
+pub const MIN_CONSERVED_BLOCK_LEN: usize = 3;
 
 type ResidueDistribution = HashMap<char, f64>;
 type ResidueCounts = HashMap<char, u64>;
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+/// Direction in which `Alignment::shift_residue()` moves a residue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShiftDirection {
+    Left,
+    Right,
+}
+
+// Also the values of `--molecule` and `:mol`, where nucleic is spelled `dna`.
+#[derive(PartialEq, Clone, Copy, Debug, clap::ValueEnum)]
 pub enum SeqType {
+    #[value(name = "dna")]
     Nucleic,
+    Rna,
     Protein,
 }
 
+impl SeqType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Nucleic => "nucleic",
+            Rna => "rna",
+            Protein => "protein",
+        }
+    }
+}
+
 pub struct Alignment {
-    pub headers: Vec<String>,
-    pub sequences: Vec<String>,
+    headers: Vec<String>,
+    sequences: Vec<String>,
     /* The consensus sequence is now a field of Alignment, and is computed once upon creation. This
      * contrasts with the very first implementation, in which the consensus was recomputed every
      * time the UI was drawn... which was very inefficient but had this funny "twinkling" effect in
@@ -33,19 +77,27 @@ pub struct Alignment {
      */
     /* These are properties of the whole _alignment_, or at least of whole columns. They cannot be
      * meaningfully attributed to a sequence. */
-    pub consensus: String,
-    pub entropies: Vec<f64>,
-    pub densities: Vec<f64>,
+    consensus: String,
+    entropies: Vec<f64>,
+    densities: Vec<f64>,
+    // Derived from the entropies and densities, and kept with them (the UI shades residues by it
+    // on every frame).
+    conservation: Vec<f64>,
 
     /* By contrast, the following are properties of sequences (at least in part). Length, for
      * example, does not depend on anything but the sequence itself, and could be a field in a
      * struct that also contains the sequence and its header. */
-    pub id_wrt_consensus: Vec<f64>,
+    id_wrt_consensus: Vec<f64>,
     // Of course the sequence length is an integer, but using an integer type like u32 would make
     // it hard (for me, at least...) to write a function that accepts a Vec of either  lengths or
     // %IDs. Tried Box, and generics, but the extra work doesn't seem warranted.
-    pub relative_seq_len: Vec<f64>,
-    pub macromolecule_type: SeqType,
+    relative_seq_len: Vec<f64>,
+    macromolecule_type: SeqType,
+    consensus_threshold: f64,
+    // T and U count as the same residue in the consensus and the identities to it.
+    ut_equivalence: bool,
+    // For each sequence, the column of each of its residues (i.e., ungapped position -> column).
+    residue_columns: Vec<Vec<usize>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -55,7 +107,8 @@ struct BestResidue {
 }
 
 impl Alignment {
-    // Makes an Alignment from a SeqFile, which is consumed.
+    /// Makes an Alignment from a SeqFile (e.g. from `seq::fasta::read_fasta_file()`), which is
+    /// consumed. Sequences shorter than the longest one are padded with spaces.
     pub fn from_file(fasta: SeqFile) -> Alignment {
         let mut headers: Vec<String> = Vec::new();
         let mut sequences: Vec<String> = Vec::new();
@@ -77,13 +130,15 @@ impl Alignment {
         let consensus = consensus(&sequences);
         let entropies = entropies(&sequences);
         let densities = densities(&sequences);
+        let conservation = conservation(&entropies, &densities);
         let id_wrt_consensus = sequences
             .iter()
             .map(|seq| percent_identity(seq, &consensus))
             .collect();
         let relative_seq_len = sequences.iter().map(|seq| seq_len_nogaps(seq)).collect();
         let first_seq = sequences.first();
-        let macromolecule_type = seq_type(first_seq.expect("No sequence found."));
+        let macromolecule_type = first_seq.map_or(Protein, |seq| seq_type(seq));
+        let residue_columns = sequences.iter().map(|seq| residue_columns(seq)).collect();
 
         Alignment {
             headers,
@@ -91,15 +146,18 @@ impl Alignment {
             consensus,
             entropies,
             densities,
+            conservation,
             id_wrt_consensus,
             relative_seq_len,
             macromolecule_type,
+            consensus_threshold: DEFAULT_CONSENSUS_THRESHOLD,
+            ut_equivalence: false,
+            residue_columns,
         }
     }
 
-    // Makes an Alignment from a Vec of headers and a Vec of Strings, which are consumed. Mostly
-    // used for testing.
-    #[allow(dead_code)]
+    /// Makes an Alignment from a Vec of headers and a Vec of (equally long) sequences, which are
+    /// consumed. Panics if there are not as many headers as sequences.
     pub fn from_vecs(hdrs: Vec<String>, seqs: Vec<String>) -> Alignment {
         assert_eq!(hdrs.len(), seqs.len());
         let headers = hdrs;
@@ -107,13 +165,15 @@ impl Alignment {
         let consensus = consensus(&sequences);
         let entropies = entropies(&sequences);
         let densities = densities(&sequences);
+        let conservation = conservation(&entropies, &densities);
         let id_wrt_consensus = sequences
             .iter()
             .map(|seq| percent_identity(seq, &consensus))
             .collect();
         let relative_seq_len = sequences.iter().map(|seq| seq_len_nogaps(seq)).collect();
         let first_seq = sequences.first();
-        let macromolecule_type = seq_type(first_seq.expect("No sequence found."));
+        let macromolecule_type = first_seq.map_or(Protein, |seq| seq_type(seq));
+        let residue_columns = sequences.iter().map(|seq| residue_columns(seq)).collect();
 
         Alignment {
             headers,
@@ -121,47 +181,509 @@ impl Alignment {
             consensus,
             entropies,
             densities,
+            conservation,
             id_wrt_consensus,
             relative_seq_len,
             macromolecule_type,
+            consensus_threshold: DEFAULT_CONSENSUS_THRESHOLD,
+            ut_equivalence: false,
+            residue_columns,
         }
     }
 
+    /// Number of sequences.
     pub fn num_seq(&self) -> usize {
         self.sequences.len()
     }
 
+    /// Number of columns.
     pub fn aln_len(&self) -> usize {
         self.sequences.first().map(|seq| seq.len()).unwrap_or(0)
     }
 
+    /// The sequence headers, in alignment order.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    // Renames the sequence at `rank` (e.g. when deduplicating or relabeling); out-of-range ranks
+    // are ignored.
+    pub(crate) fn set_header(&mut self, rank: usize, header: String) {
+        if let Some(slot) = self.headers.get_mut(rank) {
+            *slot = header;
+        }
+    }
+
+    /// The aligned sequences, gaps included, in the same order as the headers.
+    pub fn sequences(&self) -> &[String] {
+        &self.sequences
+    }
+
+    /// One character per column: the most frequent residue, upper-case if at least the consensus
+    /// threshold of the sequences share it, lower-case if at least 20% do, and '*' otherwise.
+    pub fn consensus(&self) -> &str {
+        &self.consensus
+    }
+
+    /// Shannon entropy of each column's residue distribution.
+    pub fn entropies(&self) -> &[f64] {
+        &self.entropies
+    }
+
+    /// Fraction of non-gap cells in each column.
+    pub fn densities(&self) -> &[f64] {
+        &self.densities
+    }
+
+    /// Identity of each sequence to the consensus: the fraction of columns (between 0 and 1) in
+    /// which it has the consensus character, case ignored.
+    pub fn id_wrt_consensus(&self) -> &[f64] {
+        &self.id_wrt_consensus
+    }
+
+    /// Number of residues of each sequence, relative to the alignment length.
+    pub fn relative_seq_len(&self) -> &[f64] {
+        &self.relative_seq_len
+    }
+
     pub fn macromolecule_type(&self) -> SeqType {
         self.macromolecule_type
     }
 
+    pub fn consensus_threshold(&self) -> f64 {
+        self.consensus_threshold
+    }
+
+    /// Sets the fraction of sequences that must share a residue for its column to be conserved,
+    /// and recomputes the consensus and the identities to it.
+    pub fn set_consensus_threshold(&mut self, threshold: f64) {
+        if threshold == self.consensus_threshold {
+            return;
+        }
+        self.consensus_threshold = threshold;
+        self.update_consensus();
+    }
+
+    pub fn ut_equivalence(&self) -> bool {
+        self.ut_equivalence
+    }
+
+    /// Makes the consensus and the identities to it count T and U as the same residue (as U), or
+    /// not.
+    pub fn set_ut_equivalence(&mut self, ut_equivalence: bool) {
+        if ut_equivalence == self.ut_equivalence {
+            return;
+        }
+        self.ut_equivalence = ut_equivalence;
+        self.update_consensus();
+    }
+
+    // Recomputes the consensus and the identities to it, with T read as U under U/T equivalence.
+    fn update_consensus(&mut self) {
+        let folded: Vec<String>;
+        let sequences = if self.ut_equivalence {
+            folded = self.sequences.iter().map(|seq| t_to_u(seq)).collect();
+            &folded
+        } else {
+            &self.sequences
+        };
+        self.consensus = consensus_with_threshold(sequences, self.consensus_threshold);
+        self.id_wrt_consensus = sequences
+            .iter()
+            .map(|seq| percent_identity(seq, &self.consensus))
+            .collect();
+    }
+
+    /// Fraction of all cells that are gaps (derived from the column densities, so it follows
+    /// sequence removals and insertions).
+    pub fn gap_fraction(&self) -> f64 {
+        if self.densities.is_empty() {
+            return 0.0;
+        }
+        1.0 - self.densities.iter().sum::<f64>() / self.densities.len() as f64
+    }
+
+    /// Fraction of non-gap cells in each column (the column densities).
+    pub fn column_occupancy(&self) -> Vec<f64> {
+        self.densities.clone()
+    }
+
+    /// Conservation of each column, between 0 and 1: the occupancy times the complement of the
+    /// normalized entropy (as in the bottom pane's bar chart).
+    pub fn column_conservation(&self) -> &[f64] {
+        &self.conservation
+    }
+
+    /// Number of each residue (upper-cased) in column `col`, most frequent first (ties in
+    /// alphabetical order), then the number of gaps as '-', if any.
+    pub fn column_frequencies(&self, col: usize) -> Vec<(char, u64)> {
+        if col >= self.aln_len() {
+            return Vec::new();
+        }
+        let mut gaps = 0;
+        let mut counts: ResidueCounts = HashMap::new();
+        for (residue, count) in res_count(&self.sequences, col) {
+            if is_gap(residue) {
+                gaps += count;
+            } else {
+                *counts.entry(residue.to_ascii_uppercase()).or_insert(0) += count;
+            }
+        }
+        let mut frequencies: Vec<(char, u64)> = counts
+            .into_iter()
+            .sorted_by(|(r1, c1), (r2, c2)| c2.cmp(c1).then(r1.cmp(r2)))
+            .collect();
+        if gaps > 0 {
+            frequencies.push(('-', gaps));
+        }
+        frequencies
+    }
+
+    /// Fraction of gaps in the sequence at `rank` over each of `buckets` consecutive, (nearly)
+    /// equal stretches of columns. If there are more buckets than columns, columns are repeated.
+    pub fn gap_profile(&self, rank: usize, buckets: usize) -> Vec<f64> {
+        let Some(seq) = self.sequences.get(rank) else {
+            return Vec::new();
+        };
+        let seq = seq.as_bytes();
+        let len = seq.len();
+        if len == 0 {
+            return Vec::new();
+        }
+        (0..buckets)
+            .map(|bucket| {
+                let start = bucket * len / buckets;
+                let end = ((bucket + 1) * len / buckets).max(start + 1);
+                let gaps = seq[start..end]
+                    .iter()
+                    .filter(|&&b| is_gap(b as char))
+                    .count();
+                gaps as f64 / (end - start) as f64
+            })
+            .collect()
+    }
+
+    /// The alignment downsampled to `rows` x `cols` cells (sequences and columns split into
+    /// (nearly) equal buckets, repeated if there are more cells than sequences or columns), each
+    /// the average over its bucket of the column conservation of the residues that agree with
+    /// the consensus (other residues and gaps count as 0).
+    pub fn conservation_grid(&self, rows: usize, cols: usize) -> Vec<Vec<f64>> {
+        let (num_seq, aln_len) = (self.num_seq(), self.aln_len());
+        if num_seq == 0 || aln_len == 0 {
+            return vec![vec![0.0; cols]; rows];
+        }
+        let conservation = self.column_conservation();
+        let consensus = self.consensus.as_bytes();
+        let bucket = |index: usize, buckets: usize, len: usize| {
+            let start = index * len / buckets;
+            start..((index + 1) * len / buckets).max(start + 1)
+        };
+        (0..rows)
+            .map(|row| {
+                let seqs = &self.sequences[bucket(row, rows, num_seq)];
+                (0..cols)
+                    .map(|col| {
+                        let columns = bucket(col, cols, aln_len);
+                        let nb_cells = (seqs.len() * columns.len()) as f64;
+                        let total: f64 = seqs
+                            .iter()
+                            .map(|seq| {
+                                let seq = seq.as_bytes();
+                                columns
+                                    .clone()
+                                    .filter(|&c| {
+                                        !is_gap(seq[c] as char)
+                                            && seq[c].eq_ignore_ascii_case(&consensus[c])
+                                    })
+                                    .map(|c| conservation[c])
+                                    .sum::<f64>()
+                            })
+                            .sum();
+                        total / nb_cells
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Runs of at least MIN_CONSERVED_BLOCK_LEN consecutive columns whose conservation is at least
+    /// `threshold`, as (first column, column after the last) pairs, left to right.
+    pub fn conserved_blocks(&self, threshold: f64) -> Vec<(usize, usize)> {
+        let mut blocks = Vec::new();
+        let mut start = None;
+        let conservation = self.column_conservation();
+        for (col, &value) in conservation.iter().enumerate() {
+            match (value >= threshold, start) {
+                (true, None) => start = Some(col),
+                (false, Some(first)) => {
+                    if col - first >= MIN_CONSERVED_BLOCK_LEN {
+                        blocks.push((first, col));
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(first) = start {
+            if conservation.len() - first >= MIN_CONSERVED_BLOCK_LEN {
+                blocks.push((first, conservation.len()));
+            }
+        }
+        blocks
+    }
+
+    /// The sequences without the columns whose occupancy is below `min_occupancy`. All-gap
+    /// columns are dropped whatever the threshold.
+    pub fn trimmed_sequences(&self, min_occupancy: f64) -> Vec<String> {
+        let keep: Vec<bool> = self
+            .densities
+            .iter()
+            .map(|&occupancy| occupancy > 0.0 && occupancy >= min_occupancy)
+            .collect();
+        self.sequences
+            .iter()
+            .map(|seq| {
+                seq.chars()
+                    .zip(keep.iter())
+                    .filter_map(|(c, &kept)| kept.then_some(c))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Headers that occur more than once, each listed once, in order of first appearance.
+    pub fn duplicate_headers(&self) -> Vec<String> {
+        duplicate_headers(&self.headers)
+    }
+
+    /// Proportion of differing residues (case ignored) between the sequences at `rank_a` and
+    /// `rank_b`, over the columns where neither is gapped; 1.0 if there are no such columns.
+    pub fn pairwise_pdistance(&self, rank_a: usize, rank_b: usize) -> f64 {
+        let (Some(seq_a), Some(seq_b)) = (self.sequences.get(rank_a), self.sequences.get(rank_b))
+        else {
+            return 1.0;
+        };
+        let (compared, differing) = seq_a
+            .bytes()
+            .zip(seq_b.bytes())
+            .filter(|(a, b)| !is_gap(*a as char) && !is_gap(*b as char))
+            .fold((0usize, 0usize), |(n, d), (a, b)| {
+                (n + 1, d + usize::from(!a.eq_ignore_ascii_case(&b)))
+            });
+        if compared == 0 {
+            1.0
+        } else {
+            differing as f64 / compared as f64
+        }
+    }
+
+    /// Ranks of the sequences that consist only of gaps (e.g. after slicing columns).
+    pub fn all_gap_sequences(&self) -> Vec<usize> {
+        self.residue_columns
+            .iter()
+            .enumerate()
+            .filter(|(_, columns)| columns.is_empty())
+            .map(|(rank, _)| rank)
+            .collect()
+    }
+
+    /// Columns where the sequences at `rank_a` and `rank_b` have different residues (case is
+    /// ignored). Columns gapped in either sequence count as agreeing, so that indels do not flood
+    /// the result.
+    pub fn disagreement_columns(&self, rank_a: usize, rank_b: usize) -> Vec<usize> {
+        let (Some(seq_a), Some(seq_b)) = (self.sequences.get(rank_a), self.sequences.get(rank_b))
+        else {
+            return Vec::new();
+        };
+        seq_a
+            .chars()
+            .zip(seq_b.chars())
+            .enumerate()
+            .filter(|(_, (a, b))| !is_gap(*a) && !is_gap(*b) && !a.eq_ignore_ascii_case(b))
+            .map(|(col, _)| col)
+            .collect()
+    }
+
+    /// Number of residues (non-gap characters) of the sequence at `rank`.
+    pub fn ungapped_length(&self, rank: usize) -> usize {
+        self.residue_columns.get(rank).map(Vec::len).unwrap_or(0)
+    }
+
+    /// Columns of the first and last residues of the sequence at `rank`, or None if it has none.
+    pub fn residue_span(&self, rank: usize) -> Option<(usize, usize)> {
+        let columns = self.residue_columns.get(rank)?;
+        Some((*columns.first()?, *columns.last()?))
+    }
+
+    /// Columns where every sequence has the same residue (case is ignored); a gap anywhere makes
+    /// a column non-conserved.
+    pub fn conserved_columns(&self) -> Vec<usize> {
+        let Some(first) = self.sequences.first() else {
+            return Vec::new();
+        };
+        first
+            .bytes()
+            .enumerate()
+            .filter(|(col, residue)| {
+                !is_gap(*residue as char)
+                    && self.sequences.iter().all(|seq| {
+                        seq.as_bytes()
+                            .get(*col)
+                            .is_some_and(|b| b.eq_ignore_ascii_case(residue))
+                    })
+            })
+            .map(|(col, _)| col)
+            .collect()
+    }
+
+    /// First column after `from_col` (wrapping around to the start) at which a run of gaps starts in
+    /// the sequence at `rank`, or None if it has no gaps.
+    pub fn next_gap_start(&self, rank: usize, from_col: usize) -> Option<usize> {
+        self.next_column_where(rank, from_col, |prev_is_gap, is_gap| !prev_is_gap && is_gap)
+    }
+
+    /// First column after `from_col` (wrapping around to the start) holding the first residue after
+    /// a run of gaps in the sequence at `rank`, or None if there is none.
+    pub fn next_residue_after_gap(&self, rank: usize, from_col: usize) -> Option<usize> {
+        self.next_column_where(rank, from_col, |prev_is_gap, is_gap| prev_is_gap && !is_gap)
+    }
+
+    // First column after `from_col`, wrapping around, for which `pred` holds, given whether the
+    // column before (none for the first one) and the column itself are gaps.
+    fn next_column_where(
+        &self,
+        rank: usize,
+        from_col: usize,
+        pred: impl Fn(bool, bool) -> bool,
+    ) -> Option<usize> {
+        let seq = self.sequences.get(rank)?.as_bytes();
+        let len = seq.len();
+        let from_col = from_col.min(len.checked_sub(1)?);
+        ((from_col + 1)..len).chain(0..=from_col).find(|&col| {
+            let prev_is_gap = col > 0 && is_gap(seq[col - 1] as char);
+            pred(prev_is_gap, is_gap(seq[col] as char))
+        })
+    }
+
+    /// Number of residues of the sequence at `rank` in columns 0 to `col` included, i.e. the
+    /// sequence coordinate reached at `col` (its length if `col` is past its end).
+    pub fn residues_through(&self, rank: usize, col: usize) -> usize {
+        self.residue_columns
+            .get(rank)
+            .map_or(0, |columns| columns.partition_point(|&c| c <= col))
+    }
+
+    /// Column of the `pos`-th residue (1-based, i.e. in sequence coordinates) of the sequence at
+    /// `rank`, or None if there is no such residue.
+    pub fn ungapped_to_gapped(&self, rank: usize, pos: usize) -> Option<usize> {
+        let columns = self.residue_columns.get(rank)?;
+        pos.checked_sub(1).and_then(|idx| columns.get(idx)).copied()
+    }
+
+    /// Inserts an all-gap column before column `at` (at the end if `at` is past it) in every
+    /// sequence, and recomputes the column and sequence metrics.
+    pub fn insert_gap_column(&mut self, at: usize) {
+        let gaps = "-".repeat(self.num_seq());
+        self.insert_column(at, &gaps);
+    }
+
+    /// Inserts a column before column `at` (at the end if `at` is past it), `column` holding one
+    /// character per sequence, and recomputes the column and sequence metrics. Panics if
+    /// `column` does not have as many characters as there are sequences.
+    pub fn insert_column(&mut self, at: usize, column: &str) {
+        assert_eq!(column.chars().count(), self.num_seq());
+        let at = at.min(self.aln_len());
+        for (seq, c) in self.sequences.iter_mut().zip(column.chars()) {
+            seq.insert(at, c);
+        }
+        self.update_columns();
+    }
+
+    /// Removes column `at` from every sequence and recomputes the column and sequence metrics.
+    /// Returns the removed column (one character per sequence), or None if there is no such
+    /// column.
+    pub fn delete_column(&mut self, at: usize) -> Option<String> {
+        if at >= self.aln_len() {
+            return None;
+        }
+        let column = self
+            .sequences
+            .iter_mut()
+            .map(|seq| seq.remove(at))
+            .collect();
+        self.update_columns();
+        Some(column)
+    }
+
+    /// Moves the residue at column `col` of the sequence at `rank` into the adjacent gap in
+    /// `direction` (the gap takes its place), and recomputes the column and sequence metrics.
+    /// Returns the residue's new column, or None (and leaves the alignment unchanged) if there is
+    /// no residue at `col` or the adjacent cell is not a gap.
+    pub fn shift_residue(
+        &mut self,
+        rank: usize,
+        col: usize,
+        direction: ShiftDirection,
+    ) -> Option<usize> {
+        let seq = self.sequences.get(rank)?;
+        let target = match direction {
+            ShiftDirection::Left => col.checked_sub(1)?,
+            ShiftDirection::Right => col + 1,
+        };
+        let bytes = seq.as_bytes();
+        let (&residue, &neighbour) = (bytes.get(col)?, bytes.get(target)?);
+        if is_gap(residue as char) || !is_gap(neighbour as char) {
+            return None;
+        }
+        let mut cells = seq.clone().into_bytes();
+        cells.swap(col, target);
+        self.sequences[rank] = String::from_utf8(cells).ok()?;
+        self.update_columns();
+        Some(target)
+    }
+
+    // Recomputes everything that depends on the columns, after columns were inserted or removed.
+    fn update_columns(&mut self) {
+        self.update_consensus();
+        self.update_column_metrics();
+        self.relative_seq_len = self
+            .sequences
+            .iter()
+            .map(|seq| seq_len_nogaps(seq))
+            .collect();
+        self.residue_columns = self
+            .sequences
+            .iter()
+            .map(|seq| residue_columns(seq))
+            .collect();
+    }
+
+    // Recomputes the column entropies, densities and conservation from the sequences.
+    fn update_column_metrics(&mut self) {
+        self.entropies = entropies(&self.sequences);
+        self.densities = densities(&self.sequences);
+        self.conservation = conservation(&self.entropies, &self.densities);
+    }
+
     pub fn remove_seq(&mut self, index: usize) -> Option<(String, String)> {
         if index >= self.sequences.len() {
             return None;
         }
         let header = self.headers.remove(index);
         let sequence = self.sequences.remove(index);
+        self.residue_columns.remove(index);
         if self.sequences.is_empty() {
             self.consensus.clear();
             self.entropies.clear();
             self.densities.clear();
+            self.conservation.clear();
             self.id_wrt_consensus.clear();
             self.relative_seq_len.clear();
             return Some((header, sequence));
         }
 
-        self.consensus = consensus(&self.sequences);
-        self.entropies = entropies(&self.sequences);
-        self.densities = densities(&self.sequences);
-        self.id_wrt_consensus = self
-            .sequences
-            .iter()
-            .map(|seq| percent_identity(seq, &self.consensus))
-            .collect();
+        self.update_consensus();
+        self.update_column_metrics();
         self.relative_seq_len = self
             .sequences
             .iter()
@@ -174,18 +696,13 @@ impl Alignment {
     pub fn insert_seq(&mut self, index: usize, header: String, sequence: String) {
         let idx = index.min(self.sequences.len());
         self.headers.insert(idx, header);
+        self.residue_columns.insert(idx, residue_columns(&sequence));
         self.sequences.insert(idx, sequence);
         if self.sequences.is_empty() {
             return;
         }
-        self.consensus = consensus(&self.sequences);
-        self.entropies = entropies(&self.sequences);
-        self.densities = densities(&self.sequences);
-        self.id_wrt_consensus = self
-            .sequences
-            .iter()
-            .map(|seq| percent_identity(seq, &self.consensus))
-            .collect();
+        self.update_consensus();
+        self.update_column_metrics();
         self.relative_seq_len = self
             .sequences
             .iter()
@@ -196,6 +713,16 @@ impl Alignment {
 
 // TODO should these be methods of Alignment?
 
+// See Alignment::column_conservation().
+fn conservation(entropies: &[f64], densities: &[f64]) -> Vec<f64> {
+    // If all columns have the same entropy, normalizing yields NaNs: count it as minimal.
+    let entropies: Vec<f64> = normalize(entropies)
+        .into_iter()
+        .map(|e| if e.is_nan() { 0.0 } else { e })
+        .collect();
+    product(densities, &ones_complement(&entropies))
+}
+
 fn res_count(sequences: &Vec<String>, col: usize) -> ResidueCounts {
     let mut freqs: ResidueCounts = HashMap::new();
     for seq in sequences {
@@ -205,13 +732,19 @@ fn res_count(sequences: &Vec<String>, col: usize) -> ResidueCounts {
     freqs
 }
 
-pub fn consensus(sequences: &Vec<String>) -> String {
+fn consensus(sequences: &Vec<String>) -> String {
+    consensus_with_threshold(sequences, DEFAULT_CONSENSUS_THRESHOLD)
+}
+
+// Residues shared by at least `threshold` of the sequences are upper-case, those shared by at
+// least 20% lower-case; other columns are '*'.
+fn consensus_with_threshold(sequences: &Vec<String>, threshold: f64) -> String {
     let mut consensus = String::new();
-    for j in 0..sequences[0].len() {
+    for j in 0..sequences.first().map_or(0, String::len) {
         let dist = res_count(sequences, j);
         let br = best_residue(&dist);
         let rel_freq: f64 = (br.frequency as f64 / sequences.len() as f64) as f64;
-        if rel_freq >= 0.8 {
+        if rel_freq >= threshold {
             consensus.push(br.residue);
         } else if rel_freq >= 0.2 {
             if br.residue.is_alphabetic() {
@@ -227,9 +760,9 @@ pub fn consensus(sequences: &Vec<String>) -> String {
     consensus
 }
 
-pub fn entropies(sequences: &Vec<String>) -> Vec<f64> {
+fn entropies(sequences: &Vec<String>) -> Vec<f64> {
     let mut entropies: Vec<f64> = Vec::new();
-    for j in 0..sequences[0].len() {
+    for j in 0..sequences.first().map_or(0, String::len) {
         let dist = res_count(sequences, j);
         let freq = to_freq_distrib(&dist);
         let e = entropy(&freq);
@@ -238,7 +771,7 @@ pub fn entropies(sequences: &Vec<String>) -> Vec<f64> {
     entropies
 }
 
-pub fn col_density(sequences: &Vec<String>, col: usize) -> f64 {
+fn col_density(sequences: &Vec<String>, col: usize) -> f64 {
     let mut mass = 0;
     for seq in sequences {
         match seq.as_bytes()[col] as char {
@@ -252,8 +785,8 @@ pub fn col_density(sequences: &Vec<String>, col: usize) -> f64 {
     mass as f64 / sequences.len() as f64
 }
 
-pub fn densities(sequences: &Vec<String>) -> Vec<f64> {
-    (0..sequences[0].len())
+fn densities(sequences: &Vec<String>) -> Vec<f64> {
+    (0..sequences.first().map_or(0, String::len))
         .map(|col| col_density(sequences, col))
         .collect()
 }
@@ -314,10 +847,33 @@ fn percent_identity(s1: &str, s2: &str) -> f64 {
     num_identical as f64 / s1.len() as f64
 }
 
+// T (or t) replaced by U (or u).
+fn t_to_u(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'T' => 'U',
+            't' => 'u',
+            other => other,
+        })
+        .collect()
+}
+
 fn seq_len_nogaps(s: &str) -> f64 {
     s.chars().filter(|c| c.is_alphabetic()).count() as f64 / s.len() as f64
 }
 
+/// True iff `c` is a gap character ('-', '.' or ' ').
+pub fn is_gap(c: char) -> bool {
+    matches!(c, '-' | '.' | ' ')
+}
+
+fn residue_columns(s: &str) -> Vec<usize> {
+    s.char_indices()
+        .filter(|(_, c)| !is_gap(*c))
+        .map(|(col, _)| col)
+        .collect()
+}
+
 fn seq_type(sequence: &str) -> SeqType {
     let counts = sequence.to_lowercase().chars().counts();
     let counts_u64: HashMap<char, u64> = counts.into_iter().map(|(k, v)| (k, v as u64)).collect();
@@ -329,19 +885,40 @@ fn seq_type(sequence: &str) -> SeqType {
         + *frequencies.get(&'u').unwrap_or(&0.0);
     // A quick-and dirty heuristic, I'm afraid
     if nt_freq > 0.75 {
-        Nucleic
+        if frequencies.get(&'u').unwrap_or(&0.0) > frequencies.get(&'t').unwrap_or(&0.0) {
+            Rna
+        } else {
+            Nucleic
+        }
     } else {
         Protein
     }
 }
 
+/// Strings that occur more than once in `headers`, each listed once, in order of first
+/// appearance.
+pub(crate) fn duplicate_headers(headers: &[String]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for header in headers {
+        *counts.entry(header.as_str()).or_insert(0) += 1;
+    }
+    let mut dups: Vec<String> = Vec::new();
+    for header in headers {
+        if counts[header.as_str()] > 1 && !dups.contains(header) {
+            dups.push(header.clone());
+        }
+    }
+    dups
+}
+
 #[cfg(test)]
 mod tests {
     use crate::alignment::{
         best_residue, consensus, densities, entropies, entropy, percent_identity, res_count,
         seq_len_nogaps, seq_type, to_freq_distrib, Alignment, BestResidue, ResidueCounts,
         ResidueDistribution, SeqType,
-        SeqType::{Nucleic, Protein},
+        SeqType::{Nucleic, Protein, Rna},
+        ShiftDirection,
     };
     use crate::seq::fasta::read_fasta_file;
     use approx::assert_relative_eq;
@@ -541,6 +1118,34 @@ mod tests {
         assert_eq!(Nucleic, seq_type("GAATTC"));
     }
 
+    #[test]
+    fn test_set_consensus_threshold() {
+        // Column 0 is 75% A, column 1 fully conserved.
+        let hdrs = vec!["s1", "s2", "s3", "s4"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let seqs = vec!["AC", "AC", "AC", "GC"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut aln = Alignment::from_vecs(hdrs, seqs);
+        aln.set_consensus_threshold(0.7);
+        assert_eq!("AC", aln.consensus);
+        assert_eq!(0.5, aln.id_wrt_consensus[3]);
+        aln.set_consensus_threshold(0.9);
+        assert_eq!("aC", aln.consensus);
+        assert_eq!(0.9, aln.consensus_threshold());
+        // Still 50%: identity ignores case.
+        assert_eq!(0.5, aln.id_wrt_consensus[3]);
+    }
+
+    #[test]
+    fn test_seq_type_rna() {
+        assert_eq!(Rna, seq_type("GAAUUCGCUAGCAU"));
+        assert_eq!(Nucleic, seq_type("GAATTCGCTAGCAU"));
+    }
+
     #[test]
     fn test_seq_type_05() {
         assert_eq!(Protein, seq_type("HGTSDA"));
@@ -553,7 +1158,7 @@ mod tests {
 
     #[test]
     fn test_seq_type_15() {
-        assert_eq!(Nucleic, seq_type("UUTGAU"));
+        assert_eq!(Rna, seq_type("UUTGAU"));
     }
 
     // Make sure seq files with unequal lengths get correctly padded
@@ -594,4 +1199,372 @@ mod tests {
         aln.remove_seq(0);
         assert_eq!(aln.aln_len(), 0);
     }
+
+    #[test]
+    fn test_residues_through() {
+        let aln = Alignment::from_vecs(
+            vec![String::from("R1"), String::from("R2")],
+            vec![String::from("A--CGT"), String::from("--A---")],
+        );
+        assert_eq!(aln.residues_through(0, 0), 1);
+        assert_eq!(aln.residues_through(0, 2), 1);
+        assert_eq!(aln.residues_through(0, 3), 2);
+        assert_eq!(aln.residues_through(0, 100), 4);
+        assert_eq!(aln.residues_through(1, 1), 0);
+        assert_eq!(aln.residues_through(1, 5), 1);
+        assert_eq!(aln.residues_through(2, 5), 0);
+    }
+
+    #[test]
+    fn test_insert_and_delete_column() {
+        let mut aln = Alignment::from_vecs(
+            vec![String::from("R1"), String::from("R2")],
+            vec![String::from("ACGT"), String::from("AC-T")],
+        );
+        aln.insert_gap_column(2);
+        assert_eq!(aln.aln_len(), 5);
+        assert_eq!(aln.sequences(), ["AC-GT", "AC--T"]);
+        assert_eq!(aln.densities().len(), 5);
+        assert_eq!(aln.densities()[2], 0.0);
+        assert_eq!(aln.consensus().len(), 5);
+        assert_eq!(aln.ungapped_to_gapped(0, 3), Some(3));
+
+        assert_eq!(aln.delete_column(3), Some(String::from("G-")));
+        assert_eq!(aln.sequences(), ["AC-T", "AC-T"]);
+        assert_eq!(aln.entropies().len(), 4);
+        assert_eq!(aln.id_wrt_consensus(), [1.0, 1.0]);
+        assert_eq!(aln.ungapped_to_gapped(0, 3), Some(3));
+        assert_eq!(aln.delete_column(4), None);
+
+        // Past the end, the column is appended.
+        aln.insert_column(10, "Gg");
+        assert_eq!(aln.sequences(), ["AC-TG", "AC-Tg"]);
+    }
+
+    #[test]
+    fn test_shift_residue() {
+        let mut aln = Alignment::from_vecs(
+            vec![String::from("R1"), String::from("R2")],
+            vec![String::from("AC--GT"), String::from("ACT-GT")],
+        );
+        assert_eq!(aln.shift_residue(0, 1, ShiftDirection::Right), Some(2));
+        assert_eq!(aln.sequences(), ["A-C-GT", "ACT-GT"]);
+        assert_eq!(aln.consensus().len(), 6);
+        assert_eq!(aln.ungapped_to_gapped(0, 2), Some(2));
+        assert_eq!(aln.shift_residue(0, 4, ShiftDirection::Left), Some(3));
+        assert_eq!(aln.sequences(), ["A-CG-T", "ACT-GT"]);
+
+        // Blocked by a residue, or by the alignment's edge; gaps do not move.
+        assert_eq!(aln.shift_residue(1, 1, ShiftDirection::Right), None);
+        assert_eq!(aln.shift_residue(1, 0, ShiftDirection::Left), None);
+        assert_eq!(aln.shift_residue(1, 5, ShiftDirection::Right), None);
+        assert_eq!(aln.shift_residue(1, 3, ShiftDirection::Left), None);
+        assert_eq!(aln.sequences(), ["A-CG-T", "ACT-GT"]);
+    }
+
+    #[test]
+    fn test_column_conservation_follows_edits() {
+        let mut aln = Alignment::from_vecs(
+            vec![String::from("R1"), String::from("R2")],
+            vec![String::from("AC-T"), String::from("AGGT")],
+        );
+        assert_eq!(aln.column_conservation().len(), 4);
+        assert_relative_eq!(aln.column_conservation()[0], 1.0);
+        assert!(aln.column_conservation()[1] < 1.0);
+        aln.delete_column(0);
+        assert_eq!(aln.column_conservation().len(), 3);
+        assert!(aln.column_conservation()[0] < 1.0);
+        aln.remove_seq(1);
+        assert_relative_eq!(aln.column_conservation()[0], 1.0);
+        aln.remove_seq(0);
+        assert!(aln.column_conservation().is_empty());
+    }
+
+    #[test]
+    fn test_conservation_grid() {
+        let aln = Alignment::from_vecs(
+            (1..=4).map(|i| format!("R{}", i)).collect(),
+            vec![
+                String::from("ACGTAC"),
+                String::from("ACGTTT"),
+                String::from("ACGTGG"),
+                String::from("ACGT--"),
+            ],
+        );
+        let grid = aln.conservation_grid(2, 3);
+        assert_eq!(grid.len(), 2);
+        assert!(grid.iter().all(|row| row.len() == 3));
+        // Fully conserved columns on the left, variable ones on the right.
+        assert_relative_eq!(grid[0][0], 1.0);
+        assert_relative_eq!(grid[1][1], 1.0);
+        assert!(grid[0][2] < 0.5);
+        assert!(grid.iter().flatten().all(|v| (0.0..=1.0).contains(v)));
+
+        // More cells than sequences and columns: they are repeated.
+        let grid = aln.conservation_grid(10, 20);
+        assert_eq!(grid.len(), 10);
+        assert!(grid.iter().all(|row| row.len() == 20));
+        assert_eq!(grid[0], grid[1]);
+        assert!(Alignment::from_vecs(vec![], vec![])
+            .conservation_grid(3, 4)
+            .iter()
+            .all(|row| row == &[0.0; 4]));
+    }
+
+    #[test]
+    fn test_ungapped_to_gapped() {
+        let hdrs = vec![String::from("R1"), String::from("R2")];
+        let seqs = vec![String::from("A--CGT"), String::from("-.ACGT")];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.ungapped_to_gapped(0, 1), Some(0));
+        assert_eq!(aln.ungapped_to_gapped(0, 2), Some(3));
+        assert_eq!(aln.ungapped_to_gapped(0, 4), Some(5));
+        assert_eq!(aln.ungapped_to_gapped(0, 5), None);
+        assert_eq!(aln.ungapped_to_gapped(0, 0), None);
+        assert_eq!(aln.ungapped_to_gapped(1, 1), Some(2));
+        assert_eq!(aln.ungapped_to_gapped(2, 1), None);
+    }
+
+    #[test]
+    fn test_ungapped_to_gapped_after_insert_and_remove() {
+        let hdrs = vec![String::from("R1"), String::from("R2")];
+        let seqs = vec![String::from("A--CGT"), String::from("ACGT--")];
+        let mut aln = Alignment::from_vecs(hdrs, seqs);
+        aln.remove_seq(0);
+        assert_eq!(aln.ungapped_to_gapped(0, 2), Some(1));
+        aln.insert_seq(0, String::from("R0"), String::from("---ACG"));
+        assert_eq!(aln.ungapped_to_gapped(0, 1), Some(3));
+        assert_eq!(aln.ungapped_to_gapped(1, 2), Some(1));
+    }
+
+    #[test]
+    fn test_gap_fraction() {
+        let hdrs = vec![String::from("R1"), String::from("R2")];
+        let seqs = vec![String::from("AC--"), String::from("A.GT")];
+        let mut aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.gap_fraction(), 0.375);
+        aln.remove_seq(0);
+        assert_eq!(aln.gap_fraction(), 0.25);
+    }
+
+    #[test]
+    fn test_duplicate_headers() {
+        let hdrs = vec![
+            String::from("b"),
+            String::from("a"),
+            String::from("b"),
+            String::from("c"),
+            String::from("a"),
+            String::from("b"),
+        ];
+        let seqs = vec![String::from("AC"); 6];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.duplicate_headers(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_disagreement_columns() {
+        let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+        let seqs = vec![
+            String::from("ACGT-AcG"),
+            String::from("ACCTTA-g"),
+            String::from("TCGT-ACG"),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        // Col 4 and 6 are gapped in one sequence; col 7 differs only in case.
+        assert_eq!(aln.disagreement_columns(0, 1), vec![2]);
+        assert_eq!(aln.disagreement_columns(1, 2), vec![0, 2]);
+        assert_eq!(aln.disagreement_columns(0, 0), Vec::<usize>::new());
+        assert_eq!(aln.disagreement_columns(0, 9), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_conserved_columns() {
+        let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+        let seqs = vec![
+            String::from("ACGT-A"),
+            String::from("AcTT-A"),
+            String::from("ACGTAa"),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.conserved_columns(), vec![0, 1, 3, 5]);
+        assert_eq!(aln.ungapped_length(0), 5);
+        assert_eq!(aln.ungapped_length(2), 6);
+    }
+
+    #[test]
+    fn test_residue_span() {
+        let hdrs = vec![String::from("R1"), String::from("R2")];
+        let seqs = vec![String::from("-AC-G-"), String::from("------")];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.residue_span(0), Some((1, 4)));
+        assert_eq!(aln.residue_span(1), None);
+        assert_eq!(aln.residue_span(2), None);
+    }
+
+    #[test]
+    fn test_all_gap_sequences() {
+        let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+        let seqs = vec![
+            String::from("----"),
+            String::from("A.-C"),
+            String::from("-.. "),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.all_gap_sequences(), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_pairwise_pdistance() {
+        let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+        let seqs = vec![
+            String::from("ACGT-A"),
+            String::from("AcTTCA"),
+            String::from("----G-"),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        // 5 comparable columns, 1 difference (case ignored).
+        assert_eq!(aln.pairwise_pdistance(0, 1), 0.2);
+        assert_eq!(aln.pairwise_pdistance(1, 0), 0.2);
+        assert_eq!(aln.pairwise_pdistance(0, 0), 0.0);
+        // No comparable columns.
+        assert_eq!(aln.pairwise_pdistance(0, 2), 1.0);
+    }
+
+    #[test]
+    fn test_column_occupancy() {
+        let hdrs = vec![
+            String::from("R1"),
+            String::from("R2"),
+            String::from("R3"),
+            String::from("R4"),
+        ];
+        let seqs = vec![
+            String::from("AC-G-T"),
+            String::from("A--G-T"),
+            String::from("A---.T"),
+            String::from("AC-GCT"),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.column_occupancy(), vec![1.0, 0.5, 0.0, 0.75, 0.25, 1.0]);
+        assert_eq!(
+            aln.trimmed_sequences(0.5),
+            vec!["ACGT", "A-GT", "A--T", "ACGT"]
+        );
+        // All-gap columns go even without a threshold.
+        assert_eq!(
+            aln.trimmed_sequences(0.0),
+            vec!["ACG-T", "A-G-T", "A--.T", "ACGCT"]
+        );
+    }
+
+    #[test]
+    fn test_conserved_blocks() {
+        let hdrs = vec![
+            String::from("R1"),
+            String::from("R2"),
+            String::from("R3"),
+            String::from("R4"),
+        ];
+        let seqs = vec![
+            String::from("ACDEFGHIKLMNPQ"),
+            String::from("ACDMWYHIKLVSTQ"),
+            String::from("ACDTVLHIKLRKEG"),
+            String::from("ACDNRSHIKLYWAH"),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        // Columns 0-2 and 6-9 are invariant; the last column is only 50% Q, and shorter anyway.
+        assert_eq!(aln.conserved_blocks(0.9), vec![(0, 3), (6, 10)]);
+        assert!(aln.conserved_blocks(1.1).is_empty());
+    }
+
+    #[test]
+    fn test_gap_profile() {
+        let hdrs = vec![String::from("R1"), String::from("R2")];
+        let seqs = vec![String::from("AC--GT--"), String::from("ACGTACGT")];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(aln.gap_profile(0, 4), vec![0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(aln.gap_profile(0, 2), vec![0.5, 0.5]);
+        assert_eq!(aln.gap_profile(1, 3), vec![0.0, 0.0, 0.0]);
+        // More buckets than columns.
+        assert_eq!(aln.gap_profile(0, 16).len(), 16);
+        assert!(aln.gap_profile(2, 4).is_empty());
+    }
+
+    #[test]
+    fn test_column_frequencies() {
+        let hdrs = vec![
+            String::from("R1"),
+            String::from("R2"),
+            String::from("R3"),
+            String::from("R4"),
+            String::from("R5"),
+        ];
+        let seqs = vec![
+            String::from("AT"),
+            String::from("ga"),
+            String::from("a."),
+            String::from("C-"),
+            String::from("GA"),
+        ];
+        let aln = Alignment::from_vecs(hdrs, seqs);
+        assert_eq!(
+            aln.column_frequencies(0),
+            vec![('A', 2), ('G', 2), ('C', 1)]
+        );
+        assert_eq!(
+            aln.column_frequencies(1),
+            vec![('A', 2), ('T', 1), ('-', 2)]
+        );
+        assert!(aln.column_frequencies(2).is_empty());
+    }
+
+    #[test]
+    fn test_ut_equivalence() {
+        let hdrs = vec![
+            String::from("R1"),
+            String::from("R2"),
+            String::from("R3"),
+            String::from("R4"),
+            String::from("R5"),
+        ];
+        let seqs = vec![
+            String::from("GU"),
+            String::from("GU"),
+            String::from("GU"),
+            String::from("GT"),
+            String::from("Gt"),
+        ];
+        let mut aln = Alignment::from_vecs(hdrs, seqs);
+        // 60% U, 40% T: not conserved.
+        assert_eq!(aln.consensus, "Gu");
+        assert_eq!(aln.id_wrt_consensus[3], 0.5);
+
+        aln.set_ut_equivalence(true);
+        assert!(aln.ut_equivalence());
+        assert_eq!(aln.consensus, "GU");
+        assert!(aln.id_wrt_consensus.iter().all(|&id| id == 1.0));
+        // The sequences themselves are unchanged.
+        assert_eq!(aln.sequences[3], "GT");
+
+        aln.set_ut_equivalence(false);
+        assert_eq!(aln.consensus, "Gu");
+    }
+
+    #[test]
+    fn test_gap_motions() {
+        let aln = Alignment::from_vecs(
+            vec![String::from("s1"), String::from("s2")],
+            vec![String::from("A--CG-T"), String::from("ACGTACG")],
+        );
+        assert_eq!(aln.next_gap_start(0, 0), Some(1));
+        assert_eq!(aln.next_gap_start(0, 1), Some(5));
+        assert_eq!(aln.next_gap_start(0, 5), Some(1));
+        assert_eq!(aln.next_residue_after_gap(0, 0), Some(3));
+        assert_eq!(aln.next_residue_after_gap(0, 3), Some(6));
+        assert_eq!(aln.next_residue_after_gap(0, 6), Some(3));
+        assert_eq!(aln.next_gap_start(1, 0), None);
+        assert_eq!(aln.next_residue_after_gap(1, 3), None);
+        assert_eq!(aln.next_gap_start(2, 0), None);
+    }
 }
@@ -3,12 +3,13 @@
 // Modifications (c) 2026 Peter Carlton
 
 use std::{
-    collections::{HashMap, HashSet},
-    fmt,
-    fs::{self, File},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    fmt, fs,
+    hash::{Hash, Hasher},
     io::{BufWriter, Write},
     path::{Path, PathBuf},
-    process::{Command, Stdio},
+    process::Stdio,
+    time::{Duration, Instant},
 };
 
 use hex_color::HexColor;
@@ -16,19 +17,27 @@ use regex::{Regex, RegexBuilder};
 use serde_json::Value;
 
 use crate::{
-    alignment::Alignment,
+    aligner::{tree_sidecar_path, AlignerCommand, DEFAULT_ALIGNER_TEMPLATE},
+    alignment::{duplicate_headers, is_gap, Alignment, SeqType, ShiftDirection},
     app::Metric::{PctIdWrtConsensus, SeqLen},
-    app::SeqOrdering::{MetricDecr, MetricIncr, SearchMatch, SourceFile, User},
+    app::SeqOrdering::{MetricDecr, MetricIncr, SearchMatch, Similarity, SourceFile, User},
     errors::TermalError,
+    runner::InfoFormat,
     seq::fasta::read_fasta_file,
     session::{
         SessionCurrentSearch, SessionFile, SessionLabelSearch, SessionLabelSource,
-        SessionSearchEntry, SessionSearchKind, SessionView,
+        SessionSearchEntry, SessionSearchKind, SessionView, UiPrefs,
+    },
+    tree::{
+        clade_leaf_names, parse_newick, to_newick, tree_lines_and_order,
+        tree_lines_and_order_phylogram, tree_lines_and_order_with_selection, TreeNode,
     },
-    tree::{parse_newick, tree_lines_and_order, tree_lines_and_order_with_selection, TreeNode},
 };
 
+pub use crate::runner::{StartupScheme, StartupVideoMode};
+
 type SearchColor = (u8, u8, u8);
+type SpansBySeq = Vec<Vec<(usize, usize)>>;
 
 const DEFAULT_SEARCH_PALETTE: [SearchColor; 6] = [
     (100, 0, 0),
@@ -42,6 +51,15 @@ const DEFAULT_CURRENT_SEARCH_COLOR: SearchColor = (80, 80, 80);
 const DEFAULT_MIN_COMPONENT: u8 = 100;
 const DEFAULT_GAP_DIM_FACTOR: f32 = 0.5;
 const DEFAULT_LUMINANCE_THRESHOLD: f32 = 0.55;
+// Target width (in columns) of the tree panel when drawing a phylogram.
+const PHYLOGRAM_WIDTH: usize = 40;
+// Similarity ordering needs all pairwise distances, so it is only done up to this many sequences.
+const MAX_SIMILARITY_SEQS: usize = 2000;
+// Prompt history entries kept (and saved in sessions) per prompt kind.
+const MAX_HISTORY: usize = 100;
+
+// Columns per block in the print-friendly export (`:export print`).
+const PRINT_BLOCK_WIDTH: usize = 60;
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SeqOrdering {
     SourceFile,
@@ -49,6 +67,7 @@ pub enum SeqOrdering {
     MetricDecr,
     SearchMatch,
     User,
+    Similarity,
 }
 
 impl fmt::Display for SeqOrdering {
@@ -59,6 +78,7 @@ impl fmt::Display for SeqOrdering {
             MetricDecr => '↓',
             SearchMatch => 's',
             User => 'u',
+            Similarity => '~',
         };
         write!(f, "{}", sord)
     }
@@ -74,6 +94,32 @@ pub enum Metric {
 pub enum SearchKind {
     Regex,
     Emboss,
+    /// Features of one type (the entry's name) from a GFF file (the entry's query).
+    Gff,
+}
+
+/// Prompts with their own history (sequence searches share one, whatever their kind).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PromptKind {
+    Command,
+    Search,
+}
+
+/// Output formats of `App::export_stats`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatsFormat {
+    Json,
+    Tsv,
+}
+
+impl StatsFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(StatsFormat::Json),
+            "tsv" => Some(StatsFormat::Tsv),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -163,6 +209,53 @@ pub struct SeqSearchState {
     pub sequences_with_matches: usize,
     pub matches: Vec<SeqMatch>,
     pub current_match: usize,
+    // Set when the search was restricted to part of the alignment (e.g. what is on screen).
+    pub scope: Option<SearchScope>,
+}
+
+// Sequences (by rank) and columns (from, to excluded) that a scoped search looks at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchScope {
+    pub ranks: Vec<usize>,
+    pub cols: (usize, usize),
+}
+
+// Columns where two sequences disagree (`:diff`), highlighted on both rows.
+pub struct DiffState {
+    pub rank_a: usize,
+    pub rank_b: usize,
+    pub columns: Vec<usize>,
+    pub current: usize,
+    spans_by_seq: SpansBySeq,
+}
+
+// Matches of a regex in a column annotation (e.g. Stockholm SS_cons), as column ranges.
+pub struct AnnotationSearchState {
+    pub feature: String,
+    pub ranges: Vec<(usize, usize)>,
+    pub current: usize,
+}
+
+/// A manual edit of the alignment, as reported by `App::undo_edit()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlignmentEdit {
+    Inserted { at: usize, count: usize },
+    Deleted { at: usize, count: usize },
+    // A residue of the sequence at `rank` moved through gaps from column `from` to `to`.
+    Shifted { rank: usize, from: usize, to: usize },
+}
+
+// An entry of the edit undo stack: the edit, what it removed (if anything), and the shape
+// of the alignment it left, so that it is not undone on another view or after other changes.
+struct EditRecord {
+    edit: AlignmentEdit,
+    // Removed columns (one character per sequence) and, for each column annotation, its removed
+    // characters (empty if the annotation was not edited).
+    removed_columns: Vec<String>,
+    removed_annotations: Vec<String>,
+    view: String,
+    num_seq: usize,
+    aln_len: usize,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -177,6 +270,58 @@ pub struct RejectResult {
     pub action: RejectAction,
 }
 
+// What the inspect popup shows about a sequence; columns are display columns (see
+// App::display_col()).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeqDetails {
+    pub header: String,
+    pub ungapped_len: usize,
+    pub id_wrt_consensus: f64,
+    pub num_gaps: usize,
+    pub residue_span: Option<(i64, i64)>,
+}
+
+// Differences between a tree's leaves and the alignment headers.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TreeMismatch {
+    pub unmatched_leaves: Vec<String>,
+    pub missing_headers: Vec<String>,
+}
+
+impl TreeMismatch {
+    pub fn is_empty(&self) -> bool {
+        self.unmatched_leaves.is_empty() && self.missing_headers.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        fn list(names: &[String]) -> String {
+            let shown: Vec<&str> = names.iter().take(5).map(String::as_str).collect();
+            let more = if names.len() > shown.len() {
+                ", ..."
+            } else {
+                ""
+            };
+            format!("{}{}", shown.join(", "), more)
+        }
+        let mut parts = Vec::new();
+        if !self.unmatched_leaves.is_empty() {
+            parts.push(format!(
+                "{} leaves not in alignment ({})",
+                self.unmatched_leaves.len(),
+                list(&self.unmatched_leaves)
+            ));
+        }
+        if !self.missing_headers.is_empty() {
+            parts.push(format!(
+                "{} sequences not in tree ({})",
+                self.missing_headers.len(),
+                list(&self.missing_headers)
+            ));
+        }
+        parts.join("; ")
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SeqMatch {
     pub seq_index: usize,
@@ -184,6 +329,7 @@ pub struct SeqMatch {
     pub end: usize,
 }
 
+#[derive(Clone)]
 pub struct SearchColorConfig {
     pub palette: Vec<SearchColor>,
     pub current_search: SearchColor,
@@ -263,6 +409,9 @@ pub struct SearchEntry {
     pub enabled: bool,
     pub color: SearchColor,
     pub spans_by_seq: Vec<Vec<(usize, usize)>>,
+    // The parsed records of a GFF overlay (empty for other kinds), re-mapped to columns when the
+    // alignment changes.
+    gff_records: Vec<GffRecord>,
 }
 
 pub struct SearchRegistry {
@@ -280,11 +429,33 @@ pub enum MessageKind {
     Argument,
 }
 
+// How long error messages are shown in reverse video when `flash_errors` is set.
+const ERROR_FLASH_DURATION: Duration = Duration::from_millis(500);
+
 // Simple, 1-line message (possibly just "")
 pub struct CurrentMessage {
     pub prefix: String,
     pub message: String,
     pub kind: MessageKind,
+    pub set_at: Instant,
+    // Drawn in reverse video for a moment after it is set (errors, with `flash_errors`).
+    pub flash: bool,
+}
+
+impl CurrentMessage {
+    fn new(prefix: String, message: String, kind: MessageKind) -> Self {
+        CurrentMessage {
+            prefix,
+            message,
+            kind,
+            set_at: Instant::now(),
+            flash: false,
+        }
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        self.flash && self.set_at.elapsed() < ERROR_FLASH_DURATION
+    }
 }
 
 #[derive(Clone, Default)]
@@ -317,20 +488,163 @@ impl ToolsConfig {
     }
 }
 
+// The external aligner used for unaligned input and for `:ra`, as a command template (see
+// `aligner::AlignerCommand`).
+#[derive(Clone)]
+pub struct AlignerConfig {
+    pub command: String,
+}
+
+impl Default for AlignerConfig {
+    fn default() -> Self {
+        Self {
+            command: String::from(DEFAULT_ALIGNER_TEMPLATE),
+        }
+    }
+}
+
+impl AlignerConfig {
+    pub fn from_value(value: &Value) -> Self {
+        let command = value
+            .get("aligner")
+            .and_then(|v| v.get("command"))
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        match command {
+            Some(command) => Self { command },
+            None => Self::default(),
+        }
+    }
+}
+
+// Startup display settings; unknown values are ignored, like absent ones.
+#[derive(Clone, Copy, Default)]
+pub struct DisplayConfig {
+    pub default_scheme: Option<StartupScheme>,
+    pub default_video_mode: Option<StartupVideoMode>,
+    pub flash_errors: bool,
+    pub conservation_shading: bool,
+}
+
+impl DisplayConfig {
+    pub fn from_value(value: &Value) -> Self {
+        let default_scheme = value
+            .get("default_scheme")
+            .and_then(|v| v.as_str())
+            .and_then(|s| clap::ValueEnum::from_str(s, true).ok());
+        let default_video_mode = value
+            .get("default_video_mode")
+            .and_then(|v| v.as_str())
+            .and_then(|s| clap::ValueEnum::from_str(s, true).ok());
+        let flash_errors = value
+            .get("flash_errors")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let conservation_shading = value
+            .get("conservation_shading")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        Self {
+            default_scheme,
+            default_video_mode,
+            flash_errors,
+            conservation_shading,
+        }
+    }
+}
+
+/// A colormap of residue classes from the config's `residue_classes`, e.g.
+/// `{"name": "Charge", "classes": [{"name": "acidic", "residues": "DE", "color": "#d62728"}]}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResidueClassMap {
+    pub name: String,
+    // (class name, residues, color)
+    pub classes: Vec<(String, String, (u8, u8, u8))>,
+}
+
+impl ResidueClassMap {
+    // None if the map has no name; classes without residues or with a bad color are skipped.
+    fn from_value(value: &Value) -> Option<Self> {
+        let name = value.get("name")?.as_str()?.to_string();
+        let classes = value
+            .get("classes")
+            .and_then(|v| v.as_array())
+            .map(|classes| {
+                classes
+                    .iter()
+                    .filter_map(|class| {
+                        let residues = class.get("residues")?.as_str()?;
+                        let color = parse_color_value(class.get("color")?).ok()?;
+                        let class_name = class.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                        Some((class_name.to_string(), residues.to_string(), color))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(Self { name, classes })
+    }
+}
+
+// Larger configured indent widths are capped to this.
+const MAX_NOTES_INDENT_WIDTH: u64 = 16;
+
+/// Consensus thresholds are fractions of the sequences, above 0.
+pub fn valid_consensus_threshold(threshold: f64) -> bool {
+    threshold > 0.0 && threshold <= 1.0
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[derive(Clone)]
 pub struct TermalConfig {
     pub search_colors: SearchColorConfig,
     pub tools: ToolsConfig,
+    pub aligner: AlignerConfig,
+    pub display: DisplayConfig,
+    pub consensus_threshold: Option<f64>,
+    // None: on for RNA alignments only.
+    pub ut_equivalence: Option<bool>,
+    pub notes_indent_width: Option<usize>,
+    pub residue_classes: Vec<ResidueClassMap>,
 }
 
 impl TermalConfig {
+    pub fn from_value(value: &Value) -> Self {
+        Self {
+            search_colors: SearchColorConfig::from_value(value),
+            tools: ToolsConfig::from_value(value),
+            aligner: AlignerConfig::from_value(value),
+            display: DisplayConfig::from_value(value),
+            consensus_threshold: value
+                .get("consensus_threshold")
+                .and_then(|v| v.as_f64())
+                .filter(|t| valid_consensus_threshold(*t)),
+            ut_equivalence: value.get("ut_equivalence").and_then(|v| v.as_bool()),
+            notes_indent_width: value
+                .get("notes_indent_width")
+                .and_then(|v| v.as_u64())
+                .map(|width| width.min(MAX_NOTES_INDENT_WIDTH) as usize),
+            residue_classes: value
+                .get("residue_classes")
+                .and_then(|v| v.as_array())
+                .map(|maps| {
+                    maps.iter()
+                        .filter_map(ResidueClassMap::from_value)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
     pub fn from_file(path: &Path) -> Result<Self, TermalError> {
         let contents = fs::read_to_string(path)?;
         let value: Value = serde_json::from_str(&contents)
             .map_err(|e| TermalError::Format(format!("Invalid JSON {}: {}", path.display(), e)))?;
-        Ok(Self {
-            search_colors: SearchColorConfig::from_value(&value),
-            tools: ToolsConfig::from_value(&value),
-        })
+        Ok(Self::from_value(&value))
     }
 }
 
@@ -344,6 +658,16 @@ pub struct App {
     current_view_ids: Vec<usize>,
     current_view_alignment_override: Option<Vec<String>>,
     ordering_criterion: SeqOrdering,
+    // The ordering is flipped top to bottom, whatever the criterion (see
+    // reverse_current_ordering()).
+    ordering_reversed: bool,
+    // Set by changes that a session would record (edits, views, user orderings, saved searches,
+    // notes...) and cleared when the session is saved or loaded.
+    unsaved_changes: bool,
+    // Where the session was last saved to or loaded from (see :w).
+    session_path: Option<PathBuf>,
+    // Set by --read-only: commands that change the alignment are refused.
+    read_only: bool,
     metric: Metric,
     // Specifies in which order the aligned sequences should be displayed. The elements of this Vec
     // are _indices_ into the Vec's of headers and sequences that together make up the alignment.
@@ -358,16 +682,51 @@ pub struct App {
     search_registry: SearchRegistry,
     search_color_config: SearchColorConfig,
     current_msg: CurrentMessage,
+    // Error messages flash (reverse video) for a moment when set (`flash_errors` in the config).
+    flash_errors: bool,
+    // Number shown for the first column (e.g. 10001 for a fragment of a larger sequence).
+    column_origin: i64,
     label_search_source: Option<LabelSearchSource>,
     tree_selection_range: Option<(usize, usize)>,
     emboss_bin_dir: Option<PathBuf>,
     mafft_bin_dir: Option<PathBuf>,
+    aligner_command: String,
     notes: String,
     view_notes: String,
+    command_history: Vec<String>,
+    search_history: Vec<String>,
+    // Pane layout stored in (or loaded from) a session; the UI applies it when created and after
+    // :sl, and updates it before saving.
+    pub ui_prefs: Option<UiPrefs>,
+    // Molecule type set with --molecule or :mol, overriding the detected one.
+    forced_molecule_type: Option<SeqType>,
     tree_lines: Vec<String>,
     tree_panel_width: u16,
     tree: Option<TreeNode>,
     tree_newick: Option<String>,
+    tree_phylogram: bool,
+    // Regex searches highlight capture group 1 (when the pattern has groups) instead of the whole
+    // match.
+    highlight_capture_group: bool,
+    diff_state: Option<DiffState>,
+    // Per-column annotations of the alignment file (Stockholm `#=GC` lines).
+    column_annotations: Vec<(String, String)>,
+    annotation_search: Option<AnnotationSearchState>,
+    // Manual edits (column insertions and deletions, residue shifts), most recent last.
+    edits: Vec<EditRecord>,
+    hide_all_gap_sequences: bool,
+    // Sequence IDs pinned above the scrolling rows, in pinning order.
+    pinned_ids: Vec<usize>,
+    // Sequence ID whose residues Left/Right scrolling steps through, if any.
+    scroll_reference_id: Option<usize>,
+    // Similarity ordering, keyed by a hash of the sequences it was computed from.
+    similarity_cache: Option<(u64, Vec<usize>)>,
+    collapsed_nodes: HashSet<usize>,
+    // Header of each sequence hidden by a collapsed clade -> header of the clade's representative
+    // (its first leaf), which stays visible.
+    collapsed_headers: HashMap<String, String>,
+    // Names before :relabel -> current headers.
+    header_aliases: HashMap<String, String>,
     active_search_ids: HashSet<usize>,
     current_view_output_path: PathBuf,
     rejected_ids: HashSet<usize>,
@@ -385,7 +744,10 @@ impl App {
                 sequences.push(record.sequence.clone());
             }
         }
-        Alignment::from_vecs(headers, sequences)
+        let mut alignment = Alignment::from_vecs(headers, sequences);
+        alignment.set_consensus_threshold(self.alignment.consensus_threshold());
+        alignment.set_ut_equivalence(self.alignment.ut_equivalence());
+        alignment
     }
 
     fn build_alignment_for_ids_with_sequences(
@@ -401,7 +763,10 @@ impl App {
                 seqs.push(sequence.clone());
             }
         }
-        Alignment::from_vecs(headers, seqs)
+        let mut alignment = Alignment::from_vecs(headers, seqs);
+        alignment.set_consensus_threshold(self.alignment.consensus_threshold());
+        alignment.set_ut_equivalence(self.alignment.ut_equivalence());
+        alignment
     }
 
     fn update_records_from_alignment(
@@ -410,7 +775,7 @@ impl App {
         ids: &[usize],
     ) -> Result<(), TermalError> {
         let mut seq_map: HashMap<&String, &String> = HashMap::new();
-        for (header, sequence) in alignment.headers.iter().zip(alignment.sequences.iter()) {
+        for (header, sequence) in alignment.headers().iter().zip(alignment.sequences().iter()) {
             seq_map.insert(header, sequence);
         }
         for id in ids {
@@ -495,7 +860,11 @@ impl App {
         let len = self.alignment.num_seq();
         self.ordering = (0..len).collect();
         self.reverse_ordering = (0..len).collect();
+        self.diff_state = None;
+        self.annotation_search = None;
         self.user_ordering = view.user_ordering.clone();
+        self.collapsed_nodes.clear();
+        self.collapsed_headers.clear();
         self.tree = view.tree.clone();
         self.tree_newick = view.tree_newick.clone();
         self.tree_lines = view.tree_lines.clone();
@@ -515,7 +884,7 @@ impl App {
                 let state = build_label_state_from_matches(
                     label.pattern.clone(),
                     matches,
-                    self.alignment.headers.len(),
+                    self.alignment.headers().len(),
                 );
                 self.search_state = Some(state);
             } else {
@@ -543,6 +912,7 @@ impl App {
             match SearchKind::from(current.kind) {
                 SearchKind::Regex => self.regex_search_sequences(&current.pattern),
                 SearchKind::Emboss => self.emboss_search_sequences(&current.pattern),
+                SearchKind::Gff => {}
             }
             if let Some(state) = &mut self.seq_search_state {
                 if let Some(idx) = current.current_match {
@@ -587,6 +957,10 @@ impl App {
     }
 
     fn clear_current_view_tree(&mut self) {
+        if !self.collapsed_nodes.is_empty() {
+            self.clear_collapsed_clades();
+            self.recompute_ordering();
+        }
         self.tree = None;
         self.tree_newick = None;
         self.tree_lines.clear();
@@ -670,6 +1044,7 @@ impl App {
         if name != self.current_view {
             self.store_current_view_state();
         }
+        self.mark_unsaved();
         self.views.remove(name);
         self.view_order.retain(|view_name| view_name != name);
         if name == self.current_view {
@@ -742,6 +1117,7 @@ impl App {
             selected_ids: self.selected_ids.clone(),
             cursor_id: self.cursor_id,
         };
+        self.mark_unsaved();
         self.views.insert(name.to_string(), view);
         self.view_order.push(name.to_string());
         Ok(())
@@ -793,6 +1169,7 @@ impl App {
             selected_ids: sequence_ids.iter().copied().collect(),
             cursor_id: sequence_ids.first().copied(),
         };
+        self.mark_unsaved();
         self.views.insert(name.to_string(), view);
         self.view_order.push(name.to_string());
         Ok(())
@@ -925,6 +1302,10 @@ impl App {
         if ids.is_empty() {
             return Ok(0);
         }
+        if name == "filtered" || name == "rejected" {
+            self.ensure_writable()?;
+        }
+        self.mark_unsaved();
         if name == "filtered" || name == "rejected" {
             self.ensure_filtered_rejected_views();
             for id in ids {
@@ -986,22 +1367,19 @@ impl App {
         let alignment = Alignment::from_vecs(session.headers.clone(), session.sequences.clone());
         let mut app = App::new(&filename, alignment, None);
         app.apply_session(session, filename)?;
+        app.session_path = Some(path.to_path_buf());
         Ok(app)
     }
     pub fn new(path: &str, alignment: Alignment, usr_ord: Option<Vec<String>>) -> Self {
         let len = alignment.num_seq();
         let records: Vec<SeqRecord> = alignment
-            .headers
+            .headers()
             .iter()
             .cloned()
-            .zip(alignment.sequences.iter().cloned())
+            .zip(alignment.sequences().iter().cloned())
             .map(|(header, sequence)| SeqRecord { header, sequence })
             .collect();
-        let cur_msg = CurrentMessage {
-            prefix: String::from(""),
-            message: String::from(""),
-            kind: MessageKind::Info,
-        };
+        let cur_msg = CurrentMessage::new(String::from(""), String::from(""), MessageKind::Info);
         let search_color_config = SearchColorConfig::default();
         let original_output_path = next_available_output_path(path, "orig");
         let mut views = HashMap::new();
@@ -1035,6 +1413,10 @@ impl App {
             current_view_ids: (0..len).collect(),
             current_view_alignment_override: None,
             ordering_criterion: SourceFile,
+            ordering_reversed: false,
+            unsaved_changes: false,
+            session_path: None,
+            read_only: false,
             metric: PctIdWrtConsensus,
             ordering: (0..len).collect(),
             reverse_ordering: (0..len).collect(),
@@ -1044,16 +1426,36 @@ impl App {
             search_registry: SearchRegistry::new(search_color_config.palette.clone()),
             search_color_config,
             current_msg: cur_msg,
+            flash_errors: false,
+            column_origin: 1,
             label_search_source: None,
             tree_selection_range: None,
             emboss_bin_dir: None,
             mafft_bin_dir: None,
+            aligner_command: String::from(DEFAULT_ALIGNER_TEMPLATE),
             notes: String::new(),
             view_notes: String::new(),
+            command_history: Vec::new(),
+            search_history: Vec::new(),
+            ui_prefs: None,
+            forced_molecule_type: None,
             tree_lines: Vec::new(),
             tree_panel_width: 0,
             tree: None,
             tree_newick: None,
+            tree_phylogram: false,
+            highlight_capture_group: false,
+            diff_state: None,
+            column_annotations: Vec::new(),
+            annotation_search: None,
+            edits: Vec::new(),
+            hide_all_gap_sequences: false,
+            pinned_ids: Vec::new(),
+            scroll_reference_id: None,
+            similarity_cache: None,
+            collapsed_nodes: HashSet::new(),
+            collapsed_headers: HashMap::new(),
+            header_aliases: HashMap::new(),
             active_search_ids,
             current_view_output_path: original_output_path,
             rejected_ids: HashSet::new(),
@@ -1065,12 +1467,59 @@ impl App {
     // Computed properties (TODO: could be set in a struct member, as they do not change)
     // FIXME where do we need num_seq as u16?
 
+    // The UI works in terminal (u16) coordinates: num_seq() and aln_len() are clamped to
+    // u16::MAX, and only that many sequences and columns can be displayed (see
+    // display_limit_warning()). Use the _usize variants for actual counts.
     pub fn num_seq(&self) -> u16 {
-        self.alignment.num_seq().try_into().unwrap()
+        self.num_seq_usize().min(u16::MAX as usize) as u16
+    }
+
+    // Number of sequences shown, i.e. excluding those hidden (in collapsed clades, pinned, etc.);
+    // this is what screen geometry (scrolling, zoom ratios, scrollbars) is based on.
+    pub fn num_visible_seq(&self) -> u16 {
+        self.num_visible_seq_usize().min(u16::MAX as usize) as u16
     }
 
     pub fn aln_len(&self) -> u16 {
-        self.alignment.aln_len().try_into().unwrap()
+        self.aln_len_usize().min(u16::MAX as usize) as u16
+    }
+
+    pub fn num_seq_usize(&self) -> usize {
+        self.alignment.num_seq()
+    }
+
+    pub fn num_visible_seq_usize(&self) -> usize {
+        self.ordering.len()
+    }
+
+    pub fn aln_len_usize(&self) -> usize {
+        self.alignment.aln_len()
+    }
+
+    /// The forced molecule type if there is one, else the detected one.
+    pub fn macromolecule_type(&self) -> SeqType {
+        self.forced_molecule_type
+            .unwrap_or(self.alignment.macromolecule_type())
+    }
+
+    pub fn is_molecule_type_forced(&self) -> bool {
+        self.forced_molecule_type.is_some()
+    }
+
+    pub fn force_molecule_type(&mut self, molecule_type: SeqType) {
+        self.forced_molecule_type = Some(molecule_type);
+    }
+
+    pub fn display_limit_warning(&self) -> Option<String> {
+        let limit = u16::MAX as usize;
+        let (num_seq, aln_len) = (self.num_seq_usize(), self.aln_len_usize());
+        if num_seq <= limit && aln_len <= limit {
+            return None;
+        }
+        Some(format!(
+            "Alignment has {} sequences x {} columns; only the first {} of each can be displayed",
+            num_seq, aln_len, limit
+        ))
     }
 
     pub fn all_sequences_rejected(&self) -> bool {
@@ -1096,9 +1545,49 @@ impl App {
         let json = serde_json::to_string_pretty(&session)
             .map_err(|e| TermalError::Format(format!("Invalid session JSON: {}", e)))?;
         fs::write(path, json)?;
+        self.unsaved_changes = false;
+        self.session_path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// True iff commands that change the alignment (edits, rejections, realigning...) are
+    /// refused.
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    // Fails in read-only mode; called first by the functions that change the alignment.
+    fn ensure_writable(&self) -> Result<(), TermalError> {
+        if self.read_only {
+            return Err(TermalError::Format(String::from("Read-only mode")));
+        }
         Ok(())
     }
 
+    /// The session file last saved to or loaded from, if any.
+    pub fn session_path(&self) -> Option<&Path> {
+        self.session_path.as_deref()
+    }
+
+    /// True iff something a session records has changed since it was last saved or loaded (or
+    /// since startup).
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
+
+    fn mark_unsaved(&mut self) {
+        self.unsaved_changes = true;
+    }
+
+    /// Forgets the changes made so far, e.g. those made while starting up (tree, GFF, relabeling).
+    pub fn clear_unsaved_changes(&mut self) {
+        self.unsaved_changes = false;
+    }
+
     pub fn load_session(&mut self, path: &Path) -> Result<(), TermalError> {
         let contents = fs::read_to_string(path)?;
         let session: SessionFile = serde_json::from_str(&contents)
@@ -1109,6 +1598,8 @@ impl App {
             session.source_filename.clone()
         };
         self.apply_session(session, filename)?;
+        self.unsaved_changes = false;
+        self.session_path = Some(path.to_path_buf());
         Ok(())
     }
 
@@ -1178,6 +1669,10 @@ impl App {
             } else {
                 Some(self.notes.clone())
             },
+            command_history: Some(self.command_history.clone()),
+            search_history: Some(self.search_history.clone()),
+            ui_prefs: self.ui_prefs,
+            column_origin: (self.column_origin != 1).then_some(self.column_origin),
         }
     }
 
@@ -1192,6 +1687,7 @@ impl App {
         let original_ids: Vec<usize> = (0..self.records.len()).collect();
         self.alignment = self.build_alignment_for_ids(&original_ids);
         self.ordering_criterion = SourceFile;
+        self.ordering_reversed = false;
         let len = self.alignment.num_seq();
         self.ordering = (0..len).collect();
         self.reverse_ordering = (0..len).collect();
@@ -1299,23 +1795,56 @@ impl App {
                 enabled: entry.enabled,
                 color: entry.color,
                 spans_by_seq: Vec::new(),
+                gff_records: Vec::new(),
             });
         }
         self.search_registry.next_color_index = self.search_registry.searches.len();
 
         self.notes = session.notes.unwrap_or_default();
+        self.command_history = session.command_history.unwrap_or_default();
+        self.search_history = session.search_history.unwrap_or_default();
+        self.ui_prefs = session.ui_prefs;
+        self.column_origin = session.column_origin.unwrap_or(1);
 
-        self.current_msg = CurrentMessage {
-            prefix: String::new(),
-            message: String::new(),
-            kind: MessageKind::Info,
-        };
+        self.current_msg = CurrentMessage::new(String::new(), String::new(), MessageKind::Info);
+        self.read_session_gff_records();
         if let Some(view) = self.views.get(&self.current_view).cloned() {
             self.load_view_state(view)?;
         }
         Ok(())
     }
 
+    // Sessions store GFF overlays by file and feature type: reads each file once to get the
+    // records back.
+    fn read_session_gff_records(&mut self) {
+        let mut records_by_path: HashMap<String, Vec<(String, Vec<GffRecord>)>> = HashMap::new();
+        let mut failed = Vec::new();
+        for entry in &mut self.search_registry.searches {
+            if entry.kind != SearchKind::Gff {
+                continue;
+            }
+            if !records_by_path.contains_key(&entry.query) {
+                match fs::read_to_string(&entry.query) {
+                    Ok(gff) => {
+                        records_by_path.insert(entry.query.clone(), parse_gff_records(&gff));
+                    }
+                    Err(e) => {
+                        failed.push(format!("{}: {}", entry.query, e));
+                        records_by_path.insert(entry.query.clone(), Vec::new());
+                    }
+                }
+            }
+            entry.gff_records = records_by_path[&entry.query]
+                .iter()
+                .find(|(feature_type, _)| *feature_type == entry.name)
+                .map(|(_, records)| records.clone())
+                .unwrap_or_default();
+        }
+        if !failed.is_empty() {
+            self.warning_msg(format!("Could not read GFF file(s): {}", failed.join("; ")));
+        }
+    }
+
     fn recompute_ordering(&mut self) {
         match self.ordering_criterion {
             MetricIncr => {
@@ -1346,6 +1875,14 @@ impl App {
             SourceFile => {
                 self.ordering = (0..self.alignment.num_seq()).collect();
             }
+            Similarity => {
+                // See warn_similarity_limit().
+                if self.alignment.num_seq() > MAX_SIMILARITY_SEQS {
+                    self.ordering = (0..self.alignment.num_seq()).collect();
+                } else {
+                    self.ordering = self.similarity_ordering();
+                }
+            }
             User => {
                 // Do not change ordering if no user ordering provided, or if it had
                 // problems (this is checked early on, in main(), around l. 180 (as of commit
@@ -1360,25 +1897,208 @@ impl App {
                         // Good ordering
                         // Technically, we could index by &str, but I'm not sure we'd gain a lot.
                         let mut hdr2rank: HashMap<String, usize> = HashMap::new();
-                        for (idx, hdr) in self.alignment.headers.iter().enumerate() {
+                        for (idx, hdr) in self.alignment.headers().iter().enumerate() {
                             hdr2rank.insert(hdr.to_string(), idx);
                         }
-                        // Iterate over ordering, looking up file index from the above hash.
+                        // Iterate over ordering, looking up file index from the above hash. Names
+                        // that match no header are skipped, and sequences the ordering misses
+                        // follow in alignment order (rather than being dropped).
                         let mut result: Vec<usize> = Vec::new();
-                        // TODO: now that we no longer check for discrepancies here, this should be
-                        //feasible in a sinmple map.
+                        let mut seen: HashSet<usize> = HashSet::new();
                         for hdr in uord_vec.iter() {
-                            match hdr2rank.get(hdr) {
-                                Some(rank) => result.push(*rank),
-                                None => break,
+                            if let Some(rank) = hdr2rank.get(hdr) {
+                                if seen.insert(*rank) {
+                                    result.push(*rank);
+                                }
                             }
                         }
+                        result.extend((0..self.alignment.num_seq()).filter(|r| !seen.contains(r)));
                         self.ordering = result;
                     }
                 }
             }
         }
-        self.reverse_ordering = order(&self.ordering);
+        if self.ordering_reversed {
+            self.ordering.reverse();
+        }
+        let hidden = self.hidden_rank_map();
+        if !hidden.is_empty() {
+            self.ordering.retain(|rank| !hidden.contains_key(rank));
+        }
+        let all_gap = self.hidden_all_gap_ranks();
+        if !all_gap.is_empty() {
+            self.ordering.retain(|rank| !all_gap.contains(rank));
+        }
+        let pinned = self.pinned_ranks();
+        if !pinned.is_empty() && self.ordering.iter().any(|rank| !pinned.contains(rank)) {
+            self.ordering.retain(|rank| !pinned.contains(rank));
+        }
+        let mut reverse = vec![0; self.alignment.num_seq()];
+        for (line, rank) in self.ordering.iter().enumerate() {
+            reverse[*rank] = line;
+        }
+        // Hidden sequences map to the line of their clade's representative.
+        for (rank, rep_rank) in hidden {
+            reverse[rank] = reverse[rep_rank];
+        }
+        self.reverse_ordering = reverse;
+    }
+
+    // Greedy nearest-neighbour chain: starts from the most central sequence (smallest total
+    // p-distance to the others), then repeatedly appends the closest sequence not yet placed. Ties
+    // go to the lower rank.
+    fn similarity_ordering(&mut self) -> Vec<usize> {
+        let mut hasher = DefaultHasher::new();
+        self.alignment.sequences().hash(&mut hasher);
+        let key = hasher.finish();
+        if let Some((cached_key, ordering)) = &self.similarity_cache {
+            if *cached_key == key {
+                return ordering.clone();
+            }
+        }
+        let n = self.alignment.num_seq();
+        let mut dist = vec![vec![0.0; n]; n];
+        for (i, j) in (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j))) {
+            let d = self.alignment.pairwise_pdistance(i, j);
+            dist[i][j] = d;
+            dist[j][i] = d;
+        }
+        // Index with the smallest score among the candidates (first one on ties).
+        let closest = |candidates: &mut dyn Iterator<Item = usize>,
+                       score: &dyn Fn(usize) -> f64| {
+            candidates.fold(None, |best: Option<(usize, f64)>, idx| {
+                let d = score(idx);
+                match best {
+                    Some((_, best_d)) if best_d <= d => best,
+                    _ => Some((idx, d)),
+                }
+            })
+        };
+        let mut ordering = Vec::with_capacity(n);
+        let mut used = vec![false; n];
+        if let Some((first, _)) = closest(&mut (0..n), &|i| dist[i].iter().sum::<f64>()) {
+            ordering.push(first);
+            used[first] = true;
+        }
+        while let Some(&last) = ordering.last() {
+            let Some((next, _)) = closest(&mut (0..n).filter(|j| !used[*j]), &|j| dist[last][j])
+            else {
+                break;
+            };
+            ordering.push(next);
+            used[next] = true;
+        }
+        self.similarity_cache = Some((key, ordering.clone()));
+        ordering
+    }
+
+    // Ranks of the all-gap sequences filtered out of the ordering (none if that would leave
+    // nothing to show).
+    fn hidden_all_gap_ranks(&self) -> HashSet<usize> {
+        if !self.hide_all_gap_sequences {
+            return HashSet::new();
+        }
+        let ranks = self.alignment.all_gap_sequences();
+        if ranks.len() == self.alignment.num_seq() {
+            return HashSet::new();
+        }
+        ranks.into_iter().collect()
+    }
+
+    /// Toggles hiding of all-gap sequences (they stay in the alignment); returns the new state.
+    pub fn toggle_hide_all_gap_sequences(&mut self) -> bool {
+        self.hide_all_gap_sequences = !self.hide_all_gap_sequences;
+        self.recompute_ordering();
+        self.hide_all_gap_sequences
+    }
+
+    /// Number of all-gap sequences currently hidden.
+    pub fn num_hidden_all_gap(&self) -> usize {
+        self.hidden_all_gap_ranks().len()
+    }
+
+    /// Ranks of the pinned sequences present in the current view, in pinning order.
+    pub fn pinned_ranks(&self) -> Vec<usize> {
+        self.pinned_ids
+            .iter()
+            .filter_map(|id| self.current_view_ids.iter().position(|seq_id| seq_id == id))
+            .collect()
+    }
+
+    pub fn is_pinned(&self, rank: usize) -> bool {
+        self.current_view_ids
+            .get(rank)
+            .is_some_and(|id| self.pinned_ids.contains(id))
+    }
+
+    /// Pins sequence `rank` above the scrolling rows. Returns false if it is already pinned, or if
+    /// it is the last sequence left to scroll through.
+    pub fn pin_sequence(&mut self, rank: usize) -> bool {
+        let Some(id) = self.current_view_ids.get(rank).copied() else {
+            return false;
+        };
+        if self.pinned_ids.contains(&id)
+            || !self.ordering.contains(&rank)
+            || self.ordering.len() < 2
+        {
+            return false;
+        }
+        self.pinned_ids.push(id);
+        self.recompute_ordering();
+        true
+    }
+
+    /// Returns false if sequence `rank` was not pinned.
+    pub fn unpin_sequence(&mut self, rank: usize) -> bool {
+        let Some(id) = self.current_view_ids.get(rank).copied() else {
+            return false;
+        };
+        let before = self.pinned_ids.len();
+        self.pinned_ids.retain(|pinned| *pinned != id);
+        if self.pinned_ids.len() == before {
+            return false;
+        }
+        self.recompute_ordering();
+        true
+    }
+
+    /// Unpins all sequences; returns how many were pinned in the current view.
+    pub fn unpin_all(&mut self) -> usize {
+        let count = self.pinned_ranks().len();
+        self.pinned_ids.clear();
+        self.recompute_ordering();
+        count
+    }
+
+    /// Makes Left/Right scrolling step through the residues of sequence `rank` (skipping its gap
+    /// columns), or through columns again if None.
+    pub fn set_scroll_reference(&mut self, rank: Option<usize>) {
+        self.scroll_reference_id = rank.and_then(|rank| self.current_view_ids.get(rank).copied());
+    }
+
+    /// Rank of the scrolling reference sequence, if it is in the current view.
+    pub fn scroll_reference_rank(&self) -> Option<usize> {
+        let id = self.scroll_reference_id?;
+        self.current_view_ids
+            .iter()
+            .position(|seq_id| *seq_id == id)
+    }
+
+    fn hidden_rank_map(&self) -> HashMap<usize, usize> {
+        if self.collapsed_headers.is_empty() {
+            return HashMap::new();
+        }
+        let hdr2rank: HashMap<&String, usize> = self
+            .alignment
+            .headers()
+            .iter()
+            .enumerate()
+            .map(|(idx, hdr)| (hdr, idx))
+            .collect();
+        self.collapsed_headers
+            .iter()
+            .filter_map(|(hdr, rep)| Some((*hdr2rank.get(hdr)?, *hdr2rank.get(rep)?)))
+            .collect()
     }
 
     pub fn next_ordering_criterion(&mut self) {
@@ -1389,11 +2109,13 @@ impl App {
             MetricDecr => SearchMatch,
             SearchMatch => match self.user_ordering {
                 Some(_) => User,
-                None => SourceFile,
+                None => Similarity,
             },
-            User => SourceFile,
+            User => Similarity,
+            Similarity => SourceFile,
         };
         self.recompute_ordering();
+        self.warn_similarity_limit();
     }
 
     pub fn prev_ordering_criterion(&mut self) {
@@ -1402,13 +2124,26 @@ impl App {
             MetricDecr => MetricIncr,
             SearchMatch => MetricDecr,
             User => SearchMatch,
+            SourceFile => Similarity,
             // move to User IFF valid ordering
-            SourceFile => match self.user_ordering {
+            Similarity => match self.user_ordering {
                 Some(_) => User,
                 None => SearchMatch,
             },
         };
         self.recompute_ordering();
+        self.warn_similarity_limit();
+    }
+
+    // Says so when Similarity ordering has just been selected but there are too many sequences
+    // for it (the source file order is kept); recomputing the ordering stays silent.
+    fn warn_similarity_limit(&mut self) {
+        if self.ordering_criterion == Similarity && self.alignment.num_seq() > MAX_SIMILARITY_SEQS {
+            self.warning_msg(format!(
+                "Similarity ordering is limited to {} sequences",
+                MAX_SIMILARITY_SEQS
+            ));
+        }
     }
 
     // Maps a rank (= index in the original alignment) to the corresponding line on the screen
@@ -1418,62 +2153,456 @@ impl App {
         self.reverse_ordering[rank]
     }
 
-    pub fn next_metric(&mut self) {
-        self.metric = match self.metric {
-            PctIdWrtConsensus => SeqLen,
-            SeqLen => PctIdWrtConsensus,
-        };
+    /// Sets the fraction of sequences that must share a residue for its column to count as
+    /// conserved; the ordering follows, as it may depend on the identities to the consensus.
+    pub fn set_consensus_threshold(&mut self, threshold: f64) {
+        self.alignment.set_consensus_threshold(threshold);
         self.recompute_ordering();
     }
 
-    // NOTE: for now, there are only two metrics, so next and prev are the same. This might change,
-    // however.
-    pub fn prev_metric(&mut self) {
-        self.metric = match self.metric {
-            PctIdWrtConsensus => SeqLen,
-            SeqLen => PctIdWrtConsensus,
-        };
-        self.recompute_ordering();
+    /// Numbers the first column `origin` in the ruler, column jumps and readouts (default 1), e.g.
+    /// to follow the coordinates of a genomic region. Columns are still indexed from 0 internally.
+    pub fn set_column_origin(&mut self, origin: i64) {
+        self.mark_unsaved();
+        self.column_origin = origin;
     }
 
-    pub fn output_info(&self) {
-        println!("name: {}", self.filename);
-        println!("nb_sequences: {}", self.num_seq());
-        println!("nb_columns: {}", self.aln_len());
-        println!();
+    pub fn column_origin(&self) -> i64 {
+        self.column_origin
     }
 
-    pub fn get_seq_ordering(&self) -> SeqOrdering {
-        self.ordering_criterion
+    /// Number shown for (0-based) column `col`.
+    pub fn display_col(&self, col: usize) -> i64 {
+        self.column_origin + col as i64
     }
 
-    pub fn ordering_status_label(&self) -> String {
-        match self.ordering_criterion {
-            SourceFile => String::from("o:original"),
-            SearchMatch => String::from("o:match"),
-            User => String::from("o:tree"),
-            MetricIncr => format!("o:{}↑", self.metric.short_label()),
-            MetricDecr => format!("o:{}↓", self.metric.short_label()),
-        }
+    /// Column (0-based) shown as `display`, if it is in the alignment.
+    pub fn internal_col(&self, display: i64) -> Option<usize> {
+        usize::try_from(display.checked_sub(self.column_origin)?)
+            .ok()
+            .filter(|&col| col < self.alignment.aln_len())
     }
 
-    pub fn get_metric(&self) -> Metric {
-        self.metric
+    /// Consensus of columns `start` to `end` (excluded) as a motif: residues with a majority (see
+    /// the consensus threshold) in upper case, more frequent ones in lower case, and X where
+    /// no residue stands out; gap columns are left out.
+    pub fn column_range_consensus(&self, start: usize, end: usize) -> String {
+        self.consensus_string()
+            .chars()
+            .take(end)
+            .skip(start)
+            .filter(|c| !is_gap(*c))
+            .collect()
     }
 
-    // TODO: rename to order_by_metric
-    pub fn order_values(&self) -> &Vec<f64> {
-        match self.metric {
-            PctIdWrtConsensus => &self.alignment.id_wrt_consensus,
-            SeqLen => &self.alignment.relative_seq_len,
+    /// Consensus of the whole alignment as a sequence, one character per column: unconserved
+    /// columns ('*') become X, gap columns are kept.
+    pub fn consensus_string(&self) -> String {
+        self.alignment
+            .consensus()
+            .chars()
+            .map(|c| if c == '*' { 'X' } else { c })
+            .collect()
+    }
+
+    /// Makes the consensus and the identities to it count T and U as the same residue, or not;
+    /// the ordering follows.
+    pub fn set_ut_equivalence(&mut self, ut_equivalence: bool) {
+        self.alignment.set_ut_equivalence(ut_equivalence);
+        self.recompute_ordering();
+    }
+
+    /// Removes the current view's columns whose occupancy (fraction of non-gap cells) is below
+    /// `min_occupancy`, as well as all-gap columns, and recomputes the consensus and metrics. The
+    /// trimmed sequences become the view's own, so other views are unaffected. Returns the number
+    /// of columns removed.
+    pub fn trim_columns(&mut self, min_occupancy: f64) -> Result<usize, TermalError> {
+        self.ensure_writable()?;
+        let before = self.alignment.aln_len();
+        let trimmed = self.alignment.trimmed_sequences(min_occupancy);
+        let removed = before - trimmed.first().map_or(0, String::len);
+        if removed == 0 {
+            return Ok(0);
+        }
+        self.mark_unsaved();
+        let view_ids = self.current_view_ids.clone();
+        self.alignment = self.build_alignment_for_ids_with_sequences(&view_ids, &trimmed);
+        self.update_current_view_alignment_override(Some(trimmed));
+        self.diff_state = None;
+        self.annotation_search = None;
+        self.seq_search_state = None;
+        self.refresh_saved_searches();
+        self.recompute_ordering();
+        Ok(removed)
+    }
+
+    /// Inserts `count` all-gap columns before column `at` (clamped to the alignment's end), in
+    /// every sequence of the current view; `undo_edit()` removes them.
+    pub fn insert_gap_columns(&mut self, at: usize, count: usize) -> Result<(), TermalError> {
+        self.ensure_writable()?;
+        let at = at.min(self.aln_len_usize());
+        if count == 0 {
+            return Ok(());
+        }
+        for _ in 0..count {
+            self.alignment.insert_gap_column(at);
+        }
+        for (_, text) in &mut self.column_annotations {
+            if text.is_ascii() && text.len() + count == self.alignment.aln_len() {
+                text.insert_str(at, &".".repeat(count));
+            }
+        }
+        self.push_edit(
+            AlignmentEdit::Inserted { at, count },
+            Vec::new(),
+            Vec::new(),
+        );
+        self.alignment_edited();
+        Ok(())
+    }
+
+    /// Removes up to `count` columns from column `at` on, in every sequence of the current view
+    /// (at least one column is always left); `undo_edit()` puts them back. Returns the
+    /// number of removed columns.
+    pub fn delete_columns(&mut self, at: usize, count: usize) -> Result<usize, TermalError> {
+        self.ensure_writable()?;
+        let aln_len = self.aln_len_usize();
+        let count = count
+            .min(aln_len.saturating_sub(at))
+            .min(aln_len.saturating_sub(1));
+        if count == 0 {
+            return Ok(0);
+        }
+        let removed_columns: Vec<String> = (0..count)
+            .filter_map(|_| self.alignment.delete_column(at))
+            .collect();
+        let removed_annotations = self
+            .column_annotations
+            .iter_mut()
+            .map(|(_, text)| {
+                if text.is_ascii() && text.len() == aln_len {
+                    text.drain(at..at + count).collect()
+                } else {
+                    String::new()
+                }
+            })
+            .collect();
+        self.push_edit(
+            AlignmentEdit::Deleted { at, count },
+            removed_columns,
+            removed_annotations,
+        );
+        self.alignment_edited();
+        Ok(count)
+    }
+
+    /// Moves the residue at column `col` of the sequence at `rank` by up to `count` cells in
+    /// `direction`, through gaps only; `undo_edit()` moves it back. Returns its new column, or
+    /// None if it could not move at all.
+    pub fn shift_residue(
+        &mut self,
+        rank: usize,
+        col: usize,
+        direction: ShiftDirection,
+        count: usize,
+    ) -> Result<Option<usize>, TermalError> {
+        self.ensure_writable()?;
+        let mut to = col;
+        for _ in 0..count {
+            match self.alignment.shift_residue(rank, to, direction) {
+                Some(next) => to = next,
+                None => break,
+            }
+        }
+        if to == col {
+            return Ok(None);
+        }
+        self.push_edit(
+            AlignmentEdit::Shifted {
+                rank,
+                from: col,
+                to,
+            },
+            Vec::new(),
+            Vec::new(),
+        );
+        self.alignment_edited();
+        Ok(Some(to))
+    }
+
+    /// Undoes the last column insertion or deletion or residue shift, which is returned. Fails if
+    /// there is none, or if the alignment has changed in other ways since (e.g. another view,
+    /// removed sequences).
+    pub fn undo_edit(&mut self) -> Result<AlignmentEdit, TermalError> {
+        self.ensure_writable()?;
+        let Some(record) = self.edits.pop() else {
+            return Err(TermalError::Format(String::from("No edit to undo")));
+        };
+        if record.view != self.current_view
+            || record.num_seq != self.alignment.num_seq()
+            || record.aln_len != self.aln_len_usize()
+        {
+            self.edits.clear();
+            return Err(TermalError::Format(String::from(
+                "The alignment has changed since the last edit",
+            )));
+        }
+        match record.edit {
+            AlignmentEdit::Inserted { at, count } => {
+                for _ in 0..count {
+                    self.alignment.delete_column(at);
+                }
+                let aln_len = self.alignment.aln_len();
+                for (_, text) in &mut self.column_annotations {
+                    if text.is_ascii() && text.len() == aln_len + count {
+                        text.drain(at..at + count);
+                    }
+                }
+            }
+            AlignmentEdit::Shifted { rank, from, to } => {
+                let direction = if from < to {
+                    ShiftDirection::Left
+                } else {
+                    ShiftDirection::Right
+                };
+                let mut col = to;
+                while col != from {
+                    match self.alignment.shift_residue(rank, col, direction) {
+                        Some(next) => col = next,
+                        None => break,
+                    }
+                }
+            }
+            AlignmentEdit::Deleted { at, .. } => {
+                for column in record.removed_columns.iter().rev() {
+                    self.alignment.insert_column(at, column);
+                }
+                for ((_, text), removed) in self
+                    .column_annotations
+                    .iter_mut()
+                    .zip(&record.removed_annotations)
+                {
+                    if !removed.is_empty() {
+                        text.insert_str(at, removed);
+                    }
+                }
+            }
         }
+        self.alignment_edited();
+        Ok(record.edit)
+    }
+
+    fn push_edit(
+        &mut self,
+        edit: AlignmentEdit,
+        removed_columns: Vec<String>,
+        removed_annotations: Vec<String>,
+    ) {
+        self.edits.push(EditRecord {
+            edit,
+            removed_columns,
+            removed_annotations,
+            view: self.current_view.clone(),
+            num_seq: self.alignment.num_seq(),
+            aln_len: self.aln_len_usize(),
+        });
+    }
+
+    // Brings the view and the column-based state (searches, disagreement overlay) up to date
+    // after columns were inserted or removed.
+    fn alignment_edited(&mut self) {
+        self.mark_unsaved();
+        self.update_current_view_alignment_override(Some(self.alignment.sequences().to_vec()));
+        if let Some(DiffState {
+            rank_a,
+            rank_b,
+            current,
+            ..
+        }) = self.diff_state
+        {
+            if self.set_diff(rank_a, rank_b).is_ok() {
+                if let Some(state) = &mut self.diff_state {
+                    state.current = current.min(state.columns.len().saturating_sub(1));
+                }
+            }
+        }
+        self.annotation_search = None;
+        self.recompute_current_seq_search();
+        self.refresh_saved_searches();
+        self.recompute_ordering();
+    }
+
+    pub fn next_metric(&mut self) {
+        self.metric = match self.metric {
+            PctIdWrtConsensus => SeqLen,
+            SeqLen => PctIdWrtConsensus,
+        };
+        self.recompute_ordering();
+    }
+
+    // NOTE: for now, there are only two metrics, so next and prev are the same. This might change,
+    // however.
+    pub fn prev_metric(&mut self) {
+        self.metric = match self.metric {
+            PctIdWrtConsensus => SeqLen,
+            SeqLen => PctIdWrtConsensus,
+        };
+        self.recompute_ordering();
+    }
+
+    pub fn output_info(&self, format: InfoFormat) {
+        match format {
+            InfoFormat::Text => {
+                println!("name: {}", self.filename);
+                println!("nb_sequences: {}", self.alignment.num_seq());
+                println!("nb_columns: {}", self.aln_len_usize());
+                println!();
+            }
+            InfoFormat::Json => println!("{}", self.info_json()),
+        }
+    }
+
+    /// Summary of the alignment for `-i --info-format json`, one object per file.
+    pub fn info_json(&self) -> Value {
+        let aln = &self.alignment;
+        let lengths: Vec<usize> = (0..aln.num_seq())
+            .map(|rank| aln.ungapped_length(rank))
+            .collect();
+        let mean = if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        };
+        serde_json::json!({
+            "name": self.filename,
+            "nb_sequences": aln.num_seq(),
+            "nb_columns": self.aln_len_usize(),
+            "macromolecule_type": self.macromolecule_type().name(),
+            "gap_fraction": aln.gap_fraction(),
+            "min_ungapped_length": lengths.iter().min().copied().unwrap_or(0),
+            "max_ungapped_length": lengths.iter().max().copied().unwrap_or(0),
+            "mean_ungapped_length": mean,
+            "conserved_columns": aln.conserved_columns().len(),
+        })
+    }
+
+    pub fn get_seq_ordering(&self) -> SeqOrdering {
+        self.ordering_criterion
+    }
+
+    // User ordering needs one to have been supplied; otherwise the ordering is left as is.
+    pub fn set_seq_ordering(&mut self, ordering: SeqOrdering) {
+        if ordering == User && self.user_ordering.is_none() {
+            return;
+        }
+        self.ordering_criterion = ordering;
+        self.recompute_ordering();
+        self.warn_similarity_limit();
+    }
+
+    /// Flips the current ordering top to bottom, or back, without changing its criterion; the flip
+    /// is kept when the ordering is recomputed or its criterion changes. Returns whether the
+    /// ordering is now reversed.
+    pub fn reverse_current_ordering(&mut self) -> bool {
+        self.ordering_reversed = !self.ordering_reversed;
+        self.recompute_ordering();
+        self.ordering_reversed
+    }
+
+    pub fn ordering_reversed(&self) -> bool {
+        self.ordering_reversed
+    }
+
+    pub fn ordering_status_label(&self) -> String {
+        let label = self.ordering_criterion_label();
+        if self.ordering_reversed {
+            format!("{}(rev)", label)
+        } else {
+            label
+        }
+    }
+
+    fn ordering_criterion_label(&self) -> String {
+        match self.ordering_criterion {
+            SourceFile => String::from("o:original"),
+            SearchMatch => String::from("o:match"),
+            User => String::from("o:tree"),
+            Similarity => String::from("o:simil"),
+            MetricIncr => format!("o:{}↑", self.metric.short_label()),
+            MetricDecr => format!("o:{}↓", self.metric.short_label()),
+        }
+    }
+
+    pub fn get_metric(&self) -> Metric {
+        self.metric
+    }
+
+    // TODO: rename to order_by_metric
+    pub fn order_values(&self) -> &[f64] {
+        match self.metric {
+            PctIdWrtConsensus => self.alignment.id_wrt_consensus(),
+            SeqLen => self.alignment.relative_seq_len(),
+        }
+    }
+
+    // Duplicate headers
+
+    /// Warning listing (up to three of) the headers that occur more than once, if any.
+    pub fn duplicate_headers_warning(&self) -> Option<String> {
+        let headers: Vec<String> = self.records.iter().map(|r| r.header.clone()).collect();
+        let dups = duplicate_headers(&headers);
+        if dups.is_empty() {
+            return None;
+        }
+        let mut listed = dups.iter().take(3).cloned().collect::<Vec<_>>().join(", ");
+        if dups.len() > 3 {
+            listed.push_str(&format!(" (+{} more)", dups.len() - 3));
+        }
+        Some(format!("Duplicate headers: {} - use :dedup", listed))
+    }
+
+    /// Makes headers unique by appending `#2`, `#3`, ... to the second and later occurrences of
+    /// each repeated header. Returns the number of headers renamed.
+    pub fn deduplicate_headers(&mut self) -> Result<usize, TermalError> {
+        self.ensure_writable()?;
+        let mut taken: HashSet<String> = self.records.iter().map(|r| r.header.clone()).collect();
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut renamed = 0;
+        for record in self.records.iter_mut() {
+            let count = seen.entry(record.header.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                continue;
+            }
+            let mut suffix = *count;
+            let mut candidate = format!("{}#{}", record.header, suffix);
+            while taken.contains(&candidate) {
+                suffix += 1;
+                candidate = format!("{}#{}", record.header, suffix);
+            }
+            taken.insert(candidate.clone());
+            record.header = candidate;
+            renamed += 1;
+        }
+        if renamed > 0 {
+            self.mark_unsaved();
+            for (rank, id) in self.current_view_ids.iter().enumerate() {
+                if let Some(record) = self.records.get(*id) {
+                    self.alignment.set_header(rank, record.header.clone());
+                }
+            }
+            self.refresh_saved_searches();
+            self.recompute_current_seq_search();
+            self.recompute_ordering();
+        }
+        Ok(renamed)
     }
 
     // Label search
 
     pub fn regex_search_labels(&mut self, pattern: &str) {
         // self.debug_msg("Regex search");
-        match compute_label_search_state(&self.alignment.headers, pattern) {
+        match compute_label_search_state(self.alignment.headers(), pattern) {
             Ok(state) => {
                 self.set_selection_from_ranks(&state.match_linenums);
                 self.search_state = Some(state);
@@ -1491,8 +2620,18 @@ impl App {
         };
     }
 
+    /// Rank of the first header that `pattern` matches (as in regex_search_labels()), leaving the
+    /// current search alone; None if there is none, or if `pattern` is not a valid regex.
+    pub fn first_label_match_rank(&self, pattern: &str) -> Option<usize> {
+        compute_label_search_state(self.alignment.headers(), pattern)
+            .ok()?
+            .match_linenums
+            .first()
+            .copied()
+    }
+
     pub fn select_label_by_rank(&mut self, rank: usize) -> Result<(), TermalError> {
-        if rank >= self.alignment.headers.len() {
+        if rank >= self.alignment.headers().len() {
             return Err(TermalError::Format(String::from(
                 "Sequence number out of range",
             )));
@@ -1538,8 +2677,9 @@ impl App {
                 let nb_matches = state.match_linenums.len();
                 if nb_matches > 0 {
                     // (i+n).rem(l)
-                    let new =
-                        (state.current as isize + count).rem_euclid(nb_matches as isize) as usize;
+                    let target = state.current as isize + count;
+                    let new = target.rem_euclid(nb_matches as isize) as usize;
+                    let wrapped = new as isize != target;
                     //let new = (state.current + count) % nb_matches.;
                     self.search_state.as_mut().unwrap().current = new;
                     if let Some(rank) = self.current_label_match_rank() {
@@ -1548,9 +2688,10 @@ impl App {
                         }
                     }
                     self.info_msg(format!(
-                        "match #{}/{}",
+                        "match #{}/{}{}",
                         self.search_state.as_ref().unwrap().current + 1, // +1 <- user is 1-based
-                        self.search_state.as_ref().unwrap().match_linenums.len()
+                        self.search_state.as_ref().unwrap().match_linenums.len(),
+                        if wrapped { " (wrapped)" } else { "" }
                     ));
                 } else {
                     self.info_msg("No match.");
@@ -1562,6 +2703,28 @@ impl App {
         }
     }
 
+    /// Makes the `n`-th header match (1-based, clamped to the number of matches) current and moves
+    /// the cursor to it.
+    pub fn goto_label_match(&mut self, n: usize) {
+        let Some(state) = self.search_state.as_mut() else {
+            self.info_msg("No current search.");
+            return;
+        };
+        let nb_matches = state.match_linenums.len();
+        if nb_matches == 0 {
+            self.info_msg("No match.");
+            return;
+        }
+        state.current = n.clamp(1, nb_matches) - 1;
+        let current = state.current;
+        if let Some(rank) = self.current_label_match_rank() {
+            if let Some(id) = self.current_view_ids.get(rank).copied() {
+                self.cursor_id = Some(id);
+            }
+        }
+        self.info_msg(format!("match #{}/{}", current + 1, nb_matches));
+    }
+
     pub fn current_label_match_rank(&self) -> Option<usize> {
         self.search_state
             .as_ref()
@@ -1577,6 +2740,23 @@ impl App {
             .position(|seq_id| *seq_id == id)
     }
 
+    /// Header, length, identity to the consensus, gaps, and first and last residue columns of the
+    /// sequence at `rank`.
+    pub fn seq_details(&self, rank: usize) -> Option<SeqDetails> {
+        let header = self.alignment.headers().get(rank)?.clone();
+        let ungapped_len = self.alignment.ungapped_length(rank);
+        Some(SeqDetails {
+            header,
+            ungapped_len,
+            id_wrt_consensus: self.alignment.id_wrt_consensus()[rank],
+            num_gaps: self.aln_len_usize() - ungapped_len,
+            residue_span: self
+                .alignment
+                .residue_span(rank)
+                .map(|(first, last)| (self.display_col(first), self.display_col(last))),
+        })
+    }
+
     pub fn is_cursor_rank(&self, rank: usize) -> bool {
         self.cursor_rank().map(|cur| cur == rank).unwrap_or(false)
     }
@@ -1659,6 +2839,18 @@ impl App {
         }
     }
 
+    /// Puts the cursor on sequence `rank`; returns false if there is no such sequence.
+    pub fn set_cursor_rank(&mut self, rank: usize) -> bool {
+        let Some(id) = self.current_view_ids.get(rank).copied() else {
+            return false;
+        };
+        self.cursor_id = Some(id);
+        if let Some(view) = self.views.get_mut(&self.current_view) {
+            view.cursor_id = self.cursor_id;
+        }
+        true
+    }
+
     pub fn clear_cursor(&mut self) {
         self.cursor_id = None;
         if let Some(view) = self.views.get_mut(&self.current_view) {
@@ -1757,7 +2949,7 @@ impl App {
         let state = build_label_state_from_matches(
             String::from("<tree>"),
             matches,
-            self.alignment.headers.len(),
+            self.alignment.headers().len(),
         );
         self.tree_selection_range = Some(tree_range);
         self.selected_ids.clear();
@@ -1778,11 +2970,27 @@ impl App {
     }
 
     pub fn regex_search_sequences(&mut self, pattern: &str) {
+        self.run_regex_search(pattern, None);
+    }
+
+    /// Like `regex_search_sequences()`, but only looks for matches that lie within `scope`'s
+    /// sequences and columns.
+    pub fn regex_search_sequences_in(&mut self, pattern: &str, scope: SearchScope) {
+        self.run_regex_search(pattern, Some(scope));
+    }
+
+    fn run_regex_search(&mut self, pattern: &str, scope: Option<SearchScope>) {
         if pattern.is_empty() {
             self.clear_seq_search();
             return;
         }
-        match compute_seq_search_state(&self.alignment.sequences, pattern, SearchKind::Regex) {
+        match compute_seq_search_state(
+            self.alignment.sequences(),
+            pattern,
+            SearchKind::Regex,
+            self.highlight_capture_group,
+            scope.as_ref(),
+        ) {
             Ok(state) => {
                 self.seq_search_state = Some(state);
                 if matches!(self.ordering_criterion, SearchMatch) {
@@ -1818,6 +3026,13 @@ impl App {
             .map(|state| (state.total_matches, state.sequences_with_matches))
     }
 
+    // True if the current sequence search was restricted to part of the alignment.
+    pub fn seq_search_is_scoped(&self) -> bool {
+        self.seq_search_state
+            .as_ref()
+            .is_some_and(|state| state.scope.is_some())
+    }
+
     pub fn has_seq_search(&self) -> bool {
         self.seq_search_state
             .as_ref()
@@ -1831,19 +3046,190 @@ impl App {
             .and_then(|state| state.matches.get(state.current_match).copied())
     }
 
-    pub fn increment_current_seq_match(&mut self, count: isize) -> Option<(usize, usize)> {
+    /// Moves the current sequence match by `count`, cycling through the matches; returns the
+    /// new (1-based) index, the number of matches, and whether the move wrapped around.
+    pub fn increment_current_seq_match(&mut self, count: isize) -> Option<(usize, usize, bool)> {
         if let Some(state) = &mut self.seq_search_state {
             if state.matches.is_empty() {
                 return None;
             }
             let len = state.matches.len() as isize;
-            let new = (state.current_match as isize + count).rem_euclid(len) as usize;
+            let target = state.current_match as isize + count;
+            let new = target.rem_euclid(len) as usize;
             state.current_match = new;
-            return Some((state.current_match + 1, state.matches.len()));
+            return Some((
+                state.current_match + 1,
+                state.matches.len(),
+                new as isize != target,
+            ));
         }
         None
     }
 
+    /// Makes the `n`-th sequence match (1-based, clamped to the number of matches) current; returns
+    /// its (1-based) index and the number of matches.
+    pub fn goto_seq_match(&mut self, n: usize) -> Option<(usize, usize)> {
+        let state = self.seq_search_state.as_mut()?;
+        if state.matches.is_empty() {
+            return None;
+        }
+        state.current_match = n.clamp(1, state.matches.len()) - 1;
+        Some((state.current_match + 1, state.matches.len()))
+    }
+
+    // Disagreement overlay
+
+    /// Highlights the columns where the sequences at `rank_a` and `rank_b` (0-based) disagree and
+    /// makes the first one current. Returns the number of such columns.
+    pub fn set_diff(&mut self, rank_a: usize, rank_b: usize) -> Result<usize, TermalError> {
+        let num_seq = self.alignment.num_seq();
+        if rank_a >= num_seq || rank_b >= num_seq {
+            return Err(TermalError::Format(String::from(
+                "Sequence number out of range",
+            )));
+        }
+        let columns = self.alignment.disagreement_columns(rank_a, rank_b);
+        let mut spans_by_seq: SpansBySeq = vec![Vec::new(); num_seq];
+        let spans: Vec<(usize, usize)> = columns.iter().map(|col| (*col, col + 1)).collect();
+        spans_by_seq[rank_a] = spans.clone();
+        spans_by_seq[rank_b] = spans;
+        let count = columns.len();
+        self.annotation_search = None;
+        self.diff_state = Some(DiffState {
+            rank_a,
+            rank_b,
+            columns,
+            current: 0,
+            spans_by_seq,
+        });
+        Ok(count)
+    }
+
+    pub fn clear_diff(&mut self) -> bool {
+        self.diff_state.take().is_some()
+    }
+
+    pub fn diff_state(&self) -> Option<&DiffState> {
+        self.diff_state.as_ref()
+    }
+
+    pub fn diff_spans(&self) -> Option<&SpansBySeq> {
+        self.diff_state
+            .as_ref()
+            .map(|state| &state.spans_by_seq)
+            .filter(|spans| spans.len() == self.alignment.num_seq())
+    }
+
+    /// Moves the current disagreement column by `count` (wrapping); returns the column, its
+    /// (1-based) index, the number of columns, and whether it wrapped.
+    pub fn increment_current_diff(&mut self, count: isize) -> Option<(usize, usize, usize, bool)> {
+        let state = self.diff_state.as_mut()?;
+        if state.columns.is_empty() {
+            return None;
+        }
+        let len = state.columns.len() as isize;
+        let target = state.current as isize + count;
+        state.current = target.rem_euclid(len) as usize;
+        Some((
+            state.columns[state.current],
+            state.current + 1,
+            state.columns.len(),
+            state.current as isize != target,
+        ))
+    }
+
+    // Column annotations
+
+    pub fn set_column_annotations(&mut self, annotations: Vec<(String, String)>) {
+        self.column_annotations = annotations;
+        self.annotation_search = None;
+    }
+
+    pub fn column_annotation(&self, feature: &str) -> Option<&str> {
+        self.column_annotations
+            .iter()
+            .find(|(name, _)| name == feature)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Searches the column annotation named `feature` for `pattern` (a regex, e.g. `E+` for strand
+    /// runs) and makes the first match current; `n`/`N` then cycle through the matches. Returns
+    /// the number of matches.
+    pub fn search_annotation(
+        &mut self,
+        feature: &str,
+        pattern: &str,
+    ) -> Result<usize, TermalError> {
+        let Some(text) = self.column_annotation(feature) else {
+            let known: Vec<&str> = self
+                .column_annotations
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            return Err(TermalError::Format(if known.is_empty() {
+                format!("No annotation named {} (the file has none)", feature)
+            } else {
+                format!(
+                    "No annotation named {} (have: {})",
+                    feature,
+                    known.join(", ")
+                )
+            }));
+        };
+        if text.chars().count() != self.aln_len_usize() {
+            return Err(TermalError::Format(format!(
+                "Annotation {} does not span the current alignment",
+                feature
+            )));
+        }
+        let re = Regex::new(pattern).map_err(|e| TermalError::Format(e.to_string()))?;
+        // Annotations are ASCII, but map byte offsets to columns all the same.
+        let columns: Vec<usize> = text.char_indices().map(|(offset, _)| offset).collect();
+        let to_col = |offset: usize| columns.partition_point(|&o| o < offset);
+        let ranges: Vec<(usize, usize)> = re
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| (to_col(m.start()), to_col(m.end())))
+            .collect();
+        let count = ranges.len();
+        self.diff_state = None;
+        self.annotation_search = Some(AnnotationSearchState {
+            feature: String::from(feature),
+            ranges,
+            current: 0,
+        });
+        Ok(count)
+    }
+
+    pub fn clear_annotation_search(&mut self) -> bool {
+        self.annotation_search.take().is_some()
+    }
+
+    pub fn annotation_search(&self) -> Option<&AnnotationSearchState> {
+        self.annotation_search.as_ref()
+    }
+
+    /// Moves the current annotation match by `count` (wrapping); returns its column range, its
+    /// (1-based) index, the number of matches, and whether it wrapped.
+    pub fn increment_current_annotation_match(
+        &mut self,
+        count: isize,
+    ) -> Option<((usize, usize), usize, usize, bool)> {
+        let state = self.annotation_search.as_mut()?;
+        if state.ranges.is_empty() {
+            return None;
+        }
+        let len = state.ranges.len() as isize;
+        let target = state.current as isize + count;
+        state.current = target.rem_euclid(len) as usize;
+        Some((
+            state.ranges[state.current],
+            state.current + 1,
+            state.ranges.len(),
+            state.current as isize != target,
+        ))
+    }
+
     pub fn clear_seq_search(&mut self) {
         self.seq_search_state = None;
         if matches!(self.ordering_criterion, SearchMatch) {
@@ -1851,6 +3237,28 @@ impl App {
         }
     }
 
+    /// Drops the current label, sequence and annotation searches (and their highlights), leaving
+    /// saved searches alone. Search-match ordering reverts to file order. Returns false if there
+    /// was no search to clear.
+    pub fn clear_search(&mut self) -> bool {
+        let had_search = self.search_state.is_some()
+            || self.seq_search_state.is_some()
+            || self.annotation_search.is_some();
+        self.reset_lbl_search();
+        self.seq_search_state = None;
+        self.annotation_search = None;
+        if matches!(self.ordering_criterion, SearchMatch) {
+            self.ordering_criterion = SourceFile;
+            self.recompute_ordering();
+        }
+        had_search
+    }
+
+    // The color the current search will get if it is saved.
+    pub fn next_search_color(&self) -> SearchColor {
+        self.search_registry.next_color()
+    }
+
     pub fn search_color_config(&self) -> &SearchColorConfig {
         &self.search_color_config
     }
@@ -1874,21 +3282,28 @@ impl App {
             return Err(String::from("Empty search query"));
         }
         let state = match kind {
-            SearchKind::Regex => compute_seq_search_state(&self.alignment.sequences, &query, kind)
-                .map_err(|e| format!("Malformed regex {}.", e))?,
+            SearchKind::Regex => compute_seq_search_state(
+                self.alignment.sequences(),
+                &query,
+                kind,
+                self.highlight_capture_group,
+                None,
+            )
+            .map_err(|e| format!("Malformed regex {}.", e))?,
             SearchKind::Emboss if self.emboss_bin_dir.is_none() => {
                 return Err(String::from(
                     "Emboss search unavailable. Create .msafara.config in $HOME or current directory with emboss_bin_dir.",
                 ));
             }
-            SearchKind::Emboss => compute_emboss_search_state(
-                &self.alignment.headers,
-                &self.alignment.sequences,
-                &query,
-                self.emboss_bin_dir.as_deref(),
-            )
-            .map_err(|e| format!("Emboss search failed: {}", e))?,
+            SearchKind::Emboss => {
+                compute_emboss_search_state(&self.alignment, &query, self.emboss_bin_dir.as_deref())
+                    .map_err(|e| format!("Emboss search failed: {}", e))?
+            }
+            SearchKind::Gff => {
+                return Err(String::from("Use :gff load to add GFF features"));
+            }
         };
+        self.mark_unsaved();
         self.search_registry
             .add_search(name, query, kind, state.spans_by_seq);
         if let Some(entry) = self.search_registry.searches.last() {
@@ -1904,6 +3319,7 @@ impl App {
     pub fn delete_saved_search(&mut self, index: usize) -> bool {
         let removed = self.search_registry.delete(index);
         if removed {
+            self.mark_unsaved();
             self.active_search_ids = self
                 .search_registry
                 .searches
@@ -1921,6 +3337,7 @@ impl App {
     pub fn toggle_saved_search(&mut self, index: usize) -> bool {
         let toggled = self.search_registry.toggle(index);
         if toggled {
+            self.mark_unsaved();
             self.active_search_ids = self
                 .search_registry
                 .searches
@@ -1947,6 +3364,39 @@ impl App {
         self.mafft_bin_dir = dir;
     }
 
+    pub fn set_aligner_command(&mut self, command: String) {
+        self.aligner_command = command;
+    }
+
+    fn realign_paths() -> (PathBuf, PathBuf) {
+        let mut input_path = std::env::temp_dir();
+        input_path.push(format!("msafara-align-{}.fa", std::process::id()));
+        let mut output_path = std::env::temp_dir();
+        output_path.push(format!("msafara-align-{}.out.fa", std::process::id()));
+        (input_path, output_path)
+    }
+
+    fn realign_command(&self) -> Result<AlignerCommand, TermalError> {
+        let (input_path, output_path) = Self::realign_paths();
+        let command = AlignerCommand::from_template(
+            &self.aligner_command,
+            &input_path,
+            &output_path,
+            self.mafft_bin_dir.as_deref(),
+        )?;
+        if command.is_mafft() && self.mafft_bin_dir.is_none() {
+            return Err(TermalError::Format(String::from(
+                "mafft not configured. Create .msafara.config in $HOME or current directory with mafft_bin_dir.",
+            )));
+        }
+        Ok(command)
+    }
+
+    /// The command line `realign` would run.
+    pub fn realign_command_line(&self) -> Result<String, TermalError> {
+        Ok(self.realign_command()?.command_line())
+    }
+
     pub fn emboss_search_sequences(&mut self, pattern: &str) {
         if pattern.is_empty() {
             self.clear_seq_search();
@@ -1959,12 +3409,8 @@ impl App {
             self.clear_seq_search();
             return;
         }
-        match compute_emboss_search_state(
-            &self.alignment.headers,
-            &self.alignment.sequences,
-            pattern,
-            self.emboss_bin_dir.as_deref(),
-        ) {
+        match compute_emboss_search_state(&self.alignment, pattern, self.emboss_bin_dir.as_deref())
+        {
             Ok(state) => {
                 self.seq_search_state = Some(state);
                 if matches!(self.ordering_criterion, SearchMatch) {
@@ -1978,30 +3424,40 @@ impl App {
         }
     }
 
-    pub fn remove_sequence(&mut self, rank: usize) -> Option<(String, String)> {
-        let mut removed = self.remove_sequences(&[rank]);
-        removed.pop()
+    pub fn remove_sequence(
+        &mut self,
+        rank: usize,
+    ) -> Result<Option<(String, String)>, TermalError> {
+        let mut removed = self.remove_sequences(&[rank])?;
+        Ok(removed.pop())
     }
 
-    pub fn remove_sequences(&mut self, ranks: &[usize]) -> Vec<(String, String)> {
-        self.remove_sequences_with_ranks(ranks)
+    pub fn remove_sequences(
+        &mut self,
+        ranks: &[usize],
+    ) -> Result<Vec<(String, String)>, TermalError> {
+        self.ensure_writable()?;
+        Ok(self
+            .remove_sequences_with_ranks(ranks)
             .into_iter()
             .map(|removed| (removed.header, removed.sequence))
-            .collect()
+            .collect())
     }
 
     fn remove_sequences_with_ranks(&mut self, ranks: &[usize]) -> Vec<RemovedSeq> {
         if ranks.is_empty() {
             return Vec::new();
         }
+        self.mark_unsaved();
         let mut header_set: HashSet<String> = HashSet::new();
         let current_label_header = self
             .current_label_match_rank()
-            .and_then(|rank| self.alignment.headers.get(rank))
+            .and_then(|rank| self.alignment.headers().get(rank))
             .cloned();
         let current_seq_match = self.seq_search_state.as_ref().and_then(|state| {
             let match_entry = state.matches.get(state.current_match).copied();
-            let header = match_entry.and_then(|m| self.alignment.headers.get(m.seq_index).cloned());
+            let header =
+                match_entry.and_then(|m| self.alignment.headers().get(m.seq_index).cloned());
             let span = match_entry.map(|m| (m.start, m.end));
             header.zip(span)
         });
@@ -2019,7 +3475,7 @@ impl App {
                 state
                     .match_linenums
                     .iter()
-                    .filter_map(|idx| self.alignment.headers.get(*idx).cloned())
+                    .filter_map(|idx| self.alignment.headers().get(*idx).cloned())
                     .collect::<Vec<String>>()
             })
         } else {
@@ -2046,7 +3502,7 @@ impl App {
                 });
             }
         }
-        header_set.extend(self.alignment.headers.iter().cloned());
+        header_set.extend(self.alignment.headers().iter().cloned());
         if let Some(ordering) = &mut self.user_ordering {
             ordering.retain(|hdr| header_set.contains(hdr));
         }
@@ -2073,7 +3529,7 @@ impl App {
             }
         }
         if self.current_view_alignment_override.is_some() {
-            self.update_current_view_alignment_override(Some(self.alignment.sequences.clone()));
+            self.update_current_view_alignment_override(Some(self.alignment.sequences().to_vec()));
         }
         removed
     }
@@ -2092,20 +3548,20 @@ impl App {
         match label_search_source {
             Some(LabelSearchSource::Tree) => {
                 if let Some(headers) = label_search_headers {
-                    let matches = map_headers_to_indices(&self.alignment.headers, &headers);
+                    let matches = map_headers_to_indices(self.alignment.headers(), &headers);
                     if matches.is_empty() {
                         self.search_state = None;
                     } else {
                         let mut state = build_label_state_from_matches(
                             String::from("<tree>"),
                             matches,
-                            self.alignment.headers.len(),
+                            self.alignment.headers().len(),
                         );
                         if let Some(target) = current_label_header {
                             if let Some(pos) = state
                                 .match_linenums
                                 .iter()
-                                .position(|&idx| self.alignment.headers.get(idx) == Some(&target))
+                                .position(|&idx| self.alignment.headers().get(idx) == Some(&target))
                             {
                                 state.current = pos;
                             }
@@ -2119,11 +3575,11 @@ impl App {
             }
             Some(LabelSearchSource::Regex) | None => {
                 if let Some(pattern) = label_search_pattern {
-                    match compute_label_search_state(&self.alignment.headers, &pattern) {
+                    match compute_label_search_state(self.alignment.headers(), &pattern) {
                         Ok(mut state) => {
                             if let Some(target) = current_label_header {
                                 if let Some(pos) = state.match_linenums.iter().position(|&idx| {
-                                    self.alignment.headers.get(idx) == Some(&target)
+                                    self.alignment.headers().get(idx) == Some(&target)
                                 }) {
                                     state.current = pos;
                                 }
@@ -2145,22 +3601,28 @@ impl App {
 
         if let (Some(kind), Some(pattern)) = (seq_search_kind, seq_search_pattern) {
             let state = match kind {
-                SearchKind::Regex => {
-                    compute_seq_search_state(&self.alignment.sequences, &pattern, kind)
-                        .map_err(|e| TermalError::Format(format!("Malformed regex {}.", e)))
-                }
+                SearchKind::Regex => compute_seq_search_state(
+                    self.alignment.sequences(),
+                    &pattern,
+                    kind,
+                    self.highlight_capture_group,
+                    None,
+                )
+                .map_err(|e| TermalError::Format(format!("Malformed regex {}.", e))),
                 SearchKind::Emboss => compute_emboss_search_state(
-                    &self.alignment.headers,
-                    &self.alignment.sequences,
+                    &self.alignment,
                     &pattern,
                     self.emboss_bin_dir.as_deref(),
                 ),
+                SearchKind::Gff => Err(TermalError::Format(String::from(
+                    "GFF overlays are not searches",
+                ))),
             };
             match state {
                 Ok(mut state) => {
                     if let Some((header, (start, end))) = current_seq_match {
                         if let Some(seq_index) =
-                            self.alignment.headers.iter().position(|h| h == &header)
+                            self.alignment.headers().iter().position(|h| h == &header)
                         {
                             if let Some(pos) = state.matches.iter().position(|m| {
                                 m.seq_index == seq_index && m.start == start && m.end == end
@@ -2187,6 +3649,7 @@ impl App {
         ranks: &[usize],
         path: &Path,
     ) -> Result<RejectResult, TermalError> {
+        self.ensure_writable()?;
         if ranks.is_empty() {
             return Ok(RejectResult {
                 count: 0,
@@ -2305,9 +3768,9 @@ impl App {
         let mut writer = BufWriter::new(file);
         for (header, seq) in self
             .alignment
-            .headers
+            .headers()
             .iter()
-            .zip(self.alignment.sequences.iter())
+            .zip(self.alignment.sequences().iter())
         {
             writeln!(writer, ">{}", header)?;
             writeln!(writer, "{}", seq)?;
@@ -2315,6 +3778,135 @@ impl App {
         Ok(())
     }
 
+    /// Writes the current consensus (as set by the threshold) as a single FASTA record named
+    /// `name`, on one line. Gap columns are left out, as are columns whose occupancy is below
+    /// `min_occupancy`, if given; unconserved columns ('*') are written as 'X'. Returns the length
+    /// of the sequence written.
+    pub fn write_consensus_fasta(
+        &self,
+        path: &Path,
+        name: &str,
+        min_occupancy: Option<f64>,
+    ) -> Result<usize, TermalError> {
+        let consensus: String = self
+            .consensus_string()
+            .chars()
+            .zip(self.alignment.densities().iter())
+            .filter(|(c, occupancy)| {
+                !is_gap(*c) && min_occupancy.map_or(true, |min| **occupancy >= min)
+            })
+            .map(|(c, _)| c)
+            .collect();
+        let file = fs::File::create(path)?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, ">{}", name)?;
+        writeln!(writer, "{}", consensus)?;
+        Ok(consensus.len())
+    }
+
+    // Record name for `:consensus write`: the alignment's file name without extension.
+    pub fn default_consensus_name(&self) -> String {
+        let stem = Path::new(&self.filename)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("");
+        if stem.is_empty() {
+            String::from("consensus")
+        } else {
+            format!("{}_consensus", stem)
+        }
+    }
+
+    /// Writes the current view's sequences in FASTA, with all gaps removed (headers and order
+    /// unchanged). Sequences that are only gaps are written empty; returns how many there were.
+    pub fn write_unaligned_fasta(&self, path: &Path) -> Result<usize, TermalError> {
+        let file = fs::File::create(path)?;
+        let mut writer = BufWriter::new(file);
+        let mut empty = 0;
+        for (header, seq) in self
+            .alignment
+            .headers()
+            .iter()
+            .zip(self.alignment.sequences().iter())
+        {
+            let residues: String = seq.chars().filter(|c| !is_gap(*c)).collect();
+            if residues.is_empty() {
+                empty += 1;
+            }
+            writeln!(writer, ">{}", header)?;
+            writeln!(writer, "{}", residues)?;
+        }
+        Ok(empty)
+    }
+
+    /// Writes the current view as a print-friendly (black and white) HTML figure, whatever the
+    /// color scheme: the consensus comes first, residues identical to it are shown as '.', and
+    /// conserved columns (upper case in the consensus) are in bold. Sequences are in display order
+    /// and the alignment is cut into blocks of PRINT_BLOCK_WIDTH columns.
+    pub fn write_print_html(&self, path: &Path) -> Result<(), TermalError> {
+        let consensus = self.alignment.consensus().as_bytes();
+        let mut rows: Vec<(&str, String)> =
+            vec![("Consensus", self.alignment.consensus().replace('*', " "))];
+        for &rank in &self.ordering {
+            let seq: String = self.alignment.sequences()[rank]
+                .bytes()
+                .zip(consensus)
+                .map(|(residue, cons)| {
+                    if !is_gap(residue as char) && residue.eq_ignore_ascii_case(cons) {
+                        '.'
+                    } else {
+                        residue as char
+                    }
+                })
+                .collect();
+            rows.push((&self.alignment.headers()[rank], seq));
+        }
+        let label_width = rows
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let file = fs::File::create(path)?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html>")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, "<meta charset=\"utf-8\">")?;
+        writeln!(writer, "<title>{}</title>", escape_html(&self.filename))?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<pre style=\"color: black; background: white;\">")?;
+        for start in (0..self.alignment.aln_len()).step_by(PRINT_BLOCK_WIDTH) {
+            let end = (start + PRINT_BLOCK_WIDTH).min(self.alignment.aln_len());
+            writeln!(
+                writer,
+                "{:width$} {}",
+                "",
+                self.display_col(start),
+                width = label_width
+            )?;
+            for (label, seq) in &rows {
+                let padding = label_width - label.chars().count();
+                write!(writer, "{}{} ", escape_html(label), " ".repeat(padding))?;
+                for (col, ch) in seq.chars().enumerate().take(end).skip(start) {
+                    let ch = escape_html(&ch.to_string());
+                    if consensus[col].is_ascii_uppercase() {
+                        write!(writer, "<b>{}</b>", ch)?;
+                    } else {
+                        write!(writer, "{}", ch)?;
+                    }
+                }
+                writeln!(writer)?;
+            }
+            writeln!(writer)?;
+        }
+        writeln!(writer, "</pre>")?;
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")?;
+        Ok(())
+    }
+
     pub fn append_sequence_fasta(
         &self,
         path: &Path,
@@ -2366,27 +3958,315 @@ impl App {
         self.tree_newick = Some(tree_newick);
         self.tree_lines = tree_lines;
         self.tree_panel_width = tree_panel_width;
+        if !self.collapsed_nodes.is_empty() {
+            self.clear_collapsed_clades();
+            self.recompute_ordering();
+        }
+        if self.tree_phylogram {
+            self.update_tree_lines_for_selection();
+        }
+        if let Some(view) = self.views.get_mut(&self.current_view) {
+            view.tree = self.tree.clone();
+            view.tree_newick = self.tree_newick.clone();
+            view.tree_lines = self.tree_lines.clone();
+            view.tree_panel_width = self.tree_panel_width;
+        }
+    }
+
+    pub fn is_clade_collapsed(&self, node_id: usize) -> bool {
+        self.collapsed_nodes.contains(&node_id)
+    }
+
+    /// Collapses the clade rooted at the tree node with the given id: the tree panel shows it as a
+    /// single tip, and only its first sequence stays visible in the alignment. Returns the number
+    /// of sequences hidden.
+    pub fn collapse_clade(&mut self, node_id: usize) -> Result<usize, TermalError> {
+        let Some(tree) = self.tree.as_ref() else {
+            return Err(TermalError::Format(String::from("No tree available")));
+        };
+        if clade_leaf_names(tree, node_id).len() < 2 {
+            return Err(TermalError::Format(String::from(
+                "Only internal nodes can be collapsed",
+            )));
+        }
+        let before = self.ordering.len();
+        self.collapsed_nodes.insert(node_id);
+        self.refresh_collapsed_clades()?;
+        Ok(before.saturating_sub(self.ordering.len()))
+    }
+
+    /// Expands a previously collapsed clade. Returns false if it was not collapsed.
+    pub fn expand_clade(&mut self, node_id: usize) -> Result<bool, TermalError> {
+        if !self.collapsed_nodes.remove(&node_id) {
+            return Ok(false);
+        }
+        self.refresh_collapsed_clades()?;
+        Ok(true)
+    }
+
+    fn refresh_collapsed_clades(&mut self) -> Result<(), TermalError> {
+        let mut clades: Vec<Vec<String>> = Vec::new();
+        if let Some(tree) = &self.tree {
+            let leaves = clade_leaf_names(tree, tree.id);
+            let headers = self.map_order_to_headers(leaves.clone())?;
+            let leaf2hdr: HashMap<String, String> = leaves.into_iter().zip(headers).collect();
+            for node_id in &self.collapsed_nodes {
+                let clade = clade_leaf_names(tree, *node_id)
+                    .iter()
+                    .filter_map(|leaf| leaf2hdr.get(leaf).cloned())
+                    .collect();
+                clades.push(clade);
+            }
+        }
+        // Outer clades first, so that nested collapsed clades map to the outermost
+        // representative.
+        clades.sort_by_key(|headers| std::cmp::Reverse(headers.len()));
+        self.collapsed_headers.clear();
+        for headers in clades {
+            let Some(rep) = headers.first() else {
+                continue;
+            };
+            for hdr in headers.iter().skip(1) {
+                self.collapsed_headers
+                    .entry(hdr.clone())
+                    .or_insert_with(|| rep.clone());
+            }
+        }
+        self.recompute_ordering();
+        self.update_tree_lines_for_selection();
+        Ok(())
+    }
+
+    fn clear_collapsed_clades(&mut self) {
+        self.collapsed_nodes.clear();
+        self.collapsed_headers.clear();
+    }
+
+    pub fn tree_phylogram(&self) -> bool {
+        self.tree_phylogram
+    }
+
+    /// Switches regex searches between highlighting the whole match and capture group 1, and
+    /// recomputes the current and saved searches; returns the new mode (true for group 1).
+    pub fn toggle_capture_group_highlight(&mut self) -> bool {
+        self.highlight_capture_group = !self.highlight_capture_group;
+        self.refresh_saved_searches();
+        self.recompute_current_seq_search();
+        self.highlight_capture_group
+    }
+
+    /// Switches the tree panel between cladogram and phylogram rendering; returns the new mode
+    /// (true for phylogram).
+    pub fn toggle_tree_phylogram(&mut self) -> bool {
+        self.tree_phylogram = !self.tree_phylogram;
+        self.update_tree_lines_for_selection();
+        if let Some(view) = self.views.get_mut(&self.current_view) {
+            view.tree_lines = self.tree_lines.clone();
+            view.tree_panel_width = self.tree_panel_width;
+        }
+        self.tree_phylogram
+    }
+
+    pub fn set_tree_ordering_from_tree(&mut self) -> Result<(), TermalError> {
+        let Some(tree) = self.tree.as_ref() else {
+            return Ok(());
+        };
+        let (_lines, order) = tree_lines_and_order(tree)?;
+        self.set_user_ordering(order)?;
+        Ok(())
+    }
+
+    /// Orders the sequences like the leaves of the current tree and switches to user ordering.
+    /// Sequences missing from the tree are placed after the tree-ordered ones. Returns the
+    /// differences between tree leaves and headers.
+    pub fn apply_tree_ordering(&mut self) -> Result<TreeMismatch, TermalError> {
+        let Some(tree) = self.tree.as_ref() else {
+            return Err(TermalError::Format(String::from("No tree available")));
+        };
+        let (_lines, order) = tree_lines_and_order(tree)?;
+        let (mapped, unmatched) = self.match_order_to_headers(order)?;
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut ordering: Vec<String> = mapped
+            .into_iter()
+            .filter(|hdr| seen.insert(hdr.clone()))
+            .collect();
+        let mut missing_headers = Vec::new();
+        for hdr in self.alignment.headers() {
+            if seen.insert(hdr.clone()) {
+                ordering.push(hdr.clone());
+                missing_headers.push(hdr.clone());
+            }
+        }
+        self.mark_unsaved();
+        self.user_ordering = Some(ordering);
+        self.ordering_criterion = User;
+        self.recompute_ordering();
+        Ok(TreeMismatch {
+            unmatched_leaves: unmatched,
+            missing_headers,
+        })
+    }
+
+    /// Tree leaves that match no header, and headers that match no leaf, with names matched as for
+    /// tree ordering (exactly, or after normalization, or by their first word).
+    pub fn tree_header_mismatches(&self) -> Result<TreeMismatch, TermalError> {
+        let Some(tree) = self.tree.as_ref() else {
+            return Err(TermalError::Format(String::from("No tree available")));
+        };
+        let (_lines, order) = tree_lines_and_order(tree)?;
+        let (mapped, unmatched) = self.match_order_to_headers(order)?;
+        let matched: HashSet<&String> = mapped.iter().collect();
+        let missing_headers = self
+            .alignment
+            .headers()
+            .iter()
+            .filter(|hdr| !matched.contains(hdr))
+            .cloned()
+            .collect();
+        Ok(TreeMismatch {
+            unmatched_leaves: unmatched,
+            missing_headers,
+        })
+    }
+
+    /// Reads a Newick tree from `path`, makes it the current view's tree and orders the sequences
+    /// by it. Leaves and headers that do not match are reported rather than treated as errors.
+    pub fn load_tree_file(&mut self, path: &Path) -> Result<TreeMismatch, TermalError> {
+        let newick = fs::read_to_string(path)?;
+        let tree = parse_newick(&newick)?;
+        let (lines, _order) = tree_lines_and_order(&tree)?;
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(u16::MAX as usize) as u16;
+        self.mark_unsaved();
+        self.tree_selection_range = None;
+        self.set_tree_for_current_view(tree, newick.trim().to_string(), lines, width);
+        self.apply_tree_ordering()
+    }
+
+    /// Loads GFF features from `path` as saved-search overlays, one per feature type (each gets
+    /// the next palette color). Returns the number of feature types added and the number of
+    /// records skipped because their seqid matches no sequence.
+    pub fn load_gff(&mut self, path: &Path) -> Result<(usize, usize), TermalError> {
+        let gff = fs::read_to_string(path)?;
+        let records_by_type = parse_gff_records(&gff);
+        let query = path.to_string_lossy().to_string();
+        let num_types = records_by_type.len();
+        let mut skipped = 0;
+        self.mark_unsaved();
+        for (feature_type, records) in records_by_type {
+            let (spans_by_seq, type_skipped) = gff_spans(&self.alignment, &records);
+            skipped += type_skipped;
+            self.search_registry.add_search(
+                feature_type,
+                query.clone(),
+                SearchKind::Gff,
+                spans_by_seq,
+            );
+            if let Some(entry) = self.search_registry.searches.last_mut() {
+                entry.gff_records = records;
+                self.active_search_ids.insert(entry.id);
+            }
+        }
+        self.sync_search_registry_enabled();
         if let Some(view) = self.views.get_mut(&self.current_view) {
-            view.tree = self.tree.clone();
-            view.tree_newick = self.tree_newick.clone();
-            view.tree_lines = self.tree_lines.clone();
-            view.tree_panel_width = self.tree_panel_width;
+            view.active_search_ids = self.active_search_ids.clone();
         }
+        Ok((num_types, skipped))
     }
 
-    pub fn set_tree_ordering_from_tree(&mut self) -> Result<(), TermalError> {
+    /// Writes the current tree to `path` in Newick format.
+    pub fn write_tree(&self, path: &Path) -> Result<(), TermalError> {
         let Some(tree) = self.tree.as_ref() else {
-            return Ok(());
+            return Err(TermalError::Format(String::from("No tree available")));
         };
-        let (_lines, order) = tree_lines_and_order(tree)?;
-        self.set_user_ordering(order)?;
+        fs::write(path, format!("{}\n", to_newick(tree)))?;
+        Ok(())
+    }
+
+    /// Writes per-sequence statistics (header, ungapped length, % identity to the consensus, gap
+    /// count) and global ones (sequences, columns, gap fraction, conserved columns) for the
+    /// current alignment.
+    pub fn export_stats(&self, path: &Path, format: StatsFormat) -> Result<(), TermalError> {
+        let aln = &self.alignment;
+        let aln_len = aln.aln_len();
+        let conserved = aln.conserved_columns().len();
+        let rows: Vec<(&str, usize, f64, usize)> = (0..aln.num_seq())
+            .map(|rank| {
+                let ungapped = aln.ungapped_length(rank);
+                let pct_id = aln.id_wrt_consensus().get(rank).copied().unwrap_or(0.0) * 100.0;
+                (
+                    aln.headers()[rank].as_str(),
+                    ungapped,
+                    pct_id,
+                    aln.sequences()[rank].len().saturating_sub(ungapped),
+                )
+            })
+            .collect();
+        let text = match format {
+            StatsFormat::Tsv => {
+                let mut text = format!(
+                    "# sequences\t{}\n# columns\t{}\n# gap_fraction\t{:.4}\n# conserved_columns\t{}\n",
+                    aln.num_seq(),
+                    aln_len,
+                    aln.gap_fraction(),
+                    conserved
+                );
+                text.push_str("header\tungapped_length\tpct_id_consensus\tgaps\n");
+                for (header, ungapped, pct_id, gaps) in &rows {
+                    text.push_str(&format!(
+                        "{}\t{}\t{:.2}\t{}\n",
+                        header, ungapped, pct_id, gaps
+                    ));
+                }
+                text
+            }
+            StatsFormat::Json => {
+                let per_sequence: Vec<Value> = rows
+                    .iter()
+                    .map(|(header, ungapped, pct_id, gaps)| {
+                        serde_json::json!({
+                            "header": header,
+                            "ungapped_length": ungapped,
+                            "pct_id_consensus": pct_id,
+                            "gaps": gaps,
+                        })
+                    })
+                    .collect();
+                let stats = serde_json::json!({
+                    "sequences": aln.num_seq(),
+                    "columns": aln_len,
+                    "gap_fraction": aln.gap_fraction(),
+                    "conserved_columns": conserved,
+                    "per_sequence": per_sequence,
+                });
+                let mut text = serde_json::to_string_pretty(&stats)
+                    .map_err(|e| TermalError::Format(format!("Invalid stats JSON: {}", e)))?;
+                text.push('\n');
+                text
+            }
+        };
+        fs::write(path, text)?;
         Ok(())
     }
 
     fn update_tree_lines_for_selection(&mut self) {
         if let Some(tree) = &self.tree {
             let selection = self.tree_selection_range;
-            if let Ok((lines, _order)) = tree_lines_and_order_with_selection(tree, selection) {
+            let rendered = if self.tree_phylogram {
+                tree_lines_and_order_phylogram(
+                    tree,
+                    PHYLOGRAM_WIDTH,
+                    selection,
+                    &self.collapsed_nodes,
+                )
+            } else {
+                tree_lines_and_order_with_selection(tree, selection, &self.collapsed_nodes)
+            };
+            if let Ok((lines, _order)) = rendered {
                 self.tree_lines = lines;
                 self.tree_panel_width = self
                     .tree_lines
@@ -2402,7 +4282,7 @@ impl App {
     pub fn map_tree_leaf_ranks(&self, leaf_names: &[String]) -> Result<Vec<usize>, TermalError> {
         let mapped_headers = self.map_order_to_headers(leaf_names.to_vec())?;
         let mut index_map: HashMap<&String, usize> = HashMap::new();
-        for (idx, header) in self.alignment.headers.iter().enumerate() {
+        for (idx, header) in self.alignment.headers().iter().enumerate() {
             index_map.insert(header, idx);
         }
         let mut result = Vec::new();
@@ -2414,61 +4294,42 @@ impl App {
         Ok(result)
     }
 
-    pub fn realign_with_mafft(&mut self) -> Result<(), TermalError> {
-        if self.mafft_bin_dir.is_none() {
-            return Err(TermalError::Format(String::from(
-                "mafft not configured. Create .msafara.config in $HOME or current directory with mafft_bin_dir.",
-            )));
-        }
-        let mut input_path = std::env::temp_dir();
-        let unique = format!("msafara-mafft-{}.fa", std::process::id());
-        input_path.push(unique);
-        self.write_alignment_fasta(&input_path)?;
-
-        let mut output_path = std::env::temp_dir();
-        let unique_out = format!("msafara-mafft-{}.out.fa", std::process::id());
-        output_path.push(unique_out);
+    /// Realigns the current view with the configured aligner. If the aligner leaves a guide tree
+    /// next to its input (as mafft does with --treeout), the tree becomes the view's tree and the
+    /// sequences are ordered by it.
+    pub fn realign(&mut self) -> Result<(), TermalError> {
+        self.ensure_writable()?;
+        let command = self.realign_command()?;
+        let (input_path, output_path) = Self::realign_paths();
+        let tree_path = tree_sidecar_path(&input_path);
+        // The temporary files are removed whether or not the aligner and its output are usable.
+        let result = (|| {
+            self.write_alignment_fasta(&input_path)?;
+            command.run(Stdio::null())?;
+            let tree_result = match fs::read_to_string(&tree_path) {
+                Ok(tree_text) => {
+                    let tree = parse_newick(&tree_text)?;
+                    let (lines, order) = tree_lines_and_order(&tree)?;
+                    Some((tree, tree_text, lines, order))
+                }
+                Err(_) => None,
+            };
+            let seq_file = read_fasta_file(&output_path)?;
+            Ok::<_, TermalError>((tree_result, Alignment::from_file(seq_file)))
+        })();
+        fs::remove_file(&input_path).ok();
+        fs::remove_file(&output_path).ok();
+        fs::remove_file(&tree_path).ok();
+        let (tree_result, realigned) = result?;
 
-        let tool_path = self
-            .mafft_bin_dir
-            .as_ref()
-            .map(|dir| dir.join("mafft"))
-            .unwrap_or_else(|| PathBuf::from("mafft"));
-        let output_file = File::create(&output_path)?;
-        let status = Command::new(tool_path)
-            .arg("--maxiterate")
-            .arg("1000")
-            .arg("--localpair")
-            .arg("--treeout")
-            .arg("--reorder")
-            .arg(&input_path)
-            .stdout(Stdio::from(output_file))
-            .stderr(Stdio::null())
-            .status()
-            .map_err(|e| TermalError::Format(format!("Failed to run mafft: {}", e)))?;
-        if !status.success() {
-            return Err(TermalError::Format(String::from("mafft failed")));
-        }
-
-        let tree_path = PathBuf::from(format!("{}.tree", input_path.display()));
-        let tree_text = fs::read_to_string(&tree_path)?;
-        let tree = parse_newick(&tree_text)?;
-        let (lines, order) = tree_lines_and_order(&tree)?;
-
-        let seq_file = read_fasta_file(&output_path)?;
-        let mafft_alignment = Alignment::from_file(seq_file);
         let view_ids = self.current_view_ids.clone();
         if matches!(self.current_view_kind(), ViewKind::Original) {
-            self.update_records_from_alignment(&mafft_alignment, &view_ids)?;
+            self.update_records_from_alignment(&realigned, &view_ids)?;
             self.alignment = self.build_alignment_for_ids(&view_ids);
             self.update_current_view_alignment_override(None);
         } else {
             let mut seq_map: HashMap<&String, &String> = HashMap::new();
-            for (header, sequence) in mafft_alignment
-                .headers
-                .iter()
-                .zip(mafft_alignment.sequences.iter())
-            {
+            for (header, sequence) in realigned.headers().iter().zip(realigned.sequences()) {
                 seq_map.insert(header, sequence);
             }
             let mut override_sequences = Vec::with_capacity(view_ids.len());
@@ -2489,38 +4350,45 @@ impl App {
                 self.build_alignment_for_ids_with_sequences(&view_ids, &override_sequences);
             self.update_current_view_alignment_override(Some(override_sequences));
         }
+        self.mark_unsaved();
         self.search_state = None;
         self.seq_search_state = None;
         self.label_search_source = None;
         self.tree_selection_range = None;
+        self.clear_collapsed_clades();
         self.refresh_saved_searches();
-        self.set_user_ordering(order)?;
-        self.tree_lines = lines;
-        self.tree_panel_width = self
-            .tree_lines
-            .iter()
-            .map(|line| line.chars().count())
-            .max()
-            .unwrap_or(0)
-            .min(u16::MAX as usize) as u16;
-        self.tree = Some(tree);
-        self.tree_newick = Some(tree_text);
-        if let Some(view) = self.views.get_mut(&self.current_view) {
-            view.tree = self.tree.clone();
-            view.tree_newick = self.tree_newick.clone();
-            view.tree_lines = self.tree_lines.clone();
-            view.tree_panel_width = self.tree_panel_width;
+        match tree_result {
+            Some((tree, tree_text, lines, order)) => {
+                self.set_user_ordering(order)?;
+                self.tree_lines = lines;
+                self.tree_panel_width = self
+                    .tree_lines
+                    .iter()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .min(u16::MAX as usize) as u16;
+                self.tree = Some(tree);
+                self.tree_newick = Some(tree_text);
+                if self.tree_phylogram {
+                    self.update_tree_lines_for_selection();
+                }
+                if let Some(view) = self.views.get_mut(&self.current_view) {
+                    view.tree = self.tree.clone();
+                    view.tree_newick = self.tree_newick.clone();
+                    view.tree_lines = self.tree_lines.clone();
+                    view.tree_panel_width = self.tree_panel_width;
+                }
+            }
+            None => self.clear_current_view_tree(),
         }
         self.recompute_ordering();
-
-        fs::remove_file(&input_path).ok();
-        fs::remove_file(&output_path).ok();
-        fs::remove_file(&tree_path).ok();
         Ok(())
     }
 
     pub fn set_user_ordering(&mut self, order: Vec<String>) -> Result<(), TermalError> {
         let mapped = self.map_order_to_headers(order)?;
+        self.mark_unsaved();
         self.user_ordering = Some(mapped);
         self.ordering_criterion = User;
         self.recompute_ordering();
@@ -2528,50 +4396,15 @@ impl App {
     }
 
     fn map_order_to_headers(&self, order: Vec<String>) -> Result<Vec<String>, TermalError> {
-        let expected: HashSet<String> = self.alignment.headers.iter().cloned().collect();
-        let mut token_map: HashMap<String, String> = HashMap::new();
-        let mut normalized_map: HashMap<String, String> = HashMap::new();
-        for header in &self.alignment.headers {
-            let normalized = normalize_tree_label(header);
-            insert_unique(&mut normalized_map, normalized, header)?;
-            let token = header.split_whitespace().next().unwrap_or("").to_string();
-            if token.is_empty() {
-                continue;
-            }
-            insert_unique(&mut token_map, token.clone(), header)?;
-            let token_norm = normalize_tree_label(&token);
-            insert_unique(&mut token_map, token_norm, header)?;
-        }
-
-        let mut mapped: Vec<String> = Vec::with_capacity(order.len());
-        for name in order {
-            let normalized = normalize_tree_label(&name);
-            if expected.contains(&name) {
-                mapped.push(name);
-                continue;
-            }
-            if let Some(header) = normalized_map.get(&name) {
-                mapped.push(header.clone());
-                continue;
-            }
-            if let Some(header) = normalized_map.get(&normalized) {
-                mapped.push(header.clone());
-                continue;
-            }
-            if let Some(header) = token_map.get(&name) {
-                mapped.push(header.clone());
-                continue;
-            }
-            if let Some(header) = token_map.get(&normalized) {
-                mapped.push(header.clone());
-                continue;
-            }
+        let (mapped, unmatched) = self.match_order_to_headers(order)?;
+        if let Some(name) = unmatched.first() {
             return Err(TermalError::Format(format!(
                 "Tree leaf does not match header: {}",
                 name
             )));
         }
 
+        let expected: HashSet<String> = self.alignment.headers().iter().cloned().collect();
         let provided: HashSet<String> = mapped.iter().cloned().collect();
         if expected.len() != provided.len() || expected != provided {
             return Err(TermalError::Format(String::from(
@@ -2582,18 +4415,111 @@ impl App {
         Ok(mapped)
     }
 
+    // Maps tree leaf names to alignment headers (exact match, then normalized label, then first
+    // header token). Returns the mapped headers and the names that matched no header.
+    fn match_order_to_headers(
+        &self,
+        order: Vec<String>,
+    ) -> Result<(Vec<String>, Vec<String>), TermalError> {
+        let matcher = HeaderMatcher::new(self.alignment.headers())?;
+        // Names that have been relabeled (e.g. leaves of a tree read before :relabel).
+        let aliases = HeaderMatcher::new(self.header_aliases.keys())?;
+        let mut mapped: Vec<String> = Vec::with_capacity(order.len());
+        let mut unmatched: Vec<String> = Vec::new();
+        for name in order {
+            let header = matcher.resolve(&name).or_else(|| {
+                aliases
+                    .resolve(&name)
+                    .and_then(|old| self.header_aliases.get(&old).cloned())
+                    .filter(|header| matcher.expected.contains(header))
+            });
+            match header {
+                Some(header) => mapped.push(header),
+                None => unmatched.push(name),
+            }
+        }
+        Ok((mapped, unmatched))
+    }
+
+    /// Renames sequences after `mapping`'s (old name, new name) pairs, in every view. Old names
+    /// are matched to headers like tree leaves are (exactly, or after normalization, or by their
+    /// first word); unmatched names are ignored. Orderings follow, and names from before the
+    /// renaming (e.g. in an already loaded tree) still resolve. Returns the number of renamed
+    /// sequences.
+    pub fn relabel(&mut self, mapping: &[(String, String)]) -> Result<usize, TermalError> {
+        self.ensure_writable()?;
+        let matcher = HeaderMatcher::new(self.records.iter().map(|record| &record.header))?;
+        let renames: HashMap<String, String> = mapping
+            .iter()
+            .filter_map(|(old, new)| matcher.resolve(old).map(|header| (header, new.clone())))
+            .filter(|(header, new)| header != new)
+            .collect();
+        if renames.is_empty() {
+            return Ok(0);
+        }
+        self.mark_unsaved();
+        let rename = |header: &mut String| {
+            if let Some(new) = renames.get(header) {
+                *header = new.clone();
+            }
+        };
+        let mut renamed = 0;
+        for record in &mut self.records {
+            if renames.contains_key(&record.header) {
+                renamed += 1;
+            }
+            rename(&mut record.header);
+        }
+        for rank in 0..self.alignment.num_seq() {
+            if let Some(new) = renames.get(&self.alignment.headers()[rank]) {
+                self.alignment.set_header(rank, new.clone());
+            }
+        }
+        if let Some(ordering) = &mut self.user_ordering {
+            ordering.iter_mut().for_each(rename);
+        }
+        for view in self.views.values_mut() {
+            if let Some(ordering) = &mut view.user_ordering {
+                ordering.iter_mut().for_each(rename);
+            }
+        }
+        self.header_aliases.values_mut().for_each(rename);
+        self.header_aliases.extend(renames);
+        self.clear_collapsed_clades();
+        self.recompute_ordering();
+        Ok(renamed)
+    }
+
+    /// Reads a mapping of tab-separated (old name, new name) pairs, one per line (blank lines and
+    /// lines starting with '#' are skipped), and relabels the sequences after it (see relabel()).
+    pub fn relabel_from_file(&mut self, path: &Path) -> Result<usize, TermalError> {
+        let text = fs::read_to_string(path)?;
+        let mapping = parse_relabel_mapping(&text).map_err(|e| e.in_file(path))?;
+        self.relabel(&mapping)
+    }
+
     fn refresh_saved_searches(&mut self) {
-        let sequences = &self.alignment.sequences;
+        let sequences = self.alignment.sequences();
         for entry in &mut self.search_registry.searches {
             let state = match entry.kind {
-                SearchKind::Regex => compute_seq_search_state(sequences, &entry.query, entry.kind)
-                    .map_err(|e| TermalError::Format(format!("Malformed regex: {}", e))),
-                SearchKind::Emboss => compute_emboss_search_state(
-                    &self.alignment.headers,
+                SearchKind::Regex => compute_seq_search_state(
                     sequences,
                     &entry.query,
+                    entry.kind,
+                    self.highlight_capture_group,
+                    None,
+                )
+                .map_err(|e| TermalError::Format(format!("Malformed regex: {}", e))),
+                SearchKind::Emboss => compute_emboss_search_state(
+                    &self.alignment,
+                    &entry.query,
                     self.emboss_bin_dir.as_deref(),
                 ),
+                SearchKind::Gff => Ok(gff_feature_state(
+                    &self.alignment,
+                    &entry.gff_records,
+                    &entry.name,
+                )),
             };
             entry.spans_by_seq = match state {
                 Ok(state) => state.spans_by_seq,
@@ -2606,11 +4532,35 @@ impl App {
         self.refresh_saved_searches();
     }
 
+    /// Entries of a prompt's history, oldest first.
+    pub fn history(&self, kind: PromptKind) -> &[String] {
+        match kind {
+            PromptKind::Command => &self.command_history,
+            PromptKind::Search => &self.search_history,
+        }
+    }
+
+    /// Records a submitted prompt entry, unless it is empty or repeats the previous one.
+    pub fn push_history(&mut self, kind: PromptKind, entry: &str) {
+        let history = match kind {
+            PromptKind::Command => &mut self.command_history,
+            PromptKind::Search => &mut self.search_history,
+        };
+        if entry.trim().is_empty() || history.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        history.push(entry.to_string());
+        if history.len() > MAX_HISTORY {
+            history.remove(0);
+        }
+    }
+
     pub fn notes(&self) -> &str {
         &self.notes
     }
 
     pub fn set_notes(&mut self, notes: String) {
+        self.mark_unsaved();
         self.notes = notes;
     }
 
@@ -2619,6 +4569,7 @@ impl App {
     }
 
     pub fn set_view_notes(&mut self, notes: String) {
+        self.mark_unsaved();
         self.view_notes = notes;
         if let Some(view) = self.views.get_mut(&self.current_view) {
             view.notes = self.view_notes.clone();
@@ -2631,30 +4582,33 @@ impl App {
         &self.current_msg
     }
 
+    pub fn set_flash_errors(&mut self, flash_errors: bool) {
+        self.flash_errors = flash_errors;
+    }
+
     pub fn clear_msg(&mut self) {
-        self.current_msg = CurrentMessage {
-            prefix: String::from(""),
-            message: String::from(""),
-            kind: MessageKind::Info,
-        }
+        self.current_msg =
+            CurrentMessage::new(String::from(""), String::from(""), MessageKind::Info);
     }
 
     pub fn info_msg(&mut self, msg: impl Into<String>) {
-        self.current_msg = CurrentMessage {
-            prefix: String::from(""),
-            message: msg.into(),
-            kind: MessageKind::Info,
-        };
+        self.current_msg = CurrentMessage::new(String::from(""), msg.into(), MessageKind::Info);
     }
 
     pub fn recompute_current_seq_search(&mut self) {
-        let (kind, pattern, current) = match &self.seq_search_state {
-            Some(state) => (state.kind, state.pattern.clone(), state.current_match),
+        let (kind, pattern, current, scope) = match &self.seq_search_state {
+            Some(state) => (
+                state.kind,
+                state.pattern.clone(),
+                state.current_match,
+                state.scope.clone(),
+            ),
             None => return,
         };
         match kind {
-            SearchKind::Regex => self.regex_search_sequences(&pattern),
+            SearchKind::Regex => self.run_regex_search(&pattern, scope),
             SearchKind::Emboss => self.emboss_search_sequences(&pattern),
+            SearchKind::Gff => {}
         }
         if let Some(state) = &mut self.seq_search_state {
             if current < state.matches.len() {
@@ -2664,35 +4618,22 @@ impl App {
     }
 
     pub fn warning_msg(&mut self, msg: impl Into<String>) {
-        self.current_msg = CurrentMessage {
-            prefix: String::from("WARNING: "),
-            message: msg.into(),
-            kind: MessageKind::Warning,
-        };
+        self.current_msg =
+            CurrentMessage::new(String::from("WARNING: "), msg.into(), MessageKind::Warning);
     }
 
     pub fn error_msg(&mut self, msg: impl Into<String>) {
-        self.current_msg = CurrentMessage {
-            prefix: String::from("ERROR: "),
-            message: msg.into(),
-            kind: MessageKind::Error,
-        };
+        self.current_msg =
+            CurrentMessage::new(String::from("ERROR: "), msg.into(), MessageKind::Error);
+        self.current_msg.flash = self.flash_errors;
     }
 
     pub fn debug_msg(&mut self, msg: impl Into<String>) {
-        self.current_msg = CurrentMessage {
-            prefix: String::from(""),
-            message: msg.into(),
-            kind: MessageKind::Debug,
-        };
+        self.current_msg = CurrentMessage::new(String::from(""), msg.into(), MessageKind::Debug);
     }
 
     pub fn argument_msg(&mut self, pfx: impl Into<String>, msg: impl Into<String>) {
-        self.current_msg = CurrentMessage {
-            prefix: pfx.into(),
-            message: msg.into(),
-            kind: MessageKind::Argument,
-        };
+        self.current_msg = CurrentMessage::new(pfx.into(), msg.into(), MessageKind::Argument);
     }
 
     pub fn add_argument_char(&mut self, c: char) {
@@ -2731,6 +4672,80 @@ fn strip_numeric_prefix(label: &str) -> &str {
     label
 }
 
+// Resolves names (e.g. tree leaves) to headers: as they are, or after normalization (see
+// normalize_tree_label()), or by the headers' first words.
+struct HeaderMatcher {
+    expected: HashSet<String>,
+    normalized_map: HashMap<String, String>,
+    token_map: HashMap<String, String>,
+}
+
+impl HeaderMatcher {
+    fn new<'a>(headers: impl IntoIterator<Item = &'a String>) -> Result<Self, TermalError> {
+        let mut expected: HashSet<String> = HashSet::new();
+        let mut token_map: HashMap<String, String> = HashMap::new();
+        let mut normalized_map: HashMap<String, String> = HashMap::new();
+        for header in headers {
+            expected.insert(header.clone());
+            let normalized = normalize_tree_label(header);
+            insert_unique(&mut normalized_map, normalized, header)?;
+            let token = header.split_whitespace().next().unwrap_or("").to_string();
+            if token.is_empty() {
+                continue;
+            }
+            insert_unique(&mut token_map, token.clone(), header)?;
+            let token_norm = normalize_tree_label(&token);
+            insert_unique(&mut token_map, token_norm, header)?;
+        }
+        Ok(HeaderMatcher {
+            expected,
+            normalized_map,
+            token_map,
+        })
+    }
+
+    fn resolve(&self, name: &str) -> Option<String> {
+        if self.expected.contains(name) {
+            return Some(name.to_string());
+        }
+        let normalized = normalize_tree_label(name);
+        self.normalized_map
+            .get(name)
+            .or_else(|| self.normalized_map.get(&normalized))
+            .or_else(|| self.token_map.get(name))
+            .or_else(|| self.token_map.get(&normalized))
+            .cloned()
+    }
+}
+
+// (old name, new name) pairs, tab-separated (or, failing a tab, separated by the first run of
+// white space), one per line. Blank lines and lines starting with '#' are skipped.
+fn parse_relabel_mapping(text: &str) -> Result<Vec<(String, String)>, TermalError> {
+    let mut mapping = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let fields = trimmed
+            .split_once('\t')
+            .or_else(|| trimmed.split_once(char::is_whitespace));
+        match fields {
+            Some((old, new)) if !new.trim().is_empty() => {
+                mapping.push((old.trim().to_string(), new.trim().to_string()))
+            }
+            _ => {
+                return Err(TermalError::parse(
+                    idx + 1,
+                    "relabel: expected old and new names",
+                    line,
+                ))
+            }
+        }
+    }
+    Ok(mapping)
+}
+
 fn insert_unique(
     map: &mut HashMap<String, String>,
     key: String,
@@ -2751,13 +4766,19 @@ fn insert_unique(
 
 // Computes an ordering WRT an array, that is, an array of indices of elements of the source array,
 // after sorting. Eg [3, -2, 7] -> [1, 0, 2], because the smalllest element has index 1, the next
-// has index 0, and the largest has index 2 (in the original array).
+// has index 0, and the largest has index 2 (in the original array). The sort is stable, and
+// values that compare to nothing, not even themselves (NaN, e.g. the identity of an all-gap
+// sequence), count as greater than all others, so they end up last.
 fn order<T: PartialOrd>(elems: &[T]) -> Vec<usize> {
     // let result: Vec<usize> = Vec::with_capacity(elems.len());
     let init_order: Vec<usize> = (0..elems.len()).collect();
     let zip_iter = init_order.iter().zip(elems);
     let mut unsorted_pairs: Vec<(&usize, &T)> = zip_iter.collect();
-    unsorted_pairs.sort_by(|(_, t1), (_, t2)| t1.partial_cmp(t2).expect("Unorder!"));
+    let unordered = |t: &T| t.partial_cmp(t).is_none();
+    unsorted_pairs.sort_by(|(_, t1), (_, t2)| {
+        t1.partial_cmp(t2)
+            .unwrap_or_else(|| unordered(t1).cmp(&unordered(t2)))
+    });
     unsorted_pairs
         .into_iter()
         .map(|(u, _)| *u)
@@ -2777,10 +4798,6 @@ fn ungapped_seq_and_map(seq: &str) -> (String, Vec<usize>) {
     (ungapped, map)
 }
 
-fn is_gap(c: char) -> bool {
-    matches!(c, '-' | '.' | ' ')
-}
-
 impl SearchRegistry {
     fn new(palette: Vec<SearchColor>) -> Self {
         Self {
@@ -2794,6 +4811,10 @@ impl SearchRegistry {
         &self.searches
     }
 
+    fn next_color(&self) -> SearchColor {
+        self.palette[self.next_color_index % self.palette.len()]
+    }
+
     fn add_search(
         &mut self,
         name: String,
@@ -2801,7 +4822,7 @@ impl SearchRegistry {
         kind: SearchKind,
         spans_by_seq: Vec<Vec<(usize, usize)>>,
     ) {
-        let color = self.palette[self.next_color_index % self.palette.len()];
+        let color = self.next_color();
         self.next_color_index += 1;
         let id = self.searches.len() + 1;
         self.searches.push(SearchEntry {
@@ -2812,6 +4833,7 @@ impl SearchRegistry {
             enabled: true,
             color,
             spans_by_seq,
+            gff_records: Vec::new(),
         });
     }
 
@@ -2844,28 +4866,54 @@ impl SearchRegistry {
     }
 }
 
+/// Regex search over the ungapped sequences. With `capture_group`, the span of capture group 1 is
+/// highlighted rather than the whole match, unless the pattern has no groups.
 fn compute_seq_search_state(
     sequences: &[String],
     pattern: &str,
     kind: SearchKind,
+    capture_group: bool,
+    scope: Option<&SearchScope>,
 ) -> Result<SeqSearchState, regex::Error> {
     let re = RegexBuilder::new(pattern).case_insensitive(true).build()?;
+    let group = if capture_group && re.captures_len() > 1 {
+        1
+    } else {
+        0
+    };
     let mut spans_by_seq: Vec<Vec<(usize, usize)>> = Vec::with_capacity(sequences.len());
     let mut total_matches = 0;
     let mut sequences_with_matches = 0;
     let mut matches: Vec<SeqMatch> = Vec::new();
-    for seq in sequences {
-        let (ungapped, map) = ungapped_seq_and_map(seq);
+    let in_scope: Option<HashSet<usize>> = scope.map(|scope| scope.ranks.iter().copied().collect());
+    for (rank, seq) in sequences.iter().enumerate() {
         let mut spans: Vec<(usize, usize)> = Vec::new();
-        for m in re.find_iter(&ungapped) {
+        if in_scope
+            .as_ref()
+            .is_some_and(|ranks| !ranks.contains(&rank))
+        {
+            spans_by_seq.push(spans);
+            continue;
+        }
+        // A scoped search only sees its columns; offsets are mapped back to the whole sequence.
+        let (from, to) = scope.map_or((0, seq.len()), |scope| {
+            let to = scope.cols.1.min(seq.len());
+            (scope.cols.0.min(to), to)
+        });
+        let (ungapped, map) = ungapped_seq_and_map(&seq[from..to]);
+        for caps in re.captures_iter(&ungapped) {
+            // A group that did not take part in this match has nothing to highlight.
+            let Some(m) = caps.get(group) else {
+                continue;
+            };
             if m.start() == m.end() {
                 continue;
             }
             if m.end() == 0 || m.end() > map.len() {
                 continue;
             }
-            let g_start = map[m.start()];
-            let g_end = map[m.end() - 1] + 1;
+            let g_start = from + map[m.start()];
+            let g_end = from + map[m.end() - 1] + 1;
             spans.push((g_start, g_end));
         }
         if !spans.is_empty() {
@@ -2891,6 +4939,7 @@ fn compute_seq_search_state(
         sequences_with_matches,
         matches,
         current_match: 0,
+        scope: scope.cloned(),
     })
 }
 
@@ -2960,8 +5009,7 @@ fn parse_color_value(value: &Value) -> Result<SearchColor, TermalError> {
 }
 
 fn compute_emboss_search_state(
-    headers: &[String],
-    sequences: &[String],
+    alignment: &Alignment,
     pattern: &str,
     emboss_bin_dir: Option<&Path>,
 ) -> Result<SeqSearchState, TermalError> {
@@ -2970,7 +5018,8 @@ fn compute_emboss_search_state(
             "Emboss tools not configured. Create .msafara.config in $HOME or current directory with emboss_bin_dir.",
         ))
     })?;
-    let is_nucleic = sequences
+    let is_nucleic = alignment
+        .sequences()
         .iter()
         .all(|seq| seq.chars().all(|c| is_gap(c) || is_acgt(c)));
     let tool = if is_nucleic { "fuzznuc" } else { "fuzzpro" };
@@ -2978,7 +5027,7 @@ fn compute_emboss_search_state(
     let (pmis, emboss_pattern) = parse_emboss_query(pattern);
     let emboss_pattern = emboss_pattern.to_ascii_uppercase();
 
-    let tmp_path = emboss_temp_fasta(headers, sequences)?;
+    let tmp_path = emboss_temp_fasta(alignment.sequences())?;
     let mut cmd = std::process::Command::new(tool_path);
     cmd.arg("-seq")
         .arg(&tmp_path)
@@ -2989,19 +5038,19 @@ fn compute_emboss_search_state(
         .arg("-rformat")
         .arg("gff");
     if let Some(mismatches) = pmis {
-        cmd.arg("-pmis").arg(mismatches.to_string());
+        cmd.arg("-pmismatch").arg(mismatches.to_string());
     }
-    let output = cmd
-        .output()
-        .map_err(|e| TermalError::Format(format!("Failed to run {}: {}", tool, e)))?;
+    let output = cmd.output();
     fs::remove_file(&tmp_path).ok();
+    let output =
+        output.map_err(|e| TermalError::Format(format!("Failed to run {}: {}", tool, e)))?;
 
     if !output.status.success() {
         let msg = String::from_utf8_lossy(&output.stderr);
         return Err(TermalError::Format(format!("{} failed: {}", tool, msg)));
     }
     let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_gff_to_state(headers, sequences, &stdout, pattern)
+    parse_gff_to_state(alignment, &stdout, pattern, emboss_seq_index)
 }
 
 fn parse_emboss_query(query: &str) -> (Option<u32>, &str) {
@@ -3026,30 +5075,37 @@ fn parse_emboss_query(query: &str) -> (Option<u32>, &str) {
     (Some(value), pattern)
 }
 
-fn emboss_temp_fasta(headers: &[String], sequences: &[String]) -> Result<PathBuf, TermalError> {
+fn emboss_temp_fasta(sequences: &[String]) -> Result<PathBuf, TermalError> {
     let mut path = std::env::temp_dir();
     let unique = format!("msafara-emboss-{}.fa", std::process::id());
     path.push(unique);
     let file = fs::File::create(&path)?;
     let mut writer = BufWriter::new(file);
-    for (header, seq) in headers.iter().zip(sequences.iter()) {
+    for (idx, seq) in sequences.iter().enumerate() {
         let ungapped: String = seq
             .chars()
             .filter(|c| !is_gap(*c))
             .map(|c| c.to_ascii_uppercase())
             .collect();
-        writeln!(writer, ">{}", header)?;
+        writeln!(writer, ">{}", emboss_seqid(idx))?;
         writeln!(writer, "{}", ungapped)?;
     }
+    writer.flush()?;
     Ok(path)
 }
 
-fn parse_gff_to_state(
-    headers: &[String],
-    sequences: &[String],
-    gff: &str,
-    pattern: &str,
-) -> Result<SeqSearchState, TermalError> {
+// Sequences are passed to EMBOSS under their index rather than their header, so that hits map back
+// unambiguously even when headers share their first token (which EMBOSS uses as the seqid).
+fn emboss_seqid(idx: usize) -> String {
+    format!("msafara_{}", idx)
+}
+
+fn emboss_seq_index(seqid: &str) -> Option<usize> {
+    seqid.strip_prefix("msafara_")?.parse().ok()
+}
+
+/// Maps GFF seqids to sequence indices: a seqid matches a full header or its first token.
+fn gff_header_index(headers: &[String]) -> HashMap<&str, usize> {
     let mut header_to_index: HashMap<&str, usize> = HashMap::new();
     for (idx, header) in headers.iter().enumerate() {
         header_to_index.insert(header.as_str(), idx);
@@ -3057,7 +5113,21 @@ fn parse_gff_to_state(
             header_to_index.entry(token).or_insert(idx);
         }
     }
-    let mut spans_by_seq: Vec<Vec<(usize, usize)>> = vec![Vec::new(); sequences.len()];
+    header_to_index
+}
+
+/// A GFF record: its seqid and 1-based, inclusive coordinates on the ungapped sequence.
+#[derive(Clone)]
+struct GffRecord {
+    seqid: String,
+    start: usize,
+    end: usize,
+}
+
+/// Parses GFF records, grouped by feature type (in order of first appearance). Comments and
+/// malformed records are ignored.
+fn parse_gff_records(gff: &str) -> Vec<(String, Vec<GffRecord>)> {
+    let mut records_by_type: Vec<(String, Vec<GffRecord>)> = Vec::new();
     for line in gff.lines() {
         if line.starts_with('#') || line.trim().is_empty() {
             continue;
@@ -3066,22 +5136,102 @@ fn parse_gff_to_state(
         if parts.len() < 5 {
             continue;
         }
-        let seqid = parts[0];
-        let start: usize = parts[3].parse().unwrap_or(0);
-        let end: usize = parts[4].parse().unwrap_or(0);
-        if start == 0 || end == 0 {
+        let (Ok(start), Ok(end)) = (parts[3].parse(), parts[4].parse()) else {
+            continue;
+        };
+        let record = GffRecord {
+            seqid: parts[0].to_string(),
+            start,
+            end,
+        };
+        let feature_type = parts[2];
+        match records_by_type.iter_mut().find(|(t, _)| t == feature_type) {
+            Some((_, records)) => records.push(record),
+            None => records_by_type.push((feature_type.to_string(), vec![record])),
+        }
+    }
+    records_by_type
+}
+
+/// Maps GFF records to alignment columns. Also returns the number of records skipped because
+/// their seqid matches no sequence; out-of-range coordinates are ignored.
+fn gff_spans(alignment: &Alignment, records: &[GffRecord]) -> (SpansBySeq, usize) {
+    let header_to_index = gff_header_index(alignment.headers());
+    let mut spans_by_seq: SpansBySeq = vec![Vec::new(); alignment.num_seq()];
+    let mut skipped = 0;
+    for record in records {
+        let Some(&seq_index) = header_to_index.get(record.seqid.as_str()) else {
+            skipped += 1;
+            continue;
+        };
+        if let Some(span) = gff_record_span(alignment, seq_index, record.start, record.end) {
+            spans_by_seq[seq_index].push(span);
+        }
+    }
+    (spans_by_seq, skipped)
+}
+
+/// Recomputes a GFF overlay entry (the `records` of `feature_type`) for the current alignment.
+fn gff_feature_state(
+    alignment: &Alignment,
+    records: &[GffRecord],
+    feature_type: &str,
+) -> SeqSearchState {
+    let (spans_by_seq, _) = gff_spans(alignment, records);
+    SeqSearchState {
+        kind: SearchKind::Gff,
+        pattern: feature_type.to_string(),
+        total_matches: spans_by_seq.iter().map(Vec::len).sum(),
+        sequences_with_matches: spans_by_seq.iter().filter(|s| !s.is_empty()).count(),
+        matches: Vec::new(),
+        spans_by_seq,
+        current_match: 0,
+        scope: None,
+    }
+}
+
+/// Alignment columns (end-exclusive) covered by a GFF record's 1-based, inclusive `start` and
+/// `end` on the sequence at `seq_index`, if the coordinates are valid for that sequence.
+fn gff_record_span(
+    alignment: &Alignment,
+    seq_index: usize,
+    start: usize,
+    end: usize,
+) -> Option<(usize, usize)> {
+    if start > end {
+        return None;
+    }
+    let g_start = alignment.ungapped_to_gapped(seq_index, start)?;
+    let g_end = alignment.ungapped_to_gapped(seq_index, end)? + 1;
+    Some((g_start, g_end))
+}
+
+/// Collects GFF records (e.g. EMBOSS hits) into a search state; `seq_index` maps a record's seqid
+/// to the index of its sequence.
+fn parse_gff_to_state(
+    alignment: &Alignment,
+    gff: &str,
+    pattern: &str,
+    seq_index: impl Fn(&str) -> Option<usize>,
+) -> Result<SeqSearchState, TermalError> {
+    let mut spans_by_seq: Vec<Vec<(usize, usize)>> = vec![Vec::new(); alignment.num_seq()];
+    for line in gff.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 5 {
             continue;
         }
-        let Some(&seq_index) = header_to_index.get(seqid) else {
+        let Some(idx) = seq_index(parts[0]).filter(|idx| *idx < alignment.num_seq()) else {
             continue;
         };
-        let map = ungapped_to_gapped_map(&sequences[seq_index]);
-        if start > map.len() || end > map.len() || start > end {
+        let (Ok(start), Ok(end)) = (parts[3].parse(), parts[4].parse()) else {
             continue;
+        };
+        if let Some(span) = gff_record_span(alignment, idx, start, end) {
+            spans_by_seq[idx].push(span);
         }
-        let g_start = map[start - 1];
-        let g_end = map[end - 1] + 1;
-        spans_by_seq[seq_index].push((g_start, g_end));
     }
     let mut total_matches = 0;
     let mut sequences_with_matches = 0;
@@ -3107,20 +5257,10 @@ fn parse_gff_to_state(
         sequences_with_matches,
         matches,
         current_match: 0,
+        scope: None,
     })
 }
 
-fn ungapped_to_gapped_map(seq: &str) -> Vec<usize> {
-    let mut map: Vec<usize> = Vec::new();
-    for (idx, ch) in seq.chars().enumerate() {
-        if is_gap(ch) {
-            continue;
-        }
-        map.push(idx);
-    }
-    map
-}
-
 fn is_acgt(c: char) -> bool {
     matches!(c, 'A' | 'C' | 'G' | 'T' | 'a' | 'c' | 'g' | 't')
 }
@@ -1,11 +1,18 @@
-use super::{SearchColorConfig, ToolsConfig};
+use super::{SearchColorConfig, StartupScheme, StartupVideoMode, TermalConfig, ToolsConfig};
 use crate::{
-    alignment::Alignment,
-    app::{order, App, SearchKind, SeqMatch, SeqOrdering},
+    alignment::{Alignment, ShiftDirection},
+    app::{
+        order, AlignmentEdit, App, PromptKind, ResidueClassMap, SearchKind, SearchScope, SeqMatch,
+        SeqOrdering, StatsFormat,
+    },
+    errors::TermalError,
     tree::{parse_newick, tree_lines_and_order},
 };
 use serde_json::json;
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 #[test]
 fn test_order_00() {
@@ -30,6 +37,17 @@ fn test_order_10() {
     assert_eq!(vec![2, 1, 4, 0, 3], reverse_order);
 }
 
+#[test]
+fn test_order_nan() {
+    // NaNs (e.g. the identity of an all-gap sequence) go last, in their original order; ties
+    // keep theirs too.
+    assert_eq!(
+        vec![3, 1, 4, 0, 2],
+        order(&[f64::NAN, 0.5, f64::NAN, 0.2, 0.5]),
+    );
+    assert_eq!(vec![0], order(&[f64::NAN]));
+}
+
 #[test]
 fn test_ordering_00() {
     let hdrs = vec![
@@ -101,6 +119,141 @@ fn test_msafara_config_from_value() {
     assert_eq!(tools.mafft_bin_dir, Some(PathBuf::from("/opt/mafft")));
 }
 
+#[test]
+fn test_msafara_config_display_defaults() {
+    let value = json!({
+        "default_scheme": "light",
+        "default_video_mode": "inverse"
+    });
+    let config = TermalConfig::from_value(&value);
+    assert_eq!(config.display.default_scheme, Some(StartupScheme::Light));
+    assert_eq!(
+        config.display.default_video_mode,
+        Some(StartupVideoMode::Inverse)
+    );
+
+    let config = TermalConfig::from_value(&json!({"default_scheme": "sepia"}));
+    assert_eq!(config.display.default_scheme, None);
+    assert_eq!(config.display.default_video_mode, None);
+}
+
+#[test]
+fn test_msafara_config_consensus_threshold() {
+    let config = TermalConfig::from_value(&json!({"consensus_threshold": 0.9}));
+    assert_eq!(config.consensus_threshold, Some(0.9));
+    let config = TermalConfig::from_value(&json!({"consensus_threshold": 1.5}));
+    assert_eq!(config.consensus_threshold, None);
+}
+
+#[test]
+fn test_msafara_config_ut_equivalence() {
+    let config = TermalConfig::from_value(&json!({"ut_equivalence": true}));
+    assert_eq!(config.ut_equivalence, Some(true));
+    let config = TermalConfig::from_value(&json!({}));
+    assert_eq!(config.ut_equivalence, None);
+}
+
+#[test]
+fn test_msafara_config_notes_indent_width() {
+    let config = TermalConfig::from_value(&json!({"notes_indent_width": 4}));
+    assert_eq!(config.notes_indent_width, Some(4));
+    let config = TermalConfig::from_value(&json!({"notes_indent_width": 1000}));
+    assert_eq!(config.notes_indent_width, Some(16));
+    let config = TermalConfig::from_value(&json!({"notes_indent_width": "wide"}));
+    assert_eq!(config.notes_indent_width, None);
+}
+
+#[test]
+fn test_msafara_config_residue_classes() {
+    let config = TermalConfig::from_value(&json!({
+        "residue_classes": [
+            {
+                "name": "Charge",
+                "classes": [
+                    {"name": "acidic", "residues": "DE", "color": "#d62728"},
+                    {"name": "basic", "residues": "KR", "color": [31, 119, 180]},
+                    {"name": "bad", "residues": "H", "color": "blue-ish"}
+                ]
+            },
+            {"classes": []}
+        ]
+    }));
+    assert_eq!(
+        config.residue_classes,
+        vec![ResidueClassMap {
+            name: String::from("Charge"),
+            classes: vec![
+                (String::from("acidic"), String::from("DE"), (214, 39, 40)),
+                (String::from("basic"), String::from("KR"), (31, 119, 180)),
+            ],
+        }]
+    );
+    assert!(TermalConfig::from_value(&json!({}))
+        .residue_classes
+        .is_empty());
+}
+
+#[test]
+fn test_conservation_shading_config() {
+    let config = TermalConfig::from_value(&json!({"conservation_shading": true}));
+    assert!(config.display.conservation_shading);
+    assert!(
+        !TermalConfig::from_value(&json!({}))
+            .display
+            .conservation_shading
+    );
+}
+
+#[test]
+fn test_error_messages_flash_when_configured() {
+    let config = TermalConfig::from_value(&json!({"flash_errors": true}));
+    assert!(config.display.flash_errors);
+    assert!(!TermalConfig::from_value(&json!({})).display.flash_errors);
+
+    let hdrs = vec![String::from("R1")];
+    let seqs = vec![String::from("AC")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.error_msg("off by default");
+    assert!(!app.current_message().flash);
+    assert!(!app.current_message().is_flashing());
+
+    app.set_flash_errors(config.display.flash_errors);
+    app.error_msg("oops");
+    assert!(app.current_message().flash);
+    assert!(app.current_message().is_flashing());
+    app.warning_msg("only errors flash");
+    assert!(!app.current_message().flash);
+}
+
+#[test]
+fn test_similarity_limit_warned_when_selected_only() {
+    let hdrs = (0..2001).map(|i| format!("R{}", i)).collect();
+    let seqs = vec![String::from("AC"); 2001];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.set_seq_ordering(SeqOrdering::Similarity);
+    assert!(app
+        .current_message()
+        .message
+        .contains("Similarity ordering is limited"));
+    app.clear_msg();
+    app.set_ut_equivalence(true);
+    assert!(app.current_message().message.is_empty());
+}
+
+#[test]
+fn test_ut_equivalence_survives_view_change() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("GU"), String::from("GT")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.set_ut_equivalence(true);
+    app.select_label_by_rank(0).unwrap();
+    app.select_label_by_rank(1).unwrap();
+    app.create_view_from_selection("both").unwrap();
+    app.switch_view("both").unwrap();
+    assert!(app.alignment.ut_equivalence());
+    assert_eq!(app.alignment.consensus(), "GU");
+}
+
 #[test]
 fn test_update_records_from_alignment() {
     let hdrs = vec![String::from("A"), String::from("B")];
@@ -136,6 +289,42 @@ fn test_ordering_status_label() {
     assert_eq!(app.ordering_status_label(), "o:tree");
 }
 
+#[test]
+fn test_reverse_current_ordering() {
+    let hdrs: Vec<String> = (1..=5).map(|i| format!("R{}", i)).collect();
+    // Identities to the consensus (AAAAA): 100%, 40%, 80%, 100%, 100%.
+    let seqs = vec![
+        String::from("AAAAA"),
+        String::from("ACCCA"),
+        String::from("AAACA"),
+        String::from("AAAAA"),
+        String::from("AAAAA"),
+    ];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.set_seq_ordering(SeqOrdering::MetricIncr);
+    let ordering = app.ordering.clone();
+    assert_eq!(ordering, vec![1, 2, 0, 3, 4]);
+
+    assert!(app.reverse_current_ordering());
+    assert_eq!(app.ordering, vec![4, 3, 0, 2, 1]);
+    assert_eq!(app.ordering_status_label(), "o:%id↑(rev)");
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::MetricIncr);
+    // The cursor follows its sequence.
+    app.set_cursor_rank(1);
+    assert_eq!(app.rank_to_screenline(1), 4);
+
+    // The flip survives a change of criterion.
+    app.next_ordering_criterion();
+    assert_eq!(app.ordering, vec![1, 2, 0, 3, 4]);
+    assert_eq!(app.ordering_status_label(), "o:%id↓(rev)");
+
+    app.set_seq_ordering(SeqOrdering::MetricIncr);
+    assert!(!app.reverse_current_ordering());
+    assert_eq!(app.ordering, ordering);
+    assert_eq!(app.ordering_status_label(), "o:%id↑");
+}
+
 #[test]
 fn test_create_view_from_selection() {
     let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
@@ -181,7 +370,7 @@ fn test_tree_invalidated_on_view_change() {
         view.tree_panel_width = app.tree_panel_width;
     }
 
-    app.remove_sequences(&[0]);
+    app.remove_sequences(&[0]).unwrap();
     assert!(app.tree.is_none());
     assert!(app.tree_lines.is_empty());
     let view = app.views.get("custom").expect("view");
@@ -223,6 +412,107 @@ fn test_set_tree_ordering_from_tree() {
     assert_eq!(app.ordering, vec![1, 0]);
 }
 
+#[test]
+fn test_relabel_from_mapping() {
+    let hdrs = vec![
+        String::from("ABC_001"),
+        String::from("ABC_002"),
+        String::from("ABC_003 partial"),
+    ];
+    let seqs = vec![String::from("AC"), String::from("AG"), String::from("AT")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.tree = Some(parse_newick("((ABC_003,ABC_002),ABC_001);").unwrap());
+    app.set_tree_ordering_from_tree().unwrap();
+    assert_eq!(app.ordering, vec![2, 1, 0]);
+
+    // ABC.002 matches ABC_002 after normalization; XYZ matches nothing.
+    let path =
+        std::env::temp_dir().join(format!("msafara-test-{}.relabel.tsv", std::process::id()));
+    fs::write(
+        &path,
+        "# old\tnew\nABC_001\tHomo sapiens\nABC.002\tMus musculus\n\nXYZ\tNobody\n",
+    )
+    .unwrap();
+    let renamed = app.relabel_from_file(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    assert_eq!(renamed, 2);
+    assert_eq!(
+        app.alignment.headers(),
+        vec!["Homo sapiens", "Mus musculus", "ABC_003 partial"]
+    );
+    assert_eq!(
+        app.user_ordering.as_ref().unwrap(),
+        &vec!["ABC_003 partial", "Mus musculus", "Homo sapiens"]
+    );
+
+    // The tree still has the old names, and still resolves.
+    app.set_tree_ordering_from_tree().unwrap();
+    assert_eq!(app.ordering, vec![2, 1, 0]);
+}
+
+#[test]
+fn test_column_range_consensus() {
+    let hdrs: Vec<String> = (1..=6).map(|i| format!("R{}", i)).collect();
+    // Columns: conserved A, majority C, plurality G, all gaps, no majority.
+    let seqs = vec![
+        String::from("ACG-A"),
+        String::from("ACG-C"),
+        String::from("ACG-G"),
+        String::from("ACT-T"),
+        String::from("ACA-W"),
+        String::from("ATC-S"),
+    ];
+    let app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert_eq!(app.column_range_consensus(0, 5), "ACgX");
+    assert_eq!(app.column_range_consensus(1, 3), "Cg");
+    assert_eq!(app.column_range_consensus(3, 4), "");
+    assert_eq!(app.column_range_consensus(4, 99), "X");
+    assert_eq!(app.consensus_string(), "ACg-X");
+}
+
+#[test]
+fn test_search_annotation() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("ACGTACGTACGT"), String::from("ACGTACGTACGA")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert!(app.search_annotation("SS_cons", "E+").is_err());
+
+    app.set_column_annotations(vec![(
+        String::from("SS_cons"),
+        String::from("..EEE.HH.EE."),
+    )]);
+    assert_eq!(app.search_annotation("SS_cons", "E+").unwrap(), 2);
+    assert_eq!(
+        app.annotation_search().unwrap().ranges,
+        vec![(2, 5), (9, 11)]
+    );
+    assert_eq!(
+        app.increment_current_annotation_match(1),
+        Some(((9, 11), 2, 2, false))
+    );
+    assert_eq!(
+        app.increment_current_annotation_match(1),
+        Some(((2, 5), 1, 2, true))
+    );
+
+    let err = app.search_annotation("RF", "x").unwrap_err().to_string();
+    assert!(err.contains("No annotation named RF (have: SS_cons)"));
+    assert!(app.search_annotation("SS_cons", "(").is_err());
+}
+
+#[test]
+fn test_relabel_mapping_errors() {
+    let hdrs = vec![String::from("R1")];
+    let seqs = vec![String::from("AC")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.bad.tsv", std::process::id()));
+    fs::write(&path, "R1\tone\nR2\n").unwrap();
+    let err = app.relabel_from_file(&path).unwrap_err().to_string();
+    let _ = fs::remove_file(&path);
+    assert!(err.contains("line 2: relabel: expected old and new names"));
+    assert_eq!(app.alignment.headers(), vec!["R1"]);
+}
+
 #[test]
 fn test_view_alignment_override_applied() {
     let hdrs = vec![String::from("R1"), String::from("R2")];
@@ -235,7 +525,7 @@ fn test_view_alignment_override_applied() {
         view.alignment_override = Some(vec![String::from("XX")]);
     }
     app.switch_view("picked").unwrap();
-    assert_eq!(app.alignment.sequences, vec![String::from("XX")]);
+    assert_eq!(app.alignment.sequences(), vec![String::from("XX")]);
 }
 
 #[test]
@@ -348,6 +638,26 @@ fn test_regex_lbl_search_case_insensitive() {
     assert!(app.is_label_selected(1));
 }
 
+#[test]
+fn test_first_label_match_rank() {
+    let hdrs = vec![
+        String::from("Accipiter"),
+        String::from("Aquila"),
+        String::from("Buteo"),
+    ];
+    let seqs = vec![
+        String::from("catg"),
+        String::from("catg"),
+        String::from("catg"),
+    ];
+    let app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert_eq!(app.first_label_match_rank("u"), Some(1));
+    assert_eq!(app.first_label_match_rank("^b"), Some(2));
+    assert_eq!(app.first_label_match_rank("z"), None);
+    assert_eq!(app.first_label_match_rank("(a"), None);
+    assert!(app.selection_ranks().is_empty());
+}
+
 #[test]
 fn test_regex_seq_search_spans() {
     let hdrs = vec![String::from("R1")];
@@ -370,6 +680,25 @@ fn test_regex_seq_search_spans() {
     );
 }
 
+#[test]
+fn test_regex_seq_search_capture_group_spans() {
+    let hdrs = vec![String::from("R1")];
+    let seqs = vec![String::from("AGT-GACGTGAC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.regex_search_sequences("(GT)GAC");
+    assert_eq!(app.seq_search_spans().unwrap()[0], vec![(1, 7), (7, 12)]);
+
+    assert!(app.toggle_capture_group_highlight());
+    assert_eq!(app.seq_search_spans().unwrap()[0], vec![(1, 3), (7, 9)]);
+    let cur = app.current_seq_match().unwrap();
+    assert_eq!((cur.start, cur.end), (1, 3));
+
+    // Patterns without groups still highlight the whole match.
+    app.regex_search_sequences("GAC");
+    assert_eq!(app.seq_search_spans().unwrap()[0], vec![(4, 7), (9, 12)]);
+}
+
 #[test]
 fn test_search_ordering_groups_matches() {
     let hdrs = vec![
@@ -403,12 +732,89 @@ fn test_remove_sequences_preserves_search_state() {
     app.next_ordering_criterion();
     app.next_ordering_criterion();
     app.next_ordering_criterion();
-    app.remove_sequences(&[1]);
+    app.remove_sequences(&[1]).unwrap();
     assert_eq!(app.get_seq_ordering(), SeqOrdering::SearchMatch);
     assert_eq!(app.current_seq_search_pattern(), Some("AA"));
     assert_eq!(app.seq_search_spans().unwrap().len(), 2);
 }
 
+#[test]
+fn test_clear_search() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![String::from("CC"), String::from("BB"), String::from("AA")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.add_saved_search(String::from("bb"), String::from("BB"))
+        .unwrap();
+    app.regex_search_sequences("AA");
+    app.next_ordering_criterion();
+    app.next_ordering_criterion();
+    app.next_ordering_criterion();
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::SearchMatch);
+    assert_eq!(app.ordering[0], 2);
+
+    assert!(app.clear_search());
+    assert!(app.seq_search_spans().is_none());
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::SourceFile);
+    assert_eq!(app.ordering, vec![0, 1, 2]);
+    assert_eq!(app.saved_searches().len(), 1);
+    assert!(!app.clear_search());
+}
+
+#[test]
+fn test_scoped_regex_search() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![
+        String::from("AC-GTTTACGT"),
+        String::from("TTTTTTTACGT"),
+        String::from("ACGTTTTTTTT"),
+    ];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.regex_search_sequences("ACGT");
+    assert_eq!(app.seq_search_counts(), Some((4, 3)));
+    assert!(!app.seq_search_is_scoped());
+
+    // Only R1 and R3, columns 0..6: R1's second match and all of R2's are out of scope, and R1's
+    // first one (across a gap) is found in the column slice.
+    app.regex_search_sequences_in(
+        "ACGT",
+        SearchScope {
+            ranks: vec![0, 2],
+            cols: (0, 6),
+        },
+    );
+    assert!(app.seq_search_is_scoped());
+    assert_eq!(app.seq_search_counts(), Some((2, 2)));
+    let spans = app.seq_search_spans().unwrap();
+    assert_eq!(spans[0], vec![(0, 5)]);
+    assert!(spans[1].is_empty());
+    assert_eq!(spans[2], vec![(0, 4)]);
+
+    // A match straddling the edge of the scope is not reported.
+    app.regex_search_sequences_in(
+        "ACGT",
+        SearchScope {
+            ranks: vec![0, 1, 2],
+            cols: (8, 11),
+        },
+    );
+    assert_eq!(app.seq_search_counts(), Some((0, 0)));
+
+    // Recomputing (e.g. after an edit) keeps the scope.
+    app.regex_search_sequences_in(
+        "ACGT",
+        SearchScope {
+            ranks: vec![1],
+            cols: (5, 11),
+        },
+    );
+    app.recompute_current_seq_search();
+    assert!(app.seq_search_is_scoped());
+    assert_eq!(app.current_seq_match().unwrap().seq_index, 1);
+    assert_eq!(app.seq_search_counts(), Some((1, 1)));
+}
+
 #[test]
 fn test_remove_sequences_prunes_user_ordering() {
     let hdrs = vec![
@@ -432,8 +838,8 @@ fn test_remove_sequences_prunes_user_ordering() {
         String::from("R2"),
     ])
     .unwrap();
-    app.remove_sequences(&[1]);
-    assert_eq!(app.alignment.headers.len(), 3);
+    app.remove_sequences(&[1]).unwrap();
+    assert_eq!(app.alignment.headers().len(), 3);
     assert_eq!(app.user_ordering.as_ref().unwrap().len(), 3);
     assert!(!app
         .user_ordering
@@ -460,7 +866,7 @@ fn test_remove_sequences_preserves_ordering_lengths() {
     let mut app = App::new("TEST", aln, None);
 
     app.next_ordering_criterion();
-    app.remove_sequences(&[1]);
+    app.remove_sequences(&[1]).unwrap();
     assert_eq!(app.ordering.len(), app.alignment.num_seq());
     assert_eq!(app.reverse_ordering.len(), app.alignment.num_seq());
 
@@ -468,7 +874,7 @@ fn test_remove_sequences_preserves_ordering_lengths() {
     app.next_ordering_criterion();
     app.next_ordering_criterion();
     app.next_ordering_criterion();
-    app.remove_sequences(&[0]);
+    app.remove_sequences(&[0]).unwrap();
     assert_eq!(app.ordering.len(), app.alignment.num_seq());
     assert_eq!(app.reverse_ordering.len(), app.alignment.num_seq());
 }
@@ -497,6 +903,9 @@ fn test_session_save_and_load() {
         .unwrap();
     app.regex_search_sequences("AA");
     app.set_label_matches_from_tree(vec![0, 2], (0, 2));
+    app.push_history(PromptKind::Command, "hg");
+    app.push_history(PromptKind::Search, "AA");
+    app.set_column_origin(101);
 
     let mut path = std::env::temp_dir();
     path.push("msafara-test-session.msfr");
@@ -504,7 +913,7 @@ fn test_session_save_and_load() {
     app.save_session(&path).unwrap();
 
     let loaded = App::from_session_file(&path).unwrap();
-    assert_eq!(loaded.alignment.headers.len(), 3);
+    assert_eq!(loaded.alignment.headers().len(), 3);
     assert_eq!(loaded.tree_lines.len(), 3);
     assert!(loaded.tree.is_some());
     assert_eq!(loaded.saved_searches().len(), 1);
@@ -512,7 +921,36 @@ fn test_session_save_and_load() {
     assert_eq!(loaded.selection_ranks(), vec![0, 2]);
     assert_eq!(loaded.notes(), "Session notes");
     assert_eq!(loaded.view_notes(), "View notes");
+    assert_eq!(loaded.history(PromptKind::Command), ["hg"]);
+    assert_eq!(loaded.history(PromptKind::Search), ["AA"]);
+    assert_eq!(loaded.column_origin(), 101);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_unsaved_changes() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("ACGT"), String::from("AC-T")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert!(!app.has_unsaved_changes());
+    app.set_notes(String::from("to check"));
+    assert!(app.has_unsaved_changes());
+
+    let mut path = std::env::temp_dir();
+    path.push("msafara-test-unsaved.msfr");
     let _ = std::fs::remove_file(&path);
+    assert_eq!(app.session_path(), None);
+    app.save_session(&path).unwrap();
+    assert!(!app.has_unsaved_changes());
+    assert_eq!(app.session_path(), Some(path.as_path()));
+    // Sessions do not record the ordering criterion.
+    app.next_ordering_criterion();
+    assert!(!app.has_unsaved_changes());
+    app.set_notes(String::from("checked"));
+    assert!(app.has_unsaved_changes());
+    app.load_session(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(!app.has_unsaved_changes());
 }
 
 #[test]
@@ -587,8 +1025,622 @@ fn test_parse_emboss_query() {
 fn test_parse_gff_matches_header_token() {
     let headers = vec![String::from("seq 1"), String::from("seq2")];
     let sequences = vec![String::from("ABCD"), String::from("EFGH")];
+    let aln = Alignment::from_vecs(headers, sequences);
     let gff = "seq\tsrc\tfeat\t2\t4\t.\t.\t.\tID=seq.1\n";
-    let state = super::parse_gff_to_state(&headers, &sequences, gff, "TEST").unwrap();
+    let index = super::gff_header_index(aln.headers());
+    let state = super::parse_gff_to_state(&aln, gff, "TEST", |id| index.get(id).copied()).unwrap();
     assert_eq!(state.spans_by_seq[0], vec![(1, 4)]);
     assert!(state.spans_by_seq[1].is_empty());
 }
+
+#[test]
+fn test_parse_gff_maps_residues_to_gapped_columns() {
+    let headers = vec![String::from("X"), String::from("Y")];
+    let sequences = vec![String::from("A--CGT"), String::from("ACGTAC")];
+    let aln = Alignment::from_vecs(headers, sequences);
+    let gff = "X\tsrc\tfeat\t2\t3\t.\t.\t.\t.\nX\tsrc\tfeat\t3\t9\t.\t.\t.\t.\n";
+    let index = super::gff_header_index(aln.headers());
+    let state = super::parse_gff_to_state(&aln, gff, "TEST", |id| index.get(id).copied()).unwrap();
+    assert_eq!(state.spans_by_seq[0], vec![(3, 5)]);
+    assert_eq!(state.total_matches, 1);
+}
+
+#[test]
+fn test_parse_emboss_gff_uses_sequence_index() {
+    // Headers share their first token, so only the index identifies the sequence.
+    let headers = vec![String::from("seq 1"), String::from("seq 2")];
+    let sequences = vec![String::from("AC-GT"), String::from("-ACGT")];
+    let aln = Alignment::from_vecs(headers, sequences);
+    let gff = format!(
+        "##gff-version 3\n{}\tfuzznuc\tnucleotide_motif\t2\t3\t2.000\t+\t.\tID=x\n",
+        super::emboss_seqid(1)
+    );
+    let state = super::parse_gff_to_state(&aln, &gff, "CG", super::emboss_seq_index).unwrap();
+    assert!(state.spans_by_seq[0].is_empty());
+    assert_eq!(state.spans_by_seq[1], vec![(2, 4)]);
+    assert_eq!(state.kind, SearchKind::Emboss);
+    assert_eq!(super::emboss_seq_index("seq"), None);
+}
+
+#[test]
+fn test_collapse_and_expand_clade() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![String::from("AA"), String::from("BB"), String::from("CC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    // Preorder ids: root 0, R1 1, (R2,R3) 2, R2 3, R3 4.
+    app.tree = Some(parse_newick("(R1,(R2,R3));").unwrap());
+    app.set_tree_ordering_from_tree().unwrap();
+
+    assert_eq!(app.collapse_clade(2).unwrap(), 1);
+    assert!(app.is_clade_collapsed(2));
+    assert_eq!(app.ordering, vec![0, 1]);
+    assert_eq!(app.num_visible_seq(), 2);
+    assert_eq!(app.num_seq(), 3);
+    assert_eq!(app.rank_to_screenline(2), 1);
+    assert_eq!(app.tree_lines().len(), 2);
+    assert!(app.collapse_clade(1).is_err());
+
+    assert!(app.expand_clade(2).unwrap());
+    assert_eq!(app.ordering, vec![0, 1, 2]);
+    assert_eq!(app.tree_lines().len(), 3);
+}
+
+#[test]
+fn test_apply_tree_ordering_reports_unmatched() {
+    let hdrs = vec![
+        String::from("1 CELEG-F08G5 1a"),
+        String::from("seq2"),
+        String::from("seq3"),
+    ];
+    let seqs = vec![String::from("AA"), String::from("BB"), String::from("CC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.tree = Some(parse_newick("(seq2,(1_CELEG-F08G5_1a,ghost));").unwrap());
+    let mismatch = app.apply_tree_ordering().unwrap();
+    assert_eq!(mismatch.unmatched_leaves, vec![String::from("ghost")]);
+    assert_eq!(mismatch.missing_headers, vec![String::from("seq3")]);
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::User);
+    assert_eq!(app.ordering, vec![1, 0, 2]);
+}
+
+#[test]
+fn test_tree_header_mismatches() {
+    let hdrs = vec![String::from("A"), String::from("B"), String::from("C")];
+    let seqs = vec![String::from("AA"), String::from("BB"), String::from("CC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    assert!(app.tree_header_mismatches().is_err());
+
+    app.tree = Some(parse_newick("((A,B),X);").unwrap());
+    let mismatch = app.tree_header_mismatches().unwrap();
+    assert_eq!(mismatch.unmatched_leaves, vec![String::from("X")]);
+    assert_eq!(mismatch.missing_headers, vec![String::from("C")]);
+    // Checking does not reorder.
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::SourceFile);
+}
+
+#[test]
+fn test_user_ordering_keeps_unmatched_sequences() {
+    let hdrs = vec![String::from("A"), String::from("B"), String::from("C")];
+    let seqs = vec![String::from("AA"), String::from("BB"), String::from("CC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    // An unknown name used to cut the ordering short, hiding the sequences after it.
+    app.user_ordering = Some(vec![
+        String::from("C"),
+        String::from("ghost"),
+        String::from("A"),
+    ]);
+    app.ordering_criterion = SeqOrdering::User;
+    app.recompute_ordering();
+    assert_eq!(app.ordering, vec![2, 0, 1]);
+}
+
+#[test]
+fn test_user_ordering_with_missing_leading_header() {
+    let hdrs = vec![
+        String::from("A"),
+        String::from("B"),
+        String::from("C"),
+        String::from("D"),
+    ];
+    let seqs = vec![
+        String::from("AA"),
+        String::from("BB"),
+        String::from("CC"),
+        String::from("DD"),
+    ];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.user_ordering = Some(vec![
+        String::from("nonexistent"),
+        String::from("D"),
+        String::from("B"),
+    ]);
+    app.ordering_criterion = SeqOrdering::User;
+    app.recompute_ordering();
+    assert_eq!(app.ordering.len(), app.num_visible_seq() as usize);
+    assert_eq!(app.ordering, vec![3, 1, 0, 2]);
+    for (line, rank) in app.ordering.iter().enumerate() {
+        assert_eq!(app.reverse_ordering[*rank], line);
+    }
+}
+
+#[test]
+fn test_load_tree_file() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![String::from("AA"), String::from("BB"), String::from("CC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+
+    let mut path = std::env::temp_dir();
+    path.push("msafara-test-load-tree.nwk");
+    std::fs::write(&path, "(R1,(R2,R3));\n").unwrap();
+    let mismatch = app.load_tree_file(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert!(mismatch.is_empty());
+    assert!(app.tree().is_some());
+    assert_eq!(app.tree_lines().len(), 3);
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::User);
+    assert_eq!(app.ordering, vec![0, 1, 2]);
+    let view = app.views.get("original").expect("view");
+    assert!(view.tree.is_some());
+}
+
+#[test]
+fn test_write_tree_round_trip() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![String::from("AA"), String::from("BB"), String::from("CC")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    assert!(app.write_tree(Path::new("unused.nwk")).is_err());
+    app.tree = Some(parse_newick("(R2:0.5,(R1,R3):1.5);").unwrap());
+
+    let mut path = std::env::temp_dir();
+    path.push("msafara-test-write-tree.nwk");
+    app.write_tree(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(text.trim(), "(R2:0.5,(R1,R3):1.5);");
+}
+
+#[test]
+fn test_info_json_fields() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("AC-T"), String::from("ACGT")];
+    let app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    let info = app.info_json();
+    let mut fields: Vec<&str> = info
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![
+            "conserved_columns",
+            "gap_fraction",
+            "macromolecule_type",
+            "max_ungapped_length",
+            "mean_ungapped_length",
+            "min_ungapped_length",
+            "name",
+            "nb_columns",
+            "nb_sequences",
+        ]
+    );
+    assert_eq!(info["macromolecule_type"], "nucleic");
+    assert_eq!(info["min_ungapped_length"], 3);
+    assert_eq!(info["max_ungapped_length"], 4);
+    assert_eq!(info["mean_ungapped_length"], 3.5);
+    assert_eq!(info["conserved_columns"], 3);
+}
+
+#[test]
+fn test_export_stats_tsv() {
+    // No ties in the consensus, which would otherwise be picked arbitrarily.
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![
+        String::from("AC-T"),
+        String::from("ACGT"),
+        String::from("ACGT"),
+    ];
+    let app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.stats.tsv", std::process::id()));
+    app.export_stats(&path, StatsFormat::Tsv).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    let lines: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+    assert_eq!(lines[0], "header\tungapped_length\tpct_id_consensus\tgaps");
+    assert_eq!(lines[1], "R1\t3\t75.00\t1");
+    assert!(text.contains("# conserved_columns\t3\n"));
+}
+
+#[test]
+fn test_write_unaligned_fasta() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![
+        String::from("-Ac-.T-"),
+        String::from("-------"),
+        String::from("GGA TTC"),
+    ];
+    let app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.degap.fas", std::process::id()));
+    let empty = app.write_unaligned_fasta(&path).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    assert_eq!(empty, 1);
+    assert_eq!(text, ">R1\nAcT\n>R2\n\n>R3\nGGATTC\n");
+}
+
+#[test]
+fn test_write_print_html() {
+    let hdrs = vec![String::from("R1"), String::from("R<2>"), String::from("R3")];
+    let seqs = vec![
+        String::from("ACGT"),
+        String::from("ACGA"),
+        String::from("AC-T"),
+    ];
+    let app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.print.html", std::process::id()));
+    app.write_print_html(&path).unwrap();
+    let html = fs::read_to_string(&path).unwrap();
+    let _ = fs::remove_file(&path);
+    // Columns 1 and 2 are conserved (bold); residues matching the consensus are dots.
+    assert!(html.contains("Consensus <b>A</b><b>C</b>gt\n"), "{}", html);
+    assert!(html.contains("R1        <b>.</b><b>.</b>..\n"), "{}", html);
+    assert!(
+        html.contains("R&lt;2&gt;      <b>.</b><b>.</b>.A\n"),
+        "{}",
+        html
+    );
+    assert!(html.contains("R3        <b>.</b><b>.</b>-.\n"), "{}", html);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+}
+
+#[test]
+fn test_write_consensus_fasta() {
+    let hdrs: Vec<String> = (1..=6).map(|i| format!("R{}", i)).collect();
+    let seqs = vec![
+        String::from("AC-GAT"),
+        String::from("AC-GCT"),
+        String::from("AC-GG-"),
+        String::from("AC-GT-"),
+        String::from("AC-TR-"),
+        String::from("A-ATY-"),
+    ];
+    let app = App::new("data/aln.fas", Alignment::from_vecs(hdrs, seqs), None);
+    assert_eq!(app.default_consensus_name(), "aln_consensus");
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.cons.fas", std::process::id()));
+    // Gap-majority columns are left out, the weakly conserved one is lower-case, and the
+    // unconserved one is X.
+    let len = app.write_consensus_fasta(&path, "cons", None).unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(fs::read_to_string(&path).unwrap(), ">cons\nACgX\n");
+    // Column 1 is only 5/6 occupied.
+    app.write_consensus_fasta(&path, "cons", Some(0.9)).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), ">cons\nAgX\n");
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_edits_and_undo() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("ACGTAC"), String::from("AC-TAC")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.set_column_annotations(vec![(String::from("SS_cons"), String::from("HHEE.."))]);
+    app.regex_search_sequences("TA");
+    assert_eq!(app.seq_search_spans().unwrap()[0], vec![(3, 5)]);
+
+    // Two gap columns at column 1: the match follows.
+    app.insert_gap_columns(1, 2).unwrap();
+    assert_eq!(app.aln_len_usize(), 8);
+    assert_eq!(app.alignment.sequences(), ["A--CGTAC", "A--C-TAC"]);
+    assert_eq!(app.column_annotation("SS_cons"), Some("H..HEE.."));
+    assert_eq!(app.seq_search_spans().unwrap()[0], vec![(5, 7)]);
+
+    // Deleting past the end removes what there is.
+    assert_eq!(app.delete_columns(6, 5).unwrap(), 2);
+    assert_eq!(app.alignment.sequences(), ["A--CGT", "A--C-T"]);
+    assert_eq!(app.column_annotation("SS_cons"), Some("H..HEE"));
+    assert_eq!(app.seq_search_counts(), Some((0, 0)));
+    // The last column always stays.
+    assert_eq!(app.delete_columns(0, 10).unwrap(), 5);
+    assert_eq!(app.alignment.sequences(), ["T", "T"]);
+    assert_eq!(app.delete_columns(0, 1).unwrap(), 0);
+
+    // Undo goes back through the edits.
+    assert_eq!(
+        app.undo_edit().unwrap(),
+        AlignmentEdit::Deleted { at: 0, count: 5 }
+    );
+    assert_eq!(app.alignment.sequences(), ["A--CGT", "A--C-T"]);
+    assert_eq!(
+        app.undo_edit().unwrap(),
+        AlignmentEdit::Deleted { at: 6, count: 2 }
+    );
+    assert_eq!(app.column_annotation("SS_cons"), Some("H..HEE.."));
+    assert_eq!(
+        app.undo_edit().unwrap(),
+        AlignmentEdit::Inserted { at: 1, count: 2 }
+    );
+    assert_eq!(app.alignment.sequences(), ["ACGTAC", "AC-TAC"]);
+    assert_eq!(app.column_annotation("SS_cons"), Some("HHEE.."));
+    assert_eq!(app.seq_search_spans().unwrap()[0], vec![(3, 5)]);
+    assert!(app.undo_edit().is_err());
+}
+
+#[test]
+fn test_shift_residue_and_undo() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("AC---GT"), String::from("ACGTAGT")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    // Stops at the next residue.
+    assert_eq!(
+        app.shift_residue(0, 1, ShiftDirection::Right, 10).unwrap(),
+        Some(4)
+    );
+    assert_eq!(app.alignment.sequences()[0], "A---CGT");
+    assert_eq!(
+        app.shift_residue(1, 1, ShiftDirection::Right, 1).unwrap(),
+        None
+    );
+    assert_eq!(
+        app.undo_edit().unwrap(),
+        AlignmentEdit::Shifted {
+            rank: 0,
+            from: 1,
+            to: 4
+        }
+    );
+    assert_eq!(app.alignment.sequences()[0], "AC---GT");
+    assert!(app.undo_edit().is_err());
+}
+
+#[test]
+fn test_read_only_refuses_changes() {
+    let hdrs = vec![String::from("R1"), String::from("R1"), String::from("R3")];
+    let seqs = vec![
+        String::from("AC---GT"),
+        String::from("ACGTAGT"),
+        String::from("ACGTAGT"),
+    ];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.insert_gap_columns(1, 1).unwrap();
+    app.set_read_only(true);
+    let read_only = |err: TermalError| err.to_string().contains("Read-only mode");
+    assert!(app.insert_gap_columns(0, 1).is_err_and(read_only));
+    assert!(app.delete_columns(0, 1).is_err_and(read_only));
+    assert!(app
+        .shift_residue(0, 2, ShiftDirection::Right, 1)
+        .is_err_and(read_only));
+    assert!(app.undo_edit().is_err_and(read_only));
+    assert!(app.trim_columns(0.5).is_err_and(read_only));
+    assert!(app.deduplicate_headers().is_err_and(read_only));
+    assert!(app.remove_sequences(&[0]).is_err_and(read_only));
+    assert!(app.add_ids_to_view("rejected", &[0]).is_err_and(read_only));
+    assert!(app
+        .relabel(&[(String::from("R3"), String::from("R4"))])
+        .is_err_and(read_only));
+    assert_eq!(app.alignment.sequences()[0], "A-C---GT");
+    assert_eq!(app.alignment.headers(), ["R1", "R1", "R3"]);
+}
+
+#[test]
+fn test_column_origin() {
+    let hdrs = vec![String::from("R1")];
+    let seqs = vec![String::from("ACGTACGT")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert_eq!(app.display_col(0), 1);
+    assert_eq!(app.internal_col(1), Some(0));
+    app.set_column_origin(10001);
+    assert_eq!(app.display_col(0), 10001);
+    assert_eq!(app.internal_col(10005), Some(4));
+    assert_eq!(app.internal_col(10008), Some(7));
+    assert_eq!(app.internal_col(10009), None);
+    assert_eq!(app.internal_col(5), None);
+    app.set_column_origin(-3);
+    assert_eq!(app.display_col(3), 0);
+    assert_eq!(app.internal_col(-3), Some(0));
+}
+
+#[test]
+fn test_load_gff_groups_features_by_type() {
+    let hdrs = vec![String::from("s1 first"), String::from("s2")];
+    let seqs = vec![String::from("A--CGT"), String::from("ACGTAA")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.gff", std::process::id()));
+    fs::write(
+        &path,
+        "##gff-version 3\n\
+         s1\tsrc\tgene\t2\t3\t.\t+\t.\tID=g1\n\
+         s2\tsrc\tCDS\t1\t2\t.\t+\t.\tID=c1\n\
+         s2\tsrc\tgene\t5\t6\t.\t+\t.\tID=g2\n\
+         s9\tsrc\tgene\t1\t2\t.\t+\t.\tID=g3\n",
+    )
+    .unwrap();
+    let (types, skipped) = app.load_gff(&path).unwrap();
+    fs::remove_file(&path).ok();
+    assert_eq!((types, skipped), (2, 1));
+    let searches = app.saved_searches();
+    assert_eq!(searches[0].name, "gene");
+    assert_eq!(searches[0].kind, SearchKind::Gff);
+    assert_eq!(searches[0].spans_by_seq[0], vec![(3, 5)]);
+    assert_eq!(searches[0].spans_by_seq[1], vec![(4, 6)]);
+    assert_eq!(searches[1].name, "CDS");
+    assert_eq!(searches[1].spans_by_seq[1], vec![(0, 2)]);
+    assert_ne!(searches[0].color, searches[1].color);
+    // Refreshing re-maps the loaded features: the file is not needed anymore.
+    app.refresh_saved_searches_public();
+    assert_eq!(app.saved_searches()[0].spans_by_seq[1], vec![(4, 6)]);
+}
+
+#[test]
+fn test_label_match_wrap_message() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("X3")];
+    let seqs = vec![String::from("AA"), String::from("CC"), String::from("GG")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.regex_search_labels("R");
+    app.increment_current_lbl_match(1);
+    assert_eq!(app.current_message().message, "match #2/2");
+    app.increment_current_lbl_match(1);
+    assert_eq!(app.current_message().message, "match #1/2 (wrapped)");
+    app.increment_current_lbl_match(-1);
+    assert_eq!(app.current_message().message, "match #2/2 (wrapped)");
+}
+
+#[test]
+fn test_duplicate_headers_warning_and_dedup() {
+    let hdrs = vec![
+        String::from("R1"),
+        String::from("R2"),
+        String::from("R1"),
+        // Already taken, so the second R1 must become R1#3.
+        String::from("R1#2"),
+    ];
+    let seqs = vec![
+        String::from("AAAAA"),
+        String::from("AAAAC"),
+        String::from("ACCCC"),
+        String::from("TTTTT"),
+    ];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert_eq!(
+        app.duplicate_headers_warning().as_deref(),
+        Some("Duplicate headers: R1 - use :dedup")
+    );
+
+    assert_eq!(app.deduplicate_headers().unwrap(), 1);
+    assert_eq!(app.alignment.headers(), vec!["R1", "R2", "R1#3", "R1#2"]);
+    assert_eq!(app.duplicate_headers_warning(), None);
+    assert_eq!(app.deduplicate_headers().unwrap(), 0);
+
+    // The renamed header stays with its sequence when reordering, and can be searched for.
+    app.next_ordering_criterion();
+    assert_eq!(app.ordering, vec![3, 2, 0, 1]);
+    app.regex_search_labels("#3$");
+    assert_eq!(app.search_state.as_ref().unwrap().match_linenums, vec![2]);
+}
+
+#[test]
+fn test_diff_overlay_cycles_columns() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![
+        String::from("ACGTACGT"),
+        String::from("ACCTAC-A"),
+        String::from("ACGTACGT"),
+    ];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert!(app.set_diff(0, 3).is_err());
+    assert_eq!(app.set_diff(0, 1).unwrap(), 2);
+    let spans = app.diff_spans().unwrap();
+    assert_eq!(spans[0], vec![(2, 3), (7, 8)]);
+    assert_eq!(spans[1], vec![(2, 3), (7, 8)]);
+    assert!(spans[2].is_empty());
+    assert_eq!(app.increment_current_diff(1), Some((7, 2, 2, false)));
+    assert_eq!(app.increment_current_diff(1), Some((2, 1, 2, true)));
+    assert!(app.clear_diff());
+    assert!(app.diff_spans().is_none());
+}
+
+#[test]
+fn test_hide_all_gap_sequences() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![
+        String::from("ACGT"),
+        String::from("----"),
+        String::from("AC-T"),
+    ];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert_eq!(app.num_hidden_all_gap(), 0);
+    assert!(app.toggle_hide_all_gap_sequences());
+    assert_eq!(app.ordering, vec![0, 2]);
+    assert_eq!(app.num_visible_seq(), 2);
+    assert_eq!(app.num_hidden_all_gap(), 1);
+    // Ranks still refer to the alignment's sequences.
+    app.regex_search_labels("R3");
+    assert_eq!(app.search_state.as_ref().unwrap().match_linenums, vec![2]);
+    assert_eq!(app.rank_to_screenline(2), 1);
+    assert!(!app.toggle_hide_all_gap_sequences());
+    assert_eq!(app.ordering, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_similarity_ordering() {
+    let hdrs = vec![
+        String::from("R1"),
+        String::from("R2"),
+        String::from("R3"),
+        String::from("R4"),
+    ];
+    let seqs = vec![
+        String::from("CCCCCCCCCC"),
+        String::from("AAAAAAACCC"),
+        String::from("AAAAAAAAAA"),
+        String::from("AAAAAAAACC"),
+    ];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    // Total p-distances: R1 2.5, R2 1.1, R3 1.5, R4 1.1. R2 wins the tie for most central, then
+    // comes its nearest neighbour R4 (.1), then R3 (.2 from R4, vs .8 for R1), then R1.
+    app.next_ordering_criterion(); // %id ascending
+    app.next_ordering_criterion(); // %id descending
+    app.next_ordering_criterion(); // match
+    app.next_ordering_criterion(); // no user ordering: similarity
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::Similarity);
+    assert_eq!(app.ordering_status_label(), "o:simil");
+    assert_eq!(app.ordering, vec![1, 3, 2, 0]);
+    assert_eq!(app.reverse_ordering, vec![3, 0, 2, 1]);
+    app.next_ordering_criterion();
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::SourceFile);
+    app.prev_ordering_criterion();
+    assert_eq!(app.get_seq_ordering(), SeqOrdering::Similarity);
+}
+
+#[test]
+fn test_pin_sequences() {
+    let hdrs = vec![String::from("R1"), String::from("R2"), String::from("R3")];
+    let seqs = vec![
+        String::from("ACGT"),
+        String::from("AC-T"),
+        String::from("A-GT"),
+    ];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    assert!(app.pin_sequence(2));
+    assert!(!app.pin_sequence(2));
+    assert!(app.pin_sequence(0));
+    assert_eq!(app.pinned_ranks(), vec![2, 0]);
+    assert_eq!(app.ordering, vec![1]);
+    assert_eq!(app.num_visible_seq(), 1);
+    // At least one sequence stays in the scrolling region.
+    assert!(!app.pin_sequence(1));
+    assert!(app.unpin_sequence(2));
+    assert!(!app.unpin_sequence(2));
+    assert_eq!(app.ordering, vec![1, 2]);
+    assert_eq!(app.unpin_all(), 1);
+    assert_eq!(app.ordering, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_prompt_history() {
+    let hdrs = vec![String::from("R1")];
+    let seqs = vec![String::from("ACGT")];
+    let mut app = App::new("TEST", Alignment::from_vecs(hdrs, seqs), None);
+    app.push_history(PromptKind::Command, "hg");
+    app.push_history(PromptKind::Command, "hg");
+    app.push_history(PromptKind::Command, "  ");
+    app.push_history(PromptKind::Command, "wrap");
+    app.push_history(PromptKind::Command, "hg");
+    assert_eq!(app.history(PromptKind::Command), ["hg", "wrap", "hg"]);
+    assert!(app.history(PromptKind::Search).is_empty());
+    for i in 0..150 {
+        app.push_history(PromptKind::Search, &format!("q{}", i));
+    }
+    let searches = app.history(PromptKind::Search);
+    assert_eq!(searches.len(), 100);
+    assert_eq!(searches[0], "q50");
+}
@@ -2,12 +2,48 @@
 // Copyright (c) 2025 Thomas Junier
 // Modifications (c) 2026 Peter Carlton
 
-use std::{fmt, io};
+use std::{fmt, io, path::Path};
 
 #[derive(Debug)]
 pub enum TermalError {
     Io(io::Error),
     Format(String),
+    // A malformed input file: where (1-based line) and what.
+    Parse {
+        file: Option<String>,
+        line: usize,
+        msg: String,
+    },
+}
+
+// Longest part of an offending line quoted in a parse error.
+const MAX_SNIPPET_CHARS: usize = 40;
+
+impl TermalError {
+    /// A parse error at (1-based) `line`, quoting (the start of) the offending `text`.
+    pub fn parse(line: usize, msg: &str, text: &str) -> Self {
+        let mut snippet: String = text.chars().take(MAX_SNIPPET_CHARS).collect();
+        if text.chars().count() > MAX_SNIPPET_CHARS {
+            snippet.push('…');
+        }
+        TermalError::Parse {
+            file: None,
+            line,
+            msg: format!("{}: {:?}", msg, snippet),
+        }
+    }
+
+    /// Names the file a parse error occurred in; other errors are returned unchanged.
+    pub fn in_file<P: AsRef<Path>>(self, path: P) -> Self {
+        match self {
+            TermalError::Parse { line, msg, .. } => TermalError::Parse {
+                file: Some(path.as_ref().display().to_string()),
+                line,
+                msg,
+            },
+            other => other,
+        }
+    }
 }
 
 // These allow conversion to TermalError, required for main() to return Result<()> and for '?' to
@@ -30,6 +66,16 @@ impl fmt::Display for TermalError {
         match self {
             TermalError::Io(e) => write!(f, "I/O error: {}", e),
             TermalError::Format(msg) => write!(f, "Format error: {}", msg),
+            TermalError::Parse {
+                file: Some(file),
+                line,
+                msg,
+            } => write!(f, "{}: line {}: {}", file, line, msg),
+            TermalError::Parse {
+                file: None,
+                line,
+                msg,
+            } => write!(f, "line {}: {}", line, msg),
         }
     }
 }
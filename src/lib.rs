@@ -2,6 +2,7 @@
 // Copyright (c) 2025 Thomas Junier
 // Modifications (c) 2026 Peter Carlton
 
+mod aligner;
 pub mod alignment;
 pub mod app;
 pub mod errors;
@@ -14,6 +15,8 @@ mod vec_f64_aux;
 
 use crate::errors::TermalError;
 
+pub use crate::ui::snapshot::{buffer_to_svg, buffer_to_text, render_to_buffer, RenderOpts};
+
 pub fn run() -> Result<(), TermalError> {
     runner::run()
 }
@@ -5,27 +5,38 @@
 use std::{
     fmt,
     fs::File,
-    io::{stdin, stdout, BufRead, BufReader, BufWriter, Write},
+    io::{stdin, stdout, BufRead, BufReader, BufWriter, Read, Write},
     path::{Path, PathBuf},
     process::{Command, Stdio},
-    time::Duration,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
 use log::info;
 
-use crate::alignment::Alignment;
+use crate::aligner::{
+    alignment_cache_key, cached_alignment, store_cached_alignment, tree_sidecar_path,
+    AlignerCommand, DEFAULT_ALIGNER_TEMPLATE,
+};
+use crate::alignment::{Alignment, SeqType};
 use crate::app::{App, TermalConfig};
-use crate::seq::clustal::read_clustal_file;
-use crate::seq::fasta::read_fasta_file;
-use crate::seq::stockholm::read_stockholm_file;
+use crate::seq::clustal::{read_clustal_file, read_clustal_reader};
+use crate::seq::fasta::{read_fasta_file, read_fasta_reader};
+use crate::seq::file::{check_aligned, is_ragged, maybe_gunzip};
+use crate::seq::stockholm::{read_stockholm_file_annotated, read_stockholm_reader_annotated};
 use crate::tree::{parse_newick, tree_lines_and_order, TreeNode};
-use crate::ui::{key_handling::handle_key_press, render::render_ui, UI};
+use crate::ui::{
+    key_handling::{handle_key_press, handle_mouse},
+    render::render_ui,
+    resolve_quit, UI,
+};
 
 use clap::{CommandFactory, Parser, ValueEnum};
 use serde_json::json;
 
 use crossterm::{
-    event::{self, KeyEventKind},
+    event::{self, DisableMouseCapture, EnableMouseCapture, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
@@ -37,11 +48,14 @@ use ratatui::{
 
 use crate::errors::TermalError;
 
+/// Alignment "file name" that stands for the standard input.
+const STDIN_FNAME: &str = "-";
+
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None) ]
 struct Cli {
-    /// Alignment file
-    aln_fname: Option<String>,
+    /// Alignment file(s), `-` for stdin; Ctrl-N / Ctrl-P switch between them
+    aln_fnames: Vec<String>,
 
     /// Show key bindings and exit successfully
     #[arg(short = 'b', long = "show-bindings")]
@@ -51,6 +65,10 @@ struct Cli {
     #[arg(short, long)]
     info: bool,
 
+    /// Output format of info mode
+    #[arg(long = "info-format", value_enum, default_value_t = InfoFormat::Text)]
+    info_format: InfoFormat,
+
     /// Sequence file format
     #[arg(short, long = "format", default_value_t = SeqFileFormat::FastA,
         help = "Sequence file format [fasta|clustal|stockholm] (or just f|c|s); default: fasta",
@@ -87,11 +105,52 @@ struct Cli {
     #[arg(short = 'o', long)]
     user_order: Option<String>,
 
+    /// Pad sequences of unequal length with gaps (with a warning) instead of refusing them, when
+    /// they cannot be aligned (Clustal, Stockholm, or no aligner)
+    #[arg(long = "pad")]
+    pad: bool,
+
+    /// Browse only: refuse edits, rejections, realigning, and overwriting the alignment file
+    #[arg(long = "read-only")]
+    read_only: bool,
+
+    /// Always run the aligner on unaligned input, ignoring cached alignments
+    #[arg(long = "no-cache")]
+    no_cache: bool,
+
+    /// Newick tree file (orders sequences by its leaves and shows the tree panel; first alignment)
+    #[arg(long = "tree")]
+    tree: Option<String>,
+
+    /// Tab-separated file of old and new sequence names to rename sequences with (all alignments)
+    #[arg(long = "relabel")]
+    relabel: Option<String>,
+
+    /// GFF file of features to highlight (one colored overlay per feature type; first alignment)
+    #[arg(long = "gff")]
+    gff: Option<String>,
+
     // TODO: superseded by BW colormap
     /// Disable color
     #[arg(short = 'C', long = "no-color")]
     no_color: bool,
 
+    /// Color scheme at startup [dark|light|monochrome] (overrides default_scheme in the config)
+    #[arg(long = "scheme", value_enum)]
+    scheme: Option<StartupScheme>,
+
+    /// Video mode at startup [direct|inverse] (overrides default_video_mode in the config)
+    #[arg(long = "video-mode", value_enum)]
+    video_mode: Option<StartupVideoMode>,
+
+    /// Molecule type [dna|rna|protein], overriding the detected one (selects the default colormap)
+    #[arg(long = "molecule", value_enum)]
+    molecule: Option<SeqType>,
+
+    /// Do not capture the mouse (leaves the terminal's own text selection working)
+    #[arg(long = "no-mouse")]
+    no_mouse: bool,
+
     /// Disable scrollbars (mostly for testing)
     #[arg(long = "no-scrollbars")]
     no_scrollbars: bool,
@@ -141,6 +200,31 @@ impl fmt::Display for SeqFileFormat {
     }
 }
 
+/// Output formats of `-i` (`--info-format`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum InfoFormat {
+    /// name, nb_sequences and nb_columns, one per line
+    #[default]
+    Text,
+    /// one JSON object per file, with composition and length statistics
+    Json,
+}
+
+/// Color scheme the UI starts with (`default_scheme` in `.msafara.config`, or `--scheme`).
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum StartupScheme {
+    Dark,
+    Light,
+    Monochrome,
+}
+
+/// Video mode the UI starts with (`default_video_mode` in `.msafara.config`, or `--video-mode`).
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum StartupVideoMode {
+    Direct,
+    Inverse,
+}
+
 // pub fn read_fasta_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, std::io::Error> {
 fn read_user_ordering(fname: &str) -> Result<Vec<String>, std::io::Error> {
     let uord_file = File::open(fname)?;
@@ -217,6 +301,7 @@ fn prompt_create_config() -> Result<Option<PathBuf>, TermalError> {
         "luminance_threshold": colors.luminance_threshold,
         "emboss_bin_dir": emboss_bin_dir.as_ref().map(|p| p.to_string_lossy()),
         "mafft_bin_dir": mafft_bin_dir.as_ref().map(|p| p.to_string_lossy()),
+        "aligner": { "command": DEFAULT_ALIGNER_TEMPLATE },
     });
 
     let contents = serde_json::to_string_pretty(&config)
@@ -226,15 +311,6 @@ fn prompt_create_config() -> Result<Option<PathBuf>, TermalError> {
     Ok(Some(config_path))
 }
 
-fn needs_alignment(seq_file: &crate::seq::file::SeqFile) -> bool {
-    let mut iter = seq_file.iter();
-    let Some(first) = iter.next() else {
-        return false;
-    };
-    let first_len = first.sequence.len();
-    iter.any(|rec| rec.sequence.len() != first_len)
-}
-
 struct AutoAlignResult {
     seq_file: crate::seq::file::SeqFile,
     tree: Option<TreeNode>,
@@ -244,60 +320,175 @@ struct AutoAlignResult {
     tree_error: Option<String>,
 }
 
-fn align_fasta_with_mafft(
+// Aligns `seq_file` with the aligner described by `template`. With a `cache_key`, a previously
+// cached alignment is reused, and a fresh one is cached (failures to cache are ignored).
+fn align_fasta_with_tool(
     seq_file: &crate::seq::file::SeqFile,
+    template: &str,
     mafft_bin_dir: Option<&Path>,
+    cache_key: Option<&str>,
 ) -> Result<AutoAlignResult, TermalError> {
-    let mafft_bin_dir = mafft_bin_dir.ok_or_else(|| {
-        TermalError::Format(String::from(
-            "Unaligned FASTA requires mafft. Install mafft and set mafft_bin_dir in .msafara.config.",
-        ))
-    })?;
-    let mut input_tmp = std::env::temp_dir();
-    let unique_in = format!("msafara-mafft-auto-{}.in.fa", std::process::id());
-    input_tmp.push(unique_in);
-    {
-        let file = std::fs::File::create(&input_tmp)?;
-        let mut writer = BufWriter::new(file);
-        for record in seq_file {
-            writeln!(writer, ">{}", record.header)?;
-            writeln!(writer, "{}", record.sequence)?;
-        }
+    if let Some((fasta_path, tree_path)) = cache_key.and_then(cached_alignment) {
+        println!("Unaligned FASTA detected; using cached alignment");
+        let aligned = read_fasta_file(&fasta_path)?;
+        let tree_text = tree_path.and_then(|path| std::fs::read_to_string(path).ok());
+        return Ok(auto_align_result(aligned, tree_text, None));
     }
 
+    let mut input_tmp = std::env::temp_dir();
+    let unique_in = format!("msafara-align-auto-{}.in.fa", std::process::id());
+    input_tmp.push(unique_in);
     let mut output_path = std::env::temp_dir();
-    let unique_out = format!("msafara-mafft-auto-{}.out.fa", std::process::id());
+    let unique_out = format!("msafara-align-auto-{}.out.fa", std::process::id());
     output_path.push(unique_out);
 
-    println!("Unaligned FASTA detected; running mafft --maxiterate 1000 --localpair...");
-    stdout().flush().ok();
+    let command = AlignerCommand::from_template(template, &input_tmp, &output_path, mafft_bin_dir)?;
+    if command.is_mafft() && mafft_bin_dir.is_none() {
+        return Err(TermalError::Format(String::from(
+            "Unaligned FASTA requires mafft. Install mafft and set mafft_bin_dir in .msafara.config.",
+        )));
+    }
+    let tree_path = tree_sidecar_path(&input_tmp);
+    // The temporary files are removed whether or not the aligner and its output are usable.
+    let result = (|| {
+        {
+            let file = std::fs::File::create(&input_tmp)?;
+            let mut writer = BufWriter::new(file);
+            for record in seq_file {
+                writeln!(writer, ">{}", record.header)?;
+                writeln!(writer, "{}", record.sequence)?;
+            }
+        }
+
+        println!(
+            "Unaligned FASTA detected; running aligner: {}...",
+            command.command_line()
+        );
+        stdout().flush().ok();
+
+        run_aligner_with_progress(&command)?;
+        let aligned = read_fasta_file(&output_path)?;
+
+        let mut tree_error = None;
+        let tree_text = match std::fs::read_to_string(&tree_path) {
+            Ok(text) => Some(text),
+            // Only mafft is expected to produce a guide tree.
+            Err(e) if command.is_mafft() => {
+                tree_error = Some(format!("Failed to read mafft tree: {}", e));
+                None
+            }
+            Err(_) => None,
+        };
+        if let Some(key) = cache_key {
+            if let Err(e) = store_cached_alignment(key, &output_path, tree_text.as_deref()) {
+                info!("Could not cache alignment: {}", e);
+            }
+        }
+        Ok::<_, TermalError>(auto_align_result(aligned, tree_text, tree_error))
+    })();
+    std::fs::remove_file(&input_tmp).ok();
+    std::fs::remove_file(&output_path).ok();
+    std::fs::remove_file(&tree_path).ok();
+    result
+}
+
+// Runs the aligner while showing a spinner, the elapsed time and the aligner's latest stderr line
+// (mafft reports its progress there, often using '\r'). On failure, the captured stderr is dumped.
+fn run_aligner_with_progress(command: &AlignerCommand) -> Result<(), TermalError> {
+    const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+    const MAX_STATUS_LEN: usize = 60;
+
+    let mut child = command.spawn(Stdio::piped())?;
+    let log: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let reader = child.stderr.take().map(|mut stderr| {
+        let log = Arc::clone(&log);
+        thread::spawn(move || {
+            let mut buf = [0u8; 1024];
+            let mut line: Vec<u8> = Vec::new();
+            while let Ok(n) = stderr.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                for &byte in &buf[..n] {
+                    if byte == b'\n' || byte == b'\r' {
+                        if !line.is_empty() {
+                            let text = String::from_utf8_lossy(&line).trim().to_string();
+                            log.lock().unwrap().push(text);
+                            line.clear();
+                        }
+                    } else {
+                        line.push(byte);
+                    }
+                }
+            }
+            if !line.is_empty() {
+                log.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&line).trim().to_string());
+            }
+        })
+    });
 
-    let tool_path = mafft_bin_dir.join("mafft");
-    let output_file = File::create(&output_path)?;
-    let status = Command::new(tool_path)
-        .arg("--maxiterate")
-        .arg("1000")
-        .arg("--localpair")
-        .arg("--treeout")
-        .arg("--reorder")
-        .arg(&input_tmp)
-        .stdout(Stdio::from(output_file))
-        .stderr(Stdio::inherit())
-        .status()
-        .map_err(|e| TermalError::Format(format!("Failed to run mafft: {}", e)))?;
-    if !status.success() {
-        return Err(TermalError::Format(String::from("mafft failed")));
+    let start = Instant::now();
+    let mut tick = 0;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(TermalError::Format(format!(
+                    "Failed to run {}: {}",
+                    command.name(),
+                    e
+                )));
+            }
+        }
+        let last: String = log
+            .lock()
+            .unwrap()
+            .last()
+            .map(|line| line.chars().take(MAX_STATUS_LEN).collect())
+            .unwrap_or_default();
+        print!(
+            "\r\x1b[2K{} {:>5.1}s {}",
+            SPINNER[tick % SPINNER.len()],
+            start.elapsed().as_secs_f64(),
+            last
+        );
+        stdout().flush().ok();
+        tick += 1;
+        thread::sleep(Duration::from_millis(100));
+    };
+    if let Some(reader) = reader {
+        reader.join().ok();
+    }
+    println!(
+        "\r\x1b[2KAligner finished in {:.1}s",
+        start.elapsed().as_secs_f64()
+    );
+
+    let result = command.check_status(status);
+    if result.is_err() {
+        for line in log.lock().unwrap().iter() {
+            eprintln!("{}", line);
+        }
     }
-    let aligned = read_fasta_file(&output_path)?;
+    result
+}
 
-    let mut tree_error = None;
+fn auto_align_result(
+    seq_file: crate::seq::file::SeqFile,
+    tree_text: Option<String>,
+    mut tree_error: Option<String>,
+) -> AutoAlignResult {
     let mut tree = None;
     let mut tree_newick = None;
     let mut tree_lines = Vec::new();
     let mut tree_panel_width = 0;
-    let tree_path = PathBuf::from(format!("{}.tree", input_tmp.display()));
-    match std::fs::read_to_string(&tree_path) {
-        Ok(tree_text) => match parse_newick(&tree_text) {
+    if let Some(tree_text) = tree_text {
+        match parse_newick(&tree_text) {
             Ok(parsed) => {
                 if let Ok((lines, _order)) = tree_lines_and_order(&parsed) {
                     tree_panel_width = lines
@@ -312,25 +503,233 @@ fn align_fasta_with_mafft(
                 tree_newick = Some(tree_text);
             }
             Err(e) => {
-                tree_error = Some(format!("Failed to parse mafft tree: {}", e));
+                tree_error = Some(format!("Failed to parse aligner tree: {}", e));
             }
-        },
-        Err(e) => {
-            tree_error = Some(format!("Failed to read mafft tree: {}", e));
         }
     }
-
-    std::fs::remove_file(&input_tmp).ok();
-    std::fs::remove_file(&output_path).ok();
-    std::fs::remove_file(&tree_path).ok();
-    Ok(AutoAlignResult {
-        seq_file: aligned,
+    AutoAlignResult {
+        seq_file,
         tree,
         tree_newick,
         tree_lines,
         tree_panel_width,
         tree_error,
-    })
+    }
+}
+
+// Loads one alignment (or session) file into an App: aligns unaligned FASTA if needed (ragged
+// input that cannot be aligned is refused, or padded with `--pad`), applies the user ordering and
+// the configuration.
+fn load_app(
+    cli: &Cli,
+    seq_filename: &str,
+    config: Option<&TermalConfig>,
+) -> Result<App, TermalError> {
+    let mut auto_tree: Option<(TreeNode, String, Vec<String>, u16)> = None;
+    let mut auto_tree_err: Option<String> = None;
+    let mut app = if Path::new(seq_filename).extension().and_then(|s| s.to_str()) == Some("msfr") {
+        App::from_session_file(Path::new(seq_filename))?
+    } else {
+        // Stdin can only be read once, so it is buffered: the bytes are parsed like a file and
+        // reused as the cache key should the sequences need aligning.
+        let stdin_bytes = if seq_filename == STDIN_FNAME {
+            let mut bytes = Vec::new();
+            stdin().read_to_end(&mut bytes)?;
+            Some(bytes)
+        } else {
+            None
+        };
+        let mut column_annotations = Vec::new();
+        // Set if ragged input could not be aligned and was padded instead (`--pad`).
+        let mut padded_msg: Option<String> = None;
+        let seq_file = match cli.format {
+            SeqFileFormat::FastA => {
+                let seq_file = match &stdin_bytes {
+                    Some(bytes) => read_fasta_reader(maybe_gunzip(bytes.as_slice())?)?,
+                    None => read_fasta_file(seq_filename)?,
+                };
+                if is_ragged(&seq_file) {
+                    let template = config
+                        .map(|cfg| cfg.aligner.command.as_str())
+                        .unwrap_or(DEFAULT_ALIGNER_TEMPLATE);
+                    let cache_key = if cli.no_cache {
+                        None
+                    } else {
+                        match &stdin_bytes {
+                            Some(bytes) => Some(bytes.clone()),
+                            None => std::fs::read(seq_filename).ok(),
+                        }
+                        .map(|bytes| alignment_cache_key(&bytes, template))
+                    };
+                    match align_fasta_with_tool(
+                        &seq_file,
+                        template,
+                        config.and_then(|cfg| cfg.tools.mafft_bin_dir.as_deref()),
+                        cache_key.as_deref(),
+                    ) {
+                        Ok(aligned) => {
+                            if let Some(tree) = aligned.tree {
+                                if let Some(tree_text) = aligned.tree_newick {
+                                    auto_tree = Some((
+                                        tree,
+                                        tree_text,
+                                        aligned.tree_lines,
+                                        aligned.tree_panel_width,
+                                    ));
+                                }
+                            }
+                            auto_tree_err = aligned.tree_error;
+                            aligned.seq_file
+                        }
+                        Err(e) if cli.pad => {
+                            padded_msg = Some(format!(
+                                "Could not align ({}): sequences padded with gaps",
+                                e
+                            ));
+                            seq_file
+                        }
+                        Err(e) => return Err(e),
+                    }
+                } else {
+                    seq_file
+                }
+            }
+            SeqFileFormat::Clustal => match &stdin_bytes {
+                Some(bytes) => read_clustal_reader(maybe_gunzip(bytes.as_slice())?)?,
+                None => read_clustal_file(seq_filename)?,
+            },
+            SeqFileFormat::Stockholm => {
+                let (seq_file, annotations) = match &stdin_bytes {
+                    Some(bytes) => {
+                        read_stockholm_reader_annotated(maybe_gunzip(bytes.as_slice())?)?
+                    }
+                    None => read_stockholm_file_annotated(seq_filename)?,
+                };
+                column_annotations = annotations;
+                seq_file
+            }
+        };
+        if check_aligned(&seq_file, cli.pad)? && padded_msg.is_none() {
+            padded_msg = Some(String::from(
+                "Sequences have unequal length: padded with gaps",
+            ));
+        }
+        let alignment = Alignment::from_file(seq_file);
+        let mut ordering_err_msg: Option<String> = None;
+        let mut user_ordering = match &cli.user_order {
+            Some(fname) => {
+                // TODO: should be called from_path()
+                let get_ord_vec = read_user_ordering(fname);
+                match get_ord_vec {
+                    Ok(ord_vec) => Some(ord_vec),
+                    Err(_) => {
+                        ordering_err_msg = Some(format!("Error reading ordering file {}", fname));
+                        None // => App ignores bad user ordering
+                    }
+                }
+            }
+            None => None,
+        };
+        // Check for discrepancies beween the user-specied ordering and alignment headers. The two
+        // sets should be identical.
+        if let Some(ref ord_vec) = user_ordering {
+            let mut uo_clone = ord_vec.clone();
+            let mut ah_clone = alignment.headers().to_vec();
+            uo_clone.sort();
+            ah_clone.sort();
+            if uo_clone != ah_clone {
+                ordering_err_msg = Some(String::from("Discrepancies in ordering vs alignment"));
+                // App must ignore bad user ordering
+                user_ordering = None;
+            }
+        };
+        let mut app = App::new(seq_filename, alignment, user_ordering);
+        app.set_column_annotations(column_annotations);
+        if let Some(msg) = padded_msg {
+            app.warning_msg(msg);
+        }
+        if let Some(msg) = ordering_err_msg {
+            app.error_msg(msg);
+        }
+        app
+    };
+
+    if let Some((tree, tree_newick, tree_lines, tree_panel_width)) = auto_tree.take() {
+        app.set_tree_for_current_view(tree, tree_newick, tree_lines, tree_panel_width);
+    }
+    if let Some(msg) = auto_tree_err.take() {
+        app.error_msg(msg);
+    }
+    if let Some(config) = config.cloned() {
+        app.set_search_color_config(config.search_colors);
+        app.set_emboss_bin_dir(config.tools.emboss_bin_dir);
+        app.set_mafft_bin_dir(config.tools.mafft_bin_dir);
+        app.set_aligner_command(config.aligner.command);
+        if let Some(threshold) = config.consensus_threshold {
+            app.set_consensus_threshold(threshold);
+        }
+        app.set_flash_errors(config.display.flash_errors);
+    }
+    if let Some(molecule_type) = cli.molecule {
+        app.force_molecule_type(molecule_type);
+    }
+    let ut_equivalence = config
+        .and_then(|config| config.ut_equivalence)
+        .unwrap_or(app.macromolecule_type() == SeqType::Rna);
+    app.set_ut_equivalence(ut_equivalence);
+    Ok(app)
+}
+
+// Asks the terminal to put `text` on the system clipboard (OSC 52; terminals that do not support
+// it ignore the request).
+fn copy_to_terminal_clipboard(text: &str) -> Result<(), TermalError> {
+    let mut out = stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()?;
+    Ok(())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// Leaves the alternate screen and raw mode, and releases the mouse (harmless if it was not
+// captured).
+fn restore_terminal() -> Result<(), TermalError> {
+    stdout().execute(DisableMouseCapture)?;
+    stdout().execute(LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+    Ok(())
+}
+
+// Makes panics restore the terminal before the previous hook prints the message, so that the
+// message is readable and the shell usable. Returns a closure that reinstates the previous hook.
+fn install_panic_hook() -> impl FnOnce() {
+    let previous = Arc::new(std::panic::take_hook());
+    let chained = Arc::clone(&previous);
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal().ok();
+        (chained.as_ref())(info);
+    }));
+    move || {
+        let _ = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| (previous.as_ref())(info)));
+    }
 }
 
 pub fn run() -> Result<(), TermalError> {
@@ -338,20 +737,18 @@ pub fn run() -> Result<(), TermalError> {
     info!("Starting log");
 
     let cli = Cli::parse();
-    if cli.panic {
-        panic!("User-requested panic");
-    }
-
     if cli.show_bindings {
         println!("{}", crate::ui::USER_GUIDE);
         return Ok(());
     }
 
-    if let Some(seq_filename) = &cli.aln_fname {
+    if !cli.aln_fnames.is_empty() {
         let mut config_err: Option<String> = None;
         let mut config: Option<TermalConfig> = None;
         let mut config_path = find_msafara_config();
-        if config_path.is_none() {
+        // The prompt would read the piped alignment as its answer.
+        let reads_stdin = cli.aln_fnames.iter().any(|f| f == STDIN_FNAME);
+        if config_path.is_none() && !reads_stdin {
             match prompt_create_config() {
                 Ok(Some(path)) => config_path = Some(path),
                 Ok(None) => {}
@@ -366,104 +763,72 @@ pub fn run() -> Result<(), TermalError> {
                 }
             }
         }
-        let mut auto_tree: Option<(TreeNode, String, Vec<String>, u16)> = None;
-        let mut auto_tree_err: Option<String> = None;
-        let mut app = if Path::new(seq_filename).extension().and_then(|s| s.to_str())
-            == Some("msfr")
-        {
-            App::from_session_file(Path::new(seq_filename))?
-        } else {
-            let seq_file = match cli.format {
-                SeqFileFormat::FastA => {
-                    let seq_file = read_fasta_file(seq_filename)?;
-                    if needs_alignment(&seq_file) {
-                        let aligned = align_fasta_with_mafft(
-                            &seq_file,
-                            config
-                                .as_ref()
-                                .and_then(|cfg| cfg.tools.mafft_bin_dir.as_deref()),
-                        )?;
-                        if let Some(tree) = aligned.tree {
-                            if let Some(tree_text) = aligned.tree_newick {
-                                auto_tree = Some((
-                                    tree,
-                                    tree_text,
-                                    aligned.tree_lines,
-                                    aligned.tree_panel_width,
-                                ));
-                            }
-                        }
-                        auto_tree_err = aligned.tree_error;
-                        aligned.seq_file
-                    } else {
-                        seq_file
+        let mut apps: Vec<App> = Vec::with_capacity(cli.aln_fnames.len());
+        for seq_filename in &cli.aln_fnames {
+            apps.push(load_app(&cli, seq_filename, config.as_ref())?);
+        }
+
+        if let Some(relabel_fname) = &cli.relabel {
+            for app in apps.iter_mut() {
+                match app.relabel_from_file(Path::new(relabel_fname)) {
+                    Ok(renamed) => {
+                        app.info_msg(format!("Relabeled {} sequence(s)", renamed));
                     }
-                }
-                SeqFileFormat::Clustal => read_clustal_file(seq_filename)?,
-                SeqFileFormat::Stockholm => read_stockholm_file(seq_filename)?,
-            };
-            let alignment = Alignment::from_file(seq_file);
-            let mut ordering_err_msg: Option<String> = None;
-            let mut user_ordering = match cli.user_order {
-                Some(fname) => {
-                    // TODO: should be called from_path()
-                    let get_ord_vec = read_user_ordering(&fname);
-                    match get_ord_vec {
-                        Ok(ord_vec) => Some(ord_vec),
-                        Err(_) => {
-                            ordering_err_msg =
-                                Some(format!("Error reading ordering file {}", fname));
-                            None // => App ignores bad user ordering
-                        }
+                    Err(e) => {
+                        app.error_msg(format!("Error reading {}: {}", relabel_fname, e));
                     }
                 }
-                None => None,
-            };
-            // Check for discrepancies beween the user-specied ordering and alignment headers. The two
-            // sets should be identical.
-            if let Some(ref ord_vec) = user_ordering {
-                let mut uo_clone = ord_vec.clone();
-                let mut ah_clone = alignment.headers.clone();
-                uo_clone.sort();
-                ah_clone.sort();
-                if uo_clone != ah_clone {
-                    ordering_err_msg = Some(String::from("Discrepancies in ordering vs alignment"));
-                    // App must ignore bad user ordering
-                    user_ordering = None;
-                }
-            };
-            let mut app = App::new(seq_filename, alignment, user_ordering);
-            if let Some(msg) = ordering_err_msg {
-                app.error_msg(msg);
             }
-            app
-        };
-
-        if let Some((tree, tree_newick, tree_lines, tree_panel_width)) = auto_tree.take() {
-            app.set_tree_for_current_view(tree, tree_newick, tree_lines, tree_panel_width);
         }
-        if let Some(msg) = auto_tree_err.take() {
-            app.error_msg(msg);
+
+        // The tree and GFF options apply to the first alignment.
+        let app = &mut apps[0];
+        if let Some(tree_fname) = &cli.tree {
+            match app.load_tree_file(Path::new(tree_fname)) {
+                Ok(mismatch) if !mismatch.is_empty() => {
+                    app.warning_msg(format!("Tree {}: {}", tree_fname, mismatch.summary()));
+                }
+                Ok(_) => {}
+                Err(e) => app.error_msg(format!("Error reading tree {}: {}", tree_fname, e)),
+            }
         }
         if let Some(msg) = config_err.take() {
             app.error_msg(msg);
         }
-        if let Some(config) = config.take() {
-            app.set_search_color_config(config.search_colors);
-            app.set_emboss_bin_dir(config.tools.emboss_bin_dir);
-            app.set_mafft_bin_dir(config.tools.mafft_bin_dir);
+        if let Some(gff_fname) = &cli.gff {
+            match app.load_gff(Path::new(gff_fname)) {
+                Ok((_, skipped)) if skipped > 0 => app.warning_msg(format!(
+                    "GFF {}: skipped {} records on sequences not in the alignment",
+                    gff_fname, skipped
+                )),
+                Ok(_) => {}
+                Err(e) => app.error_msg(format!("Error reading GFF {}: {}", gff_fname, e)),
+            }
+        }
+        for app in apps.iter_mut() {
+            app.refresh_saved_searches_public();
+            app.recompute_current_seq_search();
+            app.clear_unsaved_changes();
+            app.set_read_only(cli.read_only);
         }
-        app.refresh_saved_searches_public();
-        app.recompute_current_seq_search();
 
         if cli.info {
             info!("Running in debug mode.");
-            app.output_info(); // TODO: can't this be done using info_msg()?
+            for app in &apps {
+                app.output_info(cli.info_format); // TODO: can't this be done using info_msg()?
+            }
             return Ok(());
         }
 
         stdout().execute(EnterAlternateScreen)?;
         enable_raw_mode()?;
+        let restore_panic_hook = install_panic_hook();
+        if !cli.no_mouse {
+            stdout().execute(EnableMouseCapture)?;
+        }
+        if cli.panic {
+            panic!("User-requested panic");
+        }
 
         let backend = CrosstermBackend::new(stdout());
         let viewport: Viewport;
@@ -479,32 +844,54 @@ pub fn run() -> Result<(), TermalError> {
         let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
         terminal.clear()?;
 
-        let mut app_ui = UI::new(&mut app);
-        if cli.no_scrollbars {
-            app_ui.disable_scrollbars();
-        }
-        if cli.no_color {
-            app_ui.set_monochrome();
-        }
-        if cli.no_zoombox {
-            app_ui.set_zoombox(false);
-        }
-        if cli.no_zb_guides {
-            app_ui.set_zoombox_guides(false);
-        }
-        if cli.hide_labels_pane {
-            app_ui.set_left_pane_width(0);
-        }
-        if cli.hide_bottom_pane {
-            app_ui.set_bottom_pane_height(0);
-        }
-        if let Some(path) = cli.color_map {
-            app_ui.add_user_colormap(&path);
-            app_ui.prev_colormap();
+        // One UI per alignment, each borrowing its own App; keys go to the current one.
+        let num_buffers = apps.len();
+        let mut app_uis: Vec<UI> = apps.iter_mut().map(UI::new).collect();
+        for (index, app_ui) in app_uis.iter_mut().enumerate() {
+            app_ui.set_buffer_position(index, num_buffers);
+            if cli.no_scrollbars {
+                app_ui.disable_scrollbars();
+            }
+            let display = config.as_ref().map(|cfg| cfg.display).unwrap_or_default();
+            if let Some(scheme) = cli.scheme.or(display.default_scheme) {
+                app_ui.set_color_scheme(scheme);
+            }
+            if let Some(mode) = cli.video_mode.or(display.default_video_mode) {
+                app_ui.set_video_mode(mode);
+            }
+            app_ui.set_conservation_shading(display.conservation_shading);
+            if let Some(width) = config.as_ref().and_then(|cfg| cfg.notes_indent_width) {
+                app_ui.set_notes_indent_width(width);
+            }
+            for class_map in config.iter().flat_map(|cfg| &cfg.residue_classes) {
+                app_ui.add_residue_class_colormap(class_map);
+            }
+            if cli.no_color {
+                app_ui.set_monochrome();
+            }
+            if cli.no_zoombox {
+                app_ui.set_zoombox(false);
+            }
+            if cli.no_zb_guides {
+                app_ui.set_zoombox_guides(false);
+            }
+            if cli.hide_labels_pane {
+                app_ui.set_left_pane_width(0);
+            }
+            if cli.hide_bottom_pane {
+                app_ui.set_bottom_pane_height(0);
+            }
+            if let Some(path) = &cli.color_map {
+                app_ui.add_user_colormap(path);
+                app_ui.prev_colormap();
+            }
         }
+        let mut current = 0;
 
         let poll_wait = Duration::from_millis(cli.poll_wait_time);
-        terminal.draw(|f| render_ui(f, &mut app_ui))?;
+        terminal.draw(|f| render_ui(f, &mut app_uis[current]))?;
+        // Whether the modeline was last drawn with a flashing error message.
+        let mut flash_shown = false;
 
         // main loop
         loop {
@@ -512,27 +899,55 @@ pub fn run() -> Result<(), TermalError> {
             if event::poll(poll_wait)? {
                 match event::read()? {
                     event::Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        let app_ui = &mut app_uis[current];
                         app_ui.clear_dirty();
-                        let done = handle_key_press(&mut app_ui, key);
+                        let done = handle_key_press(app_ui, key);
                         if done {
                             break;
                         }
+                        if let Some(text) = app_ui.take_clipboard() {
+                            copy_to_terminal_clipboard(&text)?;
+                        }
+                        let mut dirty = app_ui.take_dirty();
+                        let quit_request = app_ui.take_quit_request();
+                        if let Some(switch) = app_ui.take_buffer_switch() {
+                            current = switch.target(current, num_buffers);
+                            dirty = true;
+                        }
+                        if let Some(request) = quit_request {
+                            if resolve_quit(&mut app_uis, &mut current, request) {
+                                break;
+                            }
+                            dirty = true;
+                        }
+                        if dirty {
+                            terminal.draw(|f| render_ui(f, &mut app_uis[current]))?;
+                        }
+                    }
+                    event::Event::Mouse(mouse) => {
+                        let app_ui = &mut app_uis[current];
+                        app_ui.clear_dirty();
+                        handle_mouse(app_ui, mouse);
                         if app_ui.take_dirty() {
-                            terminal.draw(|f| render_ui(f, &mut app_ui))?;
+                            terminal.draw(|f| render_ui(f, &mut app_uis[current]))?;
                         }
                     }
                     event::Event::Resize(_, _) => {
-                        terminal.draw(|f| render_ui(f, &mut app_ui))?;
+                        terminal.draw(|f| render_ui(f, &mut app_uis[current]))?;
                     }
                     _ => {}
                 }
+            } else if flash_shown && !app_uis[current].message_flashing() {
+                // The flash is over: redraw the message plainly.
+                terminal.draw(|f| render_ui(f, &mut app_uis[current]))?;
             }
+            flash_shown = app_uis[current].message_flashing();
         }
 
-        stdout().execute(LeaveAlternateScreen)?;
-        disable_raw_mode()?;
+        restore_terminal()?;
+        restore_panic_hook();
 
-        if let Some(msg) = app_ui.take_exit_message() {
+        if let Some(msg) = app_uis[current].take_exit_message() {
             println!("{}", msg);
         }
 
@@ -7,3 +7,30 @@ pub mod fasta;
 pub mod file;
 pub mod record;
 pub mod stockholm;
+
+use std::path::Path;
+
+use crate::errors::TermalError;
+
+use file::SeqFile;
+
+/// Reads a sequence file, choosing the format from its extension: `.aln`/`.clustal` are read as
+/// Clustal, `.sto`/`.stk`/`.sth` as Stockholm, anything else as FastA. A trailing `.gz` is
+/// skipped (the file is then decompressed on the fly).
+pub fn read_seq_file_by_extension<P: AsRef<Path>>(path: P) -> Result<SeqFile, TermalError> {
+    let path = path.as_ref();
+    let format_path = if file::is_gz_path(path) {
+        Path::new(path.file_stem().unwrap_or_default())
+    } else {
+        path
+    };
+    let ext = format_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("aln") | Some("clustal") => clustal::read_clustal_file(path),
+        Some("sto") | Some("stk") | Some("sth") => stockholm::read_stockholm_file(path),
+        _ => Ok(fasta::read_fasta_file(path)?),
+    }
+}
@@ -2,20 +2,23 @@
 // Copyright (c) 2026 Peter Carlton
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
 use crate::errors::TermalError;
-use crate::seq::file::SeqFile;
+use crate::seq::file::{open_maybe_gzip, SeqFile};
 use crate::seq::record::SeqRecord;
 
 pub fn read_clustal_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, TermalError> {
-    let file = File::open(path)?;
+    read_clustal_reader(open_maybe_gzip(&path)?).map_err(|e| e.in_file(path))
+}
+
+/// Like `read_clustal_file()`, but reads from any buffered source (e.g. stdin).
+pub fn read_clustal_reader<R: BufRead>(reader: R) -> Result<SeqFile, TermalError> {
     let mut order: Vec<String> = Vec::new();
     let mut sequences: HashMap<String, String> = HashMap::new();
 
-    for line in BufReader::new(file).lines() {
+    for (index, line) in reader.lines().enumerate() {
         let l = line?;
         let trimmed = l.trim_end();
         if trimmed.is_empty() {
@@ -38,10 +41,10 @@ pub fn read_clustal_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, TermalError
         let mut fields = trimmed.split_whitespace();
         let name = fields
             .next()
-            .ok_or_else(|| TermalError::Format(String::from("Missing sequence id")))?;
-        let fragment = fields
-            .next()
-            .ok_or_else(|| TermalError::Format(String::from("Missing sequence fragment")))?;
+            .ok_or_else(|| TermalError::parse(index + 1, "clustal: missing sequence id", &l))?;
+        let fragment = fields.next().ok_or_else(|| {
+            TermalError::parse(index + 1, "clustal: missing sequence fragment", &l)
+        })?;
         let cleaned: String = fragment.chars().filter(|c| *c != '*').collect();
         let entry = sequences.entry(name.to_string()).or_insert_with(|| {
             order.push(name.to_string());
@@ -102,4 +105,14 @@ mod tests {
 
         let _ = fs::remove_file(&path);
     }
+
+    #[test]
+    fn test_read_clustal_reader_error_line() {
+        let input: &[u8] = b"CLUSTAL W\n\nseq1 AC-GT\nseq2\n";
+        let err = read_clustal_reader(input).unwrap_err();
+        assert_eq!(
+            r#"line 4: clustal: missing sequence fragment: "seq2""#,
+            err.to_string()
+        );
+    }
 }
@@ -3,15 +3,19 @@
 // Modifications (c) 2026 Peter Carlton
 
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
-use crate::seq::file::SeqFile;
+use crate::errors::TermalError;
+use crate::seq::file::{open_maybe_gzip, SeqFile};
 use crate::seq::record::SeqRecord;
 
-pub fn read_fasta_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, std::io::Error> {
-    let file = File::open(path)?;
+pub fn read_fasta_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, TermalError> {
+    read_fasta_reader(open_maybe_gzip(&path)?).map_err(|e| e.in_file(path))
+}
+
+/// Like `read_fasta_file()`, but reads from any buffered source (e.g. stdin).
+pub fn read_fasta_reader<R: BufRead>(reader: R) -> Result<SeqFile, TermalError> {
     let mut result: SeqFile = Vec::new();
     let mut current_record = SeqRecord {
         header: String::new(),
@@ -20,8 +24,8 @@ pub fn read_fasta_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, std::io::Erro
     let mut first_header = true;
     let mut seen_ids: HashSet<String> = HashSet::new();
 
-    for line in BufReader::new(file).lines() {
-        let l: String = line.unwrap();
+    for (index, line) in reader.lines().enumerate() {
+        let l: String = line?;
         if let Some(hdr) = l.strip_prefix(">") {
             if first_header {
                 first_header = false;
@@ -35,16 +39,10 @@ pub fn read_fasta_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, std::io::Erro
             };
             let id = hdr.split_whitespace().next().unwrap_or("").to_string();
             if id.is_empty() {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "Empty FASTA header",
-                ));
+                return Err(TermalError::parse(index + 1, "fasta: empty header", &l));
             }
             if !seen_ids.insert(id.clone()) {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("Duplicate FASTA ID: {}", id),
-                ));
+                return Err(TermalError::parse(index + 1, "fasta: duplicate ID", &l));
             }
             current_record.header.push_str(&id);
         } else {
@@ -52,13 +50,24 @@ pub fn read_fasta_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, std::io::Erro
             current_record.sequence.push_str(&l);
         }
     }
-    result.push(current_record);
+    // An empty input has no records at all.
+    if !first_header || !current_record.sequence.is_empty() {
+        result.push(current_record);
+    }
     Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::seq::file::maybe_gunzip;
+
+    fn records(fasta: &SeqFile) -> Vec<(&str, &str)> {
+        fasta
+            .iter()
+            .map(|rec| (rec.header.as_str(), rec.sequence.as_str()))
+            .collect()
+    }
 
     #[test]
     fn test_read_fasta_file_1() {
@@ -87,4 +96,59 @@ mod tests {
         assert_eq!(fasta[0].header, "Some");
         assert_eq!(fasta[0].sequence, "HWYQYDSWSWHQIQDPWVASLMTGSEHNTTIVDLNVLGAMDCLWLCYCQPECFEVFSLCIEVDLPSCCWAKALCAFHMWDSMAKQCWMPEMGEVSYFYALSMFHYFLLHSRPIQPWQTHHIPYDSIVVDLIANYFYNMIVQDVDKNSNIRFDRSVMRDVMIYEFENTYATGVVFNVNGKCGQFCKNMIYVGTIETQKEYEMFKNLDCAVQKRHNLQPNCENIAMKMRIQYNGKRFRMDYWERYRCNDIKQVLPQPFTEVAMEHRTFKLWPTTRLMMSNPKCRQCLEWAAVETGWIFTTNF");
     }
+
+    #[test]
+    fn test_read_fasta_reader() {
+        let input: &[u8] = b">seq1 first\nTTGC\nCG-A\n>seq2\nTTCCCGGA\n";
+        let fasta: SeqFile = read_fasta_reader(input).unwrap();
+        assert_eq!(fasta.len(), 2);
+        assert_eq!(fasta[0].header, "seq1");
+        assert_eq!(fasta[0].sequence, "TTGCCG-A");
+        assert_eq!(fasta[1].header, "seq2");
+        assert_eq!(fasta[1].sequence, "TTCCCGGA");
+    }
+
+    #[test]
+    fn test_read_fasta_reader_empty() {
+        let fasta: SeqFile = read_fasta_reader(&b""[..]).unwrap();
+        assert!(fasta.is_empty());
+    }
+
+    #[test]
+    fn test_read_fasta_file_gzip() {
+        let plain = read_fasta_file("./data/test2.fas").unwrap();
+        let gzipped = read_fasta_file("./data/test2.fas.gz").unwrap();
+        assert!(!plain.is_empty());
+        assert_eq!(records(&plain), records(&gzipped));
+    }
+
+    #[test]
+    fn test_read_fasta_reader_gzip_magic() {
+        let bytes = std::fs::read("./data/test2.fas.gz").unwrap();
+        let fasta = read_fasta_reader(maybe_gunzip(bytes.as_slice()).unwrap()).unwrap();
+        let plain = read_fasta_file("./data/test2.fas").unwrap();
+        assert_eq!(records(&fasta), records(&plain));
+    }
+
+    #[test]
+    fn test_read_fasta_reader_error_line() {
+        let input: &[u8] = b">seq1\nACGT\n>seq2\nACGT\n>seq1 again\nACGT\n";
+        let err = read_fasta_reader(input).unwrap_err();
+        assert_eq!(
+            r#"line 5: fasta: duplicate ID: ">seq1 again""#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_read_fasta_file_error_names_file() {
+        let path = std::env::temp_dir().join(format!("msafara-bad-{}.fas", std::process::id()));
+        std::fs::write(&path, ">seq1\nACGT\n>\nACGT\n").unwrap();
+        let err = read_fasta_file(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(
+            format!(r#"{}: line 3: fasta: empty header: ">""#, path.display()),
+            err.to_string()
+        );
+    }
 }
@@ -1,9 +1,68 @@
 // SPDX-License-Identifier: MIT
 // Copyright (c) 2025 Thomas Junier
 
-use crate::seq::record::SeqRecord;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use crate::{errors::TermalError, seq::record::SeqRecord};
 
 // For our purposes, a sequence file is just a Vec of sequence records.
 //
 
 pub type SeqFile = Vec<SeqRecord>;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// True iff the sequences of `seq_file` are not all equally long, i.e. it is not an alignment.
+pub fn is_ragged(seq_file: &SeqFile) -> bool {
+    let mut iter = seq_file.iter();
+    let Some(first) = iter.next() else {
+        return false;
+    };
+    let first_len = first.sequence.len();
+    iter.any(|rec| rec.sequence.len() != first_len)
+}
+
+/// Refuses a ragged `seq_file` (see `is_ragged()`), unless `pad` is set: Alignment::from_file()
+/// then pads the shorter sequences with gaps. Returns whether `seq_file` is ragged.
+pub fn check_aligned(seq_file: &SeqFile, pad: bool) -> Result<bool, TermalError> {
+    let ragged = is_ragged(seq_file);
+    if ragged && !pad {
+        return Err(TermalError::Format(String::from(
+            "Sequences have unequal length; not an alignment (--pad pads them with gaps)",
+        )));
+    }
+    Ok(ragged)
+}
+
+/// Opens `path` for reading, transparently decompressing it if it is gzip-compressed (detected
+/// from a `.gz` extension or from the gzip magic bytes).
+pub fn open_maybe_gzip<P: AsRef<Path>>(path: P) -> std::io::Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path)?);
+    if is_gz_path(path) {
+        Ok(Box::new(BufReader::new(GzDecoder::new(reader))))
+    } else {
+        maybe_gunzip(reader)
+    }
+}
+
+/// Wraps `reader` in a gzip decoder if its content starts with the gzip magic bytes; otherwise
+/// returns it unchanged.
+pub fn maybe_gunzip<'a, R: BufRead + 'a>(mut reader: R) -> std::io::Result<Box<dyn BufRead + 'a>> {
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(GzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// True iff `path` has a `.gz` extension.
+pub fn is_gz_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
@@ -2,27 +2,62 @@
 // Copyright (c) 2025 Thomas Junier
 // Modifications (c) 2026 Peter Carlton
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
 use crate::errors::TermalError;
-use crate::seq::file::SeqFile;
+use crate::seq::file::{open_maybe_gzip, SeqFile};
 use crate::seq::record::SeqRecord;
 
+// Per-column annotations (`#=GC <feature> <string>`, e.g. SS_cons), by feature name, in the order
+// they first appear; the strings of interleaved blocks are concatenated.
+pub type ColumnAnnotations = Vec<(String, String)>;
+
 pub fn read_stockholm_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, TermalError> {
-    let file = File::open(path)?;
+    read_stockholm_file_annotated(path).map(|(records, _)| records)
+}
+
+/// Like `read_stockholm_file()`, but reads from any buffered source (e.g. stdin).
+pub fn read_stockholm_reader<R: BufRead>(reader: R) -> Result<SeqFile, TermalError> {
+    read_stockholm_reader_annotated(reader).map(|(records, _)| records)
+}
+
+/// Like `read_stockholm_file()`, but also returns the per-column (`#=GC`) annotations.
+pub fn read_stockholm_file_annotated<P: AsRef<Path>>(
+    path: P,
+) -> Result<(SeqFile, ColumnAnnotations), TermalError> {
+    read_stockholm_reader_annotated(open_maybe_gzip(&path)?).map_err(|e| e.in_file(path))
+}
+
+/// Like `read_stockholm_file_annotated()`, but reads from any buffered source.
+pub fn read_stockholm_reader_annotated<R: BufRead>(
+    reader: R,
+) -> Result<(SeqFile, ColumnAnnotations), TermalError> {
     let mut result: SeqFile = Vec::new();
+    let mut annotations: ColumnAnnotations = Vec::new();
 
-    for line in BufReader::new(file).lines() {
-        let l: String = line.unwrap();
-        let first_char = l.chars().next().unwrap();
+    for (index, line) in reader.lines().enumerate() {
+        let l: String = line?;
+        let Some(first_char) = l.chars().next() else {
+            continue; // Blank line
+        };
         match first_char {
             '/' => {
                 break;
             } // Assuming '/' is the beginning of '//', which conceivably might not be
             // true
-            '#' => {} // Annotation -> ignore.
+            '#' => {
+                // Column annotations are kept; other annotations are ignored.
+                let mut fields = l.split_whitespace();
+                if let (Some("#=GC"), Some(feature), Some(text), None) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                {
+                    match annotations.iter_mut().find(|(name, _)| name == feature) {
+                        Some((_, previous)) => previous.push_str(text),
+                        None => annotations.push((String::from(feature), String::from(text))),
+                    }
+                }
+            }
             _ => {
                 let mut fields = l.split_whitespace();
 
@@ -34,13 +69,19 @@ pub fn read_stockholm_file<P: AsRef<Path>>(path: P) -> Result<SeqFile, TermalErr
                         };
                         result.push(record);
                     }
-                    _ => return Err(TermalError::Format(String::from("Expected two fields"))),
+                    _ => {
+                        return Err(TermalError::parse(
+                            index + 1,
+                            "stockholm: expected two fields",
+                            &l,
+                        ))
+                    }
                 }
             }
         }
     }
 
-    Ok(result)
+    Ok((result, annotations))
 }
 
 #[cfg(test)]
@@ -77,4 +118,35 @@ mod tests {
     }
 
     // TODO: more tests
+
+    #[test]
+    fn test_read_stockholm_reader_error_line() {
+        let input: &[u8] = b"# STOCKHOLM 1.0\n\nseq1 AC-GT\nseq2 AC GT\n//\n";
+        let err = read_stockholm_reader(input).unwrap_err();
+        assert_eq!(
+            r#"line 4: stockholm: expected two fields: "seq2 AC GT""#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_read_stockholm_reader_blank_lines() {
+        let input: &[u8] = b"# STOCKHOLM 1.0\n\nseq1 AC-GT\n\nseq2 ACCGT\n//\n";
+        assert_eq!(2, read_stockholm_reader(input).unwrap().len());
+    }
+
+    #[test]
+    fn test_read_stockholm_column_annotations() {
+        let input: &[u8] = b"# STOCKHOLM 1.0\n#=GF ID test\n\nseq1 AC-GT\n#=GC SS_cons <<.>>\n\n\
+seq1 AA\n#=GC SS_cons ..\n#=GC RF xx\n//\n";
+        let (records, annotations) = read_stockholm_reader_annotated(input).unwrap();
+        assert_eq!(2, records.len());
+        assert_eq!(
+            annotations,
+            vec![
+                (String::from("SS_cons"), String::from("<<.>>..")),
+                (String::from("RF"), String::from("xx")),
+            ]
+        );
+    }
 }
@@ -19,6 +19,22 @@ pub struct SessionFile {
     pub current_search: Option<SessionCurrentSearch>,
     pub label_search: Option<SessionLabelSearch>,
     pub notes: Option<String>,
+    pub command_history: Option<Vec<String>>,
+    pub search_history: Option<Vec<String>>,
+    pub ui_prefs: Option<UiPrefs>,
+    // Number of the first column, if not 1 (see App::set_column_origin()).
+    pub column_origin: Option<i64>,
+}
+
+/// Pane layout and display toggles of the UI, restored when a session is reopened.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct UiPrefs {
+    pub left_pane_width: u16,
+    pub bottom_pane_height: u16,
+    pub show_scrollbars: bool,
+    pub show_zoombox: bool,
+    pub show_zb_guides: bool,
+    pub show_tree_panel: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -67,6 +83,7 @@ pub struct SessionView {
 pub enum SessionSearchKind {
     Regex,
     Emboss,
+    Gff,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
@@ -81,6 +98,7 @@ impl From<SearchKind> for SessionSearchKind {
         match kind {
             SearchKind::Regex => SessionSearchKind::Regex,
             SearchKind::Emboss => SessionSearchKind::Emboss,
+            SearchKind::Gff => SessionSearchKind::Gff,
         }
     }
 }
@@ -90,6 +108,7 @@ impl From<SessionSearchKind> for SearchKind {
         match kind {
             SessionSearchKind::Regex => SearchKind::Regex,
             SessionSearchKind::Emboss => SearchKind::Emboss,
+            SessionSearchKind::Gff => SearchKind::Gff,
         }
     }
 }
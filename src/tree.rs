@@ -2,20 +2,36 @@
 // Copyright (c) 2026 Peter Carlton
 // Modifications (c) 2026 Peter Carlton
 
+use std::collections::{HashMap, HashSet};
+
 use crate::errors::TermalError;
 
 #[derive(Debug, Clone)]
 pub struct TreeNode {
+    // Preorder index assigned by the parser. Unlike the node's address, it survives clones, so it
+    // can be used to refer to a node across renders (e.g. for collapsed clades).
+    pub id: usize,
     pub name: Option<String>,
+    pub branch_length: Option<f64>,
     pub children: Vec<TreeNode>,
 }
 
 #[derive(Clone, Copy)]
 struct NodeInfo {
-    depth: usize,
+    x: usize,
+    y: usize,
+    leaf_start: usize,
+    leaf_end: usize,
+}
+
+// A row of the drawing that ends in a tip: either a leaf, or a collapsed clade standing for
+// leaves leaf_start..=leaf_end.
+#[derive(Clone, Copy)]
+struct Tip {
     y: usize,
     leaf_start: usize,
     leaf_end: usize,
+    collapsed: bool,
 }
 
 pub fn parse_newick(input: &str) -> Result<TreeNode, TermalError> {
@@ -29,20 +45,112 @@ pub fn parse_newick(input: &str) -> Result<TreeNode, TermalError> {
 }
 
 pub fn tree_lines_and_order(root: &TreeNode) -> Result<(Vec<String>, Vec<String>), TermalError> {
-    tree_lines_and_order_with_selection(root, None)
+    tree_lines_and_order_with_selection(root, None, &HashSet::new())
 }
 
+/// Renders the tree as a cladogram. Nodes whose id is in `collapsed` are drawn as a single `◄N`
+/// tip (N being the number of leaves they hold); the returned order still lists every leaf.
 pub fn tree_lines_and_order_with_selection(
     root: &TreeNode,
     selection: Option<(usize, usize)>,
+    collapsed: &HashSet<usize>,
+) -> Result<(Vec<String>, Vec<String>), TermalError> {
+    build_tree_lines(root, selection, collapsed, None)
+}
+
+/// Like `tree_lines_and_order_with_selection`, but the horizontal position of each node is
+/// proportional to its distance from the root (sum of branch lengths), scaled so the tree spans
+/// about `width` columns. Missing branch lengths count as 1.
+pub fn tree_lines_and_order_phylogram(
+    root: &TreeNode,
+    width: usize,
+    selection: Option<(usize, usize)>,
+    collapsed: &HashSet<usize>,
+) -> Result<(Vec<String>, Vec<String>), TermalError> {
+    build_tree_lines(root, selection, collapsed, Some(width))
+}
+
+/// Returns the leaf names under the node with the given id, in tree order (empty if there is no
+/// such node).
+pub fn clade_leaf_names(root: &TreeNode, id: usize) -> Vec<String> {
+    fn find(node: &TreeNode, id: usize) -> Option<&TreeNode> {
+        if node.id == id {
+            return Some(node);
+        }
+        node.children.iter().find_map(|child| find(child, id))
+    }
+    let mut names = Vec::new();
+    if let Some(node) = find(root, id) {
+        collect_leaf_names(node, &mut names);
+    }
+    names
+}
+
+/// Serializes a tree to Newick, terminated by `;`. Names containing whitespace or Newick
+/// punctuation are single-quoted; unnamed nodes and missing branch lengths are omitted.
+pub fn to_newick(root: &TreeNode) -> String {
+    fn write_node(node: &TreeNode, out: &mut String) {
+        if !node.children.is_empty() {
+            out.push('(');
+            for (idx, child) in node.children.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                write_node(child, out);
+            }
+            out.push(')');
+        }
+        if let Some(name) = &node.name {
+            out.push_str(&quote_newick_name(name));
+        }
+        if let Some(len) = node.branch_length {
+            out.push_str(&format!(":{}", len));
+        }
+    }
+
+    let mut out = String::new();
+    write_node(root, &mut out);
+    out.push(';');
+    out
+}
+
+fn quote_newick_name(name: &str) -> String {
+    let needs_quotes = name.is_empty()
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || "()[]',:;".contains(c));
+    if needs_quotes {
+        format!("'{}'", name.replace('\'', "''"))
+    } else {
+        name.to_string()
+    }
+}
+
+fn collect_leaf_names(node: &TreeNode, names: &mut Vec<String>) {
+    if node.children.is_empty() {
+        names.push(node.name.clone().unwrap_or_default());
+    }
+    for child in &node.children {
+        collect_leaf_names(child, names);
+    }
+}
+
+fn build_tree_lines(
+    root: &TreeNode,
+    selection: Option<(usize, usize)>,
+    collapsed: &HashSet<usize>,
+    phylogram_width: Option<usize>,
 ) -> Result<(Vec<String>, Vec<String>), TermalError> {
-    let root = collapse_unary(root.clone());
-    let (node_map, leaves) = assign_rows_and_depths(&root);
-    if leaves.is_empty() {
+    let root = collapse_unary(root.clone(), collapsed);
+    let (mut node_map, tips, order) = assign_rows_and_depths(&root, collapsed);
+    if tips.is_empty() {
         return Ok((Vec::new(), Vec::new()));
     }
-    let lines = render_box_tree(&root, &node_map, &leaves, selection);
-    let order: Vec<String> = leaves.iter().map(|(_, name)| name.clone()).collect();
+    if let Some(width) = phylogram_width {
+        assign_phylogram_x(&root, width, collapsed, &mut node_map);
+    }
+    let extend_leaves = phylogram_width.is_none();
+    let lines = render_box_tree(&root, &node_map, &tips, selection, collapsed, extend_leaves);
     for name in &order {
         if name.is_empty() {
             return Err(TermalError::Format(String::from("Missing leaf name")));
@@ -51,60 +159,78 @@ pub fn tree_lines_and_order_with_selection(
     Ok((lines, order))
 }
 
-fn collapse_unary(mut node: TreeNode) -> TreeNode {
+fn collapse_unary(mut node: TreeNode, collapsed: &HashSet<usize>) -> TreeNode {
     while node.children.len() == 1 {
         let mut child = node.children.remove(0);
         if node.name.is_some() && child.name.is_none() {
             child.name = node.name.take();
         }
+        child.branch_length = match (node.branch_length, child.branch_length) {
+            (None, None) => None,
+            (outer, inner) => Some(outer.unwrap_or(1.0) + inner.unwrap_or(1.0)),
+        };
+        // Keep a collapsed id alive when its node is folded into its only child.
+        if collapsed.contains(&node.id) {
+            child.id = node.id;
+        }
         node = child;
     }
     if !node.children.is_empty() {
-        node.children = node.children.into_iter().map(collapse_unary).collect();
+        node.children = node
+            .children
+            .into_iter()
+            .map(|child| collapse_unary(child, collapsed))
+            .collect();
     }
     node
 }
 
 fn assign_rows_and_depths(
     root: &TreeNode,
-) -> (
-    std::collections::HashMap<usize, NodeInfo>,
-    Vec<(usize, String)>,
-) {
-    let mut node_map = std::collections::HashMap::new();
-    let mut leaves = Vec::new();
+    collapsed: &HashSet<usize>,
+) -> (HashMap<usize, NodeInfo>, Vec<Tip>, Vec<String>) {
+    let mut node_map = HashMap::new();
+    let mut tips = Vec::new();
+    let mut order = Vec::new();
     let mut next_y = 0;
-    let mut next_leaf = 0;
 
     fn walk(
         node: &TreeNode,
         depth: usize,
+        collapsed: &HashSet<usize>,
         next_y: &mut usize,
-        next_leaf: &mut usize,
-        node_map: &mut std::collections::HashMap<usize, NodeInfo>,
-        leaves: &mut Vec<(usize, String)>,
+        node_map: &mut HashMap<usize, NodeInfo>,
+        tips: &mut Vec<Tip>,
+        order: &mut Vec<String>,
     ) -> (usize, usize, usize) {
-        if node.children.is_empty() {
+        let is_collapsed = !node.children.is_empty() && collapsed.contains(&node.id);
+        if node.children.is_empty() || is_collapsed {
             let y = *next_y;
             *next_y += 1;
-            let leaf_idx = *next_leaf;
-            *next_leaf += 1;
+            let leaf_start = order.len();
+            collect_leaf_names(node, order);
+            let leaf_end = order.len() - 1;
             node_map.insert(
-                node as *const _ as usize,
+                node.id,
                 NodeInfo {
-                    depth,
+                    x: depth * 2,
                     y,
-                    leaf_start: leaf_idx,
-                    leaf_end: leaf_idx,
+                    leaf_start,
+                    leaf_end,
                 },
             );
-            leaves.push((y, node.name.clone().unwrap_or_default()));
-            return (y, leaf_idx, leaf_idx);
+            tips.push(Tip {
+                y,
+                leaf_start,
+                leaf_end,
+                collapsed: is_collapsed,
+            });
+            return (y, leaf_start, leaf_end);
         }
         let child_infos: Vec<(usize, usize, usize)> = node
             .children
             .iter()
-            .map(|child| walk(child, depth + 1, next_y, next_leaf, node_map, leaves))
+            .map(|child| walk(child, depth + 1, collapsed, next_y, node_map, tips, order))
             .collect();
         let y_top = child_infos.iter().map(|(y, _, _)| *y).min().unwrap();
         let y_bottom = child_infos.iter().map(|(y, _, _)| *y).max().unwrap();
@@ -112,9 +238,9 @@ fn assign_rows_and_depths(
         let leaf_start = child_infos.iter().map(|(_, s, _)| *s).min().unwrap();
         let leaf_end = child_infos.iter().map(|(_, _, e)| *e).max().unwrap();
         node_map.insert(
-            node as *const _ as usize,
+            node.id,
             NodeInfo {
-                depth,
+                x: depth * 2,
                 y,
                 leaf_start,
                 leaf_end,
@@ -126,23 +252,87 @@ fn assign_rows_and_depths(
     walk(
         root,
         0,
+        collapsed,
         &mut next_y,
-        &mut next_leaf,
         &mut node_map,
-        &mut leaves,
+        &mut tips,
+        &mut order,
     );
-    (node_map, leaves)
+    (node_map, tips, order)
+}
+
+// Overrides the cladogram x positions with positions proportional to the distance from the root.
+// Each edge keeps at least two columns so that connectors remain readable.
+fn assign_phylogram_x(
+    root: &TreeNode,
+    width: usize,
+    collapsed: &HashSet<usize>,
+    node_map: &mut HashMap<usize, NodeInfo>,
+) {
+    fn visible_children<'a>(node: &'a TreeNode, collapsed: &HashSet<usize>) -> &'a [TreeNode] {
+        if collapsed.contains(&node.id) {
+            &[]
+        } else {
+            &node.children
+        }
+    }
+
+    fn max_dist(node: &TreeNode, dist: f64, collapsed: &HashSet<usize>) -> f64 {
+        visible_children(node, collapsed)
+            .iter()
+            .map(|child| max_dist(child, dist + edge_length(child), collapsed))
+            .fold(dist, f64::max)
+    }
+
+    fn walk(
+        node: &TreeNode,
+        dist: f64,
+        parent_x: Option<usize>,
+        scale: f64,
+        collapsed: &HashSet<usize>,
+        node_map: &mut HashMap<usize, NodeInfo>,
+    ) {
+        let scaled = (dist * scale).round() as usize;
+        let x = match parent_x {
+            Some(px) => scaled.max(px + 2),
+            None => 0,
+        };
+        if let Some(info) = node_map.get_mut(&node.id) {
+            info.x = x;
+        }
+        for child in visible_children(node, collapsed) {
+            let child_dist = dist + edge_length(child);
+            walk(child, child_dist, Some(x), scale, collapsed, node_map);
+        }
+    }
+
+    let total = max_dist(root, 0.0, collapsed);
+    let scale = if total > 0.0 {
+        width.saturating_sub(1) as f64 / total
+    } else {
+        0.0
+    };
+    walk(root, 0.0, None, scale, collapsed, node_map);
+}
+
+fn edge_length(node: &TreeNode) -> f64 {
+    match node.branch_length {
+        Some(len) if len.is_finite() => len.max(0.0),
+        _ => 1.0,
+    }
 }
 
 fn render_box_tree(
     root: &TreeNode,
-    node_map: &std::collections::HashMap<usize, NodeInfo>,
-    leaves: &[(usize, String)],
+    node_map: &HashMap<usize, NodeInfo>,
+    tips: &[Tip],
     selection: Option<(usize, usize)>,
+    collapsed: &HashSet<usize>,
+    extend_leaves: bool,
 ) -> Vec<String> {
-    let n_rows = leaves.iter().map(|(y, _)| *y).max().unwrap_or(0) + 1;
-    let max_depth = node_map.values().map(|info| info.depth).max().unwrap_or(0);
-    let tree_width = max_depth * 2 + 1;
+    let n_rows = tips.iter().map(|tip| tip.y).max().unwrap_or(0) + 1;
+    let max_x = node_map.values().map(|info| info.x).max().unwrap_or(0);
+    let tree_width = max_x + 1;
     let mut grid: Vec<Vec<char>> = vec![vec![' '; tree_width]; n_rows];
 
     fn to_heavy(ch: char) -> char {
@@ -207,24 +397,21 @@ fn render_box_tree(
 
     fn draw_internal(
         node: &TreeNode,
-        node_map: &std::collections::HashMap<usize, NodeInfo>,
+        node_map: &HashMap<usize, NodeInfo>,
         grid: &mut [Vec<char>],
         selection: Option<(usize, usize)>,
+        collapsed: &HashSet<usize>,
     ) {
-        let info = node_map[&(node as *const _ as usize)];
-        if node.children.is_empty() {
+        let info = node_map[&node.id];
+        if node.children.is_empty() || collapsed.contains(&node.id) {
             return;
         }
         let parent_selected = selection
             .map(|(start, end)| start <= info.leaf_start && end >= info.leaf_end)
             .unwrap_or(false);
-        let x_node = info.depth * 2;
+        let x_node = info.x;
         let x_conn = x_node + 1;
-        let kid_infos: Vec<NodeInfo> = node
-            .children
-            .iter()
-            .map(|kid| node_map[&(kid as *const _ as usize)])
-            .collect();
+        let kid_infos: Vec<NodeInfo> = node.children.iter().map(|kid| node_map[&kid.id]).collect();
         let ys: Vec<usize> = kid_infos.iter().map(|k| k.y).collect();
         let y_top = *ys.iter().min().unwrap();
         let y_bottom = *ys.iter().max().unwrap();
@@ -246,22 +433,19 @@ fn render_box_tree(
                 '├'
             };
             put(grid, y, x_conn, jch, child_selected);
-            let x_child = ki.depth * 2;
+            let x_child = ki.x;
             for x in (x_conn + 1)..=x_child {
                 put(grid, y, x, '─', child_selected);
             }
-            draw_internal(kid, node_map, grid, selection);
+            draw_internal(kid, node_map, grid, selection, collapsed);
         }
     }
 
-    draw_internal(root, node_map, &mut grid, selection);
+    draw_internal(root, node_map, &mut grid, selection, collapsed);
 
-    let leaf_rows: std::collections::HashMap<usize, usize> = leaves
-        .iter()
-        .enumerate()
-        .map(|(idx, (y, _))| (*y, idx))
-        .collect();
-    for (y, leaf_idx) in leaf_rows {
+    // In a phylogram, leaf tips mark distance from the root and must not be extended.
+    for tip in tips.iter().filter(|_| extend_leaves) {
+        let y = tip.y;
         let row = &grid[y];
         let mut last = None;
         for x in (0..row.len()).rev() {
@@ -272,7 +456,7 @@ fn render_box_tree(
         }
         let start = last.map(|l| l + 1).unwrap_or(0);
         let leaf_selected = selection
-            .map(|(start, end)| start <= leaf_idx && end >= leaf_idx)
+            .map(|(start, end)| start <= tip.leaf_start && end >= tip.leaf_end)
             .unwrap_or(false);
         for x in start..tree_width {
             put(&mut grid, y, x, '─', leaf_selected);
@@ -300,14 +484,21 @@ fn render_box_tree(
         }
     }
 
-    grid.into_iter()
+    let mut lines: Vec<String> = grid
+        .into_iter()
         .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
-        .collect()
+        .collect();
+    for tip in tips.iter().filter(|tip| tip.collapsed) {
+        let count = tip.leaf_end - tip.leaf_start + 1;
+        lines[tip.y].push_str(&format!("◄{}", count));
+    }
+    lines
 }
 
 struct Parser {
     chars: Vec<char>,
     pos: usize,
+    next_id: usize,
 }
 
 impl Parser {
@@ -315,6 +506,7 @@ impl Parser {
         Self {
             chars: input.chars().collect(),
             pos: 0,
+            next_id: 0,
         }
     }
 
@@ -329,6 +521,8 @@ impl Parser {
     }
 
     fn parse_node(&mut self) -> Result<TreeNode, TermalError> {
+        let id = self.next_id;
+        self.next_id += 1;
         self.skip_whitespace();
         if self.peek() == Some('(') {
             self.pos += 1;
@@ -351,13 +545,20 @@ impl Parser {
                 }
             }
             let name = self.parse_name_opt();
-            self.skip_branch_length();
-            Ok(TreeNode { name, children })
+            let branch_length = self.parse_branch_length();
+            Ok(TreeNode {
+                id,
+                name,
+                branch_length,
+                children,
+            })
         } else {
             let name = self.parse_name()?;
-            self.skip_branch_length();
+            let branch_length = self.parse_branch_length();
             Ok(TreeNode {
+                id,
                 name: Some(name),
+                branch_length,
                 children: Vec::new(),
             })
         }
@@ -373,6 +574,9 @@ impl Parser {
 
     fn parse_name(&mut self) -> Result<String, TermalError> {
         self.skip_whitespace();
+        if self.peek() == Some('\'') {
+            return self.parse_quoted_name();
+        }
         let start = self.pos;
         while let Some(c) = self.peek() {
             if matches!(c, ':' | ',' | ')' | '(' | ';') || c.is_whitespace() {
@@ -386,18 +590,50 @@ impl Parser {
         Ok(self.chars[start..self.pos].iter().collect())
     }
 
-    fn skip_branch_length(&mut self) {
+    // Quoted labels may contain any character; a doubled quote stands for a literal one.
+    fn parse_quoted_name(&mut self) -> Result<String, TermalError> {
+        self.pos += 1;
+        let mut name = String::new();
+        loop {
+            match self.peek() {
+                Some('\'') => {
+                    self.pos += 1;
+                    if self.peek() == Some('\'') {
+                        name.push('\'');
+                        self.pos += 1;
+                    } else {
+                        return Ok(name);
+                    }
+                }
+                Some(c) => {
+                    name.push(c);
+                    self.pos += 1;
+                }
+                None => {
+                    return Err(TermalError::Format(String::from(
+                        "Unterminated quoted name",
+                    )));
+                }
+            }
+        }
+    }
+
+    fn parse_branch_length(&mut self) -> Option<f64> {
         self.skip_whitespace();
         if self.peek() != Some(':') {
-            return;
+            return None;
         }
         self.pos += 1;
+        self.skip_whitespace();
+        let start = self.pos;
         while let Some(c) = self.peek() {
             if matches!(c, ',' | ')' | ';') || c.is_whitespace() {
                 break;
             }
             self.pos += 1;
         }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse().ok()
     }
 }
 
@@ -411,4 +647,45 @@ mod tests {
         let (_lines, order) = tree_lines_and_order(&tree).unwrap();
         assert_eq!(order, vec!["A", "B", "C"]);
     }
+
+    #[test]
+    fn parse_newick_branch_lengths() {
+        let tree = parse_newick("(A:0.5,(B:1,C):2.25);").unwrap();
+        assert_eq!(tree.branch_length, None);
+        assert_eq!(tree.children[0].branch_length, Some(0.5));
+        assert_eq!(tree.children[1].branch_length, Some(2.25));
+        assert_eq!(tree.children[1].children[1].branch_length, None);
+    }
+
+    #[test]
+    fn phylogram_scales_by_branch_length() {
+        let tree = parse_newick("(A:1,B:9);").unwrap();
+        let (lines, order) =
+            tree_lines_and_order_phylogram(&tree, 21, None, &HashSet::new()).unwrap();
+        assert_eq!(order, vec!["A", "B"]);
+        assert_eq!(lines[0].chars().count(), 3);
+        assert_eq!(lines[1].chars().count(), 21);
+    }
+
+    #[test]
+    fn newick_round_trip() {
+        let input = "((A:0.1,'seq 2':0.25)inner:1,'it''s':2);";
+        let tree = parse_newick(input).unwrap();
+        assert_eq!(to_newick(&tree), input);
+        let reparsed = parse_newick(&to_newick(&tree)).unwrap();
+        let (_lines, order) = tree_lines_and_order(&reparsed).unwrap();
+        assert_eq!(order, vec!["A", "seq 2", "it's"]);
+    }
+
+    #[test]
+    fn collapsed_clade_renders_as_single_tip() {
+        // Preorder ids: root 0, A 1, (B,C) 2, B 3, C 4.
+        let tree = parse_newick("(A,(B,C));").unwrap();
+        let collapsed: HashSet<usize> = [2].into_iter().collect();
+        let (lines, order) = tree_lines_and_order_with_selection(&tree, None, &collapsed).unwrap();
+        assert_eq!(order, vec!["A", "B", "C"]);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with("◄2"));
+        assert_eq!(clade_leaf_names(&tree, 2), vec!["B", "C"]);
+    }
 }
@@ -10,6 +10,7 @@ mod line_editor;
 mod msg_theme;
 mod notes_editor;
 pub mod render;
+pub mod snapshot;
 mod style;
 mod svg;
 mod zoombox;
@@ -17,32 +18,50 @@ mod zoombox;
 use std::{
     cmp::{max, min},
     fmt,
+    ops::Range,
     path::Path,
 };
 
 use bitflags::bitflags;
 
-use ratatui::layout::Size;
+use ratatui::layout::{Rect, Size};
 use ratatui::style::{Color, Style};
 use ratatui::text::Span;
 
 use self::{
     aln_widget::{SearchHighlight, SearchHighlightConfig},
-    color_map::colormap_gecos,
+    color_map::{colormap_gecos, ColorMap},
     color_scheme::{ColorScheme, Theme},
     line_editor::LineEditor,
     notes_editor::NotesEditor,
+    render::{retained_col_ndx, retained_seq_ndx},
+    zoombox::zoombox_span,
 };
 
 use crate::{
-    app::{App, SearchKind, SeqOrdering},
+    alignment::{Alignment, SeqType, ShiftDirection},
+    app::{
+        AlignmentEdit, App, PromptKind, ResidueClassMap, SearchKind, SearchScope, SeqOrdering,
+        StartupScheme, StartupVideoMode, TreeMismatch,
+    },
     errors::TermalError,
+    seq::read_seq_file_by_extension,
+    session::UiPrefs,
     tree::TreeNode,
 };
 
 const V_SCROLLBAR_WIDTH: u16 = 1;
 const MIN_COLS_SHOWN: u16 = 1;
+const MIN_ROWS_SHOWN: u16 = 1;
+const DEFAULT_LEFT_PANE_WIDTH: u16 = 18; // Reasonable default, I'd say...
+const DEFAULT_BOTTOM_PANE_HEIGHT: u16 = 5;
+// One line of the bottom pane, plus its lower border
+const MIN_BOTTOM_PANE_HEIGHT: u16 = 2;
 const BORDER_WIDTH: u16 = 1;
+// Pinned sequences beyond this many scroll within their own sub-pane.
+const MAX_PINNED_ROWS: u16 = 5;
+// Minimum column conservation (as in the bottom pane's bar chart) within conserved blocks.
+const CONSERVED_BLOCK_THRESHOLD: f64 = 0.8;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ZoomLevel {
@@ -59,6 +78,35 @@ fn detect_truecolor() -> bool {
     colorterm.contains("truecolor") || colorterm.contains("24bit")
 }
 
+// How headers are shown in the label pane (display only).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeaderDisplay {
+    // Whole header, cut at the pane's right edge.
+    Full,
+    // Headers too long for the pane lose their middle, replaced by '…'.
+    MiddleEllipsis,
+    // Only the first whitespace-delimited word.
+    FirstWord,
+}
+
+// Case in which residues are drawn (display only).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaseFold {
+    AsIs,
+    Upper,
+    Lower,
+}
+
+impl CaseFold {
+    pub fn apply(&self, c: char) -> char {
+        match self {
+            CaseFold::AsIs => c,
+            CaseFold::Upper => c.to_ascii_uppercase(),
+            CaseFold::Lower => c.to_ascii_lowercase(),
+        }
+    }
+}
+
 #[derive(Debug)]
 enum BottomPanePosition {
     Adjacent,
@@ -101,12 +149,20 @@ enum InputMode {
         editor: LineEditor,
     },
     ConfirmSessionOverwrite {
-        editor: LineEditor,
+        // The :ss prompt to go back to if not confirmed (None after :w or :wq).
+        editor: Option<LineEditor>,
         path: String,
+        // Quit once saved (:wq).
+        quit: bool,
     },
     SearchList {
         selected: usize,
     },
+    SearchName {
+        editor: LineEditor,
+        query: String,
+        kind: SearchKind,
+    },
     SessionList {
         selected: usize,
         files: Vec<String>,
@@ -121,6 +177,12 @@ enum InputMode {
     ConfirmViewDelete {
         name: String,
     },
+    // Quitting with unsaved changes (see App::has_unsaved_changes()).
+    ConfirmQuit,
+    // Details of the sequence at `rank` in a popup; any key closes it.
+    Inspect {
+        rank: usize,
+    },
     ViewList {
         selected: usize,
     },
@@ -140,6 +202,17 @@ enum InputMode {
     TreeNav {
         nav: TreeNav,
     },
+    // Overlay listing the tree leaves and headers that do not match (`:treecheck`).
+    TreeCheck {
+        mismatch: TreeMismatch,
+        scroll: usize,
+    },
+    // Conservation map of the whole alignment in the alignment pane (`:overview`), with a
+    // selected cell.
+    Overview {
+        row: usize,
+        col: usize,
+    },
     // ExCommand { buffer: String },
 }
 
@@ -158,6 +231,7 @@ enum RejectMode {
 
 #[derive(Clone, PartialEq)]
 struct TreeNavNode {
+    tree_id: usize,
     parent: Option<usize>,
     children: Vec<usize>,
     depth: usize,
@@ -183,6 +257,16 @@ impl TreeNav {
         self.leaf_ranks[start..=end].to_vec()
     }
 
+    // Id (in the parsed tree) of the current node, if it is an internal node.
+    fn current_internal_tree_id(&self) -> Option<usize> {
+        let node = &self.nodes[self.current];
+        if node.children.is_empty() {
+            None
+        } else {
+            Some(node.tree_id)
+        }
+    }
+
     fn move_right(&mut self) -> bool {
         let children = &self.nodes[self.current].children;
         if children.is_empty() {
@@ -249,6 +333,7 @@ fn build_tree_nav(app: &App, tree: &TreeNode) -> Result<TreeNav, TermalError> {
     ) -> (usize, usize, usize) {
         let id = nodes.len();
         nodes.push(TreeNavNode {
+            tree_id: node.id,
             parent,
             children: Vec::new(),
             depth,
@@ -293,6 +378,97 @@ fn build_tree_nav(app: &App, tree: &TreeNode) -> Result<TreeNav, TermalError> {
 
 pub const USER_GUIDE: &str = include_str!("ui/bindings.md");
 
+// Requests to show another alignment when several files are open (Ctrl-N / Ctrl-P). The UI only
+// records the request; the runner owns the buffers and performs the switch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BufferSwitch {
+    Next,
+    Previous,
+}
+
+/// How a quit request deals with unsaved changes (see resolve_quit()).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuitRequest {
+    /// Ask whether to quit anyway (q).
+    Confirm,
+    /// Refuse, with a warning (:q, :wq).
+    Refuse,
+}
+
+/// Handles `request` when `uis` are the open alignments and `current` the one shown: returns true
+/// if none has unsaved changes, else shows the first one that does and asks for confirmation or
+/// refuses.
+pub fn resolve_quit(uis: &mut [UI], current: &mut usize, request: QuitRequest) -> bool {
+    let unsaved = (0..uis.len())
+        .map(|offset| (*current + offset) % uis.len())
+        .find(|&index| uis[index].has_unsaved_changes());
+    match unsaved {
+        Some(index) => {
+            *current = index;
+            match request {
+                QuitRequest::Confirm => uis[index].confirm_quit(),
+                QuitRequest::Refuse => uis[index].refuse_quit(),
+            }
+            false
+        }
+        None => true,
+    }
+}
+
+impl BufferSwitch {
+    // Index of the buffer to show, given the current index and the number of buffers (wraps).
+    pub fn target(self, current: usize, count: usize) -> usize {
+        if count == 0 {
+            return 0;
+        }
+        match self {
+            BufferSwitch::Next => (current + 1) % count,
+            BufferSwitch::Previous => (current + count - 1) % count,
+        }
+    }
+}
+
+// Where the view was, and which sequence had the cursor (see UI::push_viewport_mark()).
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ViewportMark {
+    top_line: u16,
+    leftmost_col: u16,
+    cursor_rank: Option<usize>,
+}
+
+// A second alignment shown below the main one (`:split <file>`), e.g. a trimmed version of the
+// same alignment. It scrolls vertically on its own; when linked, its leftmost column follows the
+// main alignment's (clamped to its own length).
+pub struct SplitPane {
+    pub name: String,
+    pub alignment: Alignment,
+    pub top_line: u16,
+    pub leftmost_col: u16,
+    pub linked: bool,
+    // Inner size of the split's sequence pane, known after layout.
+    pub pane_size: Option<Size>,
+}
+
+impl SplitPane {
+    fn visible_rows(&self) -> u16 {
+        self.pane_size.map(|size| size.height).unwrap_or(0)
+    }
+
+    fn visible_cols(&self) -> u16 {
+        self.pane_size.map(|size| size.width).unwrap_or(0)
+    }
+
+    pub fn max_top_line(&self) -> u16 {
+        let num_seq = self.alignment.num_seq().min(u16::MAX as usize) as u16;
+        num_seq.saturating_sub(self.visible_rows())
+    }
+
+    pub fn max_leftmost_col(&self) -> u16 {
+        let aln_len = self.alignment.aln_len().min(u16::MAX as usize) as u16;
+        aln_len.saturating_sub(self.visible_cols())
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 #[allow(dead_code)]
 enum LabelSearchDirection {
@@ -354,6 +530,8 @@ pub struct UI<'a> {
     // possible that they need not be stored at all, as they can in principle be computed when the
     // layout is known.
     aln_pane_size: Option<Size>,
+    // Where the alignment pane is on screen, to map mouse clicks.
+    aln_pane_area: Option<Rect>,
     frame_size: Option<Size>, // whole app
     full_screen: bool,
     video_mode: VideoMode,
@@ -363,12 +541,73 @@ pub struct UI<'a> {
     exit_message: Option<String>,
     show_tree_panel: bool,
     dirty: bool,
+    // (index, count) of this UI's alignment among those open, and any pending switch request.
+    buffer_position: (usize, usize),
+    buffer_switch: Option<BufferSwitch>,
+    // Set by q / :q / :wq; the runner checks every open alignment for unsaved changes (see
+    // resolve_quit()).
+    quit_request: Option<QuitRequest>,
+    // Text to be put on the terminal's clipboard by the main loop.
+    clipboard: Option<String>,
+    // Spaces inserted by Tab in the notes editor (`notes_indent_width` in the config).
+    notes_indent_width: usize,
+    split: Option<SplitPane>,
+    pinned_top_line: u16,
+    // The search prompt was opened with Ctrl-/ and only looks at what is on screen.
+    search_on_screen: bool,
+    // Positions to come back to, pushed when a search prompt opens (see push_viewport_mark()).
+    viewport_marks: Vec<ViewportMark>,
+    // Wrap mode: the alignment is laid out in stacked blocks as wide as the sequence pane.
+    wrap_mode: bool,
+    wrap_top_block: usize,
+    // Draw gaps as middle dots instead of their own characters.
+    dot_gaps: bool,
+    // Number sequences by their distance to the cursor line (Vim's 'relativenumber').
+    relative_line_numbers: bool,
+    // Show, right of the alignment, each sequence's coordinate at the rightmost column shown.
+    end_coords: bool,
+    // Match navigation centers the match instead of putting it at the top left.
+    center_matches: bool,
+    // Alignment column highlighted by the vertical guide, if shown.
+    guide_col: Option<usize>,
+    // Popup with the residue composition of the guide column.
+    show_column_popup: bool,
+    // Last conserved block jumped to, and the leftmost column it left the view at: while the
+    // view stays there, `{` / `}` continue from that block even if it could not be scrolled to
+    // the left edge.
+    last_conserved_block: Option<(usize, u16)>,
+    // Likewise for `(` / `)`: the last column jumped to along the cursor sequence, and the leftmost
+    // column it left the view at.
+    last_gap_motion: Option<(usize, u16)>,
+    // Columns whose occupancy is below this fraction are dimmed (`:occupancy`).
+    min_occupancy: Option<f64>,
+    // Overlay listing the current colormap's residues and colors.
+    show_legend: bool,
+    // Row in the bottom pane showing where the cursor sequence has gaps.
+    show_gap_map: bool,
+    header_display: HeaderDisplay,
+    case_fold: CaseFold,
+    // Lower-case (soft-masked) residues are drawn dimmed.
+    mask_aware: bool,
+    // Residue colors fade toward the background in poorly conserved columns.
+    conservation_shading: bool,
+    // While recalling prompt history: index of the entry shown, and the text typed before.
+    history_nav: Option<(usize, String)>,
 }
 
 impl<'a> UI<'a> {
     pub fn new(app: &'a mut App) -> Self {
-        let macromolecule_type = app.alignment.macromolecule_type();
+        let macromolecule_type = app.macromolecule_type();
         app.info_msg("Press '?' for help");
+        if let Some(warning) = app.duplicate_headers_warning() {
+            app.warning_msg(warning);
+        }
+        if app.num_seq() == 0 || app.aln_len() == 0 {
+            app.warning_msg("Empty alignment");
+        }
+        if let Some(warning) = app.display_limit_warning() {
+            app.warning_msg(warning);
+        }
         let color_schemes = vec![
             ColorScheme::color_scheme_dark(macromolecule_type),
             ColorScheme::color_scheme_light(macromolecule_type),
@@ -376,7 +615,7 @@ impl<'a> UI<'a> {
         ];
         let default_color_scheme_index = color_schemes.len() - 1;
         let use_truecolor = detect_truecolor();
-        UI {
+        let mut ui = UI {
             app,
             color_schemes,
             current_color_scheme_index: default_color_scheme_index,
@@ -388,12 +627,13 @@ impl<'a> UI<'a> {
             highlight_retained_cols: false,
             top_line: 0,
             leftmost_col: 0,
-            left_pane_width: 18, // Reasonable default, I'd say...
+            left_pane_width: DEFAULT_LEFT_PANE_WIDTH,
             previous_left_pane_width: 0,
-            bottom_pane_height: 5,
-            previous_bottom_pane_height: 0,
+            bottom_pane_height: DEFAULT_BOTTOM_PANE_HEIGHT,
+            previous_bottom_pane_height: DEFAULT_BOTTOM_PANE_HEIGHT,
             bottom_pane_position: BottomPanePosition::Adjacent,
             aln_pane_size: None,
+            aln_pane_area: None,
             frame_size: None,
             full_screen: false,
             video_mode: VideoMode::Direct,
@@ -403,9 +643,89 @@ impl<'a> UI<'a> {
             exit_message: None,
             show_tree_panel: false,
             dirty: false,
+            buffer_position: (0, 1),
+            buffer_switch: None,
+            quit_request: None,
+            clipboard: None,
+            notes_indent_width: notes_editor::DEFAULT_INDENT_WIDTH,
+            split: None,
+            pinned_top_line: 0,
+            search_on_screen: false,
+            viewport_marks: Vec::new(),
+            wrap_mode: false,
+            wrap_top_block: 0,
+            dot_gaps: false,
+            relative_line_numbers: false,
+            end_coords: false,
+            center_matches: false,
+            guide_col: None,
+            show_column_popup: false,
+            last_conserved_block: None,
+            last_gap_motion: None,
+            min_occupancy: None,
+            show_legend: false,
+            show_gap_map: false,
+            header_display: HeaderDisplay::Full,
+            case_fold: CaseFold::AsIs,
+            mask_aware: false,
+            conservation_shading: false,
+            history_nav: None,
+        };
+        ui.apply_ui_prefs();
+        ui
+    }
+
+    // ****************************************************************
+    // Sessions
+
+    fn ui_prefs(&self) -> UiPrefs {
+        UiPrefs {
+            left_pane_width: self.left_pane_width,
+            bottom_pane_height: self.bottom_pane_height,
+            show_scrollbars: self.show_scrollbars,
+            show_zoombox: self.show_zoombox,
+            show_zb_guides: self.show_zb_guides,
+            show_tree_panel: self.show_tree_panel,
         }
     }
 
+    // Applies the layout loaded with the session, if any. Hidden panes are shown again at their
+    // default size.
+    pub fn apply_ui_prefs(&mut self) {
+        let Some(prefs) = self.app.ui_prefs else {
+            return;
+        };
+        self.left_pane_width = prefs.left_pane_width;
+        self.previous_left_pane_width = if prefs.left_pane_width > 0 {
+            prefs.left_pane_width
+        } else {
+            DEFAULT_LEFT_PANE_WIDTH
+        };
+        self.bottom_pane_height = prefs.bottom_pane_height;
+        self.previous_bottom_pane_height = if prefs.bottom_pane_height > 0 {
+            prefs.bottom_pane_height
+        } else {
+            DEFAULT_BOTTOM_PANE_HEIGHT
+        };
+        self.show_scrollbars = prefs.show_scrollbars;
+        self.show_zoombox = prefs.show_zoombox;
+        self.show_zb_guides = prefs.show_zb_guides;
+        self.show_tree_panel = prefs.show_tree_panel;
+    }
+
+    /// Saves the session, including the current pane layout.
+    pub fn save_session(&mut self, path: &Path) -> Result<(), TermalError> {
+        self.app.ui_prefs = Some(self.ui_prefs());
+        self.app.save_session(path)
+    }
+
+    /// Loads a session and the pane layout stored in it (if any).
+    pub fn load_session(&mut self, path: &Path) -> Result<(), TermalError> {
+        self.app.load_session(path)?;
+        self.apply_ui_prefs();
+        Ok(())
+    }
+
     pub fn reset_help_scroll(&mut self) {
         self.help_scroll = 0;
     }
@@ -435,6 +755,340 @@ impl<'a> UI<'a> {
         self.exit_message.take()
     }
 
+    pub fn set_buffer_position(&mut self, index: usize, count: usize) {
+        self.buffer_position = (index, count);
+    }
+
+    pub fn buffer_count(&self) -> usize {
+        self.buffer_position.1
+    }
+
+    pub fn request_buffer_switch(&mut self, switch: BufferSwitch) {
+        self.buffer_switch = Some(switch);
+    }
+
+    pub fn take_buffer_switch(&mut self) -> Option<BufferSwitch> {
+        self.buffer_switch.take()
+    }
+
+    pub fn request_quit(&mut self, request: QuitRequest) {
+        self.quit_request = Some(request);
+    }
+
+    pub fn take_quit_request(&mut self) -> Option<QuitRequest> {
+        self.quit_request.take()
+    }
+
+    // Asks whether to quit in spite of this alignment's unsaved changes.
+    pub fn confirm_quit(&mut self) {
+        self.input_mode = InputMode::ConfirmQuit;
+        self.app
+            .warning_msg("Unsaved changes — quit anyway? (y/n; :w saves the session)");
+        self.dirty = true;
+    }
+
+    // Declines to quit because of this alignment's unsaved changes.
+    fn refuse_quit(&mut self) {
+        self.app
+            .warning_msg("Unsaved changes (:q! quits anyway, :w saves the session)");
+        self.dirty = true;
+    }
+
+    pub fn notes_indent_width(&self) -> usize {
+        self.notes_indent_width
+    }
+
+    pub fn set_notes_indent_width(&mut self, width: usize) {
+        self.notes_indent_width = width;
+    }
+
+    pub fn copy_to_clipboard(&mut self, text: impl Into<String>) {
+        self.clipboard = Some(text.into());
+    }
+
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+
+    /// Columns (from, to excluded) whose consensus `:motif` copies by default: the conserved block
+    /// under the guide, or else the visible columns.
+    pub fn motif_columns(&self) -> (usize, usize) {
+        let block = self.guide_col.and_then(|col| {
+            self.app
+                .alignment
+                .conserved_blocks(CONSERVED_BLOCK_THRESHOLD)
+                .into_iter()
+                .find(|&(start, end)| start <= col && col < end)
+        });
+        block.unwrap_or_else(|| {
+            let left = self.leftmost_col as usize;
+            let right = (left + self.max_nb_col_shown() as usize).min(self.app.aln_len_usize());
+            (left, right)
+        })
+    }
+
+    pub fn search_prompt(&self) -> String {
+        if self.search_on_screen {
+            String::from("Search on screen: ")
+        } else {
+            String::from("Search: ")
+        }
+    }
+
+    /// The sequences and columns on screen, for a Ctrl-/ search. Zoomed out, every sequence that
+    /// is not hidden is on screen (if sampled), so they are all in scope.
+    pub fn screen_search_scope(&self) -> SearchScope {
+        if self.zoom_level != ZoomLevel::ZoomedIn {
+            let mut ranks = self.app.ordering.clone();
+            ranks.extend(self.app.pinned_ranks());
+            return SearchScope {
+                ranks,
+                cols: (0, self.app.aln_len_usize()),
+            };
+        }
+        let top = (self.top_line as usize).min(self.app.ordering.len());
+        let bottom = (top + self.max_nb_seq_shown() as usize).min(self.app.ordering.len());
+        let mut ranks = self.app.ordering[top..bottom].to_vec();
+        ranks.extend(self.app.pinned_ranks());
+        let left = self.leftmost_col as usize;
+        let right = (left + self.max_nb_col_shown() as usize).min(self.app.aln_len_usize());
+        SearchScope {
+            ranks,
+            cols: (left.min(right), right),
+        }
+    }
+
+    // File name and position (e.g. "b.fa [2/3]") when several alignments are open.
+    pub fn buffer_label(&self) -> Option<String> {
+        let (index, count) = self.buffer_position;
+        if count < 2 {
+            return None;
+        }
+        let name = Path::new(&self.app.filename)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or(self.app.filename.as_str());
+        Some(format!("{} [{}/{}]", name, index + 1, count))
+    }
+
+    /****************************************************************/
+    // Split pane
+
+    pub fn open_split(&mut self, path: &Path) -> Result<(), TermalError> {
+        let seq_file = read_seq_file_by_extension(path)?;
+        if seq_file.is_empty() {
+            return Err(TermalError::Format(format!(
+                "No sequences in {}",
+                path.display()
+            )));
+        }
+        let name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| path.display().to_string());
+        self.split = Some(SplitPane {
+            name,
+            alignment: Alignment::from_file(seq_file),
+            top_line: 0,
+            leftmost_col: 0,
+            linked: true,
+            pane_size: None,
+        });
+        Ok(())
+    }
+
+    // Returns false if there was no split to close.
+    pub fn close_split(&mut self) -> bool {
+        self.split.take().is_some()
+    }
+
+    pub fn split(&self) -> Option<&SplitPane> {
+        self.split.as_ref()
+    }
+
+    pub fn set_split_pane_size(&mut self, size: Size) {
+        if let Some(split) = &mut self.split {
+            split.pane_size = Some(size);
+            split.top_line = split.top_line.min(split.max_top_line());
+            split.leftmost_col = split.leftmost_col.min(split.max_leftmost_col());
+        }
+    }
+
+    // Toggles linked horizontal scrolling; returns the new state, or None without a split.
+    pub fn toggle_split_link(&mut self) -> Option<bool> {
+        let leftmost_col = self.leftmost_col;
+        let split = self.split.as_mut()?;
+        split.linked = !split.linked;
+        if !split.linked {
+            // Start unlinked scrolling from where the linked pane was.
+            split.leftmost_col = leftmost_col.min(split.max_leftmost_col());
+        }
+        Some(split.linked)
+    }
+
+    pub fn scroll_split_lines(&mut self, delta: i32) {
+        if let Some(split) = &mut self.split {
+            let line = (split.top_line as i32 + delta).clamp(0, split.max_top_line() as i32);
+            split.top_line = line as u16;
+        }
+    }
+
+    // Horizontal scrolling of the split only applies when it is not linked.
+    pub fn scroll_split_cols(&mut self, delta: i32) {
+        if let Some(split) = &mut self.split {
+            if !split.linked {
+                let col =
+                    (split.leftmost_col as i32 + delta).clamp(0, split.max_leftmost_col() as i32);
+                split.leftmost_col = col as u16;
+            }
+        }
+    }
+
+    // Leftmost column shown in the split: the main alignment's when linked, clamped so that the
+    // shorter alignment does not scroll past its end.
+    pub fn split_leftmost_col(&self) -> u16 {
+        match &self.split {
+            Some(split) if split.linked => self.leftmost_col.min(split.max_leftmost_col()),
+            Some(split) => split.leftmost_col,
+            None => 0,
+        }
+    }
+
+    /****************************************************************/
+    // Pinned sequences
+
+    // Height (without borders) of the pinned sub-pane; 0 when nothing is pinned.
+    pub fn pinned_rows_shown(&self) -> u16 {
+        let num_pinned = self.app.pinned_ranks().len().min(u16::MAX as usize) as u16;
+        num_pinned.min(MAX_PINNED_ROWS)
+    }
+
+    pub fn pinned_top_line(&self) -> u16 {
+        let num_pinned = self.app.pinned_ranks().len().min(u16::MAX as usize) as u16;
+        let max_top = num_pinned.saturating_sub(self.pinned_rows_shown());
+        self.pinned_top_line.min(max_top)
+    }
+
+    pub fn scroll_pinned_lines(&mut self, delta: i32) {
+        let line = (self.pinned_top_line() as i32 + delta).max(0);
+        self.pinned_top_line = line as u16;
+        self.pinned_top_line = self.pinned_top_line();
+    }
+
+    // Pins or unpins the cursor row; returns the new state, or None without a cursor (or when the
+    // row is the last one left in the scrolling region).
+    pub fn toggle_pin_on_cursor(&mut self) -> Option<bool> {
+        let rank = self.app.cursor_rank()?;
+        if self.app.is_pinned(rank) {
+            self.app.unpin_sequence(rank);
+            Some(false)
+        } else if self.app.pin_sequence(rank) {
+            // Show the newly pinned row, which comes last.
+            self.pinned_top_line = u16::MAX;
+            self.pinned_top_line = self.pinned_top_line();
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /****************************************************************/
+    // Wrap mode
+
+    pub fn wrap_mode(&self) -> bool {
+        self.wrap_mode
+    }
+
+    // Wrap mode only exists zoomed in; returns the new state.
+    pub fn toggle_wrap_mode(&mut self) -> bool {
+        self.wrap_mode = !self.wrap_mode;
+        if self.wrap_mode {
+            self.zoom_level = ZoomLevel::ZoomedIn;
+            self.wrap_top_block = self.leftmost_col as usize / self.wrap_width();
+        }
+        self.sync_wrap_leftmost_col();
+        self.wrap_mode
+    }
+
+    // Columns per block: the width of the sequence pane.
+    pub fn wrap_width(&self) -> usize {
+        match self.aln_pane_size {
+            Some(_) => (self.max_nb_col_shown() as usize).max(1),
+            None => 1,
+        }
+    }
+
+    // Rows per block: a column-number line, the sequences, and a blank separator line.
+    pub fn wrap_block_height(&self) -> usize {
+        self.app.num_visible_seq() as usize + 2
+    }
+
+    pub fn num_wrap_blocks(&self) -> usize {
+        (self.app.aln_len() as usize)
+            .div_ceil(self.wrap_width())
+            .max(1)
+    }
+
+    pub fn wrap_top_block(&self) -> usize {
+        self.wrap_top_block.min(self.num_wrap_blocks() - 1)
+    }
+
+    pub fn scroll_wrap_blocks(&mut self, delta: isize) {
+        let last = self.num_wrap_blocks() as isize - 1;
+        self.wrap_top_block = (self.wrap_top_block() as isize + delta).clamp(0, last) as usize;
+        self.sync_wrap_leftmost_col();
+    }
+
+    pub fn jump_to_wrap_block(&mut self, block: usize) {
+        self.wrap_top_block = block.min(self.num_wrap_blocks() - 1);
+        self.sync_wrap_leftmost_col();
+    }
+
+    // Blocks that (at least partly) fit in the sequence pane.
+    pub fn visible_wrap_blocks(&self) -> Range<usize> {
+        let top = self.wrap_top_block();
+        let rows = self.max_nb_seq_shown() as usize;
+        let count = rows.div_ceil(self.wrap_block_height()).max(1);
+        top..(top + count).min(self.num_wrap_blocks())
+    }
+
+    // The bottom pane (consensus, position) follows the top block's columns.
+    fn sync_wrap_leftmost_col(&mut self) {
+        if self.wrap_mode && self.aln_pane_size.is_some() {
+            let col = (self.wrap_top_block() * self.wrap_width()).min(u16::MAX as usize) as u16;
+            self.leftmost_col = col.min(self.max_leftmost_col());
+        }
+    }
+
+    /****************************************************************/
+    // Prompt history
+
+    // Puts the previous (`older`) or next history entry in `editor`; going past the newest entry
+    // brings back the text that was being typed.
+    pub fn recall_history(&mut self, kind: PromptKind, editor: &mut LineEditor, older: bool) {
+        let history = self.app.history(kind);
+        let nav = match (self.history_nav.take(), older) {
+            (None, true) if !history.is_empty() => Some((history.len() - 1, editor.text())),
+            (None, _) => None,
+            (Some((idx, draft)), true) => Some((idx.saturating_sub(1), draft)),
+            (Some((idx, draft)), false) if idx + 1 < history.len() => Some((idx + 1, draft)),
+            (Some((_, draft)), false) => {
+                editor.set_text(&draft);
+                None
+            }
+        };
+        if let Some((idx, _)) = &nav {
+            editor.set_text(&history[*idx]);
+        }
+        self.history_nav = nav;
+    }
+
+    // Called when a prompt is submitted or cancelled.
+    pub fn reset_history_nav(&mut self) {
+        self.history_nav = None;
+    }
+
     pub fn mark_dirty(&mut self) {
         self.dirty = true;
     }
@@ -454,7 +1108,7 @@ impl<'a> UI<'a> {
     }
 
     pub fn set_user_ordering_from_headers(&mut self) -> Result<(), TermalError> {
-        let headers = self.app.alignment.headers.clone();
+        let headers = self.app.alignment.headers().to_vec();
         self.app.set_user_ordering(headers)
     }
 
@@ -482,18 +1136,51 @@ impl<'a> UI<'a> {
         self.app.num_seq()
     }
 
+    pub fn num_visible_sequences(&self) -> u16 {
+        self.app.num_visible_seq()
+    }
+
     pub fn cursor_rank(&self) -> Option<usize> {
         self.app.cursor_rank()
     }
 
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.app.has_unsaved_changes()
+    }
+
+    pub fn clear_unsaved_changes(&mut self) {
+        self.app.clear_unsaved_changes();
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.app.set_read_only(read_only);
+    }
+
+    pub fn seq_ordering(&self) -> SeqOrdering {
+        self.app.get_seq_ordering()
+    }
+
+    pub fn has_seq_search(&self) -> bool {
+        self.app.has_seq_search()
+    }
+
+    // True while the modeline message is to be drawn flashing (see CurrentMessage::is_flashing()).
+    pub fn message_flashing(&self) -> bool {
+        self.app.current_message().is_flashing()
+    }
+
     pub fn selection_len(&self) -> usize {
         self.app.selection_ranks().len()
     }
 
+    pub fn diff_columns(&self) -> Option<&[usize]> {
+        self.app.diff_state().map(|state| state.columns.as_slice())
+    }
+
     pub fn select_label_by_rank(&mut self, rank: usize) -> Result<(), TermalError> {
         self.app.select_label_by_rank(rank)?;
         if let Some(line) = self.app.current_label_match_screenlinenum() {
-            self.jump_to_line(line as u16);
+            self.show_line_and_col(line as u16, None);
         }
         Ok(())
     }
@@ -527,9 +1214,15 @@ impl<'a> UI<'a> {
         self.max_nb_seq_shown()
     }
 
+    pub fn visible_cols(&self) -> u16 {
+        self.max_nb_col_shown()
+    }
+
     fn max_nb_col_shown(&self) -> u16 {
         let width = self.aln_pane_size.unwrap().width;
-        width.saturating_sub(2) // Borders - TODO: use constants!
+        width
+            .saturating_sub(2) // Borders - TODO: use constants!
+            .saturating_sub(self.end_coords_width())
     }
 
     // Resizing (as when the user resizes the terminal window where Termal runs) affects
@@ -540,12 +1233,19 @@ impl<'a> UI<'a> {
     // l_max, etc.
 
     pub fn adjust_seq_pane_position(&mut self) {
+        // After growing, there may be nothing left to zoom out of. (After shrinking, the zoom box
+        // follows from the clamped position, see zoombox_rect().)
+        if self.zoom_level != ZoomLevel::ZoomedIn && self.aln_wrt_seq_pane() == AlnWRTSeqPane::Fits
+        {
+            self.zoom_level = ZoomLevel::ZoomedIn;
+        }
         if self.leftmost_col > self.max_leftmost_col() {
             self.leftmost_col = self.max_leftmost_col();
         }
         if self.top_line > self.max_top_line() {
             self.top_line = self.max_top_line();
         }
+        self.sync_wrap_leftmost_col();
     }
 
     /****************************************************************/
@@ -560,8 +1260,8 @@ impl<'a> UI<'a> {
 
     // FIXME: use saturating arithmetic (also next fn)
     pub fn max_top_line(&self) -> u16 {
-        if self.app.num_seq() >= self.max_nb_seq_shown() {
-            self.app.num_seq() - self.max_nb_seq_shown()
+        if self.app.num_visible_seq() >= self.max_nb_seq_shown() {
+            self.app.num_visible_seq() - self.max_nb_seq_shown()
         } else {
             0
         }
@@ -634,12 +1334,61 @@ impl<'a> UI<'a> {
     }
 
     pub fn hide_bottom_pane(&mut self) {
-        self.previous_bottom_pane_height = self.bottom_pane_height;
+        if self.bottom_pane_height > 0 {
+            self.previous_bottom_pane_height = self.bottom_pane_height;
+        }
         self.bottom_pane_height = 0;
     }
 
     pub fn show_bottom_pane(&mut self) {
-        self.bottom_pane_height = 5;
+        self.bottom_pane_height = if self.previous_bottom_pane_height > 0 {
+            self.previous_bottom_pane_height
+        } else {
+            DEFAULT_BOTTOM_PANE_HEIGHT
+        };
+    }
+
+    // The sequence pane keeps its borders and at least MIN_ROWS_SHOWN rows (as well as the
+    // legend and gap map rows, if any).
+    fn max_bottom_pane_height(&self) -> u16 {
+        let frame_height = self.frame_size.map(|size| size.height).unwrap_or(u16::MAX);
+        let extra_rows = self.shown_bottom_pane_height() - self.bottom_pane_height;
+        frame_height
+            .saturating_sub(2 * BORDER_WIDTH + MIN_ROWS_SHOWN + extra_rows)
+            .max(MIN_BOTTOM_PANE_HEIGHT)
+    }
+
+    pub fn grow_bottom_pane(&mut self, amount: u16) {
+        self.bottom_pane_height = min(
+            max(
+                self.bottom_pane_height.saturating_add(amount),
+                MIN_BOTTOM_PANE_HEIGHT,
+            ),
+            self.max_bottom_pane_height(),
+        );
+    }
+
+    pub fn shrink_bottom_pane(&mut self, amount: u16) {
+        // A hidden pane stays hidden ('c' shows it again).
+        if self.bottom_pane_height > 0 {
+            self.bottom_pane_height = max(
+                self.bottom_pane_height.saturating_sub(amount),
+                MIN_BOTTOM_PANE_HEIGHT,
+            );
+        }
+    }
+
+    // The bottom pane gains a row for the saved-search legend while any saved search is enabled,
+    // and one for the gap map while it is shown.
+    fn shown_bottom_pane_height(&self) -> u16 {
+        if self.bottom_pane_height == 0 {
+            return 0;
+        }
+        self.bottom_pane_height + u16::from(self.has_search_legend()) + u16::from(self.show_gap_map)
+    }
+
+    fn has_search_legend(&self) -> bool {
+        self.app.saved_searches().iter().any(|entry| entry.enabled)
     }
 
     // ****************************************************************
@@ -652,7 +1401,7 @@ impl<'a> UI<'a> {
         if self.app.aln_len() > self.max_nb_col_shown() {
             rel |= AlnWRTSeqPane::TooWide;
         }
-        if self.app.num_seq() > self.max_nb_seq_shown() {
+        if self.app.num_visible_seq() > self.max_nb_seq_shown() {
             rel |= AlnWRTSeqPane::TooTall;
         }
 
@@ -665,6 +1414,11 @@ impl<'a> UI<'a> {
     }
 
     pub fn cycle_zoom(&mut self) {
+        if self.is_empty_alignment() {
+            self.app.warning_msg("Empty alignment");
+            return;
+        }
+        self.wrap_mode = false;
         self.zoom_level = match self.zoom_level {
             ZoomLevel::ZoomedIn => {
                 // ZoomedOut, unless alignment fits
@@ -679,12 +1433,24 @@ impl<'a> UI<'a> {
         }
     }
 
+    // An empty alignment has nothing to zoom: its ratios are 1.
     pub fn h_ratio(&self) -> f64 {
+        if self.is_empty_alignment() {
+            return 1.0;
+        }
         self.max_nb_col_shown() as f64 / self.app.aln_len() as f64
     }
 
     pub fn v_ratio(&self) -> f64 {
-        self.max_nb_seq_shown() as f64 / self.app.num_seq() as f64
+        if self.is_empty_alignment() {
+            return 1.0;
+        }
+        self.max_nb_seq_shown() as f64 / self.app.num_visible_seq() as f64
+    }
+
+    // No sequences, or no columns.
+    pub fn is_empty_alignment(&self) -> bool {
+        self.app.num_seq() == 0 || self.app.aln_len() == 0
     }
 
     // ZoomLevel::ZoomedOutAR mode uses a _single_ ratio, which is usually the minimum of the
@@ -694,7 +1460,7 @@ impl<'a> UI<'a> {
         let min_ratio = self.h_ratio().min(self.v_ratio());
         let max_ratio = self.h_ratio().max(self.v_ratio());
         let max_r_cols = (self.app.aln_len() as f64 * max_ratio).floor() as u16;
-        let max_r_seqs = (self.app.num_seq() as f64 * max_ratio).floor() as u16;
+        let max_r_seqs = (self.app.num_visible_seq() as f64 * max_ratio).floor() as u16;
 
         if max_r_cols == self.max_nb_col_shown() && max_r_seqs == self.max_nb_seq_shown() {
             max_ratio
@@ -711,96 +1477,22 @@ impl<'a> UI<'a> {
         self.show_zoombox = !self.show_zoombox;
     }
 
-    pub fn zoombox_top(&self) -> usize {
-        match self.zoom_level {
-            ZoomLevel::ZoomedOut => ((self.top_line as f64) * self.v_ratio()).floor() as usize,
-            ZoomLevel::ZoomedOutAR => {
-                let ratio = self.common_ratio();
-                let mut zb_top = ((self.top_line as f64) * ratio).floor() as usize;
-                if zb_top >= self.max_nb_seq_shown() as usize {
-                    zb_top = (self.max_nb_seq_shown() as usize).saturating_sub(1);
-                }
-                zb_top
-            }
-            _ => panic!(
-                "zoombox_top() should not be called in {:?} mode\n",
-                self.zoom_level
-            ),
-        }
-    }
-
-    pub fn zoombox_bottom(&self, seq_para_len: usize) -> usize {
-        match self.zoom_level {
-            ZoomLevel::ZoomedOut => {
-                let mut zb_bottom: usize = (((self.top_line + self.max_nb_seq_shown()) as f64)
-                    * self.v_ratio())
-                .round() as usize;
-                // If h_a < h_p
-                if zb_bottom > self.app.num_seq() as usize {
-                    zb_bottom = self.app.num_seq() as usize;
-                }
-                zb_bottom
-            }
-            ZoomLevel::ZoomedOutAR => {
-                let ratio = self.common_ratio();
-                let aln_para_height = min(seq_para_len as u16, self.max_nb_seq_shown());
-                let mut zb_bottom =
-                    (((self.top_line + self.max_nb_seq_shown()) as f64) * ratio).round() as usize;
-                // If h_a < h_p
-                if zb_bottom > aln_para_height as usize {
-                    zb_bottom = aln_para_height as usize;
-                }
-                zb_bottom
-            }
-            _ => panic!(
-                "zoombox_bottom() should not be called in {:?} mode\n",
-                self.zoom_level
-            ),
-        }
-    }
-
-    pub fn zoombox_left(&self) -> usize {
-        match self.zoom_level {
-            ZoomLevel::ZoomedOut => ((self.leftmost_col as f64) * self.h_ratio()).floor() as usize,
-            ZoomLevel::ZoomedOutAR => {
-                let ratio = self.common_ratio();
-                ((self.leftmost_col as f64) * ratio).floor() as usize
-            }
-            _ => panic!(
-                "zoombox_left() should not be called in {:?} mode\n",
-                self.zoom_level
-            ),
-        }
-    }
-
-    pub fn zoombox_right(&self, max_nb_col_shown_ar: usize) -> usize {
-        match self.zoom_level {
-            ZoomLevel::ZoomedOut => {
-                let mut zb_right = (((self.leftmost_col + self.max_nb_col_shown()) as f64)
-                    * self.h_ratio())
-                .floor() as usize;
-                // If w_a < w_p
-                if zb_right > self.app.aln_len() as usize {
-                    zb_right = self.app.aln_len() as usize;
-                }
-                zb_right
-            }
-            ZoomLevel::ZoomedOutAR => {
-                let ratio = self.common_ratio();
-                let aln_para_width = min(max_nb_col_shown_ar as u16, self.max_nb_col_shown());
-                let mut zb_right = (((self.leftmost_col + self.max_nb_col_shown()) as f64) * ratio)
-                    .floor() as usize;
-                // If w_a < w_p
-                if zb_right > aln_para_width as usize {
-                    zb_right = aln_para_width as usize;
-                }
-                zb_right
-            }
-            _ => panic!(
-                "zoombox_left() should not be called in {:?} mode\n",
-                self.zoom_level
-            ),
-        }
+    // Zoom box edges in the zoomed-out pane, as (top, bottom, left, right), bottom and right being
+    // exclusive. The box spans exactly the retained rows and columns that lie in the window shown
+    // when zoomed in (from top_line and leftmost_col on), so there is no rounding to drift. It
+    // always covers at least one row and one column, and never goes past the retained ones.
+    pub fn zoombox_rect(&self) -> (usize, usize, usize, usize) {
+        let (top, bottom) = zoombox_span(
+            &retained_seq_ndx(self),
+            self.top_line as usize,
+            self.max_nb_seq_shown() as usize,
+        );
+        let (left, right) = zoombox_span(
+            &retained_col_ndx(self),
+            self.leftmost_col as usize,
+            self.max_nb_col_shown() as usize,
+        );
+        (top, bottom, left, right)
     }
 
     pub fn cycle_bottom_pane_position(&mut self) {
@@ -829,6 +1521,51 @@ impl<'a> UI<'a> {
         &self.color_schemes[self.current_color_scheme_index]
     }
 
+    pub fn relative_line_numbers(&self) -> bool {
+        self.relative_line_numbers
+    }
+
+    // Returns the new state.
+    pub fn toggle_end_coords(&mut self) -> bool {
+        self.end_coords = !self.end_coords;
+        self.end_coords
+    }
+
+    // Width of the end coordinates gutter (a space, then as many digits as the alignment length
+    // has); 0 when it is off, and in wrap mode or zoomed out, where it is not shown.
+    pub fn end_coords_width(&self) -> u16 {
+        if !self.end_coords || self.zoom_level != ZoomLevel::ZoomedIn || self.wrap_mode() {
+            return 0;
+        }
+        let aln_len = self.app.aln_len_usize().max(1);
+        aln_len.ilog10() as u16 + 2
+    }
+
+    /// Coordinate (1-based, gaps not counted) of the sequence at `rank` at the rightmost column
+    /// shown: the number of its residues up to there.
+    pub fn end_coord(&self, rank: usize) -> usize {
+        let right = (self.leftmost_col as usize + self.max_nb_col_shown() as usize)
+            .min(self.app.aln_len_usize())
+            .saturating_sub(1);
+        self.app.alignment.residues_through(rank, right)
+    }
+
+    // Returns the new state.
+    pub fn toggle_relative_line_numbers(&mut self) -> bool {
+        self.relative_line_numbers = !self.relative_line_numbers;
+        self.relative_line_numbers
+    }
+
+    // Returns the new state.
+    pub fn toggle_dot_gaps(&mut self) -> bool {
+        self.dot_gaps = !self.dot_gaps;
+        self.dot_gaps
+    }
+
+    pub fn gap_char(&self) -> Option<char> {
+        self.dot_gaps.then_some('·')
+    }
+
     fn color_scheme_mut(&mut self) -> &mut ColorScheme {
         &mut self.color_schemes[self.current_color_scheme_index]
     }
@@ -844,6 +1581,102 @@ impl<'a> UI<'a> {
         self.current_color_scheme_index %= nb_color_schemes;
     }
 
+    // NOTE: relies on the order of the color schemes (dark, light, monochrome).
+    pub fn set_color_scheme(&mut self, scheme: StartupScheme) {
+        self.current_color_scheme_index = match scheme {
+            StartupScheme::Dark => 0,
+            StartupScheme::Light => 1,
+            StartupScheme::Monochrome => self.color_schemes.len() - 1,
+        };
+    }
+
+    pub fn set_video_mode(&mut self, mode: StartupVideoMode) {
+        self.video_mode = match mode {
+            StartupVideoMode::Direct => VideoMode::Direct,
+            StartupVideoMode::Inverse => VideoMode::Inverse,
+        };
+    }
+
+    /// Forces the molecule type and makes its default colormap current in every color scheme.
+    pub fn set_molecule_type(&mut self, molecule_type: SeqType) {
+        self.app.force_molecule_type(molecule_type);
+        for cs in &mut self.color_schemes {
+            cs.select_default_colormap(molecule_type);
+        }
+    }
+
+    pub fn case_fold(&self) -> CaseFold {
+        self.case_fold
+    }
+
+    // As is -> upper case -> lower case -> as is. Returns the new case.
+    pub fn cycle_case_fold(&mut self) -> CaseFold {
+        self.case_fold = match self.case_fold {
+            CaseFold::AsIs => CaseFold::Upper,
+            CaseFold::Upper => CaseFold::Lower,
+            CaseFold::Lower => CaseFold::AsIs,
+        };
+        self.case_fold
+    }
+
+    pub fn mask_aware(&self) -> bool {
+        self.mask_aware
+    }
+
+    // Returns the new state.
+    pub fn toggle_mask_aware(&mut self) -> bool {
+        self.mask_aware = !self.mask_aware;
+        self.mask_aware
+    }
+
+    pub fn conservation_shading(&self) -> bool {
+        self.conservation_shading
+    }
+
+    pub fn set_conservation_shading(&mut self, on: bool) {
+        self.conservation_shading = on;
+    }
+
+    // Returns the new state.
+    pub fn toggle_conservation_shading(&mut self) -> bool {
+        self.conservation_shading = !self.conservation_shading;
+        self.conservation_shading
+    }
+
+    pub fn header_display(&self) -> HeaderDisplay {
+        self.header_display
+    }
+
+    // Full -> middle ellipsis -> first word -> full. Returns the new mode.
+    pub fn cycle_header_display(&mut self) -> HeaderDisplay {
+        self.header_display = match self.header_display {
+            HeaderDisplay::Full => HeaderDisplay::MiddleEllipsis,
+            HeaderDisplay::MiddleEllipsis => HeaderDisplay::FirstWord,
+            HeaderDisplay::FirstWord => HeaderDisplay::Full,
+        };
+        self.header_display
+    }
+
+    pub fn show_legend(&self) -> bool {
+        self.show_legend
+    }
+
+    // Returns the new state.
+    pub fn toggle_legend(&mut self) -> bool {
+        self.show_legend = !self.show_legend;
+        self.show_legend
+    }
+
+    pub fn show_gap_map(&self) -> bool {
+        self.show_gap_map
+    }
+
+    // Returns the new state.
+    pub fn toggle_gap_map(&mut self) -> bool {
+        self.show_gap_map = !self.show_gap_map;
+        self.show_gap_map
+    }
+
     pub fn set_monochrome(&mut self) {
         // NOTE: this relies on the convention that the monochrome color scheme is last in the
         // list.
@@ -865,6 +1698,24 @@ impl<'a> UI<'a> {
         }
     }
 
+    /// Adds a colormap of residue classes (from the config) to the color schemes, to be reached
+    /// with m/M or :colormap.
+    pub fn add_residue_class_colormap(&mut self, class_map: &ResidueClassMap) {
+        let classes: Vec<(&str, Color)> = class_map
+            .classes
+            .iter()
+            .map(|(_, residues, (r, g, b))| (residues.as_str(), Color::Rgb(*r, *g, *b)))
+            .collect();
+        let cmap = ColorMap::from_classes(class_map.name.clone(), &classes);
+        for cs in &mut self.color_schemes {
+            cs.insert_colormap(cmap.clone());
+        }
+    }
+
+    pub fn select_colormap_by_name(&mut self, name: &str) -> Result<(), TermalError> {
+        self.color_scheme_mut().select_colormap_by_name(name)
+    }
+
     pub fn next_colormap(&mut self) {
         let cs: &mut ColorScheme = self.color_scheme_mut();
         cs.next_colormap();
@@ -900,7 +1751,17 @@ impl<'a> UI<'a> {
     pub fn session_save_text(&self) -> String {
         match &self.input_mode {
             InputMode::SessionSave { editor } => editor.text(),
-            InputMode::ConfirmSessionOverwrite { editor, .. } => editor.text(),
+            InputMode::ConfirmSessionOverwrite {
+                editor: Some(editor),
+                ..
+            } => editor.text(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn search_name_text(&self) -> String {
+        match &self.input_mode {
+            InputMode::SearchName { editor, .. } => editor.text(),
             _ => String::new(),
         }
     }
@@ -917,14 +1778,19 @@ impl<'a> UI<'a> {
         let mut highlights: Vec<SearchHighlight> = Vec::new();
         let config = self.app.search_color_config();
         let current_match = self.app.current_seq_match();
+        // The current search's matches take the color they will have once saved; the current
+        // match stands out in the `current_search` color.
         if let Some(spans) = self.app.seq_search_spans() {
+            let (r, g, b) = self.app.next_search_color();
             highlights.push(SearchHighlight {
                 spans_by_seq: spans,
-                color: Color::Rgb(
-                    config.current_search.0,
-                    config.current_search.1,
-                    config.current_search.2,
-                ),
+                color: Color::Rgb(r, g, b),
+            });
+        }
+        if let Some(spans) = self.app.diff_spans() {
+            highlights.push(SearchHighlight {
+                spans_by_seq: spans,
+                color: Color::Rgb(220, 0, 0),
             });
         }
         for entry in self.app.saved_searches() {
@@ -943,6 +1809,7 @@ impl<'a> UI<'a> {
                 gap_dim_factor: config.gap_dim_factor,
                 luminance_threshold: config.luminance_threshold,
                 current_match,
+                current_color: config.current_search,
                 use_truecolor: self.use_truecolor,
             },
         )
@@ -990,13 +1857,55 @@ impl<'a> UI<'a> {
         }
     }
 
+    pub(crate) fn notes_editor_mut(&mut self) -> Option<&mut NotesEditor> {
+        match &mut self.input_mode {
+            InputMode::Notes { editor, .. } => Some(editor),
+            _ => None,
+        }
+    }
+
     fn search_kind_label(kind: SearchKind) -> &'static str {
         match kind {
             SearchKind::Regex => "R",
             SearchKind::Emboss => "E",
+            SearchKind::Gff => "G",
         }
     }
 
+    /// Molecule type, size and gap content of the alignment, shown when there is no message.
+    pub fn composition_summary(&self) -> String {
+        let alignment = &self.app.alignment;
+        let kind = self.app.macromolecule_type().name();
+        let forced = if self.app.is_molecule_type_forced() {
+            " (forced)"
+        } else {
+            ""
+        };
+        format!(
+            "{}{} | {} seqs | {} cols | {:.0}% gaps",
+            kind,
+            forced,
+            alignment.num_seq(),
+            alignment.aln_len(),
+            alignment.gap_fraction() * 100.0
+        )
+    }
+
+    /// Color swatch and name of each enabled saved search, in the order their highlights are
+    /// blended (overlapping highlights are shown in the average of their colors).
+    pub fn search_legend_spans(&self) -> Vec<Span<'static>> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for entry in self.app.saved_searches().iter().filter(|e| e.enabled) {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            let color = self.map_color(Color::Rgb(entry.color.0, entry.color.1, entry.color.2));
+            spans.push(Span::styled("  ", Style::default().bg(color)));
+            spans.push(Span::raw(format!(" {}", entry.name)));
+        }
+        spans
+    }
+
     pub fn search_status_line_spans(&self) -> Vec<Span<'static>> {
         let mut spans: Vec<Span<'static>> = Vec::new();
         spans.push(Span::raw("Saved: "));
@@ -1097,6 +2006,10 @@ impl<'a> UI<'a> {
     }
 
     pub fn scroll_one_col_left(&mut self, count: u16) {
+        if let Some(col) = self.reference_residue_col(-(count as isize)) {
+            self.leftmost_col = min(col as u16, self.max_leftmost_col());
+            return;
+        }
         self.leftmost_col = self.leftmost_col.saturating_sub(count);
     }
 
@@ -1105,12 +2018,45 @@ impl<'a> UI<'a> {
     }
 
     pub fn scroll_one_col_right(&mut self, count: u16) {
+        if let Some(col) = self.reference_residue_col(count as isize) {
+            self.leftmost_col = min(col as u16, self.max_leftmost_col());
+            return;
+        }
         self.leftmost_col = min(
             self.leftmost_col.saturating_add(count),
             self.max_leftmost_col(),
         );
     }
 
+    // With a scrolling reference (see `App::set_scroll_reference`), the column of its residue
+    // `delta` residues away from the leftmost column (clamped to its first and last residues); a
+    // gap column counts as lying just after the residue before it. None without a reference, or
+    // if it has no residues.
+    fn reference_residue_col(&self, delta: isize) -> Option<usize> {
+        let rank = self.app.scroll_reference_rank()?;
+        let alignment = &self.app.alignment;
+        let nb_residues = alignment.ungapped_length(rank);
+        if nb_residues == 0 {
+            return None;
+        }
+        let col = self.leftmost_col as usize;
+        let through = alignment.residues_through(rank, col);
+        let on_residue = alignment.ungapped_to_gapped(rank, through) == Some(col);
+        let target = if delta < 0 && !on_residue {
+            through as isize + 1 + delta
+        } else {
+            through as isize + delta
+        };
+        let target_col =
+            alignment.ungapped_to_gapped(rank, target.clamp(1, nb_residues as isize) as usize)?;
+        // Never backwards, e.g. when left of the first residue.
+        Some(if delta < 0 {
+            min(target_col, col)
+        } else {
+            max(target_col, col)
+        })
+    }
+
     // By screens
 
     pub fn scroll_one_screen_up(&mut self, count: u16) {
@@ -1222,37 +2168,637 @@ impl<'a> UI<'a> {
 
     pub fn jump_to_col(&mut self, col: u16) {
         // -1 <- 1-based
-        self.leftmost_col = min(col - 1, self.max_leftmost_col());
+        self.leftmost_col = min(col.saturating_sub(1), self.max_leftmost_col());
+    }
+
+    /// Scrolls to the column shown as `display` (see `App::set_column_origin`), clamped to the
+    /// alignment; returns false if it was out of it.
+    pub fn jump_to_display_col(&mut self, display: i64) -> bool {
+        let internal = self.app.internal_col(display);
+        let col = internal.unwrap_or(if display < self.app.column_origin() {
+            0
+        } else {
+            self.app.aln_len() as usize
+        });
+        self.leftmost_col = min(col as u16, self.max_leftmost_col());
+        internal.is_some()
     }
 
     pub fn jump_to_pct_line(&mut self, pct: u16) {
         let clamped_pct = min(100, pct);
-        let tgt_line = (clamped_pct as f64 / 100.0 * self.app.num_seq() as f64).round() as u16;
-        self.top_line = tgt_line;
+        let tgt_line =
+            (clamped_pct as f64 / 100.0 * self.app.num_visible_seq() as f64).round() as u16;
+        self.top_line = min(tgt_line, self.max_top_line());
+    }
+
+    /// Puts the cursor on sequence number `n` (1-based, as in the label numbers pane, clamped to
+    /// the number of sequences) and scrolls it into view, wherever the ordering put it.
+    pub fn jump_to_seq_number(&mut self, n: usize) {
+        let num_seq = self.app.num_seq_usize();
+        if num_seq == 0 {
+            return;
+        }
+        let rank = n.clamp(1, num_seq) - 1;
+        self.app.set_cursor_rank(rank);
+        let screenline = self.app.rank_to_screenline(rank) as u16;
+        self.show_line_and_col(screenline, None);
+        self.app.info_msg(format!(
+            "Sequence {} ({})",
+            rank + 1,
+            self.app.alignment.headers()[rank]
+        ));
+    }
+
+    /// Puts the cursor on the shown sequence with the highest (`high`) or lowest value of the
+    /// current metric (see App::order_values()), e.g. the most typical or the outlier sequence by
+    /// %id to the consensus, and scrolls to it. Ties go to the first sequence in the file.
+    pub fn jump_to_metric_extreme(&mut self, high: bool) {
+        let values = self.app.order_values();
+        let mut candidates: Vec<usize> = self
+            .app
+            .ordering
+            .iter()
+            .copied()
+            .filter(|&rank| !values[rank].is_nan())
+            .collect();
+        candidates.sort_unstable();
+        let extreme = candidates.into_iter().reduce(|best, rank| {
+            let better = if high {
+                values[rank] > values[best]
+            } else {
+                values[rank] < values[best]
+            };
+            if better {
+                rank
+            } else {
+                best
+            }
+        });
+        let Some(rank) = extreme else {
+            self.app.warning_msg("No sequence to jump to");
+            return;
+        };
+        let value = values[rank];
+        self.app.set_cursor_rank(rank);
+        let screenline = self.app.rank_to_screenline(rank) as u16;
+        self.show_line_and_col(screenline, None);
+        self.app.info_msg(format!(
+            "{} {}: {:.2}, sequence {} ({})",
+            if high { "Highest" } else { "Lowest" },
+            self.app.get_metric(),
+            value,
+            rank + 1,
+            self.app.alignment.headers()[rank]
+        ));
+    }
+
+    /// Puts the cursor on the sequence shown at `pct`% of the way down the alignment (clamped to
+    /// 100%) and centers the view on it.
+    pub fn cursor_to_pct_line(&mut self, pct: u16) {
+        let num_seq = self.app.ordering.len();
+        if num_seq == 0 {
+            return;
+        }
+        let clamped_pct = min(100, pct);
+        let tgt_line = (clamped_pct as f64 / 100.0 * num_seq as f64).round() as usize;
+        let tgt_line = min(tgt_line, num_seq - 1);
+        let rank = self.app.ordering[tgt_line];
+        self.app.set_cursor_rank(rank);
+        self.center_on_line(tgt_line as u16);
     }
 
     pub fn jump_to_pct_col(&mut self, pct: u16) {
         let clamped_pct = min(100, pct);
         let tgt_col = (clamped_pct as f64 / 100.0 * self.app.aln_len() as f64).round() as u16;
-        self.leftmost_col = tgt_col;
+        self.leftmost_col = min(tgt_col, self.max_leftmost_col());
+    }
+
+    /// Remembers the top line, leftmost column and cursor, e.g. when a search prompt opens, so
+    /// that pop_viewport_mark() can come back to them if the search is canceled.
+    pub fn push_viewport_mark(&mut self) {
+        self.viewport_marks.push(ViewportMark {
+            top_line: self.top_line,
+            leftmost_col: self.leftmost_col,
+            cursor_rank: self.app.cursor_rank(),
+        });
+    }
+
+    /// Forgets the last mark pushed by push_viewport_mark(); if `restore`, first returns to it.
+    pub fn pop_viewport_mark(&mut self, restore: bool) {
+        let Some(mark) = self.viewport_marks.pop() else {
+            return;
+        };
+        if restore {
+            self.top_line = mark.top_line;
+            self.leftmost_col = mark.leftmost_col;
+            match mark.cursor_rank {
+                Some(rank) => {
+                    self.app.set_cursor_rank(rank);
+                }
+                None => self.app.clear_cursor(),
+            }
+        }
+    }
+
+    /// Scrolls to the first header that the partial `pattern` of the label search being typed
+    /// matches, or back to where the search started (the last viewport mark) if there is none, or
+    /// if `pattern` is empty or not yet a valid regex. The current search is left alone.
+    pub fn preview_label_search(&mut self, pattern: &str) {
+        let first = match pattern {
+            "" => None,
+            _ => self.app.first_label_match_rank(pattern),
+        };
+        match first {
+            Some(rank) => {
+                let line = self.app.rank_to_screenline(rank);
+                self.show_line_and_col(line as u16, None);
+            }
+            None => {
+                if let Some(mark) = self.viewport_marks.last() {
+                    (self.top_line, self.leftmost_col) = (mark.top_line, mark.leftmost_col);
+                }
+            }
+        }
     }
 
     pub fn jump_to_next_lbl_match(&mut self, count: i16) {
         self.app.increment_current_lbl_match(count as isize);
         let next_match_orig_line = self.app.current_label_match_screenlinenum();
         if let Some(line) = next_match_orig_line {
-            self.jump_to_line(line as u16);
+            self.show_line_and_col(line as u16, None);
         }
     }
 
     pub fn jump_to_next_seq_match(&mut self, count: i16) {
-        if let Some((cur, total)) = self.app.increment_current_seq_match(count as isize) {
+        let position = self.app.increment_current_seq_match(count as isize);
+        self.show_seq_match(position);
+    }
+
+    /// Jumps to the `n`-th (1-based) header match.
+    pub fn jump_to_lbl_match(&mut self, n: usize) {
+        self.app.goto_label_match(n);
+        if let Some(line) = self.app.current_label_match_screenlinenum() {
+            self.jump_to_line(line as u16);
+        }
+    }
+
+    /// Jumps to the `n`-th (1-based) sequence match.
+    pub fn jump_to_seq_match(&mut self, n: usize) {
+        let position = self
+            .app
+            .goto_seq_match(n)
+            .map(|(cur, total)| (cur, total, false));
+        self.show_seq_match(position);
+    }
+
+    // Moves to the next (count > 0) or previous disagreement column of `:diff` and scrolls to it.
+    pub fn jump_to_next_diff(&mut self, count: isize) {
+        match self.app.increment_current_diff(count) {
+            Some((col, cur, total, wrapped)) => {
+                self.show_diff_column(col);
+                let wrapped = if wrapped { " (wrapped)" } else { "" };
+                self.app.info_msg(format!(
+                    "difference {} of {} (column {}){}",
+                    cur,
+                    total,
+                    self.app.display_col(col),
+                    wrapped
+                ));
+            }
+            None => self.app.info_msg("No differences"),
+        }
+    }
+
+    pub fn jump_to_next_annotation_match(&mut self, count: isize) {
+        match self.app.increment_current_annotation_match(count) {
+            Some(((start, end), cur, total, wrapped)) => {
+                self.show_line_and_col(self.top_line, Some(start as u16));
+                let feature = self
+                    .app
+                    .annotation_search()
+                    .map_or(String::new(), |state| state.feature.clone());
+                let wrapped = if wrapped { " (wrapped)" } else { "" };
+                self.app.info_msg(format!(
+                    "{} match {} of {} (columns {}-{}){}",
+                    feature,
+                    cur,
+                    total,
+                    start + 1,
+                    end,
+                    wrapped
+                ));
+            }
+            None => self.app.info_msg("No annotation matches"),
+        }
+    }
+
+    pub fn show_diff_column(&mut self, col: usize) {
+        let screenline = match self.app.diff_state() {
+            Some(state) => self.app.rank_to_screenline(state.rank_a) as u16,
+            None => self.top_line,
+        };
+        self.show_line_and_col(screenline, Some(col as u16));
+    }
+
+    // Brings a target into view: at the top (and left) edge, or in the middle of the pane if
+    // `center_matches` is set.
+    fn show_line_and_col(&mut self, line: u16, col: Option<u16>) {
+        if self.center_matches {
+            self.center_on_line(line);
+            if let Some(col) = col {
+                self.center_on_col(col);
+            }
+        } else {
+            self.jump_to_line(line);
+            if let Some(col) = col {
+                self.leftmost_col = col;
+            }
+        }
+    }
+
+    // Scrolls so that (0-based) `line` is in the middle of the pane, as far as possible.
+    pub fn center_on_line(&mut self, line: u16) {
+        self.jump_to_line(line.saturating_sub(self.max_nb_seq_shown() / 2));
+    }
+
+    // Scrolls so that (0-based) `col` is in the middle of the pane, as far as possible.
+    pub fn center_on_col(&mut self, col: u16) {
+        let leftmost_col = col.saturating_sub(self.max_nb_col_shown() / 2);
+        self.leftmost_col = min(leftmost_col, self.max_leftmost_col());
+    }
+
+    /// Returns to a clean view: file order, no current search, zoomed in and scrolled to the top
+    /// left, with the panes shown. Saved searches, notes and marks are kept.
+    pub fn reset_view(&mut self) {
+        self.app.clear_search();
+        self.app.set_seq_ordering(SeqOrdering::SourceFile);
+        self.zoom_level = ZoomLevel::ZoomedIn;
+        if self.full_screen {
+            self.show_label_pane();
+            self.show_bottom_pane();
+            self.full_screen = false;
+        }
+        self.top_line = 0;
+        self.leftmost_col = 0;
+        self.app.info_msg("View reset");
+    }
+
+    /// Number of rows and columns of the overview map: one cell per character inside the
+    /// alignment pane (as last drawn).
+    pub fn overview_size(&self) -> (usize, usize) {
+        self.aln_pane_area.map_or((0, 0), |area| {
+            (
+                area.height.saturating_sub(2) as usize,
+                area.width.saturating_sub(2) as usize,
+            )
+        })
+    }
+
+    /// Leaves the overview for the zoomed-in view, with the cursor on the first sequence of cell
+    /// (`row`, `col`) of the map and its first column at the left.
+    pub fn jump_to_overview_cell(&mut self, row: usize, col: usize) {
+        let (rows, cols) = self.overview_size();
+        self.input_mode = InputMode::Normal;
+        if rows == 0 || cols == 0 || self.is_empty_alignment() {
+            return;
+        }
+        let rank = min(row, rows - 1) * self.app.num_seq_usize() / rows;
+        let aln_col = min(col, cols - 1) * self.app.aln_len_usize() / cols;
+        self.wrap_mode = false;
+        self.zoom_level = ZoomLevel::ZoomedIn;
+        self.jump_to_seq_number(rank + 1);
+        self.leftmost_col = min(aln_col as u16, self.max_leftmost_col());
+        self.app.info_msg(format!(
+            "Sequence {}, column {}",
+            rank + 1,
+            self.app.display_col(aln_col)
+        ));
+    }
+
+    // Centers the current sequence match, else the cursor line; returns false if there is
+    // neither.
+    pub fn center_on_target(&mut self) -> bool {
+        if let Some(m) = self.app.current_seq_match() {
+            let screenline = self.app.rank_to_screenline(m.seq_index) as u16;
+            self.center_on_line(screenline);
+            self.center_on_col(m.start as u16);
+            true
+        } else if let Some(rank) = self.app.cursor_rank() {
+            let screenline = self.app.rank_to_screenline(rank) as u16;
+            self.center_on_line(screenline);
+            true
+        } else {
+            false
+        }
+    }
+
+    // Returns the new state.
+    pub fn toggle_center_matches(&mut self) -> bool {
+        self.center_matches = !self.center_matches;
+        self.center_matches
+    }
+
+    pub fn guide_col(&self) -> Option<usize> {
+        self.guide_col
+    }
+
+    // Shows the vertical guide in the middle of the visible columns, or hides it. Returns the
+    // new state.
+    pub fn toggle_guide(&mut self) -> bool {
+        if self.guide_col.is_some() || self.app.aln_len_usize() == 0 {
+            self.guide_col = None;
+        } else {
+            let middle = self.leftmost_col as usize + self.max_nb_col_shown() as usize / 2;
+            self.guide_col = Some(min(middle, self.app.aln_len_usize() - 1));
+        }
+        self.guide_col.is_some()
+    }
+
+    pub fn hide_guide(&mut self) {
+        self.guide_col = None;
+        self.show_column_popup = false;
+    }
+
+    pub fn show_column_popup(&self) -> bool {
+        self.show_column_popup && self.guide_col.is_some()
+    }
+
+    // Shows the guide column's composition, or hides it. Without a guide, there is nothing to
+    // show. Returns the new state.
+    pub fn toggle_column_popup(&mut self) -> bool {
+        self.show_column_popup = !self.show_column_popup && self.guide_col.is_some();
+        self.show_column_popup
+    }
+
+    pub fn hide_column_popup(&mut self) {
+        self.show_column_popup = false;
+    }
+
+    /// Inserts `count` all-gap columns at the guide column (which then shows the first of them).
+    /// Returns false if the guide is not shown.
+    pub fn insert_gap_columns_at_guide(&mut self, count: usize) -> Result<bool, TermalError> {
+        let Some(col) = self.guide_col else {
+            return Ok(false);
+        };
+        self.app.insert_gap_columns(col, count)?;
+        Ok(true)
+    }
+
+    /// Removes `count` columns from the guide column on; returns how many were removed.
+    pub fn delete_columns_at_guide(&mut self, count: usize) -> Result<usize, TermalError> {
+        let Some(col) = self.guide_col else {
+            return Ok(0);
+        };
+        let removed = self.app.delete_columns(col, count)?;
+        self.clamp_to_alignment();
+        Ok(removed)
+    }
+
+    /// Moves the cursor sequence's residue under the guide by up to `count` cells in `direction`,
+    /// through gaps only, and moves the guide along. Returns its new column, or None if there is
+    /// no guide or cursor, or if it could not move.
+    pub fn shift_residue_at_guide(
+        &mut self,
+        direction: ShiftDirection,
+        count: usize,
+    ) -> Result<Option<usize>, TermalError> {
+        let (Some(col), Some(rank)) = (self.guide_col, self.app.cursor_rank()) else {
+            return Ok(None);
+        };
+        let Some(to) = self.app.shift_residue(rank, col, direction, count)? else {
+            return Ok(None);
+        };
+        self.move_guide(to as isize - col as isize);
+        Ok(Some(to))
+    }
+
+    /// Undoes the last edit (column insertion or deletion, residue shift) and puts the guide (if
+    /// shown) where it was.
+    pub fn undo_edit(&mut self) -> Result<AlignmentEdit, TermalError> {
+        let edit = self.app.undo_edit()?;
+        if self.guide_col.is_some() {
+            let (AlignmentEdit::Inserted { at, .. }
+            | AlignmentEdit::Deleted { at, .. }
+            | AlignmentEdit::Shifted { from: at, .. }) = edit;
+            self.guide_col = Some(at);
+        }
+        self.clamp_to_alignment();
+        Ok(edit)
+    }
+
+    // Keeps the guide and the scrolling position within the alignment, e.g. after it has lost
+    // columns.
+    fn clamp_to_alignment(&mut self) {
+        let aln_len = self.app.aln_len_usize();
+        self.guide_col = self
+            .guide_col
+            .map(|col| min(col, aln_len.saturating_sub(1)));
+        self.leftmost_col = min(self.leftmost_col, self.max_leftmost_col());
+    }
+
+    /// Residue counts of the guide column, e.g. "A:12 C:3 G:0 T:5 -:2". Nucleotides are always
+    /// all listed, in alphabetical order; amino acids only if present, most frequent first.
+    pub fn column_composition(&self) -> Option<String> {
+        let col = self.guide_col?;
+        let mut frequencies = self.app.alignment.column_frequencies(col);
+        let nucleotides = match self.app.macromolecule_type() {
+            SeqType::Nucleic => "ACGT",
+            SeqType::Rna => "ACGU",
+            SeqType::Protein => "",
+        };
+        if !nucleotides.is_empty() {
+            let count = |residue: char| {
+                frequencies
+                    .iter()
+                    .find(|(r, _)| *r == residue)
+                    .map_or(0, |(_, count)| *count)
+            };
+            let mut ordered: Vec<(char, u64)> =
+                nucleotides.chars().map(|n| (n, count(n))).collect();
+            ordered.extend(
+                frequencies
+                    .iter()
+                    .filter(|(r, _)| !nucleotides.contains(*r)),
+            );
+            // Other residues (e.g. N) go after the nucleotides, but before the gaps.
+            ordered.sort_by_key(|(r, _)| *r == '-');
+            frequencies = ordered;
+        }
+        Some(
+            frequencies
+                .iter()
+                .map(|(residue, count)| format!("{}:{}", residue, count))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    /// Scrolls to the start of the `count`-th conserved block (see Alignment::conserved_blocks())
+    /// right of the leftmost column, or the last one, and shows its number in the modeline.
+    pub fn next_conserved_block(&mut self, count: usize) {
+        self.jump_to_conserved_block(count as isize);
+    }
+
+    /// Like next_conserved_block(), but leftwards.
+    pub fn prev_conserved_block(&mut self, count: usize) {
+        self.jump_to_conserved_block(-(count as isize));
+    }
+
+    fn jump_to_conserved_block(&mut self, delta: isize) {
+        let blocks = self
+            .app
+            .alignment
+            .conserved_blocks(CONSERVED_BLOCK_THRESHOLD);
+        if blocks.is_empty() {
+            self.app.warning_msg("No conserved blocks");
+            return;
+        }
+        let left = self.leftmost_col as usize;
+        // Blocks strictly before the view's left edge, and those starting at it or before.
+        let (before, at_or_before) = match self.last_conserved_block {
+            Some((idx, col)) if col == self.leftmost_col && idx < blocks.len() => (idx, idx + 1),
+            _ => (
+                blocks.partition_point(|&(start, _)| start < left),
+                blocks.partition_point(|&(start, _)| start <= left),
+            ),
+        };
+        let target = if delta > 0 {
+            at_or_before + delta as usize - 1
+        } else {
+            match before.checked_sub(delta.unsigned_abs()) {
+                Some(idx) => idx,
+                None if before == 0 => {
+                    self.app.info_msg("No conserved block to the left");
+                    return;
+                }
+                None => 0,
+            }
+        };
+        if target >= blocks.len() && at_or_before >= blocks.len() {
+            self.app.info_msg("No conserved block to the right");
+            return;
+        }
+        let target = min(target, blocks.len() - 1);
+        let (start, end) = blocks[target];
+        self.leftmost_col = min(start as u16, self.max_leftmost_col());
+        self.last_conserved_block = Some((target, self.leftmost_col));
+        self.app.info_msg(format!(
+            "block {}/{} (columns {}-{})",
+            target + 1,
+            blocks.len(),
+            start + 1,
+            end
+        ));
+    }
+
+    /// Scrolls to the count-th next gap run of the cursor sequence (wrapping around), or moves the
+    /// guide there if it is shown.
+    pub fn next_gap_start(&mut self, count: usize) {
+        self.jump_along_cursor_seq(count, true);
+    }
+
+    /// Like next_gap_start(), but to the first residue after a gap run.
+    pub fn next_residue_after_gap(&mut self, count: usize) {
+        self.jump_along_cursor_seq(count, false);
+    }
+
+    fn jump_along_cursor_seq(&mut self, count: usize, gap_start: bool) {
+        let Some(rank) = self.app.cursor_rank() else {
+            self.app.warning_msg("No cursor sequence");
+            return;
+        };
+        let mut col = match (self.guide_col, self.last_gap_motion) {
+            (Some(guide), _) => guide,
+            (None, Some((col, left))) if left == self.leftmost_col => col,
+            _ => self.leftmost_col as usize,
+        };
+        let mut wrapped = false;
+        for _ in 0..count {
+            let next = if gap_start {
+                self.app.alignment.next_gap_start(rank, col)
+            } else {
+                self.app.alignment.next_residue_after_gap(rank, col)
+            };
+            let Some(next) = next else {
+                self.app.info_msg(if gap_start {
+                    "No more gaps"
+                } else {
+                    "No more residues after gaps"
+                });
+                return;
+            };
+            wrapped |= next <= col;
+            col = next;
+        }
+        match self.guide_col {
+            Some(guide) => self.move_guide(col as isize - guide as isize),
+            None => {
+                self.leftmost_col = min(col as u16, self.max_leftmost_col());
+                self.last_gap_motion = Some((col, self.leftmost_col));
+            }
+        }
+        self.app.info_msg(format!(
+            "{} at column {}{}",
+            if gap_start { "Gap" } else { "Residue" },
+            self.app.display_col(col),
+            if wrapped { " (wrapped)" } else { "" }
+        ));
+    }
+
+    pub fn min_occupancy(&self) -> Option<f64> {
+        self.min_occupancy
+    }
+
+    // Dims the columns whose occupancy is below `threshold` (None: no dimming).
+    pub fn set_min_occupancy(&mut self, threshold: Option<f64>) {
+        self.min_occupancy = threshold;
+    }
+
+    // Removes the columns whose occupancy is below `min_occupancy` (see App::trim_columns()),
+    // dropping the guide if its column is gone. Returns the number of columns removed.
+    pub fn trim_columns(&mut self, min_occupancy: f64) -> Result<usize, TermalError> {
+        let removed = self.app.trim_columns(min_occupancy)?;
+        if self
+            .guide_col
+            .is_some_and(|col| col >= self.app.aln_len_usize())
+        {
+            self.guide_col = None;
+        }
+        Ok(removed)
+    }
+
+    // Moves the guide by `delta` columns (within the alignment), scrolling to keep it in view
+    // when zoomed in.
+    pub fn move_guide(&mut self, delta: isize) {
+        let Some(col) = self.guide_col else {
+            return;
+        };
+        let last = self.app.aln_len_usize().saturating_sub(1);
+        let col = col.saturating_add_signed(delta).min(last);
+        self.guide_col = Some(col);
+        self.app
+            .info_msg(format!("Guide at column {}", self.app.display_col(col)));
+        if self.zoom_level == ZoomLevel::ZoomedIn && !self.wrap_mode {
+            let leftmost = self.leftmost_col as usize;
+            let shown = self.max_nb_col_shown() as usize;
+            if col < leftmost {
+                self.leftmost_col = col as u16;
+            } else if col >= leftmost + shown {
+                self.leftmost_col = min((col + 1 - shown) as u16, self.max_leftmost_col());
+            }
+        }
+    }
+
+    // Scrolls the current sequence match into view and reports its position among the matches.
+    fn show_seq_match(&mut self, position: Option<(usize, usize, bool)>) {
+        if let Some((cur, total, wrapped)) = position {
             if let Some(m) = self.app.current_seq_match() {
                 let screenline = self.app.rank_to_screenline(m.seq_index) as u16;
-                self.jump_to_line(screenline);
-                self.leftmost_col = m.start as u16;
+                self.show_line_and_col(screenline, Some(m.start as u16));
             }
-            self.app.info_msg(format!("match {} of {}", cur, total));
+            let wrapped = if wrapped { " (wrapped)" } else { "" };
+            self.app
+                .info_msg(format!("match {} of {}{}", cur, total, wrapped));
         } else {
             self.app.info_msg("No current search");
         }
@@ -8,18 +8,33 @@ use ratatui::{
     widgets::Widget,
 };
 
-use crate::{app::SeqMatch, ui::zoombox::draw_zoombox_border};
+use crate::{
+    alignment::is_gap,
+    app::SeqMatch,
+    ui::{zoombox::draw_zoombox_border, CaseFold},
+};
+
+// Background of the guide column; search and feature highlights are drawn over it.
+const GUIDE_BG: Color = Color::Indexed(238);
+// Background of columns below the occupancy threshold (`:occupancy`).
+const SPARSE_BG: Color = Color::Indexed(235);
+// With conservation shading, residues in the least conserved columns keep this fraction of their
+// color, so that they stay legible.
+const MIN_SHADE: f64 = 0.25;
 
 pub struct SearchHighlight<'a> {
     pub spans_by_seq: &'a [Vec<(usize, usize)>],
     pub color: Color,
 }
 
+#[derive(Clone, Copy)]
 pub struct SearchHighlightConfig {
     pub min_component: u8,
     pub gap_dim_factor: f32,
     pub luminance_threshold: f32,
     pub current_match: Option<SeqMatch>,
+    // Color of the current match, which is not blended with the other highlights.
+    pub current_color: (u8, u8, u8),
     pub use_truecolor: bool,
 }
 
@@ -32,6 +47,18 @@ pub struct SeqPane<'a> {
     pub highlights: &'a [SearchHighlight<'a>],
     pub highlight_config: SearchHighlightConfig,
     pub underline_seq_index: Option<usize>,
+    // Alignment column highlighted by the vertical guide, if any.
+    pub guide_col: Option<usize>,
+    // Columns whose occupancy (see Alignment::column_occupancy()) is below the threshold, if any,
+    // are dimmed.
+    pub occupancy: &'a [f64],
+    pub min_occupancy: Option<f64>,
+    // Per-column conservation (see Alignment::column_conservation()), if residue colors are to be
+    // shaded by it; the style LUT then holds unmapped RGB colors.
+    pub conservation: Option<&'a [f64]>,
+    // Drawn instead of gap characters, if set (e.g. '·').
+    pub gap_char: Option<char>,
+    pub case_fold: CaseFold,
     // TODO: not sure this is required - if not, also remove from other SeqPane* structs
     pub base_style: Style, // optional, for clearing/background
 }
@@ -73,6 +100,20 @@ impl<'a> Widget for SeqPane<'a> {
                 }
                 let b = seq[j];
                 let mut style = self.style_lut[b as usize].bg(Color::Black);
+                if let Some(conservation) = self.conservation {
+                    style = shade_by_conservation(
+                        style,
+                        b as char,
+                        conservation.get(j).copied().unwrap_or(1.0),
+                        self.highlight_config.use_truecolor,
+                    );
+                }
+                if is_sparse(self.occupancy, self.min_occupancy, j) {
+                    style = style.bg(SPARSE_BG).add_modifier(Modifier::DIM);
+                }
+                if self.guide_col == Some(j) {
+                    style = style.bg(GUIDE_BG);
+                }
                 if let Some((color, use_black_fg, is_current)) = highlight_color(j, b as char) {
                     style = style.bg(color);
                     if use_black_fg {
@@ -88,7 +129,7 @@ impl<'a> Widget for SeqPane<'a> {
 
                 buf.cell_mut(Position::from((area.x + c as u16, area.y + r as u16)))
                     .expect("Wrong position")
-                    .set_char(b as char)
+                    .set_char(display_char(b as char, self.gap_char, self.case_fold))
                     .set_style(style);
             }
         }
@@ -96,7 +137,7 @@ impl<'a> Widget for SeqPane<'a> {
 }
 
 pub struct SeqPaneZoomedOut<'a> {
-    pub sequences: &'a [String],    // alignment.sequences
+    pub sequences: &'a [String],    // alignment.sequences()
     pub ordering: &'a [usize],      // ordering map
     pub retained_rows: &'a [usize], // indices into "logical rows"
     pub retained_cols: &'a [usize], // indices into alignment columns
@@ -104,6 +145,12 @@ pub struct SeqPaneZoomedOut<'a> {
     pub highlights: &'a [SearchHighlight<'a>],
     pub highlight_config: SearchHighlightConfig,
     pub underline_seq_index: Option<usize>,
+    pub guide_col: Option<usize>,
+    pub occupancy: &'a [f64],
+    pub min_occupancy: Option<f64>,
+    pub conservation: Option<&'a [f64]>,
+    pub gap_char: Option<char>,
+    pub case_fold: CaseFold,
     pub base_style: Style, // for clearing/background
     pub show_zoombox: bool,
     pub zb_top: usize,
@@ -131,6 +178,26 @@ impl<'a> Widget for SeqPaneZoomedOut<'a> {
         // Render sampled rows/cols
         let max_r = rows.min(self.retained_rows.len());
         let max_c = cols.min(self.retained_cols.len());
+        // The guide is drawn on the retained column that stands for it.
+        let guide_c = self.guide_col.map(|col| {
+            self.retained_cols
+                .partition_point(|&j| j <= col)
+                .saturating_sub(1)
+        });
+        // Likewise, the current match is marked on the retained row and column that stand for its
+        // sequence and start, even if sampling skips them.
+        let current_cell = self.highlight_config.current_match.and_then(|m| {
+            let line = self.ordering.iter().position(|&idx| idx == m.seq_index)?;
+            let r = self
+                .retained_rows
+                .partition_point(|&i| i <= line)
+                .saturating_sub(1);
+            let c = self
+                .retained_cols
+                .partition_point(|&j| j <= m.start)
+                .saturating_sub(1);
+            Some((r, c, m))
+        });
 
         for r in 0..max_r {
             let i = self.retained_rows[r];
@@ -141,9 +208,10 @@ impl<'a> Widget for SeqPaneZoomedOut<'a> {
 
             let seq_index = self.ordering[i];
             let seq_bytes = self.sequences[seq_index].as_bytes();
-            let highlight_color = |col: usize, ch: char| {
+            let highlight_color_at = |seq_index: usize, col: usize, ch: char| {
                 highlight_color(self.highlights, &self.highlight_config, seq_index, col, ch)
             };
+            let highlight_color = |col: usize, ch: char| highlight_color_at(seq_index, col, ch);
             let underline_row = self
                 .underline_seq_index
                 .map(|idx| idx == seq_index)
@@ -158,7 +226,27 @@ impl<'a> Widget for SeqPaneZoomedOut<'a> {
 
                 let b = seq_bytes[j];
                 let mut style = self.style_lut[b as usize].bg(Color::Black);
-                if let Some((color, use_black_fg, is_current)) = highlight_color(j, b as char) {
+                if let Some(conservation) = self.conservation {
+                    style = shade_by_conservation(
+                        style,
+                        b as char,
+                        conservation.get(j).copied().unwrap_or(1.0),
+                        self.highlight_config.use_truecolor,
+                    );
+                }
+                if is_sparse(self.occupancy, self.min_occupancy, j) {
+                    style = style.bg(SPARSE_BG).add_modifier(Modifier::DIM);
+                }
+                if guide_c == Some(c) {
+                    style = style.bg(GUIDE_BG);
+                }
+                let highlight = match current_cell {
+                    Some((current_r, current_c, m)) if (current_r, current_c) == (r, c) => {
+                        highlight_color_at(m.seq_index, m.start, b as char)
+                    }
+                    _ => highlight_color(j, b as char),
+                };
+                if let Some((color, use_black_fg, is_current)) = highlight {
                     style = style.bg(color);
                     if use_black_fg {
                         style = style.fg(Color::Black);
@@ -173,7 +261,7 @@ impl<'a> Widget for SeqPaneZoomedOut<'a> {
 
                 buf.cell_mut(Position::from((area.x + c as u16, area.y + r as u16)))
                     .expect("Wrong position")
-                    .set_char(b as char)
+                    .set_char(display_char(b as char, self.gap_char, self.case_fold))
                     .set_style(style);
             }
         }
@@ -192,6 +280,29 @@ impl<'a> Widget for SeqPaneZoomedOut<'a> {
     }
 }
 
+fn is_sparse(occupancy: &[f64], min_occupancy: Option<f64>, col: usize) -> bool {
+    min_occupancy.is_some_and(|min| occupancy.get(col).is_some_and(|&occ| occ < min))
+}
+
+// Blends a residue's (RGB) color toward the black background as its column's conservation drops,
+// then maps it to the terminal's palette. Gaps and non-RGB colors are left alone.
+fn shade_by_conservation(style: Style, ch: char, conservation: f64, use_truecolor: bool) -> Style {
+    let Some(Color::Rgb(mut r, mut g, mut b)) = style.fg else {
+        return style;
+    };
+    if !is_gap(ch) {
+        let factor = MIN_SHADE + (1.0 - MIN_SHADE) * conservation.clamp(0.0, 1.0);
+        dim_color(&mut r, &mut g, &mut b, factor as f32);
+    }
+    if use_truecolor {
+        style.fg(Color::Rgb(r, g, b))
+    } else {
+        style.fg(Color::Indexed(crate::ui::color_map::rgb_to_ansi256(
+            r, g, b,
+        )))
+    }
+}
+
 fn in_spans(spans: &[(usize, usize)], col: usize) -> bool {
     spans.iter().any(|(start, end)| *start <= col && col < *end)
 }
@@ -203,6 +314,15 @@ fn highlight_color(
     col: usize,
     ch: char,
 ) -> Option<(Color, bool, bool)> {
+    let is_current = config
+        .current_match
+        .map(|m| m.seq_index == seq_index && m.start <= col && col < m.end)
+        .unwrap_or(false);
+    if is_current {
+        let (mut r, mut g, mut b) = config.current_color;
+        normalize_min_component(&mut r, &mut g, &mut b, config.min_component);
+        return Some(finish_highlight(config, r, g, b, ch, true));
+    }
     let colors: Vec<(u8, u8, u8)> = highlights
         .iter()
         .filter_map(|highlight| {
@@ -218,21 +338,29 @@ fn highlight_color(
     }
     let (mut r, mut g, mut b) = blend_colors(&colors);
     normalize_min_component(&mut r, &mut g, &mut b, config.min_component);
+    Some(finish_highlight(config, r, g, b, ch, false))
+}
+
+// Dims gaps and picks the foreground that reads best on the highlight.
+fn finish_highlight(
+    config: &SearchHighlightConfig,
+    mut r: u8,
+    mut g: u8,
+    mut b: u8,
+    ch: char,
+    is_current: bool,
+) -> (Color, bool, bool) {
     if is_gap(ch) {
         dim_color(&mut r, &mut g, &mut b, config.gap_dim_factor);
     }
     let lum = luminance(r, g, b);
     let use_black_fg = lum >= config.luminance_threshold;
-    let is_current = config
-        .current_match
-        .map(|m| m.seq_index == seq_index && m.start <= col && col < m.end)
-        .unwrap_or(false);
     let color = if config.use_truecolor {
         Color::Rgb(r, g, b)
     } else {
         Color::Indexed(crate::ui::color_map::rgb_to_ansi256(r, g, b))
     };
-    Some((color, use_black_fg, is_current))
+    (color, use_black_fg, is_current)
 }
 
 fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
@@ -278,13 +406,26 @@ fn luminance(r: u8, g: u8, b: u8) -> f32 {
     (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
 }
 
-fn is_gap(c: char) -> bool {
-    matches!(c, '-' | '.' | ' ')
+fn display_char(c: char, gap_char: Option<char>, case_fold: CaseFold) -> char {
+    match gap_char {
+        Some(gap_char) if is_gap(c) => gap_char,
+        _ => case_fold.apply(c),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{blend_colors, dim_color, normalize_min_component};
+    use ratatui::{
+        prelude::{Buffer, Rect},
+        style::{Color, Style},
+        widgets::Widget,
+    };
+
+    use super::{
+        blend_colors, dim_color, luminance, normalize_min_component, shade_by_conservation,
+        SearchHighlightConfig, SeqPane,
+    };
+    use crate::ui::CaseFold;
 
     #[test]
     fn blend_and_normalize() {
@@ -302,4 +443,50 @@ mod tests {
         dim_color(&mut r, &mut g, &mut b, 0.5);
         assert_eq!((r, g, b), (50, 40, 30));
     }
+
+    #[test]
+    fn conserved_columns_are_brighter() {
+        // The same residue in a fully conserved column and in a 20%-conserved one.
+        let sequences = vec![String::from("LL")];
+        let mut style_lut = [Style::default(); 256];
+        style_lut[b'L' as usize] = Style::default().fg(Color::Rgb(200, 100, 50));
+        let pane = SeqPane {
+            sequences: &sequences,
+            ordering: &[0],
+            top_i: 0,
+            left_j: 0,
+            style_lut: &style_lut,
+            highlights: &[],
+            highlight_config: SearchHighlightConfig {
+                min_component: 0,
+                gap_dim_factor: 1.0,
+                luminance_threshold: 1.0,
+                current_match: None,
+                current_color: (0, 0, 0),
+                use_truecolor: true,
+            },
+            underline_seq_index: None,
+            guide_col: None,
+            occupancy: &[],
+            min_occupancy: None,
+            conservation: Some(&[1.0, 0.2]),
+            gap_char: None,
+            case_fold: CaseFold::AsIs,
+            base_style: Style::default(),
+        };
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        pane.render(area, &mut buf);
+        let brightness = |x: u16| match buf[(x, 0)].fg {
+            Color::Rgb(r, g, b) => luminance(r, g, b),
+            other => panic!("unexpected color {:?}", other),
+        };
+        assert_eq!(brightness(0), luminance(200, 100, 50));
+        assert!(brightness(0) > brightness(1));
+        assert!(brightness(1) > 0.0);
+
+        // Gaps keep their color.
+        let style = Style::default().fg(Color::Rgb(200, 100, 50));
+        assert_eq!(shade_by_conservation(style, '-', 0.0, true), style);
+    }
 }
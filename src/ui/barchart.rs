@@ -3,7 +3,7 @@
 // Modifications (c) 2026 Peter Carlton
 
 // 0 <= v <= 1
-pub fn values_barchart(values: &Vec<f64>) -> String {
+pub fn values_barchart(values: &[f64]) -> String {
     let mut blocks = String::with_capacity(values.len());
     for v in values {
         blocks.push(if *v <= 0.11111111111111112 {
@@ -12,17 +12,18 @@ use serde_json::Value::Object;
 
 use crate::errors::TermalError;
 use crate::ui::color_scheme::{
+    CLASS_AROMATIC, CLASS_HYDROPHOBIC, CLASS_NEGATIVE, CLASS_POLAR, CLASS_POSITIVE, CLASS_SPECIAL,
     CLUSTALX_BLUE, CLUSTALX_CYAN, CLUSTALX_GREEN, CLUSTALX_MAGENTA, CLUSTALX_ORANGE, CLUSTALX_PINK,
-    CLUSTALX_RED, CLUSTALX_YELLOW, JALVIEW_NUCLEOTIDE_A, JALVIEW_NUCLEOTIDE_B,
-    JALVIEW_NUCLEOTIDE_C, JALVIEW_NUCLEOTIDE_D, JALVIEW_NUCLEOTIDE_G, JALVIEW_NUCLEOTIDE_H,
-    JALVIEW_NUCLEOTIDE_I, JALVIEW_NUCLEOTIDE_K, JALVIEW_NUCLEOTIDE_M, JALVIEW_NUCLEOTIDE_N,
-    JALVIEW_NUCLEOTIDE_R, JALVIEW_NUCLEOTIDE_S, JALVIEW_NUCLEOTIDE_T, JALVIEW_NUCLEOTIDE_U,
-    JALVIEW_NUCLEOTIDE_V, JALVIEW_NUCLEOTIDE_W, JALVIEW_NUCLEOTIDE_X, JALVIEW_NUCLEOTIDE_Y, ORANGE,
+    CLUSTALX_RED, CLUSTALX_YELLOW, HYDROPATHY_INTERMEDIATE, HYDROPHILIC, HYDROPHOBIC,
+    JALVIEW_NUCLEOTIDE_A, JALVIEW_NUCLEOTIDE_B, JALVIEW_NUCLEOTIDE_C, JALVIEW_NUCLEOTIDE_D,
+    JALVIEW_NUCLEOTIDE_G, JALVIEW_NUCLEOTIDE_H, JALVIEW_NUCLEOTIDE_I, JALVIEW_NUCLEOTIDE_K,
+    JALVIEW_NUCLEOTIDE_M, JALVIEW_NUCLEOTIDE_N, JALVIEW_NUCLEOTIDE_R, JALVIEW_NUCLEOTIDE_S,
+    JALVIEW_NUCLEOTIDE_T, JALVIEW_NUCLEOTIDE_U, JALVIEW_NUCLEOTIDE_V, JALVIEW_NUCLEOTIDE_W,
+    JALVIEW_NUCLEOTIDE_X, JALVIEW_NUCLEOTIDE_Y, ORANGE, RNA_NUCLEOTIDE_U,
 };
 
 #[derive(Clone)]
 pub struct ColorMap {
-    #[allow(dead_code)]
     pub name: String,
     map: HashMap<char, Color>,
 }
@@ -32,6 +33,10 @@ impl ColorMap {
         ColorMap { name, map }
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn get(&self, residue: char) -> Color {
         if let Some(color) = self.map.get(&residue) {
             *color
@@ -40,10 +45,36 @@ impl ColorMap {
         }
     }
 
-    #[allow(dead_code)]
+    /// Groups `residues` by color, in order of first appearance, e.g. for a legend.
+    pub fn residue_groups(&self, residues: &str) -> Vec<(Color, String)> {
+        let mut groups: Vec<(Color, String)> = Vec::new();
+        for residue in residues.chars() {
+            let color = self.get(residue);
+            match groups.iter_mut().find(|(c, _)| *c == color) {
+                Some((_, group)) => group.push(residue),
+                None => groups.push((color, residue.to_string())),
+            }
+        }
+        groups
+    }
+
     pub fn insert(&mut self, residue: char, color: Color) {
         self.map.insert(residue, color);
     }
+
+    /// Makes a colormap that gives every residue of a class (in either case) the class's color,
+    /// e.g. [("AVLIMC", yellow), ("DE", red)]. A residue listed in several classes takes the
+    /// color of the last one.
+    pub fn from_classes(name: String, classes: &[(&str, Color)]) -> ColorMap {
+        let mut map = HashMap::from([('-', Color::Gray)]);
+        for (residues, color) in classes {
+            for residue in residues.chars() {
+                map.insert(residue.to_ascii_uppercase(), *color);
+                map.insert(residue.to_ascii_lowercase(), *color);
+            }
+        }
+        ColorMap::new(name, map)
+    }
 }
 
 impl fmt::Display for ColorMap {
@@ -57,6 +88,9 @@ pub fn builtin_polychrome_colormaps() -> Vec<ColorMap> {
         color_map_jalview_nt(), // Keep the nucleotide map at index 0 (see
         color_map_clustalx(),
         color_map_lesk(),
+        color_map_rna(), // RNA default, see default_colormap_index()
+        color_map_taylor_classes(),
+        color_map_hydrophobicity(),
     ]
 }
 
@@ -271,6 +305,42 @@ pub fn color_map_jalview_nt() -> ColorMap {
     )
 }
 
+// JalView's nucleotide colors, but with a color of its own for U.
+pub fn color_map_rna() -> ColorMap {
+    let mut map = color_map_jalview_nt();
+    map.name = "RNA".into();
+    map.insert('U', RNA_NUCLEOTIDE_U);
+    map.insert('u', RNA_NUCLEOTIDE_U);
+    map
+}
+
+// Physicochemical classes, after Taylor's (1997) color wheel.
+pub fn color_map_taylor_classes() -> ColorMap {
+    ColorMap::from_classes(
+        "Classes".into(),
+        &[
+            ("AVLIMC", CLASS_HYDROPHOBIC),
+            ("FWY", CLASS_AROMATIC),
+            ("STNQ", CLASS_POLAR),
+            ("KRH", CLASS_POSITIVE),
+            ("DE", CLASS_NEGATIVE),
+            ("GP", CLASS_SPECIAL),
+        ],
+    )
+}
+
+// Kyte-Doolittle hydropathy in three bins: hydrophobic, intermediate, and hydrophilic.
+pub fn color_map_hydrophobicity() -> ColorMap {
+    ColorMap::from_classes(
+        "Hydrophobicity".into(),
+        &[
+            ("IVLFCMA", HYDROPHOBIC),
+            ("GTSWYP", HYDROPATHY_INTERMEDIATE),
+            ("HEQDNKR", HYDROPHILIC),
+        ],
+    )
+}
+
 pub fn colormap_gecos(path: &str) -> Result<ColorMap, TermalError> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
@@ -294,7 +364,14 @@ pub fn colormap_gecos(path: &str) -> Result<ColorMap, TermalError> {
         color_map.insert('-', Color::Gray);
     }
 
-    Ok(ColorMap::new("custom".into(), color_map))
+    // Named after the map's "name" entry, else after the file.
+    let name = cm["name"].as_str().map(String::from).unwrap_or_else(|| {
+        std::path::Path::new(path).file_stem().map_or_else(
+            || String::from("custom"),
+            |stem| stem.to_string_lossy().into_owned(),
+        )
+    });
+    Ok(ColorMap::new(name, color_map))
 }
 
 pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
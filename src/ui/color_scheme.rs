@@ -4,13 +4,14 @@
 
 use std::fmt;
 
-use ratatui::prelude::Color;
+use ratatui::prelude::{Color, Style};
 
 use crate::{
     alignment::SeqType,
+    errors::TermalError,
     ui::{
         color_map::{builtin_polychrome_colormaps, monochrome_colormap, ColorMap},
-        color_scheme::SeqType::Protein,
+        color_scheme::SeqType::{Nucleic, Protein, Rna},
     },
 };
 
@@ -18,6 +19,18 @@ use crate::{
 pub const ORANGE: Color = Color::Rgb(255, 165, 0);
 pub const SALMON: Color = Color::Rgb(250, 128, 114);
 
+// Residue class colors (see color_map_taylor_classes() and color_map_hydrophobicity() in
+// color_map.rs).
+pub const CLASS_HYDROPHOBIC: Color = Color::Rgb(230, 200, 40);
+pub const CLASS_AROMATIC: Color = Color::Rgb(230, 140, 30);
+pub const CLASS_POLAR: Color = Color::Rgb(40, 180, 40);
+pub const CLASS_POSITIVE: Color = Color::Rgb(50, 100, 230);
+pub const CLASS_NEGATIVE: Color = Color::Rgb(220, 40, 40);
+pub const CLASS_SPECIAL: Color = Color::Rgb(200, 100, 200);
+pub const HYDROPHOBIC: Color = Color::Rgb(220, 60, 40);
+pub const HYDROPATHY_INTERMEDIATE: Color = Color::Rgb(200, 170, 200);
+pub const HYDROPHILIC: Color = Color::Rgb(40, 90, 220);
+
 // ClustalX colors (source:
 // https://www.cgl.ucsf.edu/chimera/1.2065/docs/ContributedSoftware/multalignviewer/colprot.par)
 pub const CLUSTALX_RED: Color = Color::Rgb(229, 51, 25);
@@ -36,6 +49,8 @@ pub const JALVIEW_NUCLEOTIDE_C: Color = Color::from_u32(0x00FFB340);
 pub const JALVIEW_NUCLEOTIDE_G: Color = Color::from_u32(0x00EB413C);
 pub const JALVIEW_NUCLEOTIDE_T: Color = Color::from_u32(0x003C88EE);
 pub const JALVIEW_NUCLEOTIDE_U: Color = Color::from_u32(0x003C88EE);
+// U in the RNA map, which is otherwise JalView's (where U is T's blue).
+pub const RNA_NUCLEOTIDE_U: Color = Color::from_u32(0x00B57EDC);
 pub const JALVIEW_NUCLEOTIDE_I: Color = Color::from_u32(0x00ffffff);
 pub const JALVIEW_NUCLEOTIDE_X: Color = Color::from_u32(0x004f6f6f);
 pub const JALVIEW_NUCLEOTIDE_R: Color = Color::from_u32(0x00CD5C5C);
@@ -79,14 +94,15 @@ pub struct ColorScheme {
     pub residue_colormap_index: usize,
     pub zoombox_color: Color,
     pub conservation_color: Color,
+    // Gaps get this style regardless of the residue colormap, so that residues stand out.
+    pub gap_style: Style,
 }
 
 impl ColorScheme {
     // TODO: the Vec of colormaps should depend on the macromolecule, i.e. only protein maps for aa,
     // and only nt maps for nt.
     pub fn color_scheme_dark(macromolecule_type: SeqType) -> Self {
-        // These are indices into the Vec of built-in color maps, see color_maps.rs
-        let index = if macromolecule_type == Protein { 1 } else { 0 };
+        let index = default_colormap_index(macromolecule_type);
         ColorScheme {
             theme: Theme::Dark,
             label_num_color: Color::LightGreen,
@@ -95,12 +111,12 @@ impl ColorScheme {
             residue_colormap_index: index,
             zoombox_color: Color::Cyan,
             conservation_color: SALMON,
+            gap_style: Style::new().fg(Color::DarkGray),
         }
     }
 
     pub fn color_scheme_light(macromolecule_type: SeqType) -> Self {
-        // These are indices into the Vec of built-in color maps, see color_maps.rs
-        let index = if macromolecule_type == Protein { 1 } else { 0 };
+        let index = default_colormap_index(macromolecule_type);
         ColorScheme {
             theme: Theme::Light,
             label_num_color: Color::from_u32(0x00008000),
@@ -109,6 +125,7 @@ impl ColorScheme {
             residue_colormap_index: index,
             zoombox_color: Color::Cyan,
             conservation_color: SALMON,
+            gap_style: Style::new().fg(Color::DarkGray),
         }
     }
 
@@ -121,6 +138,7 @@ impl ColorScheme {
             residue_colormap_index: 0,
             zoombox_color: Color::White,
             conservation_color: Color::White,
+            gap_style: Style::new().fg(Color::Reset).bg(Color::Reset),
         }
     }
 
@@ -130,10 +148,50 @@ impl ColorScheme {
         self.residue_colormaps.insert(0, cmap);
     }
 
+    // Inserts a colormap before the built-in ones, like add_colormap(), but keeps the current map
+    // current.
+    pub fn insert_colormap(&mut self, cmap: ColorMap) {
+        self.add_colormap(cmap);
+        self.residue_colormap_index += 1;
+    }
+
+    // Makes the built-in map for `macromolecule_type` current (user colormaps are inserted
+    // before the built-in ones). The monochrome scheme has only one map.
+    pub fn select_default_colormap(&mut self, macromolecule_type: SeqType) {
+        if self.theme == Theme::Monochrome {
+            return;
+        }
+        let first_builtin = self.residue_colormaps.len() - builtin_polychrome_colormaps().len();
+        self.residue_colormap_index = first_builtin + default_colormap_index(macromolecule_type);
+    }
+
     pub fn current_residue_colormap(&self) -> &ColorMap {
         &(self.residue_colormaps[self.residue_colormap_index])
     }
 
+    pub fn colormap_names(&self) -> Vec<&str> {
+        self.residue_colormaps.iter().map(ColorMap::name).collect()
+    }
+
+    /// Makes the colormap named `name` current (case is ignored if no name matches exactly).
+    /// Fails, listing the available names, if there is no such map.
+    pub fn select_colormap_by_name(&mut self, name: &str) -> Result<(), TermalError> {
+        let names = self.colormap_names();
+        let index = names
+            .iter()
+            .position(|n| *n == name)
+            .or_else(|| names.iter().position(|n| n.eq_ignore_ascii_case(name)))
+            .ok_or_else(|| {
+                TermalError::Format(format!(
+                    "No colormap named {} (have: {})",
+                    name,
+                    names.join(", ")
+                ))
+            })?;
+        self.residue_colormap_index = index;
+        Ok(())
+    }
+
     pub fn next_colormap(&mut self) {
         let size = self.residue_colormaps.len();
         self.residue_colormap_index += 1;
@@ -153,3 +211,12 @@ impl fmt::Display for ColorScheme {
         write!(f, "{} {}", self.theme, self.current_residue_colormap())
     }
 }
+
+// Index into the Vec of built-in color maps, see color_maps.rs
+fn default_colormap_index(macromolecule_type: SeqType) -> usize {
+    match macromolecule_type {
+        Nucleic => 0,
+        Protein => 1,
+        Rna => 3,
+    }
+}
@@ -2,23 +2,39 @@
 //
 // Copyright (c) 2025 Thomas Junier
 // Modifications (c) 2026 Peter Carlton
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use clap::ValueEnum;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position};
 use std::fs;
+use std::path::Path;
 
 use super::{
     line_editor::LineEditor,
     InputMode,
     InputMode::{
-        Command, ConfirmOverwrite, ConfirmReject, ConfirmSessionOverwrite, ConfirmViewDelete,
-        ExportSvg, Help, LabelSearch, Normal, Notes, PendingCount, Search, SearchList, SessionList,
-        SessionSave, TreeNav, ViewCreate, ViewCreateWithList, ViewDelete, ViewList, ViewMove,
+        Command, ConfirmOverwrite, ConfirmQuit, ConfirmReject, ConfirmSessionOverwrite,
+        ConfirmViewDelete, ExportSvg, Help, Inspect, LabelSearch, Normal, Notes, Overview,
+        PendingCount, Search, SearchList, SearchName, SessionList, SessionSave, TreeCheck, TreeNav,
+        ViewCreate, ViewCreateWithList, ViewDelete, ViewList, ViewMove,
     },
     //SearchDirection,
-    {NotesTarget, RejectMode, ZoomLevel, UI},
+    {
+        BufferSwitch, CaseFold, HeaderDisplay, NotesTarget, QuitRequest, RejectMode, Theme,
+        ZoomLevel, UI,
+    },
+};
+use crate::alignment::{SeqType, ShiftDirection};
+use crate::app::{
+    valid_consensus_threshold, AlignmentEdit, PromptKind, RejectAction, RejectResult, SearchKind,
+    StatsFormat, TreeMismatch,
 };
-use crate::app::{RejectAction, RejectResult, SearchKind};
 use std::collections::HashSet;
 
+// Characters typed into prompts and the notes editor (any printable one, not only ASCII).
+fn is_text_char(c: char) -> bool {
+    !c.is_control()
+}
+
 fn handle_notes(
     ui: &mut UI,
     key_event: KeyEvent,
@@ -35,6 +51,11 @@ fn handle_notes(
             ui.app.clear_msg();
             mark_dirty(ui);
         }
+        KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            ui.input_mode = InputMode::Normal;
+            ui.app.info_msg("Notes not saved");
+            mark_dirty(ui);
+        }
         KeyCode::Enter => {
             editor.newline();
             ui.input_mode = InputMode::Notes { editor, target };
@@ -45,14 +66,23 @@ fn handle_notes(
             ui.input_mode = InputMode::Notes { editor, target };
             mark_dirty(ui);
         }
+        KeyCode::Tab => {
+            editor.indent();
+            ui.input_mode = InputMode::Notes { editor, target };
+            mark_dirty(ui);
+        }
+        KeyCode::BackTab => {
+            editor.dedent();
+            ui.input_mode = InputMode::Notes { editor, target };
+            mark_dirty(ui);
+        }
         KeyCode::Char('m') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
             editor.newline();
             ui.input_mode = InputMode::Notes { editor, target };
             mark_dirty(ui);
         }
         KeyCode::Char(c)
-            if (c.is_ascii_graphic() || c == ' ')
-                && !key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+            if is_text_char(c) && !key_event.modifiers.contains(KeyModifiers::CONTROL) =>
         {
             editor.insert_char(c);
             ui.input_mode = InputMode::Notes { editor, target };
@@ -152,21 +182,33 @@ pub fn handle_key_press(ui: &mut UI, key_event: KeyEvent) -> bool {
         PendingCount { count } => done = handle_pending_count_key(ui, key_event, count),
         LabelSearch { pattern } => handle_label_search(ui, key_event, &pattern),
         Search { editor, kind } => handle_search(ui, key_event, editor, kind),
-        Command { editor } => handle_command(ui, key_event, editor),
+        Command { editor } => done = handle_command(ui, key_event, editor),
         ExportSvg { editor, full } => handle_export_svg(ui, key_event, editor, full),
         ConfirmOverwrite { editor, path, full } => {
             handle_confirm_overwrite(ui, key_event, editor, path, full)
         }
         SessionSave { editor } => handle_session_save(ui, key_event, editor),
-        ConfirmSessionOverwrite { editor, path } => {
-            handle_confirm_session_overwrite(ui, key_event, editor, path)
+        ConfirmSessionOverwrite { editor, path, quit } => {
+            handle_confirm_session_overwrite(ui, key_event, editor, path, quit)
         }
         SearchList { selected } => handle_search_list(ui, key_event, selected),
+        SearchName {
+            editor,
+            query,
+            kind,
+        } => handle_search_name(ui, key_event, editor, query, kind),
         SessionList { selected, files } => handle_session_list(ui, key_event, selected, &files),
         Notes { editor, target } => handle_notes(ui, key_event, editor, target),
         ConfirmReject { mode } => handle_confirm_reject(ui, key_event, mode),
         ConfirmViewDelete { name } => handle_confirm_view_delete(ui, key_event, &name),
+        ConfirmQuit => done = handle_confirm_quit(ui, key_event),
+        Inspect { .. } => {
+            ui.input_mode = InputMode::Normal;
+            mark_dirty(ui);
+        }
         TreeNav { nav } => handle_tree_nav(ui, key_event, nav),
+        TreeCheck { mismatch, scroll } => handle_tree_check(ui, key_event, mismatch, scroll),
+        Overview { row, col } => handle_overview(ui, key_event, row, col),
         ViewList { selected } => handle_view_list(ui, key_event, selected),
         ViewCreate { editor } => handle_view_create(ui, key_event, editor),
         ViewCreateWithList { editor } => handle_view_create_with_list(ui, key_event, editor),
@@ -188,9 +230,134 @@ fn mark_dirty(ui: &mut UI) {
     ui.mark_dirty();
 }
 
+// Mouse events only apply in normal mode: the wheel scrolls (by screenfuls with Shift) and a left
+// click in the alignment pane puts the cursor on the clicked sequence.
+pub fn handle_mouse(ui: &mut UI, mouse_event: MouseEvent) {
+    if let InputMode::Overview { .. } = ui.input_mode {
+        if mouse_event.kind == MouseEventKind::Down(MouseButton::Left) {
+            if let Some((row, col)) = clicked_overview_cell(ui, mouse_event.column, mouse_event.row)
+            {
+                ui.jump_to_overview_cell(row, col);
+                mark_dirty(ui);
+            }
+        }
+        return;
+    }
+    if ui.input_mode != Normal {
+        return;
+    }
+    let by_screen = mouse_event.modifiers.contains(KeyModifiers::SHIFT);
+    match mouse_event.kind {
+        MouseEventKind::ScrollDown => {
+            match ui.zoom_level() {
+                _ if ui.wrap_mode() => ui.scroll_wrap_blocks(1),
+                _ if by_screen => ui.scroll_one_screen_down(1),
+                ZoomLevel::ZoomedIn => ui.scroll_one_line_down(1),
+                ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => ui.scroll_zoombox_one_line_down(1),
+            }
+            mark_dirty(ui);
+        }
+        MouseEventKind::ScrollUp => {
+            match ui.zoom_level() {
+                _ if ui.wrap_mode() => ui.scroll_wrap_blocks(-1),
+                _ if by_screen => ui.scroll_one_screen_up(1),
+                ZoomLevel::ZoomedIn => ui.scroll_one_line_up(1),
+                ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => ui.scroll_zoombox_one_line_up(1),
+            }
+            mark_dirty(ui);
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some((line, col)) = clicked_line_and_col(ui, mouse_event.column, mouse_event.row)
+            {
+                if let Some(rank) = ui.app.ordering.get(line).copied() {
+                    ui.app.set_cursor_rank(rank);
+                    ui.app.info_msg(format!(
+                        "Sequence {} ({}), column {}",
+                        rank + 1,
+                        ui.app.alignment.headers()[rank],
+                        ui.app.display_col(col)
+                    ));
+                    mark_dirty(ui);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Overview map cell under screen position (x, y), if any.
+fn clicked_overview_cell(ui: &UI, x: u16, y: u16) -> Option<(usize, usize)> {
+    let inner = ui.aln_pane_area?.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    inner
+        .contains(Position { x, y })
+        .then(|| ((y - inner.y) as usize, (x - inner.x) as usize))
+}
+
+// Display line and alignment column under screen position (x, y), if it is on a residue of the
+// alignment pane.
+fn clicked_line_and_col(ui: &UI, x: u16, y: u16) -> Option<(usize, usize)> {
+    let inner = ui.aln_pane_area?.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    if !inner.contains(Position { x, y }) {
+        return None;
+    }
+    let row = (y - inner.y) as usize;
+    let col = (x - inner.x) as usize;
+    let (line, aln_col) = match ui.zoom_level() {
+        _ if ui.wrap_mode() => {
+            let block = ui.wrap_top_block() + row / ui.wrap_block_height();
+            let row_in_block = row % ui.wrap_block_height();
+            // The first row of a block holds column numbers, the last one is blank.
+            if row_in_block == 0 || row_in_block > ui.app.num_visible_seq() as usize {
+                return None;
+            }
+            (row_in_block - 1, block * ui.wrap_width() + col)
+        }
+        ZoomLevel::ZoomedIn => (
+            ui.top_line() as usize + row,
+            ui.leftmost_col() as usize + col,
+        ),
+        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => (
+            *super::render::retained_seq_ndx(ui).get(row)?,
+            *super::render::retained_col_ndx(ui).get(col)?,
+        ),
+    };
+    if line >= ui.app.num_visible_seq() as usize || aln_col >= ui.app.aln_len() as usize {
+        return None;
+    }
+    Some((line, aln_col))
+}
+
+fn switch_buffer(ui: &mut UI, switch: BufferSwitch) {
+    if ui.buffer_count() < 2 {
+        ui.app.info_msg("Only one alignment file is open");
+    } else {
+        ui.request_buffer_switch(switch);
+    }
+    mark_dirty(ui);
+}
+
 fn handle_normal_key(ui: &mut UI, key_event: KeyEvent) -> bool {
     let mut done = false;
     match key_event.code {
+        // Ctrl-/: search only what is on screen (terminals report it as Ctrl-/, Ctrl-_ or Ctrl-7).
+        KeyCode::Char('/') | KeyCode::Char('_') | KeyCode::Char('7')
+            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            ui.search_on_screen = true;
+            ui.push_viewport_mark();
+            ui.input_mode = InputMode::Search {
+                editor: LineEditor::new(),
+                kind: SearchKind::Regex,
+            };
+            ui.app.argument_msg(ui.search_prompt(), String::from(""));
+            mark_dirty(ui);
+        }
         // 1-9: enter pending count mode
         KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
             let d = (c as u8 - b'0') as usize;
@@ -199,14 +366,30 @@ fn handle_normal_key(ui: &mut UI, key_event: KeyEvent) -> bool {
             ui.app.add_argument_char(c);
             mark_dirty(ui);
         }
+        // Esc closes the column popup first, and only then hides the guide.
+        KeyCode::Esc if ui.show_column_popup() => {
+            ui.hide_column_popup();
+            mark_dirty(ui);
+        }
         KeyCode::Esc => {
-            ui.app.reset_lbl_search();
-            ui.app.clear_msg();
+            ui.hide_guide();
+            if ui.app.clear_search() {
+                ui.app.info_msg("Search cleared");
+            } else {
+                ui.app.clear_msg();
+            }
             mark_dirty(ui);
         }
-        // Q, q, and Ctrl-C quit
-        KeyCode::Char('q') | KeyCode::Char('Q') => done = true,
+        // Q and q quit (after confirmation if there are unsaved changes), Ctrl-C quits
+        KeyCode::Char('q') | KeyCode::Char('Q') => ui.request_quit(QuitRequest::Confirm),
         KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => done = true,
+        // Ctrl-N / Ctrl-P: next / previous alignment file
+        KeyCode::Char('n') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            switch_buffer(ui, BufferSwitch::Next);
+        }
+        KeyCode::Char('p') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            switch_buffer(ui, BufferSwitch::Previous);
+        }
         // TODO: search
         KeyCode::Char('?') => {
             ui.reset_help_scroll();
@@ -214,6 +397,7 @@ fn handle_normal_key(ui: &mut UI, key_event: KeyEvent) -> bool {
             mark_dirty(ui);
         }
         KeyCode::Char('"') => {
+            ui.push_viewport_mark();
             ui.input_mode = InputMode::LabelSearch {
                 pattern: String::from(""),
             };
@@ -229,6 +413,7 @@ fn handle_normal_key(ui: &mut UI, key_event: KeyEvent) -> bool {
             mark_dirty(ui);
         }
         KeyCode::Char('/') => {
+            ui.push_viewport_mark();
             ui.input_mode = InputMode::Search {
                 editor: LineEditor::new(),
                 kind: SearchKind::Regex,
@@ -238,6 +423,7 @@ fn handle_normal_key(ui: &mut UI, key_event: KeyEvent) -> bool {
             mark_dirty(ui);
         }
         KeyCode::Char('\\') => {
+            ui.push_viewport_mark();
             ui.input_mode = InputMode::Search {
                 editor: LineEditor::new(),
                 kind: SearchKind::Emboss,
@@ -252,6 +438,50 @@ fn handle_normal_key(ui: &mut UI, key_event: KeyEvent) -> bool {
     done
 }
 
+fn handle_tree_check(ui: &mut UI, key_event: KeyEvent, mismatch: TreeMismatch, scroll: usize) {
+    let scroll = match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            ui.input_mode = InputMode::Normal;
+            mark_dirty(ui);
+            return;
+        }
+        KeyCode::Up | KeyCode::Char('k') => scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => scroll + 1,
+        KeyCode::PageUp => scroll.saturating_sub(ui.help_page_height()),
+        KeyCode::PageDown | KeyCode::Char(' ') => scroll + ui.help_page_height(),
+        _ => return,
+    };
+    // The render clamps the scroll to the list.
+    ui.input_mode = InputMode::TreeCheck { mismatch, scroll };
+    mark_dirty(ui);
+}
+
+fn handle_overview(ui: &mut UI, key_event: KeyEvent, row: usize, col: usize) {
+    let (rows, cols) = ui.overview_size();
+    let (row, col) = match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            ui.input_mode = InputMode::Normal;
+            mark_dirty(ui);
+            return;
+        }
+        KeyCode::Enter => {
+            ui.jump_to_overview_cell(row, col);
+            mark_dirty(ui);
+            return;
+        }
+        KeyCode::Up | KeyCode::Char('k') => (row.saturating_sub(1), col),
+        KeyCode::Down | KeyCode::Char('j') => (row + 1, col),
+        KeyCode::Left | KeyCode::Char('h') => (row, col.saturating_sub(1)),
+        KeyCode::Right | KeyCode::Char('l') => (row, col + 1),
+        _ => return,
+    };
+    ui.input_mode = InputMode::Overview {
+        row: row.min(rows.saturating_sub(1)),
+        col: col.min(cols.saturating_sub(1)),
+    };
+    mark_dirty(ui);
+}
+
 fn handle_help_key(ui: &mut UI, key_event: KeyEvent) {
     match key_event.code {
         KeyCode::Esc | KeyCode::Char('?') => {
@@ -322,6 +552,20 @@ fn parse_rank_list(arg: &str) -> Result<Vec<usize>, String> {
     Ok(result)
 }
 
+// The arguments of ex-command `cmd` if its leading words are exactly those of `name` (e.g.
+// "tree save"), else None.
+fn command_args<'a>(cmd: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = cmd.trim();
+    for word in name.split_whitespace() {
+        let (first, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if first != word {
+            return None;
+        }
+        rest = tail.trim_start();
+    }
+    Some(rest)
+}
+
 fn handle_pending_count_key(ui: &mut UI, key_event: KeyEvent, count: usize) -> bool {
     let mut done = false;
     match key_event.code {
@@ -334,8 +578,8 @@ fn handle_pending_count_key(ui: &mut UI, key_event: KeyEvent, count: usize) -> b
             ui.app.add_argument_char(c);
             mark_dirty(ui);
         }
-        // Q, q, and Ctrl-C quit
-        KeyCode::Char('q') | KeyCode::Char('Q') => done = true,
+        // Q and q quit (after confirmation if there are unsaved changes), Ctrl-C quits
+        KeyCode::Char('q') | KeyCode::Char('Q') => ui.request_quit(QuitRequest::Confirm),
         KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => done = true,
         KeyCode::Esc => {
             ui.input_mode = InputMode::Normal;
@@ -352,17 +596,22 @@ fn handle_pending_count_key(ui: &mut UI, key_event: KeyEvent, count: usize) -> b
     done
 }
 
+// Each keystroke previews the first match of the pattern typed so far (see
+// UI::preview_label_search()); Enter makes it the current search, Esc returns to where the view
+// was when the search started.
 fn handle_label_search(ui: &mut UI, key_event: KeyEvent, pattern: &str) {
     match key_event.code {
         KeyCode::Esc => {
+            ui.pop_viewport_mark(true);
             ui.input_mode = InputMode::Normal;
             ui.app.clear_msg();
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || ' ' == c => {
+        KeyCode::Char(c) if is_text_char(c) => {
             ui.app.add_argument_char(c);
             let mut updated_pattern = pattern.to_string();
             updated_pattern.push(c);
+            ui.preview_label_search(&updated_pattern);
             ui.input_mode = InputMode::LabelSearch {
                 pattern: updated_pattern,
             };
@@ -372,12 +621,14 @@ fn handle_label_search(ui: &mut UI, key_event: KeyEvent, pattern: &str) {
             ui.app.pop_argument_char();
             let mut updated_pattern = pattern.to_string();
             updated_pattern.pop();
+            ui.preview_label_search(&updated_pattern);
             ui.input_mode = InputMode::LabelSearch {
                 pattern: updated_pattern,
             };
             mark_dirty(ui);
         }
         KeyCode::Enter => {
+            ui.pop_viewport_mark(false);
             ui.app.regex_search_labels(pattern);
             ui.input_mode = InputMode::Normal;
             if ui.app.search_state.is_some() {
@@ -393,37 +644,52 @@ fn handle_label_search(ui: &mut UI, key_event: KeyEvent, pattern: &str) {
 fn handle_search(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor, kind: SearchKind) {
     match key_event.code {
         KeyCode::Esc => {
+            ui.pop_viewport_mark(true);
+            ui.reset_history_nav();
+            ui.search_on_screen = false;
             ui.input_mode = InputMode::Normal;
             ui.app.clear_msg();
             mark_dirty(ui);
         }
         KeyCode::Enter => {
             let query = editor.text();
+            ui.pop_viewport_mark(false);
+            ui.reset_history_nav();
+            ui.app.push_history(PromptKind::Search, &query);
             match kind {
+                SearchKind::Regex if ui.search_on_screen => {
+                    let scope = ui.screen_search_scope();
+                    ui.app.regex_search_sequences_in(&query, scope);
+                }
                 SearchKind::Regex => ui.app.regex_search_sequences(&query),
                 SearchKind::Emboss => ui.app.emboss_search_sequences(&query),
+                SearchKind::Gff => {}
             }
+            ui.search_on_screen = false;
             ui.input_mode = InputMode::Normal;
             if let Some((total, sequences)) = ui.app.seq_search_counts() {
+                let where_ = if ui.app.seq_search_is_scoped() {
+                    " on screen"
+                } else {
+                    ""
+                };
                 ui.app
-                    .info_msg(format!("{total} matches in {sequences} sequences"));
+                    .info_msg(format!("{total} matches in {sequences} sequences{where_}"));
             } else if query.is_empty() {
                 ui.app.info_msg("0 matches in 0 sequences");
             }
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => {
+        KeyCode::Char(c) if is_text_char(c) => {
             editor.insert_char(c);
             ui.input_mode = InputMode::Search { editor, kind };
-            ui.app
-                .argument_msg(String::from("Search: "), ui.search_query());
+            ui.app.argument_msg(ui.search_prompt(), ui.search_query());
             mark_dirty(ui);
         }
         KeyCode::Backspace => {
             editor.backspace();
             ui.input_mode = InputMode::Search { editor, kind };
-            ui.app
-                .argument_msg(String::from("Search: "), ui.search_query());
+            ui.app.argument_msg(ui.search_prompt(), ui.search_query());
             mark_dirty(ui);
         }
         KeyCode::Left => {
@@ -446,20 +712,40 @@ fn handle_search(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor, kind:
             ui.input_mode = InputMode::Search { editor, kind };
             mark_dirty(ui);
         }
+        KeyCode::Up | KeyCode::Down => {
+            ui.recall_history(
+                PromptKind::Search,
+                &mut editor,
+                key_event.code == KeyCode::Up,
+            );
+            ui.input_mode = InputMode::Search { editor, kind };
+            ui.app.argument_msg(ui.search_prompt(), ui.search_query());
+            mark_dirty(ui);
+        }
         _ => {}
     }
 }
 
-fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
+fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) -> bool {
     match key_event.code {
         KeyCode::Esc => {
+            ui.reset_history_nav();
             ui.input_mode = InputMode::Normal;
             ui.app.clear_msg();
             mark_dirty(ui);
         }
         KeyCode::Enter => {
             let cmd = editor.text();
+            ui.reset_history_nav();
+            ui.app.push_history(PromptKind::Command, &cmd);
             ui.input_mode = InputMode::Normal;
+            if let Some(done) = write_quit_command(ui, cmd.trim()) {
+                mark_dirty(ui);
+                return done;
+            }
+            if changes_alignment(ui, cmd.trim()) && refuse_read_only(ui) {
+                return false;
+            }
             if cmd.trim() == "s" {
                 let selected = 0;
                 ui.input_mode = InputMode::SearchList { selected };
@@ -483,26 +769,30 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
                 ui.input_mode = InputMode::ExportSvg { editor, full: true };
                 ui.app.argument_msg(String::new(), ui.export_svg_text());
             } else if cmd.trim() == "ra" {
-                ui.app.info_msg("Running mafft...");
-                match ui.app.realign_with_mafft() {
+                if let Ok(cmdline) = ui.app.realign_command_line() {
+                    ui.app.info_msg(format!("Running {}...", cmdline));
+                }
+                match ui.app.realign() {
                     Ok(()) => {
                         ui.show_tree_panel(true);
-                        ui.app.info_msg("Realigned with mafft");
+                        ui.app.info_msg("Realigned");
                     }
-                    Err(e) => ui.app.error_msg(format!("mafft failed: {}", e)),
+                    Err(e) => ui.app.error_msg(format!("Alignment failed: {}", e)),
                 }
             } else if cmd.trim() == "tn" {
                 if ui.app.tree().is_none() {
-                    ui.app.info_msg("Running mafft...");
-                    match ui.app.realign_with_mafft() {
+                    if let Ok(cmdline) = ui.app.realign_command_line() {
+                        ui.app.info_msg(format!("Running {}...", cmdline));
+                    }
+                    match ui.app.realign() {
                         Ok(()) => {
                             ui.show_tree_panel(true);
-                            ui.app.info_msg("Realigned with mafft");
+                            ui.app.info_msg("Realigned");
                         }
                         Err(e) => {
-                            ui.app.error_msg(format!("mafft failed: {}", e));
+                            ui.app.error_msg(format!("Alignment failed: {}", e));
                             mark_dirty(ui);
-                            return;
+                            return false;
                         }
                     }
                 }
@@ -514,7 +804,7 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
                                 ui.app
                                     .error_msg(format!("Tree ordering unavailable: {}", e));
                                 mark_dirty(ui);
-                                return;
+                                return false;
                             }
                             apply_tree_nav_selection(ui, &nav);
                             ui.input_mode = InputMode::TreeNav { nav };
@@ -525,6 +815,449 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
                     },
                     None => ui.app.warning_msg("No tree available"),
                 }
+            } else if cmd.trim() == "treecheck" {
+                match ui.app.tree_header_mismatches() {
+                    Ok(mismatch) if mismatch.is_empty() => {
+                        ui.app.info_msg("Tree leaves and headers match")
+                    }
+                    Ok(mismatch) => {
+                        ui.app.warning_msg(mismatch.summary());
+                        ui.input_mode = InputMode::TreeCheck {
+                            mismatch,
+                            scroll: 0,
+                        };
+                    }
+                    Err(e) => ui.app.error_msg(format!("Tree check unavailable: {}", e)),
+                }
+            } else if cmd.trim() == "treeorder" {
+                match ui.app.apply_tree_ordering() {
+                    Ok(mismatch) if mismatch.is_empty() => {
+                        ui.app.info_msg("Ordered sequences by tree");
+                    }
+                    Ok(mismatch) => ui
+                        .app
+                        .warning_msg(format!("Ordered sequences by tree; {}", mismatch.summary())),
+                    Err(e) => ui
+                        .app
+                        .error_msg(format!("Tree ordering unavailable: {}", e)),
+                }
+            } else if let Some(arg) = command_args(&cmd, "tree save") {
+                let path = arg.trim();
+                if path.is_empty() {
+                    ui.app.warning_msg("Usage: :tree save <file>");
+                } else {
+                    match ui.app.write_tree(Path::new(path)) {
+                        Ok(()) => ui.app.info_msg(format!("Wrote tree to {}", path)),
+                        Err(e) => ui.app.error_msg(format!("Tree save failed: {}", e)),
+                    }
+                }
+            } else if let Some(name) = command_args(&cmd, "colormap") {
+                match name.trim() {
+                    "" => {
+                        let names = ui.color_scheme().colormap_names().join(", ");
+                        ui.app.info_msg(format!("Colormaps: {}", names));
+                    }
+                    name => match ui.select_colormap_by_name(name) {
+                        Ok(()) => ui.app.info_msg(format!(
+                            "Colormap {}",
+                            ui.color_scheme().current_residue_colormap()
+                        )),
+                        Err(e) => ui.app.error_msg(format!("Colormap not changed: {}", e)),
+                    },
+                }
+            } else if cmd.trim() == "overview" {
+                if ui.app.aln_len_usize() == 0 {
+                    ui.app.warning_msg("Empty alignment");
+                } else {
+                    ui.input_mode = InputMode::Overview { row: 0, col: 0 };
+                    ui.app.info_msg(
+                        "Overview: arrows (hjkl) select a cell, Enter or click jumps there, Esc closes",
+                    );
+                }
+            } else if let Some(arg) = command_args(&cmd, "origin") {
+                match arg.trim() {
+                    "" => ui
+                        .app
+                        .info_msg(format!("Columns numbered from {}", ui.app.column_origin())),
+                    arg => match arg.parse::<i64>() {
+                        Ok(origin) => {
+                            ui.app.set_column_origin(origin);
+                            ui.app.info_msg(format!("Columns numbered from {}", origin));
+                        }
+                        Err(_) => ui.app.warning_msg("Usage: :origin <first column number>"),
+                    },
+                }
+            } else if let Some(arg) = cmd.trim().strip_prefix("col ") {
+                match arg.trim().parse::<i64>() {
+                    Ok(display) => {
+                        if !ui.jump_to_display_col(display) {
+                            ui.app.warning_msg(format!(
+                                "Column {} is outside {}-{}",
+                                display,
+                                ui.app.display_col(0),
+                                ui.app.display_col(ui.app.aln_len() as usize - 1)
+                            ));
+                        }
+                    }
+                    Err(_) => ui.app.warning_msg("Usage: :col <column number>"),
+                }
+            } else if let Some(n) = cmd
+                .trim()
+                .strip_prefix('n')
+                .and_then(|arg| arg.trim().parse::<usize>().ok())
+            {
+                ui.jump_to_seq_number(n);
+            } else if cmd.trim() == "max" || cmd.trim() == "min" {
+                ui.jump_to_metric_extreme(cmd.trim() == "max");
+            } else if cmd.trim() == "mask" {
+                if ui.toggle_mask_aware() {
+                    ui.app.info_msg("Lower-case (soft-masked) residues dimmed");
+                } else {
+                    ui.app.info_msg("Lower-case residues shown normally");
+                }
+            } else if let Some(arg) = command_args(&cmd, "occupancy") {
+                match arg.trim() {
+                    "" | "off" => {
+                        ui.set_min_occupancy(None);
+                        ui.app.info_msg("Sparse columns not marked");
+                    }
+                    arg => match arg.parse::<f64>() {
+                        Ok(threshold) if (0.0..=1.0).contains(&threshold) => {
+                            ui.set_min_occupancy(Some(threshold));
+                            let sparse = ui
+                                .app
+                                .alignment
+                                .column_occupancy()
+                                .iter()
+                                .filter(|&&occupancy| occupancy < threshold)
+                                .count();
+                            ui.app.info_msg(format!(
+                                "{} column(s) below {} occupancy",
+                                sparse, threshold
+                            ));
+                        }
+                        _ => ui
+                            .app
+                            .warning_msg("Usage: :occupancy <threshold in [0, 1]>|off"),
+                    },
+                }
+            } else if let Some(arg) = command_args(&cmd, "trim") {
+                let threshold = match arg.trim() {
+                    "" => Some(0.0),
+                    arg => arg.parse::<f64>().ok().filter(|t| (0.0..=1.0).contains(t)),
+                };
+                match threshold {
+                    Some(threshold) => match ui.trim_columns(threshold) {
+                        Ok(removed) => ui.app.info_msg(format!("Removed {} column(s)", removed)),
+                        Err(e) => ui.app.warning_msg(e.to_string()),
+                    },
+                    None => ui.app.warning_msg("Usage: :trim [min occupancy in [0, 1]]"),
+                }
+            } else if let Some(arg) = command_args(&cmd, "consensus write") {
+                let mut args: Vec<&str> = arg.split_whitespace().collect();
+                // A trailing number is the minimum occupancy of the columns written.
+                let min_occupancy = match args.last().and_then(|a| a.parse::<f64>().ok()) {
+                    Some(min) if args.len() > 1 && (0.0..=1.0).contains(&min) => {
+                        args.pop();
+                        Some(min)
+                    }
+                    _ => None,
+                };
+                match args.as_slice() {
+                    [path, name @ ..] if name.len() <= 1 => {
+                        let name = name
+                            .first()
+                            .map(|n| n.to_string())
+                            .unwrap_or_else(|| ui.app.default_consensus_name());
+                        match ui
+                            .app
+                            .write_consensus_fasta(Path::new(path), &name, min_occupancy)
+                        {
+                            Ok(len) => ui.app.info_msg(format!(
+                                "Wrote consensus '{}' ({} residues) to {}",
+                                name, len, path
+                            )),
+                            Err(e) => ui.app.error_msg(format!("Consensus export failed: {}", e)),
+                        }
+                    }
+                    _ => ui
+                        .app
+                        .warning_msg("Usage: :consensus write <file> [name] [min occupancy]"),
+                }
+            } else if let Some(arg) = command_args(&cmd, "consensus") {
+                match arg.trim().parse::<f64>() {
+                    Ok(threshold) if valid_consensus_threshold(threshold) => {
+                        ui.app.set_consensus_threshold(threshold);
+                        ui.app
+                            .info_msg(format!("Consensus threshold set to {}", threshold));
+                    }
+                    _ if arg.trim().is_empty() => ui.app.info_msg(format!(
+                        "Consensus threshold: {}",
+                        ui.app.alignment.consensus_threshold()
+                    )),
+                    _ => ui
+                        .app
+                        .warning_msg("Usage: :consensus <threshold in (0, 1]>"),
+                }
+            } else if cmd.trim() == "ut" {
+                let ut_equivalence = !ui.app.alignment.ut_equivalence();
+                ui.app.set_ut_equivalence(ut_equivalence);
+                if ut_equivalence {
+                    ui.app.info_msg("U and T count as the same residue");
+                } else {
+                    ui.app.info_msg("U and T count as different residues");
+                }
+            } else if let Some(arg) = command_args(&cmd, "mol") {
+                match SeqType::from_str(arg.trim(), true) {
+                    Ok(molecule_type) => {
+                        ui.set_molecule_type(molecule_type);
+                        ui.app
+                            .info_msg(format!("Molecule type forced to {}", molecule_type.name()));
+                    }
+                    Err(_) => ui.app.warning_msg("Usage: :mol dna|rna|protein"),
+                }
+            } else if let Some(arg) = command_args(&cmd, "stats") {
+                let mut args = arg.split_whitespace();
+                match (args.next().and_then(StatsFormat::parse), args.next()) {
+                    (Some(format), Some(path)) => {
+                        match ui.app.export_stats(Path::new(path), format) {
+                            Ok(()) => ui.app.info_msg(format!("Wrote statistics to {}", path)),
+                            Err(e) => ui.app.error_msg(format!("Stats export failed: {}", e)),
+                        }
+                    }
+                    _ => ui.app.warning_msg("Usage: :stats json|tsv <file>"),
+                }
+            } else if let Some(arg) = command_args(&cmd, "degap") {
+                let path = arg.trim();
+                if path.is_empty() {
+                    ui.app.warning_msg("Usage: :degap <file>");
+                } else {
+                    match ui.app.write_unaligned_fasta(Path::new(path)) {
+                        Ok(0) => ui
+                            .app
+                            .info_msg(format!("Wrote ungapped sequences to {}", path)),
+                        Ok(empty) => ui.app.warning_msg(format!(
+                            "Wrote ungapped sequences to {} ({} empty: only gaps)",
+                            path, empty
+                        )),
+                        Err(e) => ui.app.error_msg(format!("Degapped export failed: {}", e)),
+                    }
+                }
+            } else if let Some(arg) = command_args(&cmd, "export") {
+                match arg.split_whitespace().collect::<Vec<_>>().as_slice() {
+                    ["print", path] => match ui.app.write_print_html(Path::new(path)) {
+                        Ok(()) => ui
+                            .app
+                            .info_msg(format!("Wrote print-friendly alignment to {}", path)),
+                        Err(e) => ui.app.error_msg(format!("Print export failed: {}", e)),
+                    },
+                    _ => ui.app.warning_msg("Usage: :export print <file>"),
+                }
+            } else if let Some(arg) = command_args(&cmd, "motif") {
+                let bounds: Vec<usize> = arg
+                    .split_whitespace()
+                    .filter_map(|n| n.parse::<usize>().ok())
+                    .collect();
+                let columns = match (arg.split_whitespace().count(), bounds.as_slice()) {
+                    (0, _) => Some(ui.motif_columns()),
+                    // 1-based, inclusive
+                    (2, &[from, to]) if from >= 1 && from <= to => Some((from - 1, to)),
+                    _ => None,
+                };
+                match columns {
+                    Some((start, end)) => {
+                        let motif = ui.app.column_range_consensus(start, end);
+                        if motif.is_empty() {
+                            ui.app.warning_msg("No residues in these columns");
+                        } else {
+                            ui.app.info_msg(format!(
+                                "Copied motif of columns {}-{}: {}",
+                                start + 1,
+                                end.min(ui.app.aln_len_usize()),
+                                motif
+                            ));
+                            ui.copy_to_clipboard(motif);
+                        }
+                    }
+                    None => ui.app.warning_msg("Usage: :motif [<from> <to>]"),
+                }
+            } else if let Some(arg) = command_args(&cmd, "relabel") {
+                let path = arg.trim();
+                if path.is_empty() {
+                    ui.app.warning_msg("Usage: :relabel <file>");
+                } else {
+                    match ui.app.relabel_from_file(Path::new(path)) {
+                        Ok(renamed) => ui
+                            .app
+                            .info_msg(format!("Relabeled {} sequence(s)", renamed)),
+                        Err(e) => ui.app.error_msg(format!("Relabeling failed: {}", e)),
+                    }
+                }
+            } else if let Some(arg) = command_args(&cmd, "tree load") {
+                let path = arg.trim();
+                if path.is_empty() {
+                    ui.app.warning_msg("Usage: :tree load <file>");
+                } else {
+                    match ui.app.load_tree_file(Path::new(path)) {
+                        Ok(mismatch) => {
+                            ui.show_tree_panel(true);
+                            if mismatch.is_empty() {
+                                ui.app.info_msg(format!("Loaded tree from {}", path));
+                            } else {
+                                ui.app.warning_msg(format!(
+                                    "Loaded tree from {}; {}",
+                                    path,
+                                    mismatch.summary()
+                                ));
+                            }
+                        }
+                        Err(e) => ui.app.error_msg(format!("Tree load failed: {}", e)),
+                    }
+                }
+            } else if let Some(arg) = command_args(&cmd, "gff load") {
+                let path = arg.trim();
+                if path.is_empty() {
+                    ui.app.warning_msg("Usage: :gff load <file>");
+                } else {
+                    match ui.app.load_gff(Path::new(path)) {
+                        Ok((types, 0)) => ui
+                            .app
+                            .info_msg(format!("Loaded {} feature types from {}", types, path)),
+                        Ok((types, skipped)) => ui.app.warning_msg(format!(
+                            "Loaded {} feature types from {}; skipped {} records on sequences not in the alignment",
+                            types, path, skipped
+                        )),
+                        Err(e) => ui.app.error_msg(format!("GFF load failed: {}", e)),
+                    }
+                }
+            } else if cmd.trim() == "split" {
+                if ui.close_split() {
+                    ui.app.info_msg("Closed split");
+                } else {
+                    ui.app
+                        .warning_msg("Usage: :split <file> (:split again to close)");
+                }
+            } else if let Some(arg) = cmd.trim().strip_prefix("split ") {
+                let path = arg.trim();
+                match ui.open_split(Path::new(path)) {
+                    Ok(()) => ui.app.info_msg(format!(
+                        "Showing {} below (Ctrl-L: toggle linked scrolling)",
+                        path
+                    )),
+                    Err(e) => ui.app.error_msg(format!("Split failed: {}", e)),
+                }
+            } else if cmd.trim() == "diff" {
+                if ui.app.clear_diff() {
+                    ui.app.info_msg("Difference overlay off");
+                } else {
+                    ui.app.warning_msg("Usage: :diff <seq#> <seq#>");
+                }
+            } else if let Some(arg) = cmd.trim().strip_prefix("diff ") {
+                let ranks: Vec<usize> = arg
+                    .split_whitespace()
+                    .filter_map(|n| n.parse::<usize>().ok())
+                    .filter(|n| *n > 0)
+                    .collect();
+                if ranks.len() != 2 || arg.split_whitespace().count() != 2 {
+                    ui.app.warning_msg("Usage: :diff <seq#> <seq#>");
+                } else {
+                    match ui.app.set_diff(ranks[0] - 1, ranks[1] - 1) {
+                        Ok(0) => ui.app.info_msg(format!(
+                            "Sequences {} and {} agree (gaps ignored)",
+                            ranks[0], ranks[1]
+                        )),
+                        Ok(_) => ui.jump_to_next_diff(0),
+                        Err(e) => ui.app.error_msg(format!("Diff failed: {}", e)),
+                    }
+                }
+            } else if cmd.trim() == "ann" {
+                if ui.app.clear_annotation_search() {
+                    ui.app.info_msg("Annotation search off");
+                } else {
+                    ui.app
+                        .warning_msg("Usage: :ann <feature> <regex> (e.g. :ann SS_cons E+)");
+                }
+            } else if let Some(arg) = cmd.trim().strip_prefix("ann ") {
+                match arg.trim().split_once(char::is_whitespace) {
+                    Some((feature, pattern)) => {
+                        match ui.app.search_annotation(feature, pattern.trim()) {
+                            Ok(0) => ui.app.info_msg(format!(
+                                "No match for {} in {}",
+                                pattern.trim(),
+                                feature
+                            )),
+                            Ok(_) => ui.jump_to_next_annotation_match(0),
+                            Err(e) => ui.app.error_msg(format!("Annotation search failed: {}", e)),
+                        }
+                    }
+                    None => ui
+                        .app
+                        .warning_msg("Usage: :ann <feature> <regex> (e.g. :ann SS_cons E+)"),
+                }
+            } else if cmd.trim() == "hg" {
+                if ui.app.toggle_hide_all_gap_sequences() {
+                    ui.app.info_msg(format!(
+                        "Hiding {} all-gap sequences",
+                        ui.app.num_hidden_all_gap()
+                    ));
+                } else {
+                    ui.app.info_msg("Showing all-gap sequences");
+                }
+            } else if cmd.trim() == "pin" {
+                // `P` already saves the current search, so pinning lives under `:pin`.
+                match ui.toggle_pin_on_cursor() {
+                    Some(true) => ui.app.info_msg(format!(
+                        "Pinned ({} in total; Alt-Up/Down scroll them)",
+                        ui.app.pinned_ranks().len()
+                    )),
+                    Some(false) => ui.app.info_msg("Unpinned"),
+                    None if ui.app.cursor_rank().is_none() => {
+                        ui.app.warning_msg("No cursor row to pin (use . or x)")
+                    }
+                    None => ui.app.warning_msg("Cannot pin the last unpinned sequence"),
+                }
+            } else if cmd.trim() == "unpin" {
+                match ui.app.unpin_all() {
+                    0 => ui.app.info_msg("No pinned sequences"),
+                    n => ui.app.info_msg(format!("Unpinned {} sequences", n)),
+                }
+            } else if cmd.trim() == "cm" {
+                if ui.toggle_center_matches() {
+                    ui.app.info_msg("Match navigation centers matches");
+                } else {
+                    ui.app
+                        .info_msg("Match navigation puts matches at the top left");
+                }
+            } else if cmd.trim() == "wrap" {
+                if ui.toggle_wrap_mode() {
+                    ui.app
+                        .info_msg("Wrap mode: up/down move by blocks (:wrap again to leave)");
+                } else {
+                    ui.app.info_msg("Wrap mode off");
+                }
+            } else if cmd.trim() == "dedup" {
+                match ui.app.deduplicate_headers() {
+                    Ok(0) => ui.app.info_msg("No duplicate headers"),
+                    Ok(n) => ui.app.info_msg(format!("Renamed {} duplicate headers", n)),
+                    Err(e) => ui.app.warning_msg(e.to_string()),
+                }
+            } else if cmd.trim() == "cg" {
+                if ui.app.toggle_capture_group_highlight() {
+                    ui.app
+                        .info_msg("Regex searches highlight capture group 1 (if any)");
+                } else {
+                    ui.app.info_msg("Regex searches highlight the whole match");
+                }
+            } else if cmd.trim() == "tp" {
+                if ui.app.tree().is_some() {
+                    if ui.app.toggle_tree_phylogram() {
+                        ui.app.info_msg("Tree: phylogram (branch lengths)");
+                    } else {
+                        ui.app.info_msg("Tree: cladogram");
+                    }
+                } else {
+                    ui.app.warning_msg("No tree available");
+                }
             } else if cmd.trim() == "tt" {
                 if ui.app.has_tree_panel() {
                     ui.toggle_tree_panel();
@@ -598,14 +1331,14 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
                         Err(msg) => {
                             ui.app.warning_msg(msg);
                             mark_dirty(ui);
-                            return;
+                            return false;
                         }
                     }
                 };
                 if ranks.is_empty() {
                     ui.app.warning_msg("No sequences to move");
                     mark_dirty(ui);
-                    return;
+                    return false;
                 }
                 let views = ui.app.view_names();
                 let first = views
@@ -621,7 +1354,7 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
                 if ranks.is_empty() {
                     ui.app.warning_msg("No selected sequences");
                     mark_dirty(ui);
-                    return;
+                    return false;
                 }
                 let out_path = ui.app.rejected_output_path();
                 match ui.app.reject_sequences(&ranks, &out_path) {
@@ -634,7 +1367,7 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
                     Err(e) => {
                         ui.app.error_msg(format!("Session list failed: {}", e));
                         mark_dirty(ui);
-                        return;
+                        return false;
                     }
                 };
                 let mut files: Vec<String> = read_dir
@@ -694,7 +1427,7 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
             }
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => {
+        KeyCode::Char(c) if is_text_char(c) => {
             editor.insert_char(c);
             ui.input_mode = InputMode::Command { editor };
             ui.app.argument_msg(String::from(":"), ui.command_text());
@@ -726,8 +1459,54 @@ fn handle_command(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
             ui.input_mode = InputMode::Command { editor };
             mark_dirty(ui);
         }
+        KeyCode::Tab => {
+            if let Some(completed) = complete_command(ui, &editor.text()) {
+                editor.set_text(&completed);
+            }
+            ui.input_mode = InputMode::Command { editor };
+            ui.app.argument_msg(String::from(":"), ui.command_text());
+            mark_dirty(ui);
+        }
+        KeyCode::Up | KeyCode::Down => {
+            ui.recall_history(
+                PromptKind::Command,
+                &mut editor,
+                key_event.code == KeyCode::Up,
+            );
+            ui.input_mode = InputMode::Command { editor };
+            ui.app.argument_msg(String::from(":"), ui.command_text());
+            mark_dirty(ui);
+        }
         _ => {}
     }
+    false
+}
+
+// Completes the argument of `:colormap` to the longest prefix shared by the colormap names that
+// start with it (case ignored), if that makes it longer.
+fn complete_command(ui: &UI, text: &str) -> Option<String> {
+    let prefix = text.strip_prefix("colormap ")?.trim_start();
+    let names = ui.color_scheme().colormap_names();
+    let candidates: Vec<&str> = names
+        .into_iter()
+        .filter(|name| {
+            name.len() >= prefix.len()
+                && name.is_char_boundary(prefix.len())
+                && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+        })
+        .collect();
+    let first = candidates.first()?;
+    let common = candidates.iter().skip(1).fold(first.len(), |len, name| {
+        first
+            .char_indices()
+            .zip(name.chars())
+            .take_while(|((i, a), b)| *i < len && a == b)
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+    });
+    (common > prefix.len() || candidates.len() == 1)
+        .then(|| format!("colormap {}", &first[..common.max(prefix.len())]))
 }
 
 fn apply_tree_nav_selection(ui: &mut UI, nav: &super::TreeNav) {
@@ -805,6 +1584,24 @@ fn handle_tree_nav(ui: &mut UI, key_event: KeyEvent, mut nav: super::TreeNav) {
         KeyCode::Down | KeyCode::Char('j') => {
             changed = nav.move_down();
         }
+        KeyCode::Char('c') => {
+            match nav.current_internal_tree_id() {
+                Some(node_id) if ui.app.is_clade_collapsed(node_id) => {
+                    match ui.app.expand_clade(node_id) {
+                        Ok(_) => ui.app.info_msg("Expanded clade"),
+                        Err(e) => ui.app.error_msg(format!("Expand failed: {}", e)),
+                    }
+                }
+                Some(node_id) => match ui.app.collapse_clade(node_id) {
+                    Ok(hidden) => ui
+                        .app
+                        .info_msg(format!("Collapsed clade ({} sequences hidden)", hidden)),
+                    Err(e) => ui.app.error_msg(format!("Collapse failed: {}", e)),
+                },
+                None => ui.app.warning_msg("Only internal nodes can be collapsed"),
+            }
+            mark_dirty(ui);
+        }
         _ => {}
     }
     if changed {
@@ -815,6 +1612,8 @@ fn handle_tree_nav(ui: &mut UI, key_event: KeyEvent, mut nav: super::TreeNav) {
     ui.input_mode = InputMode::TreeNav { nav };
 }
 
+// Deleting and toggling have side effects, so they stay out of the match guards.
+#[allow(clippy::collapsible_match)]
 fn handle_search_list(ui: &mut UI, key_event: KeyEvent, selected: usize) {
     match key_event.code {
         KeyCode::Esc => {
@@ -862,13 +1661,28 @@ fn handle_search_list(ui: &mut UI, key_event: KeyEvent, selected: usize) {
             }
         }
         KeyCode::Char('c') => {
-            if let Some(entry) = ui.app.saved_searches().get(selected) {
-                let query = entry.query.clone();
-                match entry.kind {
-                    SearchKind::Regex => ui.app.regex_search_sequences(&query),
-                    SearchKind::Emboss => ui.app.emboss_search_sequences(&query),
-                }
-                ui.app.info_msg("Current search set");
+            set_current_search_from_list(ui, selected);
+            mark_dirty(ui);
+        }
+        KeyCode::Enter => {
+            if set_current_search_from_list(ui, selected) {
+                ui.input_mode = InputMode::Normal;
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            let len = ui.app.saved_searches().len();
+            if len > 0 {
+                let selected = selected.checked_sub(1).unwrap_or(len - 1);
+                ui.input_mode = InputMode::SearchList { selected };
+                mark_dirty(ui);
+            }
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            let len = ui.app.saved_searches().len();
+            if len > 0 {
+                let selected = (selected + 1) % len;
+                ui.input_mode = InputMode::SearchList { selected };
                 mark_dirty(ui);
             }
         }
@@ -889,6 +1703,25 @@ fn handle_search_list(ui: &mut UI, key_event: KeyEvent, selected: usize) {
     }
 }
 
+// Makes the selected saved search the current search; returns false if it can't be.
+fn set_current_search_from_list(ui: &mut UI, selected: usize) -> bool {
+    let Some(entry) = ui.app.saved_searches().get(selected) else {
+        return false;
+    };
+    let query = entry.query.clone();
+    match entry.kind {
+        SearchKind::Regex => ui.app.regex_search_sequences(&query),
+        SearchKind::Emboss => ui.app.emboss_search_sequences(&query),
+        SearchKind::Gff => {
+            ui.app
+                .warning_msg("GFF features can't be the current search");
+            return false;
+        }
+    }
+    ui.app.info_msg("Current search set");
+    true
+}
+
 fn handle_view_list(ui: &mut UI, key_event: KeyEvent, selected: usize) {
     match key_event.code {
         KeyCode::Esc => {
@@ -1062,6 +1895,56 @@ fn handle_view_move(ui: &mut UI, key_event: KeyEvent, selected: usize, ranks: &[
     }
 }
 
+fn search_name_prompt(query: &str) -> String {
+    format!("Search name (default {}): ", query)
+}
+
+fn handle_search_name(
+    ui: &mut UI,
+    key_event: KeyEvent,
+    mut editor: LineEditor,
+    query: String,
+    kind: SearchKind,
+) {
+    match key_event.code {
+        KeyCode::Esc => {
+            ui.input_mode = InputMode::Normal;
+            ui.app.clear_msg();
+            mark_dirty(ui);
+            return;
+        }
+        KeyCode::Enter => {
+            let name = editor.text().trim().to_string();
+            let name = if name.is_empty() { query.clone() } else { name };
+            ui.input_mode = InputMode::Normal;
+            match ui.app.add_saved_search_with_kind(name.clone(), query, kind) {
+                Ok(_) => {
+                    ui.app.clear_seq_search();
+                    ui.app.info_msg(format!("Saved search '{}'", name));
+                }
+                Err(e) => ui.app.error_msg(e),
+            }
+            mark_dirty(ui);
+            return;
+        }
+        KeyCode::Char(c) if is_text_char(c) => editor.insert_char(c),
+        KeyCode::Backspace => editor.backspace(),
+        KeyCode::Left => editor.move_left(),
+        KeyCode::Right => editor.move_right(),
+        KeyCode::Home => editor.move_home(),
+        KeyCode::End => editor.move_end(),
+        _ => return,
+    }
+    let text = editor.text();
+    ui.input_mode = InputMode::SearchName {
+        editor,
+        query: query.clone(),
+        kind,
+    };
+    ui.app.argument_msg(search_name_prompt(&query), text);
+    mark_dirty(ui);
+}
+
 fn handle_view_create(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor) {
     match key_event.code {
         KeyCode::Esc => {
@@ -1083,7 +1966,7 @@ fn handle_view_create(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor)
             }
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => {
+        KeyCode::Char(c) if is_text_char(c) => {
             editor.insert_char(c);
             ui.input_mode = InputMode::ViewCreate { editor };
             ui.app
@@ -1142,7 +2025,7 @@ fn handle_view_create_with_list(ui: &mut UI, key_event: KeyEvent, mut editor: Li
             }
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => {
+        KeyCode::Char(c) if is_text_char(c) => {
             editor.insert_char(c);
             ui.input_mode = InputMode::ViewCreateWithList { editor };
             ui.app
@@ -1213,7 +2096,7 @@ fn handle_export_svg(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor, f
             }
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => {
+        KeyCode::Char(c) if is_text_char(c) => {
             editor.insert_char(c);
             ui.input_mode = InputMode::ExportSvg { editor, full };
             ui.app.argument_msg(String::new(), ui.export_svg_text());
@@ -1264,13 +2147,17 @@ fn handle_session_save(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor)
                 return;
             }
             if std::path::Path::new(&path).exists() {
-                ui.input_mode = InputMode::ConfirmSessionOverwrite { editor, path };
+                ui.input_mode = InputMode::ConfirmSessionOverwrite {
+                    editor: Some(editor),
+                    path,
+                    quit: false,
+                };
                 ui.app.argument_msg(
                     String::from("File exists. Overwrite? (y/n) "),
                     String::new(),
                 );
             } else {
-                match ui.app.save_session(std::path::Path::new(&path)) {
+                match ui.save_session(std::path::Path::new(&path)) {
                     Ok(_) => ui.app.info_msg(format!("Session saved -> {}", path)),
                     Err(e) => ui.app.error_msg(format!("Save failed: {}", e)),
                 }
@@ -1278,7 +2165,7 @@ fn handle_session_save(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor)
             }
             mark_dirty(ui);
         }
-        KeyCode::Char(c) if c.is_ascii_graphic() || c == ' ' => {
+        KeyCode::Char(c) if is_text_char(c) => {
             editor.insert_char(c);
             ui.input_mode = InputMode::SessionSave { editor };
             ui.app
@@ -1319,22 +2206,36 @@ fn handle_session_save(ui: &mut UI, key_event: KeyEvent, mut editor: LineEditor)
 fn handle_confirm_session_overwrite(
     ui: &mut UI,
     key_event: KeyEvent,
-    editor: LineEditor,
+    editor: Option<LineEditor>,
     path: String,
+    quit: bool,
 ) {
     match key_event.code {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
-            match ui.app.save_session(std::path::Path::new(&path)) {
-                Ok(_) => ui.app.info_msg(format!("Session saved -> {}", path)),
+            match ui.save_session(std::path::Path::new(&path)) {
+                Ok(_) => {
+                    ui.app.info_msg(format!("Session saved -> {}", path));
+                    if quit {
+                        ui.request_quit(QuitRequest::Refuse);
+                    }
+                }
                 Err(e) => ui.app.error_msg(format!("Save failed: {}", e)),
             }
             ui.input_mode = InputMode::Normal;
             mark_dirty(ui);
         }
         _ => {
-            ui.input_mode = InputMode::SessionSave { editor };
-            ui.app
-                .argument_msg(String::from("Session: "), ui.session_save_text());
+            match editor {
+                Some(editor) => {
+                    ui.input_mode = InputMode::SessionSave { editor };
+                    ui.app
+                        .argument_msg(String::from("Session: "), ui.session_save_text());
+                }
+                None => {
+                    ui.input_mode = InputMode::Normal;
+                    ui.app.info_msg("Write canceled");
+                }
+            }
             mark_dirty(ui);
         }
     }
@@ -1367,7 +2268,7 @@ fn handle_session_list(ui: &mut UI, key_event: KeyEvent, mut selected: usize, fi
         }
         KeyCode::Enter => {
             if let Some(name) = files.get(selected) {
-                match ui.app.load_session(std::path::Path::new(name)) {
+                match ui.load_session(std::path::Path::new(name)) {
                     Ok(()) => ui.app.info_msg(format!("Loaded session {}", name)),
                     Err(e) => ui.app.error_msg(format!("Load failed: {}", e)),
                 }
@@ -1471,6 +2372,113 @@ fn perform_reject(ui: &mut UI, mode: RejectMode) {
     mark_dirty(ui);
 }
 
+// In read-only mode, warns and returns true: the caller must then leave the alignment alone.
+fn refuse_read_only(ui: &mut UI) -> bool {
+    if ui.app.read_only() {
+        ui.app.warning_msg("Read-only mode");
+        mark_dirty(ui);
+    }
+    ui.app.read_only()
+}
+
+// True iff ex-command `cmd` edits the alignment or removes sequences from it (:tn realigns when
+// there is no tree yet).
+fn changes_alignment(ui: &UI, cmd: &str) -> bool {
+    let word = cmd
+        .split_once(char::is_whitespace)
+        .map_or(cmd, |(word, _)| word);
+    matches!(
+        word,
+        "ra" | "dedup" | "rc" | "ru" | "rm" | "rs" | "trim" | "relabel" | "rn"
+    ) || (cmd == "tn" && ui.app.tree().is_none())
+}
+
+// True iff `a` and `b` are the same existing file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+// Vi-style :w [file], :wq [file], :q and :q!. Returns None if `cmd` is none of these, else
+// whether to quit at once (:q!); :q and :wq request a quit, which the runner confirms if any
+// open alignment has unsaved changes. Without a file, :w and :wq save to the current session
+// file; any other existing file is only overwritten once confirmed.
+fn write_quit_command(ui: &mut UI, cmd: &str) -> Option<bool> {
+    let (name, arg) = match cmd.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (cmd, ""),
+    };
+    match name {
+        "q" if arg.is_empty() => {
+            ui.request_quit(QuitRequest::Refuse);
+            Some(false)
+        }
+        "q!" if arg.is_empty() => Some(true),
+        "w" | "wq" => {
+            let path = if arg.is_empty() {
+                match ui.app.session_path() {
+                    Some(path) => path.to_path_buf(),
+                    None => {
+                        ui.app.warning_msg("No session file: use :w <file>");
+                        return Some(false);
+                    }
+                }
+            } else {
+                Path::new(arg).to_path_buf()
+            };
+            if same_file(&path, Path::new(&ui.app.filename)) && refuse_read_only(ui) {
+                return Some(false);
+            }
+            let is_session_file = ui
+                .app
+                .session_path()
+                .is_some_and(|session| same_file(&path, session));
+            if path.exists() && !is_session_file {
+                ui.input_mode = InputMode::ConfirmSessionOverwrite {
+                    editor: None,
+                    path: arg.to_string(),
+                    quit: name == "wq",
+                };
+                ui.app.argument_msg(
+                    String::from("File exists. Overwrite? (y/n) "),
+                    String::new(),
+                );
+                return Some(false);
+            }
+            match ui.save_session(&path) {
+                Ok(()) => {
+                    ui.app
+                        .info_msg(format!("Session saved -> {}", path.display()));
+                    if name == "wq" {
+                        ui.request_quit(QuitRequest::Refuse);
+                    }
+                    Some(false)
+                }
+                Err(e) => {
+                    ui.app.error_msg(format!("Save failed: {}", e));
+                    Some(false)
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+fn handle_confirm_quit(ui: &mut UI, key_event: KeyEvent) -> bool {
+    match key_event.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => return true,
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            ui.input_mode = InputMode::Normal;
+            ui.app.clear_msg();
+            mark_dirty(ui);
+        }
+        _ => {}
+    }
+    false
+}
+
 fn handle_confirm_view_delete(ui: &mut UI, key_event: KeyEvent, name: &str) {
     match key_event.code {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
@@ -1493,6 +2501,11 @@ fn handle_confirm_view_delete(ui: &mut UI, key_event: KeyEvent, name: &str) {
 fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>) {
     let count = count_arg.unwrap_or(1);
 
+    // The column popup follows the guide, but goes away with any other command.
+    if !matches!(key_event.code, KeyCode::Char('=' | ',' | '.')) {
+        ui.hide_column_popup();
+    }
+
     // debug!("key event: {:#?}", key_event.code);
     match key_event.code {
         // ----- Hide/Show panes -----
@@ -1533,6 +2546,120 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
 
         // ----- Motion -----
 
+        // Ctrl-arrows scroll the split's alignment (horizontally only when unlinked), Ctrl-L
+        // toggles linked horizontal scrolling.
+        KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+            if key_event.modifiers.contains(KeyModifiers::CONTROL) && ui.split().is_some() =>
+        {
+            let count = count as i32;
+            match key_event.code {
+                KeyCode::Up => ui.scroll_split_lines(-count),
+                KeyCode::Down => ui.scroll_split_lines(count),
+                KeyCode::Left => ui.scroll_split_cols(-count),
+                KeyCode::Right => ui.scroll_split_cols(count),
+                _ => panic!("Expected only arrow keycodes"),
+            }
+            mark_dirty(ui);
+        }
+        // In wrap mode, vertical motion goes by blocks.
+        KeyCode::Up
+        | KeyCode::Down
+        | KeyCode::Char('k')
+        | KeyCode::Char('j')
+        | KeyCode::Char('K')
+        | KeyCode::Char('J')
+        | KeyCode::Char(' ')
+        | KeyCode::Char('g')
+        | KeyCode::Char('G')
+            if ui.wrap_mode()
+                && !key_event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            let count = count as isize;
+            match key_event.code {
+                KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => {
+                    ui.scroll_wrap_blocks(-count)
+                }
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') | KeyCode::Char(' ') => {
+                    ui.scroll_wrap_blocks(count)
+                }
+                KeyCode::Char('g') => ui.jump_to_wrap_block(0),
+                KeyCode::Char('G') => ui.jump_to_wrap_block(usize::MAX),
+                _ => panic!("Expected only vertical motion keycodes"),
+            }
+            mark_dirty(ui);
+        }
+
+        // Alt-Up/Down scroll the pinned rows when there are more than fit.
+        KeyCode::Up | KeyCode::Down if key_event.modifiers.contains(KeyModifiers::ALT) => {
+            let count = count as i32;
+            if key_event.code == KeyCode::Up {
+                ui.scroll_pinned_lines(-count);
+            } else {
+                ui.scroll_pinned_lines(count);
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            ui.reset_view();
+            mark_dirty(ui);
+        }
+        KeyCode::Char('o') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            ui.app.reverse_current_ordering();
+            ui.app
+                .info_msg(format!("Ordering: {}", ui.app.ordering_status_label()));
+            mark_dirty(ui);
+        }
+        KeyCode::Char('l') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            match ui.toggle_split_link() {
+                Some(true) => ui.app.info_msg("Split: linked horizontal scrolling"),
+                Some(false) => ui
+                    .app
+                    .info_msg("Split: independent scrolling (Ctrl-arrows)"),
+                None => ui.app.warning_msg("No split (:split <file>)"),
+            }
+            mark_dirty(ui);
+        }
+        // Ctrl-T / Ctrl-B: the sequence with the highest / lowest value of the current metric (as
+        // :max / :min).
+        KeyCode::Char('t') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            ui.jump_to_metric_extreme(true);
+            mark_dirty(ui);
+        }
+        KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            ui.jump_to_metric_extreme(false);
+            mark_dirty(ui);
+        }
+
+        // With the guide shown, Shift-Left/Right move the cursor sequence's residue under it
+        // through gaps.
+        KeyCode::Left | KeyCode::Right
+            if key_event.modifiers.contains(KeyModifiers::SHIFT) && ui.guide_col().is_some() =>
+        {
+            let direction = if key_event.code == KeyCode::Left {
+                ShiftDirection::Left
+            } else {
+                ShiftDirection::Right
+            };
+            if ui.app.cursor_rank().is_none() {
+                ui.app
+                    .warning_msg("No cursor sequence (click one, or :n <N>)");
+            } else {
+                match ui.shift_residue_at_guide(direction, count) {
+                    Ok(Some(to)) => ui.app.info_msg(format!(
+                        "Residue moved to column {} (u to undo)",
+                        ui.app.display_col(to)
+                    )),
+                    Ok(None) => ui
+                        .app
+                        .warning_msg("No residue under the guide, or no gap next to it"),
+                    Err(e) => ui.app.warning_msg(e.to_string()),
+                }
+            }
+            mark_dirty(ui);
+        }
+
         // Arrows - late introduction, but might be friendlier to new users.
         KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
             // Non-shifted arrow keys
@@ -1655,6 +2782,55 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             mark_dirty(ui);
         }
 
+        // Column editing, while the guide is shown: i inserts gap columns at the guide, x deletes
+        // columns from it on, u undoes the last such edit.
+        KeyCode::Char('i') if ui.guide_col().is_some() => {
+            match ui.insert_gap_columns_at_guide(count) {
+                Ok(_) => ui
+                    .app
+                    .info_msg(format!("Inserted {} gap column(s) (u to undo)", count)),
+                Err(e) => ui.app.warning_msg(e.to_string()),
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char('x') if ui.guide_col().is_some() => {
+            match ui.delete_columns_at_guide(count) {
+                Ok(0) => ui.app.warning_msg("Cannot delete the last column"),
+                Ok(removed) => ui
+                    .app
+                    .info_msg(format!("Deleted {} column(s) (u to undo)", removed)),
+                Err(e) => ui.app.warning_msg(e.to_string()),
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char('u') => {
+            match ui.undo_edit() {
+                Ok(AlignmentEdit::Inserted { count, .. }) => ui
+                    .app
+                    .info_msg(format!("Undid the insertion of {} column(s)", count)),
+                Ok(AlignmentEdit::Deleted { count, .. }) => ui
+                    .app
+                    .info_msg(format!("Undid the deletion of {} column(s)", count)),
+                Ok(AlignmentEdit::Shifted { from, .. }) => ui.app.info_msg(format!(
+                    "Residue back at column {}",
+                    ui.app.display_col(from)
+                )),
+                Err(e) => ui.app.warning_msg(format!("Undo failed: {}", e)),
+            }
+            mark_dirty(ui);
+        }
+
+        // Details of the cursor sequence
+        KeyCode::Enter => {
+            match ui.app.cursor_rank() {
+                Some(rank) => ui.input_mode = InputMode::Inspect { rank },
+                None => ui
+                    .app
+                    .warning_msg("No cursor sequence (click one, or :n <N>)"),
+            }
+            mark_dirty(ui);
+        }
+
         // Selection
         KeyCode::Char('x') => {
             ui.app.toggle_selection_on_cursor();
@@ -1684,10 +2860,11 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
         // Column
         KeyCode::Char('|') => {
             if count_arg.is_some() {
-                ui.jump_to_col(count as u16);
+                ui.jump_to_display_col(count as i64);
                 mark_dirty(ui);
             } else {
-                let editor = super::notes_editor::NotesEditor::new(ui.app.view_notes());
+                let editor = super::notes_editor::NotesEditor::new(ui.app.view_notes())
+                    .with_indent_width(ui.notes_indent_width());
                 ui.input_mode = InputMode::Notes {
                     editor,
                     target: NotesTarget::View,
@@ -1704,13 +2881,60 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             mark_dirty(ui);
         }
 
+        // Vertical, moving the cursor (and centering on it)
+        KeyCode::Char('&') => {
+            ui.cursor_to_pct_line(count as u16);
+            mark_dirty(ui);
+        }
+
         // Horizontal
         KeyCode::Char('#') => {
             ui.jump_to_pct_col(count as u16);
             mark_dirty(ui);
         }
 
+        // Conserved blocks
+        KeyCode::Char('}') => {
+            ui.next_conserved_block(count);
+            mark_dirty(ui);
+        }
+        KeyCode::Char('{') => {
+            ui.prev_conserved_block(count);
+            mark_dirty(ui);
+        }
+        KeyCode::Char(')') => {
+            ui.next_gap_start(count);
+            mark_dirty(ui);
+        }
+        KeyCode::Char('(') => {
+            ui.next_residue_after_gap(count);
+            mark_dirty(ui);
+        }
+
         // Cursor navigation
+        // With a count, n jumps to that header match (e.g. 247n).
+        // With a difference overlay, n/N cycle through the disagreeing columns.
+        // With an annotation search (:ann), they cycle through its matches.
+        KeyCode::Char('n') if ui.app.annotation_search().is_some() => {
+            ui.jump_to_next_annotation_match(count as isize);
+            mark_dirty(ui);
+        }
+        KeyCode::Char('N') if ui.app.annotation_search().is_some() => {
+            ui.jump_to_next_annotation_match(-(count as isize));
+            mark_dirty(ui);
+        }
+        KeyCode::Char('n') if ui.app.diff_state().is_some() => {
+            ui.jump_to_next_diff(count as isize);
+            mark_dirty(ui);
+        }
+        KeyCode::Char('N') if ui.app.diff_state().is_some() => {
+            ui.jump_to_next_diff(-(count as isize));
+            mark_dirty(ui);
+        }
+        KeyCode::Char('n') if count_arg.is_some() && ui.app.search_state.is_some() => {
+            ui.jump_to_lbl_match(count);
+            mark_dirty(ui);
+        }
         KeyCode::Char('n') => {
             ui.app.move_cursor(count as isize);
             mark_dirty(ui);
@@ -1719,10 +2943,55 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             ui.app.move_cursor(-(count as isize));
             mark_dirty(ui);
         }
+        // Left/Right scrolling by the residues of the cursor sequence
+        KeyCode::Char('\'') => {
+            if ui.app.scroll_reference_rank().is_some() {
+                ui.app.set_scroll_reference(None);
+                ui.app.info_msg("Scrolling by columns");
+            } else if let Some(rank) = ui.app.cursor_rank() {
+                ui.app.set_scroll_reference(Some(rank));
+                ui.app.info_msg(format!(
+                    "Scrolling by the residues of {}",
+                    ui.app.alignment.headers()[rank]
+                ));
+            } else {
+                ui.app
+                    .warning_msg("No cursor sequence (click one, or :n <N>)");
+            }
+            mark_dirty(ui);
+        }
+        // Vertical guide: V toggles it, ',' / '.' move it while it is shown.
+        KeyCode::Char('V') => {
+            if ui.toggle_guide() {
+                ui.app.info_msg("Guide on (',' / '.' to move, Esc to hide)");
+            } else {
+                ui.app.info_msg("Guide off");
+            }
+            mark_dirty(ui);
+        }
+        // Residue composition of the guide column
+        KeyCode::Char('=') => {
+            if !ui.toggle_column_popup() && ui.guide_col().is_none() {
+                ui.app.warning_msg("No guide column (V shows it)");
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char(',') if ui.guide_col().is_some() => {
+            ui.move_guide(-(count as isize));
+            mark_dirty(ui);
+        }
+        KeyCode::Char('.') if ui.guide_col().is_some() => {
+            ui.move_guide(count as isize);
+            mark_dirty(ui);
+        }
         KeyCode::Char('.') => {
             ui.app.toggle_cursor();
             mark_dirty(ui);
         }
+        KeyCode::Char(']') if count_arg.is_some() => {
+            ui.jump_to_seq_match(count);
+            mark_dirty(ui);
+        }
         KeyCode::Char(']') => {
             ui.jump_to_next_seq_match(count as i16);
             mark_dirty(ui);
@@ -1742,6 +3011,16 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             mark_dirty(ui);
         }
 
+        // Bottom pane height
+        KeyCode::Char('+') => {
+            ui.grow_bottom_pane(count as u16);
+            mark_dirty(ui);
+        }
+        KeyCode::Char('_') => {
+            ui.shrink_bottom_pane(count as u16);
+            mark_dirty(ui);
+        }
+
         // Zoom
         KeyCode::Char('z') => {
             ui.cycle_zoom();
@@ -1787,6 +3066,39 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             ui.toggle_video_mode();
             mark_dirty(ui);
         }
+        KeyCode::Char('C') => {
+            if !ui.center_on_target() {
+                ui.app
+                    .warning_msg("Nothing to center (no current match or cursor)");
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char(';') => {
+            if ui.toggle_end_coords() {
+                ui.app
+                    .info_msg("Sequence coordinates at the right edge (zoomed in, unwrapped)");
+            } else {
+                ui.app.info_msg("Right-edge coordinates off");
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char('R') => {
+            if ui.toggle_relative_line_numbers() {
+                ui.app
+                    .info_msg("Relative numbering (absolute without a cursor)");
+            } else {
+                ui.app.info_msg("Absolute numbering");
+            }
+            mark_dirty(ui);
+        }
+        KeyCode::Char('D') => {
+            if ui.toggle_dot_gaps() {
+                ui.app.info_msg("Gaps shown as ·");
+            } else {
+                ui.app.info_msg("Gaps shown as in the file");
+            }
+            mark_dirty(ui);
+        }
 
         KeyCode::Char('s') => {
             ui.next_color_scheme();
@@ -1797,6 +3109,53 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             mark_dirty(ui);
         }
 
+        // Header display: full, middle ellipsis, first word
+        KeyCode::Char('U') => {
+            let msg = match ui.cycle_header_display() {
+                HeaderDisplay::Full => "Headers: full",
+                HeaderDisplay::MiddleEllipsis => "Headers: long ones shortened in the middle",
+                HeaderDisplay::FirstWord => "Headers: first word only",
+            };
+            ui.app.info_msg(msg);
+            mark_dirty(ui);
+        }
+
+        // Residue case: as is, upper, lower
+        KeyCode::Char('~') => {
+            let msg = match ui.cycle_case_fold() {
+                CaseFold::AsIs => "Residues: case as is",
+                CaseFold::Upper => "Residues: upper case",
+                CaseFold::Lower => "Residues: lower case",
+            };
+            ui.app.info_msg(msg);
+            mark_dirty(ui);
+        }
+
+        // Conservation shading
+        KeyCode::Char('`') => {
+            let msg = if !ui.toggle_conservation_shading() {
+                "Residues colored normally"
+            } else if ui.theme() == Theme::Monochrome {
+                "Residues shaded by column conservation (in color schemes only)"
+            } else {
+                "Residues shaded by column conservation"
+            };
+            ui.app.info_msg(msg);
+            mark_dirty(ui);
+        }
+
+        // Colormap legend
+        KeyCode::Char('E') => {
+            ui.toggle_legend();
+            mark_dirty(ui);
+        }
+
+        // Gap map of the cursor sequence
+        KeyCode::Char('*') => {
+            ui.toggle_gap_map();
+            mark_dirty(ui);
+        }
+
         // Switch to next/previous colormap in the list
         KeyCode::Char('m') => {
             ui.next_colormap();
@@ -1829,6 +3188,7 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
 
         // ----- Search -----
         KeyCode::Char('/') => {
+            ui.push_viewport_mark();
             ui.input_mode = InputMode::Search {
                 editor: LineEditor::new(),
                 kind: SearchKind::Regex,
@@ -1838,6 +3198,7 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             mark_dirty(ui);
         }
         KeyCode::Char('\\') => {
+            ui.push_viewport_mark();
             ui.input_mode = InputMode::Search {
                 editor: LineEditor::new(),
                 kind: SearchKind::Emboss,
@@ -1846,21 +3207,23 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
                 .argument_msg(String::from("Search: "), String::from(""));
             mark_dirty(ui);
         }
+        KeyCode::Char('F') => {
+            ui.input_mode = InputMode::SearchList { selected: 0 };
+            mark_dirty(ui);
+        }
         KeyCode::Char('P') => {
             if let (Some(query), Some(kind)) = (
                 ui.app.current_seq_search_pattern(),
                 ui.app.current_seq_search_kind(),
             ) {
-                match ui
-                    .app
-                    .add_saved_search_with_kind(query.to_string(), query.to_string(), kind)
-                {
-                    Ok(_) => {
-                        ui.app.clear_seq_search();
-                        ui.app.info_msg("Saved current search");
-                    }
-                    Err(e) => ui.app.error_msg(e),
-                }
+                let query = query.to_string();
+                ui.app
+                    .argument_msg(search_name_prompt(&query), String::new());
+                ui.input_mode = InputMode::SearchName {
+                    editor: LineEditor::new(),
+                    query,
+                    kind,
+                };
             } else {
                 ui.app.warning_msg("No current search to save");
             }
@@ -1870,6 +3233,9 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
         // ----- Editing -----
         // Filter alignment through external command (à la Vim's '!')
         KeyCode::Char('!') => {
+            if refuse_read_only(ui) {
+                return;
+            }
             let ranks = selected_ranks(ui);
             if ranks.is_empty() {
                 ui.app.warning_msg("No selected sequences");
@@ -1903,7 +3269,8 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
             mark_dirty(ui);
         }
         KeyCode::Char('@') => {
-            let editor = super::notes_editor::NotesEditor::new(ui.app.notes());
+            let editor = super::notes_editor::NotesEditor::new(ui.app.notes())
+                .with_indent_width(ui.notes_indent_width());
             ui.input_mode = InputMode::Notes {
                 editor,
                 target: NotesTarget::Global,
@@ -1926,7 +3293,7 @@ fn dispatch_command(ui: &mut UI, key_event: KeyEvent, count_arg: Option<usize>)
 
 #[cfg(test)]
 mod tests {
-    use super::parse_rank_list;
+    use super::{command_args, parse_rank_list};
 
     #[test]
     fn parse_rank_list_single_and_range() {
@@ -1938,4 +3305,19 @@ mod tests {
     fn parse_rank_list_rejects_zero() {
         assert!(parse_rank_list("0").is_err());
     }
+
+    #[test]
+    fn command_args_match_whole_words() {
+        assert_eq!(
+            command_args(" stats json out.json", "stats"),
+            Some("json out.json")
+        );
+        assert_eq!(command_args("trim", "trim"), Some(""));
+        assert_eq!(
+            command_args("tree   save t.nwk", "tree save"),
+            Some("t.nwk")
+        );
+        assert_eq!(command_args("statsjson", "stats"), None);
+        assert_eq!(command_args("consensus writer", "consensus write"), None);
+    }
 }
@@ -19,6 +19,12 @@ impl LineEditor {
         self.chars.iter().collect()
     }
 
+    // Replaces the whole text; the cursor goes to its end.
+    pub fn set_text(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
     pub fn insert_char(&mut self, c: char) {
         self.chars.insert(self.cursor, c);
         self.cursor += 1;
@@ -69,6 +75,20 @@ mod tests {
         assert_eq!(editor.text(), "ab");
     }
 
+    #[test]
+    fn edits_multibyte_text() {
+        let mut editor = LineEditor::new();
+        editor.set_text("café déjà");
+        editor.move_left();
+        editor.backspace();
+        editor.insert_char('ç');
+        assert_eq!(editor.text(), "café déçà");
+        editor.move_home();
+        editor.move_right();
+        editor.insert_char('ñ');
+        assert_eq!(editor.text(), "cñafé déçà");
+    }
+
     #[test]
     fn move_home_end() {
         let mut editor = LineEditor::new();
@@ -1,12 +1,19 @@
 // SPDX-License-Identifier: MIT
 // Copyright (c) 2026 Peter Carlton
 
+/// Spaces added by Tab (`indent()`) and removed by Shift-Tab (`dedent()`), unless configured.
+pub const DEFAULT_INDENT_WIDTH: usize = 2;
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct NotesEditor {
     lines: Vec<String>,
     row: usize,
     col: usize,
+    // First line shown: a visual line when wrapping, else a logical one.
     scroll: usize,
+    // Lines are soft-wrapped to this many characters for display, and Up/Down move by visual lines.
+    wrap_width: Option<usize>,
+    indent_width: usize,
 }
 
 impl NotesEditor {
@@ -20,22 +27,27 @@ impl NotesEditor {
             row: 0,
             col: 0,
             scroll: 0,
+            wrap_width: None,
+            indent_width: DEFAULT_INDENT_WIDTH,
         }
     }
 
-    pub fn text(&self) -> String {
-        self.lines.join("\n")
+    pub fn with_indent_width(mut self, width: usize) -> Self {
+        self.indent_width = width;
+        self
     }
 
-    pub fn lines(&self) -> &[String] {
-        &self.lines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
     }
 
-    pub fn row(&self) -> usize {
+    #[cfg(test)]
+    fn row(&self) -> usize {
         self.row
     }
 
-    pub fn col(&self) -> usize {
+    #[cfg(test)]
+    fn col(&self) -> usize {
         self.col
     }
 
@@ -48,22 +60,52 @@ impl NotesEditor {
         self.scroll = scroll;
     }
 
+    pub fn set_wrap_width(&mut self, width: Option<usize>) {
+        self.wrap_width = width.filter(|w| *w > 0);
+    }
+
+    /// The lines as displayed `width` characters wide: long lines are broken after the last space
+    /// that fits (or at the width, if there is none).
+    pub fn wrapped_lines(&self, width: usize) -> Vec<String> {
+        self.lines
+            .iter()
+            .flat_map(|line| {
+                wrap_segments(line, width)
+                    .into_iter()
+                    .map(move |(start, end)| line[start..end].to_string())
+            })
+            .collect()
+    }
+
+    /// The cursor's visual line (among all the wrapped lines) and column.
+    pub fn visual_cursor(&self) -> (usize, usize) {
+        let Some(width) = self.wrap_width else {
+            let x = self.current_line()[..self.col].chars().count();
+            return (self.row, x);
+        };
+        let before: usize = self.lines[..self.row]
+            .iter()
+            .map(|line| wrap_segments(line, width).len())
+            .sum();
+        let segments = wrap_segments(self.current_line(), width);
+        let k = segment_of(&segments, self.col);
+        let x = self.current_line()[segments[k].0..self.col].chars().count();
+        (before + k, x)
+    }
+
     pub fn insert_char(&mut self, c: char) {
-        let idx = self.col;
-        let line = self.current_line_mut();
-        let insert_at = idx.min(line.len());
-        line.insert(insert_at, c);
-        self.col = insert_at + 1;
+        let insert_at = self.col.min(self.current_line().len());
+        self.current_line_mut().insert(insert_at, c);
+        self.col = insert_at + c.len_utf8();
     }
 
     pub fn backspace(&mut self) {
         if self.col > 0 {
-            let idx = self.col;
-            let line = self.current_line_mut();
-            let idx = idx.min(line.len());
-            if idx > 0 {
-                line.remove(idx - 1);
-                self.col = idx - 1;
+            let idx = self.col.min(self.current_line().len());
+            if let Some(prev) = self.current_line()[..idx].chars().next_back() {
+                let start = idx - prev.len_utf8();
+                self.current_line_mut().remove(start);
+                self.col = start;
             }
         } else if self.row > 0 {
             let current = self.lines.remove(self.row);
@@ -75,24 +117,28 @@ impl NotesEditor {
     }
 
     pub fn delete_word_left(&mut self) {
-        let start = {
-            let line = self.current_line();
-            if line.is_empty() {
-                self.col = 0;
-                return;
-            }
-            let mut idx = self.col.min(line.len());
-            while idx > 0 && line.as_bytes()[idx - 1].is_ascii_whitespace() {
-                idx -= 1;
-            }
-            while idx > 0 && !line.as_bytes()[idx - 1].is_ascii_whitespace() {
-                idx -= 1;
-            }
-            idx
-        };
-        while self.col > start {
-            self.backspace();
-        }
+        let start = word_start_before(self.current_line(), self.col);
+        let end = self.col.min(self.current_line().len());
+        self.current_line_mut().replace_range(start..end, "");
+        self.col = start;
+    }
+
+    /// Inserts the indent width's worth of spaces at the start of the current line; the cursor
+    /// follows the text.
+    pub fn indent(&mut self) {
+        let width = self.indent_width;
+        self.current_line_mut().insert_str(0, &" ".repeat(width));
+        self.col += width;
+    }
+
+    /// Removes up to the indent width's worth of spaces from the start of the current line; the
+    /// cursor follows the text.
+    pub fn dedent(&mut self) {
+        let line = self.current_line();
+        let removed = line.len() - line.trim_start_matches(' ').len();
+        let removed = removed.min(self.indent_width);
+        self.current_line_mut().replace_range(..removed, "");
+        self.col = self.col.saturating_sub(removed);
     }
 
     pub fn newline(&mut self) {
@@ -106,8 +152,8 @@ impl NotesEditor {
     }
 
     pub fn move_left(&mut self) {
-        if self.col > 0 {
-            self.col -= 1;
+        if let Some(prev) = self.current_line()[..self.col].chars().next_back() {
+            self.col -= prev.len_utf8();
         } else if self.row > 0 {
             self.row -= 1;
             self.col = self.lines[self.row].len();
@@ -115,9 +161,8 @@ impl NotesEditor {
     }
 
     pub fn move_right(&mut self) {
-        let len = self.current_line().len();
-        if self.col < len {
-            self.col += 1;
+        if let Some(next) = self.current_line()[self.col..].chars().next() {
+            self.col += next.len_utf8();
         } else if self.row + 1 < self.lines.len() {
             self.row += 1;
             self.col = 0;
@@ -125,19 +170,64 @@ impl NotesEditor {
     }
 
     pub fn move_up(&mut self) {
-        if self.row > 0 {
+        let Some(width) = self.wrap_width else {
+            if self.row > 0 {
+                let x = self.current_line()[..self.col].chars().count();
+                self.row -= 1;
+                self.col = char_offset(self.current_line(), x);
+            }
+            return;
+        };
+        let segments = wrap_segments(self.current_line(), width);
+        let k = segment_of(&segments, self.col);
+        let x = self.current_line()[segments[k].0..self.col].chars().count();
+        if k > 0 {
+            self.col = self.col_in_segment(&segments, k - 1, x);
+        } else if self.row > 0 {
             self.row -= 1;
-            self.col = self.col.min(self.current_line().len());
+            let segments = wrap_segments(self.current_line(), width);
+            self.col = self.col_in_segment(&segments, segments.len() - 1, x);
         }
     }
 
     pub fn move_down(&mut self) {
-        if self.row + 1 < self.lines.len() {
+        let Some(width) = self.wrap_width else {
+            if self.row + 1 < self.lines.len() {
+                let x = self.current_line()[..self.col].chars().count();
+                self.row += 1;
+                self.col = char_offset(self.current_line(), x);
+            }
+            return;
+        };
+        let segments = wrap_segments(self.current_line(), width);
+        let k = segment_of(&segments, self.col);
+        let x = self.current_line()[segments[k].0..self.col].chars().count();
+        if k + 1 < segments.len() {
+            self.col = self.col_in_segment(&segments, k + 1, x);
+        } else if self.row + 1 < self.lines.len() {
             self.row += 1;
-            self.col = self.col.min(self.current_line().len());
+            let segments = wrap_segments(self.current_line(), width);
+            self.col = self.col_in_segment(&segments, 0, x);
         }
     }
 
+    // Byte offset of the `x`-th character of segment `k` of the current line, kept within the
+    // segment (its end belongs to the next segment, unless it is the last one).
+    fn col_in_segment(&self, segments: &[(usize, usize)], k: usize, x: usize) -> usize {
+        let (start, end) = segments[k];
+        let text = &self.current_line()[start..end];
+        let last = if k + 1 < segments.len() {
+            text.chars().count().saturating_sub(1)
+        } else {
+            text.chars().count()
+        };
+        start
+            + text
+                .char_indices()
+                .nth(x.min(last))
+                .map_or(text.len(), |(offset, _)| offset)
+    }
+
     pub fn move_line_start(&mut self) {
         self.col = 0;
     }
@@ -147,39 +237,27 @@ impl NotesEditor {
     }
 
     pub fn move_word_left(&mut self) {
-        let line = self.current_line();
-        if line.is_empty() {
-            self.col = 0;
-            return;
-        }
-        let mut idx = self.col.min(line.len());
-        while idx > 0 && line.as_bytes()[idx - 1].is_ascii_whitespace() {
-            idx -= 1;
-        }
-        while idx > 0 && !line.as_bytes()[idx - 1].is_ascii_whitespace() {
-            idx -= 1;
-        }
-        self.col = idx;
+        self.col = word_start_before(self.current_line(), self.col);
     }
 
     pub fn move_word_right(&mut self) {
         let line = self.current_line();
-        let len = line.len();
-        let mut idx = self.col.min(len);
-        while idx < len && !line.as_bytes()[idx].is_ascii_whitespace() {
-            idx += 1;
-        }
-        while idx < len && line.as_bytes()[idx].is_ascii_whitespace() {
-            idx += 1;
-        }
-        self.col = idx;
+        let from = self.col.min(line.len());
+        // Skip the rest of the current word, then the spaces after it.
+        let mut rest = line[from..]
+            .char_indices()
+            .skip_while(|(_, c)| !c.is_whitespace());
+        self.col = rest
+            .find(|(_, c)| !c.is_whitespace())
+            .map_or(line.len(), |(offset, _)| from + offset);
     }
 
     pub fn ensure_visible(&mut self, height: usize) {
-        if self.row < self.scroll {
-            self.scroll = self.row;
-        } else if self.row >= self.scroll + height {
-            self.scroll = self.row.saturating_sub(height.saturating_sub(1));
+        let (row, _) = self.visual_cursor();
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + height {
+            self.scroll = row.saturating_sub(height.saturating_sub(1));
         }
     }
 
@@ -192,6 +270,62 @@ impl NotesEditor {
     }
 }
 
+// Byte ranges of the visual lines of `line` when wrapped to `width` characters; an empty line has
+// one, empty, segment.
+fn wrap_segments(line: &str, width: usize) -> Vec<(usize, usize)> {
+    let offsets: Vec<usize> = line
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(line.len()))
+        .collect();
+    let num_chars = offsets.len() - 1;
+    if width == 0 {
+        return vec![(0, line.len())];
+    }
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while num_chars - start > width {
+        let limit = start + width;
+        // Break after the last space that fits, else (one long word) at the width.
+        let end = (start + 1..=limit)
+            .rev()
+            .find(|&i| line[offsets[i - 1]..offsets[i]].starts_with(char::is_whitespace))
+            .unwrap_or(limit);
+        segments.push((offsets[start], offsets[end]));
+        start = end;
+    }
+    segments.push((offsets[start], line.len()));
+    segments
+}
+
+// Byte offset of the start of the word before byte offset `col` (skipping spaces first).
+fn word_start_before(line: &str, col: usize) -> usize {
+    let col = col.min(line.len());
+    let mut before = line[..col]
+        .char_indices()
+        .rev()
+        .skip_while(|(_, c)| c.is_whitespace());
+    match before.find(|(_, c)| c.is_whitespace()) {
+        Some((offset, c)) => offset + c.len_utf8(),
+        None => 0,
+    }
+}
+
+// Byte offset of the `x`-th character of `line` (its end if it is shorter).
+fn char_offset(line: &str, x: usize) -> usize {
+    line.char_indices()
+        .nth(x)
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+// Index of the segment holding byte offset `col`.
+fn segment_of(segments: &[(usize, usize)], col: usize) -> usize {
+    segments
+        .iter()
+        .rposition(|&(start, _)| start <= col)
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::NotesEditor;
@@ -208,6 +342,131 @@ mod tests {
         assert_eq!(editor.col(), 4);
     }
 
+    #[test]
+    fn wraps_long_lines_at_word_boundaries() {
+        let editor = NotesEditor::new("the quick brown fox jumps\nover\nabcdefghijkl");
+        assert_eq!(
+            editor.wrapped_lines(10),
+            vec![
+                "the quick ",
+                "brown fox ",
+                "jumps",
+                "over",
+                "abcdefghij",
+                "kl"
+            ]
+        );
+        assert_eq!(
+            editor.text(),
+            "the quick brown fox jumps\nover\nabcdefghijkl"
+        );
+    }
+
+    #[test]
+    fn moves_across_wrapped_segments() {
+        let mut editor = NotesEditor::new("the quick brown fox jumps\nover");
+        editor.set_wrap_width(Some(10));
+        editor.move_right();
+        editor.move_right();
+        assert_eq!(editor.visual_cursor(), (0, 2));
+        editor.move_down();
+        assert_eq!((editor.row(), editor.col()), (0, 12));
+        assert_eq!(editor.visual_cursor(), (1, 2));
+        editor.move_down();
+        assert_eq!(editor.visual_cursor(), (2, 2));
+        editor.move_down();
+        assert_eq!((editor.row(), editor.col()), (1, 2));
+        assert_eq!(editor.visual_cursor(), (3, 2));
+        editor.move_up();
+        editor.move_up();
+        assert_eq!((editor.row(), editor.col()), (0, 12));
+
+        editor.ensure_visible(2);
+        assert_eq!(editor.scroll(), 0);
+        editor.move_down();
+        editor.ensure_visible(2);
+        assert_eq!(editor.scroll(), 1);
+        editor.move_down();
+        editor.ensure_visible(2);
+        assert_eq!(editor.scroll(), 2);
+    }
+
+    #[test]
+    fn keeps_cursor_on_shorter_wrapped_segment() {
+        // Segments "aaaa ", "bbbbbbbbbb", "bbbbb".
+        let mut editor = NotesEditor::new("aaaa bbbbbbbbbbbbbbb");
+        editor.set_wrap_width(Some(10));
+        for _ in 0..14 {
+            editor.move_right();
+        }
+        assert_eq!(editor.visual_cursor(), (1, 9));
+        // The end of a segment is the start of the next one: stop on its last character.
+        editor.move_up();
+        assert_eq!(editor.col(), 4);
+        assert_eq!(editor.visual_cursor(), (0, 4));
+        editor.move_line_end();
+        assert_eq!(editor.visual_cursor(), (2, 5));
+        editor.move_up();
+        assert_eq!(editor.col(), 10);
+    }
+
+    #[test]
+    fn edits_multibyte_text_on_char_boundaries() {
+        let mut editor = NotesEditor::new("café déjà");
+        editor.move_line_end();
+        editor.move_word_left();
+        assert_eq!(editor.col(), "café ".len());
+        editor.move_left();
+        editor.move_left();
+        assert_eq!(editor.col(), "caf".len());
+        editor.move_right();
+        assert_eq!(editor.col(), "café".len());
+        editor.move_word_right();
+        assert_eq!(editor.col(), "café ".len());
+        editor.move_word_right();
+        assert_eq!(editor.col(), "café déjà".len());
+        editor.backspace();
+        editor.insert_char('ü');
+        assert_eq!(editor.text(), "café déjü");
+        editor.delete_word_left();
+        assert_eq!(editor.text(), "café ");
+        editor.delete_word_left();
+        assert_eq!(editor.text(), "");
+
+        // Up/Down keep the character column, not the byte offset.
+        let mut editor = NotesEditor::new("ééé\nabcdef");
+        editor.move_line_end();
+        editor.move_down();
+        assert_eq!(editor.col(), 3);
+        editor.move_up();
+        editor.newline();
+        assert_eq!(editor.text(), "ééé\n\nabcdef");
+    }
+
+    #[test]
+    fn indents_and_dedents_current_line() {
+        let mut editor = NotesEditor::new("- item\n   three");
+        editor.move_right();
+        editor.indent();
+        assert_eq!(editor.text(), "  - item\n   three");
+        assert_eq!(editor.col(), 3);
+        editor.indent();
+        editor.dedent();
+        assert_eq!(editor.text(), "  - item\n   three");
+        assert_eq!(editor.col(), 3);
+
+        let mut editor = NotesEditor::new("   three").with_indent_width(4);
+        editor.move_right();
+        editor.dedent();
+        assert_eq!(editor.text(), "three");
+        assert_eq!(editor.col(), 0);
+        editor.dedent();
+        assert_eq!(editor.text(), "three");
+        editor.indent();
+        assert_eq!(editor.text(), "    three");
+        assert_eq!(editor.col(), 4);
+    }
+
     #[test]
     fn delete_word_left_removes_word() {
         let mut editor = NotesEditor::new("abc def");
@@ -3,23 +3,23 @@
 // Modifications (c) 2026 Peter Carlton
 
 use ratatui::{
-    prelude::{Constraint, Direction, Layout, Line, Margin, Rect, Span, Style, Text},
+    prelude::{Constraint, Direction, Layout, Line, Margin, Position, Rect, Span, Style, Text},
     style::{Color, Modifier, Stylize},
     widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
 
 use super::{
-    aln_widget::{SeqPane, SeqPaneZoomedOut},
+    aln_widget::{SearchHighlightConfig, SeqPane, SeqPaneZoomedOut},
     barchart::{value_to_hbar, values_barchart},
     color_scheme::Theme,
     msg_theme::style_for,
     style::{build_style_lut, get_residue_style},
-    AlnWRTSeqPane, BottomPanePosition, InputMode, VideoMode, ZoomLevel, BORDER_WIDTH,
-    MIN_COLS_SHOWN, UI, V_SCROLLBAR_WIDTH,
+    AlnWRTSeqPane, BottomPanePosition, HeaderDisplay, InputMode, VideoMode, ZoomLevel,
+    BORDER_WIDTH, MIN_COLS_SHOWN, UI, V_SCROLLBAR_WIDTH,
 };
 
-use crate::vec_f64_aux::{normalize, ones_complement, product};
+use crate::alignment::{Alignment, SeqType};
 
 /*****************************************************************
  * Panel Texts
@@ -27,7 +27,7 @@ use crate::vec_f64_aux::{normalize, ones_complement, product};
  * for all zoom levels
 *****************************************************************/
 
-fn retained_col_ndx(ui: &UI) -> Vec<usize> {
+pub(super) fn retained_col_ndx(ui: &UI) -> Vec<usize> {
     match ui.zoom_level {
         ZoomLevel::ZoomedIn => {
             panic!("should not be called in zoomed-in mode")
@@ -35,35 +35,52 @@ fn retained_col_ndx(ui: &UI) -> Vec<usize> {
         ZoomLevel::ZoomedOut => every_nth(ui.app.aln_len() as usize, ui.max_nb_col_shown().into()),
         ZoomLevel::ZoomedOutAR => {
             let ratio = ui.common_ratio();
-            // This call to round() is ok as it is not an indx into an array.
-            let num_retained_cols: usize = (ui.app.aln_len() as f64 * ratio).round() as usize;
+            // This call to round() is ok as it is not an indx into an array. Rounding up must
+            // not yield more columns than the pane has, though.
+            let num_retained_cols: usize = ((ui.app.aln_len() as f64 * ratio).round() as usize)
+                .min(ui.max_nb_col_shown().into());
             every_nth(ui.app.aln_len() as usize, num_retained_cols)
         }
     }
 }
 
-fn retained_seq_ndx(ui: &UI) -> Vec<usize> {
+pub(super) fn retained_seq_ndx(ui: &UI) -> Vec<usize> {
     match ui.zoom_level {
         ZoomLevel::ZoomedIn => {
             panic!("should not be called in zoomed-in mode")
         }
-        ZoomLevel::ZoomedOut => every_nth(ui.app.num_seq() as usize, ui.max_nb_seq_shown().into()),
+        ZoomLevel::ZoomedOut => every_nth(
+            ui.app.num_visible_seq() as usize,
+            ui.max_nb_seq_shown().into(),
+        ),
         ZoomLevel::ZoomedOutAR => {
             let ratio = ui.common_ratio();
-            // This call to round() is ok as it is not an indx into an array.
-            let num_retained_seqs: usize = (ui.app.num_seq() as f64 * ratio).round() as usize;
-            every_nth(ui.app.num_seq() as usize, num_retained_seqs)
+            // Same as for columns.
+            let num_retained_seqs: usize = ((ui.app.num_visible_seq() as f64 * ratio).round()
+                as usize)
+                .min(ui.max_nb_seq_shown().into());
+            every_nth(ui.app.num_visible_seq() as usize, num_retained_seqs)
         }
     }
 }
 
 fn compute_label_numbers<'a>(ui: &UI) -> Vec<Line<'a>> {
     let num_cols = ui.seq_num_max_len() as usize;
+    // Relative numbering is relative to the cursor's line, which keeps its absolute number.
+    let cursor_line = ui
+        .app
+        .cursor_rank()
+        .and_then(|rank| ui.app.ordering.iter().position(|r| *r == rank))
+        .filter(|_| ui.relative_line_numbers());
     let numbers = ui
         .app
         .ordering
         .iter()
-        .map(|n| Line::from(format!("{:1$}!", n + 1, num_cols))) // n+1 -> 1-based (for humans...)
+        .enumerate()
+        .map(|(line, n)| match cursor_line {
+            Some(cl) if cl != line => Line::from(format!("{:1$}!", cl.abs_diff(line), num_cols)),
+            _ => Line::from(format!("{:1$}!", n + 1, num_cols)), // n+1 -> 1-based (for humans...)
+        })
         .collect();
     match ui.zoom_level {
         ZoomLevel::ZoomedIn => numbers,
@@ -97,7 +114,33 @@ fn compute_seq_metrics<'a>(ui: &UI) -> Vec<Line<'a>> {
     }
 }
 
-fn zoom_in_lbl_text<'a>(ui: &UI) -> Vec<Line<'a>> {
+// Shortens `s` to at most `width` characters by replacing its middle with '…', so that both ends
+// stay visible. Counts characters, not bytes.
+fn truncate_middle(s: &str, width: usize) -> String {
+    let len = s.chars().count();
+    if len <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail_len = (width - 1) / 2;
+    let head_len = width - 1 - tail_len;
+    let head: String = s.chars().take(head_len).collect();
+    let tail: String = s.chars().skip(len - tail_len).collect();
+    format!("{}…{}", head, tail)
+}
+
+// The header as shown in a label pane `width` characters wide.
+fn displayed_header(ui: &UI, header: &str, width: usize) -> String {
+    match ui.header_display() {
+        HeaderDisplay::Full => header.to_string(),
+        HeaderDisplay::MiddleEllipsis => truncate_middle(header, width),
+        HeaderDisplay::FirstWord => header.split_whitespace().next().unwrap_or("").to_string(),
+    }
+}
+
+fn zoom_in_lbl_text<'a>(ui: &UI, width: usize) -> Vec<Line<'a>> {
     ui.app
         .ordering
         .iter()
@@ -110,13 +153,13 @@ fn zoom_in_lbl_text<'a>(ui: &UI) -> Vec<Line<'a>> {
             if ui.app.is_cursor_rank(*i) {
                 hl_style = Style::default().bg(Color::Red).fg(Color::Black);
             }
-            let span = Span::styled(ui.app.alignment.headers[*i].clone(), hl_style);
-            Line::from(span)
+            let header = displayed_header(ui, &ui.app.alignment.headers()[*i], width);
+            Line::from(Span::styled(header, hl_style))
         })
         .collect()
 }
 
-fn zoom_out_lbl_text<'a>(ui: &UI) -> Vec<Line<'a>> {
+fn zoom_out_lbl_text<'a>(ui: &UI, width: usize) -> Vec<Line<'a>> {
     let mut ztext: Vec<Line> = Vec::new();
 
     for i in retained_seq_ndx(ui) {
@@ -130,7 +173,7 @@ fn zoom_out_lbl_text<'a>(ui: &UI) -> Vec<Line<'a>> {
             hl_style = Style::default().bg(Color::Red).fg(Color::Black);
         }
         ztext.push(Line::from(Span::styled(
-            ui.app.alignment.headers[rank].clone(),
+            displayed_header(ui, &ui.app.alignment.headers()[rank], width),
             hl_style,
         )));
     }
@@ -174,6 +217,12 @@ struct Panes {
     // Bottom pane: position, consensus, etc.
     bottom: Rect,
 
+    // Second alignment (`:split`): labels and sequences
+    split: Option<(Rect, Rect)>,
+
+    // Pinned sequences, above the scrolling ones: labels and sequences
+    pinned: Option<(Rect, Rect)>,
+
     dialog: Rect,
 }
 
@@ -184,9 +233,12 @@ struct Panes {
 // do a first pass through Layout in order to determine this.
 fn max_num_seq(f: &Frame, ui: &UI) -> u16 {
     match ui.zoom_level {
-        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedIn => ui.app.num_seq(),
+        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedIn => ui.app.num_visible_seq(),
         ZoomLevel::ZoomedOutAR => {
-            let v_constraints = vec![Constraint::Fill(1), Constraint::Max(ui.bottom_pane_height)];
+            let v_constraints = vec![
+                Constraint::Fill(1),
+                Constraint::Length(ui.shown_bottom_pane_height()),
+            ];
             let top_chunk = Layout::new(Direction::Vertical, v_constraints).split(f.area())[0];
 
             let aln_pane = Layout::new(
@@ -195,16 +247,25 @@ fn max_num_seq(f: &Frame, ui: &UI) -> u16 {
             )
             .split(top_chunk)[1];
 
-            let v_ratio = (aln_pane.height - 2) as f64 / ui.app.num_seq() as f64;
+            let v_ratio = aln_pane.height.saturating_sub(2 + pinned_pane_height(ui)) as f64
+                / ui.app.num_visible_seq() as f64;
             // This is WRONG - need to discount left panes' width
             let h_ratio = (aln_pane.width - 2) as f64 / ui.app.aln_len() as f64;
             let ratio = h_ratio.min(v_ratio);
 
-            (ui.app.num_seq() as f64 * ratio).round() as u16
+            (ui.app.num_visible_seq() as f64 * ratio).round() as u16
         }
     }
 }
 
+// Height of the pinned sub-pane, borders included (0 if nothing is pinned).
+fn pinned_pane_height(ui: &UI) -> u16 {
+    match ui.pinned_rows_shown() {
+        0 => 0,
+        rows => rows + 2,
+    }
+}
+
 fn delineate_help_pane(frame_area: Rect) -> Rect {
     // We take all the screen except the top, bottom, left and right 10%. This means dividing the
     // screen in three vertically, taking the middle 80%, and then dividing that in three and
@@ -236,16 +297,46 @@ fn make_layout(f: &Frame, ui: &UI) -> Panes {
     let mns = max_num_seq(f, ui);
 
     let constraints: Vec<Constraint> = match ui.bottom_pane_position {
+        // The split needs the whole height; the two alignments share it equally. Wrap mode also
+        // needs more lines than there are sequences.
+        _ if ui.split().is_some() || ui.wrap_mode() => vec![
+            Constraint::Fill(1),
+            Constraint::Length(ui.shown_bottom_pane_height()),
+        ],
         BottomPanePosition::Adjacent => vec![
-            Constraint::Max(mns + 2), // + 2 <- borders
+            Constraint::Max(mns + 2 + pinned_pane_height(ui)), // + 2 <- borders
             // Constraint::Max(ui.app.num_seq()),
-            Constraint::Max(ui.bottom_pane_height),
+            Constraint::Length(ui.shown_bottom_pane_height()),
         ],
         BottomPanePosition::ScreenBottom => {
-            vec![Constraint::Fill(1), Constraint::Max(ui.bottom_pane_height)]
+            vec![
+                Constraint::Fill(1),
+                Constraint::Length(ui.shown_bottom_pane_height()),
+            ]
         }
     };
     let v_panes = Layout::new(Direction::Vertical, constraints).split(f.area());
+    let (upper_area, split_area) = if ui.split().is_some() {
+        let halves = Layout::new(
+            Direction::Vertical,
+            vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+        )
+        .split(v_panes[0]);
+        (halves[0], Some(halves[1]))
+    } else {
+        (v_panes[0], None)
+    };
+    let (pinned_area, upper_area) = match pinned_pane_height(ui) {
+        0 => (None, upper_area),
+        height => {
+            let areas = Layout::new(
+                Direction::Vertical,
+                vec![Constraint::Length(height), Constraint::Fill(1)],
+            )
+            .split(upper_area);
+            (Some(areas[0]), areas[1])
+        }
+    };
 
     let min_seq_pane_width = V_SCROLLBAR_WIDTH + MIN_COLS_SHOWN + BORDER_WIDTH;
     let tree_width = if ui.is_tree_panel_visible() {
@@ -261,7 +352,7 @@ fn make_layout(f: &Frame, ui: &UI) -> Panes {
             Constraint::Min(min_seq_pane_width),
         ],
     )
-    .split(v_panes[0]);
+    .split(upper_area);
     let label_area = if ui.is_tree_panel_visible() {
         let left_split = Layout::new(
             Direction::Horizontal,
@@ -291,6 +382,20 @@ fn make_layout(f: &Frame, ui: &UI) -> Panes {
     )
     .split(v_panes[1]);
 
+    let split_horizontally = |area: Rect| {
+        let panes = Layout::new(
+            Direction::Horizontal,
+            vec![
+                Constraint::Max(left_total),
+                Constraint::Min(min_seq_pane_width),
+            ],
+        )
+        .split(area);
+        (panes[0], panes[1])
+    };
+    let split = split_area.map(split_horizontally);
+    let pinned = pinned_area.map(split_horizontally);
+
     // The dialog is only used in help mode, but we compute its position now all the same.
     let help_dialog_pane = delineate_help_pane(f.area());
 
@@ -312,19 +417,34 @@ fn make_layout(f: &Frame, ui: &UI) -> Panes {
         sequence: upper_panes[1],
         corner: lower_panes[0],
         bottom: lower_panes[1],
+        split,
+        pinned,
         dialog: help_dialog_pane,
     }
 }
 
 // Ticks and tick marks (e.g. for bottom pane)
 
-fn tick_marks(aln_length: usize, primary: Option<char>, secondary: Option<char>) -> String {
+// Marks every 10th (primary) and 5th (secondary) column, as numbered from `origin`. The first
+// nine columns only get ':' marks, as their labels take up the room.
+fn tick_marks(
+    aln_length: usize,
+    origin: i64,
+    primary: Option<char>,
+    secondary: Option<char>,
+) -> String {
     let mut ticks = String::with_capacity(aln_length);
-    ticks += "    :    ";
-    for i in 10..aln_length {
-        ticks.push(if i % 10 == 0 {
+    for k in 0..aln_length.saturating_sub(1).max(9) {
+        let col = origin + k as i64;
+        ticks.push(if k < 9 {
+            if col.rem_euclid(5) == 0 {
+                ':'
+            } else {
+                ' '
+            }
+        } else if col.rem_euclid(10) == 0 {
             primary.unwrap_or('|')
-        } else if i % 5 == 0 {
+        } else if col.rem_euclid(5) == 0 {
             secondary.unwrap_or(' ')
         } else {
             ' '
@@ -334,15 +454,26 @@ fn tick_marks(aln_length: usize, primary: Option<char>, secondary: Option<char>)
     ticks
 }
 
-fn tick_position(aln_length: usize) -> String {
-    let mut intervals: Vec<String> = vec![String::from("1       10")];
-    let mut tens = 20;
-    while tens < aln_length {
-        let int = format!("{:>10}", tens);
+// Column numbers, as numbered from `origin`: the first column's on the left, then every 10th
+// one's, ending at its column (and left out where it would run into the previous one).
+fn tick_position(aln_length: usize, origin: i64) -> String {
+    let mut positions = origin.to_string();
+    let first_tens = origin + (10 - origin.rem_euclid(10)) % 10;
+    let mut tens = if first_tens == origin {
+        first_tens + 10
+    } else {
+        first_tens
+    };
+    while ((tens - origin) as usize) + 1 < aln_length.max(11) {
+        let label = tens.to_string();
+        let end = (tens - origin) as usize + 1;
+        let width = positions.chars().count();
+        if end >= width + 1 + label.len() {
+            positions.push_str(&format!("{:>1$}", label, end - width));
+        }
         tens += 10;
-        intervals.push(int);
     }
-    intervals.join("")
+    positions
 }
 
 /****************************************************************
@@ -356,9 +487,9 @@ fn compute_title(ui: &UI) -> String {
         ui.app.filename,
         ui.app.current_view_name(),
         ui.max_nb_seq_shown(),
-        ui.app.num_seq(),
+        ui.app.num_visible_seq_usize(),
         ui.max_nb_col_shown(),
-        ui.app.aln_len(),
+        ui.app.aln_len_usize(),
         ui.color_scheme(),
         ui.video_mode,
     );
@@ -366,6 +497,7 @@ fn compute_title(ui: &UI) -> String {
         "{} | {} ",
         title,
         match ui.zoom_level {
+            ZoomLevel::ZoomedIn if ui.wrap_mode() => "Wrapped",
             ZoomLevel::ZoomedIn => "Zoomed in",
             ZoomLevel::ZoomedOut => "Zoomed out ",
             ZoomLevel::ZoomedOutAR => "Z. out (Aspect)",
@@ -373,24 +505,45 @@ fn compute_title(ui: &UI) -> String {
     )
 }
 
-fn compute_labels_pane_text<'a>(ui: &'a UI<'a>) -> Vec<Line<'a>> {
+// First line shown in the left-hand panes (numbers, labels, metrics, tree).
+fn left_pane_top_line(ui: &UI) -> u16 {
+    match ui.zoom_level() {
+        _ if ui.wrap_mode() => 0,
+        ZoomLevel::ZoomedIn => ui.top_line,
+        ZoomLevel::ZoomedOut => 0,
+        ZoomLevel::ZoomedOutAR => 0,
+    }
+}
+
+// In wrap mode, the left-hand panes repeat their lines for each block shown, with blank lines
+// facing the block's column numbers and the separator.
+fn wrap_left_pane_lines<'a>(ui: &UI, lines: Vec<Line<'a>>) -> Vec<Line<'a>> {
+    if !ui.wrap_mode() {
+        return lines;
+    }
+    let mut wrapped = Vec::new();
+    for _ in ui.visible_wrap_blocks() {
+        wrapped.push(Line::default());
+        wrapped.extend(lines.iter().cloned());
+        wrapped.push(Line::default());
+    }
+    wrapped
+}
+
+fn compute_labels_pane_text<'a>(ui: &'a UI<'a>, width: usize) -> Vec<Line<'a>> {
     let labels: Vec<Line> = match ui.zoom_level {
-        ZoomLevel::ZoomedIn => zoom_in_lbl_text(ui),
-        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => zoom_out_lbl_text(ui),
+        ZoomLevel::ZoomedIn => zoom_in_lbl_text(ui, width),
+        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => zoom_out_lbl_text(ui, width),
     };
 
-    labels
+    wrap_left_pane_lines(ui, labels)
 }
 
 fn render_label_nums_pane(f: &mut Frame, num_chunk: Rect, ui: &UI) {
     let style = get_label_num_style(ui.theme(), ui.get_label_num_color());
-    let lbl_nums = Text::from(compute_label_numbers(ui)).style(style);
+    let lbl_nums = Text::from(wrap_left_pane_lines(ui, compute_label_numbers(ui))).style(style);
     let lbl_num_block = Block::default().borders(Borders::TOP | Borders::LEFT | Borders::BOTTOM);
-    let top_lbl_line = match ui.zoom_level() {
-        ZoomLevel::ZoomedIn => ui.top_line,
-        ZoomLevel::ZoomedOut => 0,
-        ZoomLevel::ZoomedOutAR => 0,
-    };
+    let top_lbl_line = left_pane_top_line(ui);
     let lbl_num_para = Paragraph::new(lbl_nums)
         .scroll((top_lbl_line, 0))
         .block(lbl_num_block);
@@ -399,24 +552,23 @@ fn render_label_nums_pane(f: &mut Frame, num_chunk: Rect, ui: &UI) {
 
 fn render_tree_pane(f: &mut Frame, tree_chunk: Rect, ui: &UI) {
     let block = Block::default().borders(Borders::TOP | Borders::LEFT | Borders::BOTTOM);
-    let top_line = match ui.zoom_level() {
-        ZoomLevel::ZoomedIn => ui.top_line,
-        ZoomLevel::ZoomedOut => 0,
-        ZoomLevel::ZoomedOutAR => 0,
-    };
-    let text = Text::from_iter(ui.app.tree_lines().iter().cloned());
+    let top_line = left_pane_top_line(ui);
+    let lines = ui
+        .app
+        .tree_lines()
+        .iter()
+        .cloned()
+        .map(Line::from)
+        .collect();
+    let text = Text::from(wrap_left_pane_lines(ui, lines));
     let para = Paragraph::new(text).scroll((top_line, 0)).block(block);
     f.render_widget(para, tree_chunk);
 }
 
 fn render_labels_pane(f: &mut Frame, seq_chunk: Rect, ui: &UI) {
-    let labels = compute_labels_pane_text(ui);
     let lbl_block = Block::default().borders(Borders::TOP | Borders::LEFT | Borders::BOTTOM);
-    let top_lbl_line = match ui.zoom_level() {
-        ZoomLevel::ZoomedIn => ui.top_line,
-        ZoomLevel::ZoomedOut => 0,
-        ZoomLevel::ZoomedOutAR => 0,
-    };
+    let labels = compute_labels_pane_text(ui, lbl_block.inner(seq_chunk).width as usize);
+    let top_lbl_line = left_pane_top_line(ui);
     let lbl_para = Paragraph::new(labels)
         .scroll((top_lbl_line, 0))
         .block(lbl_block);
@@ -424,14 +576,11 @@ fn render_labels_pane(f: &mut Frame, seq_chunk: Rect, ui: &UI) {
 }
 
 fn render_seq_metrics_pane(f: &mut Frame, num_chunk: Rect, ui: &UI) {
-    let seq_metrics = Text::from(compute_seq_metrics(ui)).style(ui.get_seq_metric_style());
+    let seq_metrics = Text::from(wrap_left_pane_lines(ui, compute_seq_metrics(ui)))
+        .style(ui.get_seq_metric_style());
     let seq_metrics_block =
         Block::default().borders(Borders::TOP | Borders::LEFT | Borders::BOTTOM);
-    let top_lbl_line = match ui.zoom_level() {
-        ZoomLevel::ZoomedIn => ui.top_line,
-        ZoomLevel::ZoomedOut => 0,
-        ZoomLevel::ZoomedOutAR => 0,
-    };
+    let top_lbl_line = left_pane_top_line(ui);
     let seq_metrics_para = Paragraph::new(seq_metrics)
         .scroll((top_lbl_line, 0))
         .block(seq_metrics_block);
@@ -447,14 +596,65 @@ fn render_alignment_pane(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
     f.render_widget(aln_block, aln_chunk);
 
     let style_lut = build_style_lut(ui);
+    let conservation = conservation_shading(ui, &ui.app.alignment);
     let (highlights, highlight_config) = ui.search_highlights();
     let underline_seq_index = ui.app.cursor_rank();
     let base_style = Style::default().bg(Color::Black);
 
     match ui.zoom_level {
+        ZoomLevel::ZoomedIn if ui.wrap_mode() => {
+            let width = ui.wrap_width();
+            let block_height = ui.wrap_block_height();
+            let top_block = ui.wrap_top_block();
+            let bottom = inner_aln_block.y + inner_aln_block.height;
+            for block in ui.visible_wrap_blocks() {
+                let y = inner_aln_block.y + ((block - top_block) * block_height) as u16;
+                if y >= bottom {
+                    break;
+                }
+                let first_col = block * width;
+                let last_col = (first_col + width).min(ui.app.aln_len() as usize);
+                let header = wrap_block_header(
+                    ui.app.display_col(first_col),
+                    ui.app.display_col(last_col.saturating_sub(1)),
+                    last_col - first_col,
+                );
+                let header_area = Rect::new(inner_aln_block.x, y, inner_aln_block.width, 1);
+                f.render_widget(Paragraph::new(header).style(base_style), header_area);
+                let rows_area = Rect::new(
+                    inner_aln_block.x,
+                    y + 1,
+                    inner_aln_block.width,
+                    (bottom - y - 1).min(ui.app.num_visible_seq()),
+                );
+                let pane = SeqPane {
+                    sequences: ui.app.alignment.sequences(),
+                    ordering: &ui.app.ordering,
+                    top_i: 0,
+                    left_j: first_col,
+                    style_lut: &style_lut,
+                    highlights: &highlights,
+                    highlight_config,
+                    underline_seq_index,
+                    guide_col: ui.guide_col(),
+                    occupancy: ui.app.alignment.densities(),
+                    min_occupancy: ui.min_occupancy(),
+                    conservation,
+                    gap_char: ui.gap_char(),
+                    case_fold: ui.case_fold(),
+                    base_style,
+                };
+                f.render_widget(pane, rows_area);
+            }
+        }
         ZoomLevel::ZoomedIn => {
+            let gutter_width = ui.end_coords_width().min(inner_aln_block.width);
+            let residues_area = Rect {
+                width: inner_aln_block.width - gutter_width,
+                ..inner_aln_block
+            };
             let pane = SeqPane {
-                sequences: &ui.app.alignment.sequences,
+                sequences: ui.app.alignment.sequences(),
                 ordering: &ui.app.ordering,
                 top_i: ui.top_line as usize,
                 left_j: ui.leftmost_col as usize,
@@ -462,14 +662,29 @@ fn render_alignment_pane(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
                 highlights: &highlights,
                 highlight_config,
                 underline_seq_index,
+                guide_col: ui.guide_col(),
+                occupancy: ui.app.alignment.densities(),
+                min_occupancy: ui.min_occupancy(),
+                conservation,
+                gap_char: ui.gap_char(),
+                case_fold: ui.case_fold(),
                 base_style,
             };
-            f.render_widget(pane, inner_aln_block);
+            f.render_widget(pane, residues_area);
+            if gutter_width > 0 {
+                let gutter_area = Rect {
+                    x: residues_area.x + residues_area.width,
+                    width: gutter_width,
+                    ..inner_aln_block
+                };
+                render_end_coords(f, gutter_area, ui);
+            }
         }
         ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => {
             let zoombox_color = ui.get_zoombox_color();
+            let (zb_top, zb_bottom, zb_left, zb_right) = ui.zoombox_rect();
             let pane = SeqPaneZoomedOut {
-                sequences: &ui.app.alignment.sequences,
+                sequences: ui.app.alignment.sequences(),
                 ordering: &ui.app.ordering,
                 retained_rows: &retained_seq_ndx(ui),
                 retained_cols: &retained_col_ndx(ui),
@@ -477,12 +692,18 @@ fn render_alignment_pane(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
                 highlights: &highlights,
                 highlight_config,
                 underline_seq_index,
+                guide_col: ui.guide_col(),
+                occupancy: ui.app.alignment.densities(),
+                min_occupancy: ui.min_occupancy(),
+                conservation,
+                gap_char: ui.gap_char(),
+                case_fold: ui.case_fold(),
                 base_style,
                 show_zoombox: ui.show_zoombox,
-                zb_top: ui.zoombox_top(),
-                zb_bottom: ui.zoombox_bottom(retained_seq_ndx(ui).len()),
-                zb_left: ui.zoombox_left(),
-                zb_right: ui.zoombox_right(retained_col_ndx(ui).len()),
+                zb_top,
+                zb_bottom,
+                zb_left,
+                zb_right,
                 zb_style: Style::new().fg(zoombox_color),
             };
             f.render_widget(pane, inner_aln_block);
@@ -492,16 +713,22 @@ fn render_alignment_pane(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
     // let seq_para = Paragraph::new(seq).block(aln_block);
     // f.render_widget(seq_para, aln_chunk);
 
-    if ui.zoom_level == ZoomLevel::ZoomedIn && ui.show_scrollbars {
+    if ui.show_scrollbars && !ui.wrap_mode() {
         let zoombox_color = ui.get_zoombox_color();
         // vertical scrollbar
         if (AlnWRTSeqPane::TooTall == (ui.aln_wrt_seq_pane() & AlnWRTSeqPane::TooTall))
             && ui.max_nb_seq_shown() > 2
         {
-            let mut v_scrollbar_state = ScrollbarState::default()
-                .content_length((ui.app.num_seq() - ui.max_nb_seq_shown()).into())
-                .viewport_content_length((ui.max_nb_seq_shown() - 2).into())
-                .position(ui.top_line.into());
+            let mut v_scrollbar_state = match ui.zoom_level {
+                ZoomLevel::ZoomedIn => ScrollbarState::default()
+                    .content_length((ui.app.num_visible_seq() - ui.max_nb_seq_shown()).into())
+                    .viewport_content_length((ui.max_nb_seq_shown() - 2).into())
+                    .position(ui.top_line.into()),
+                ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => {
+                    let (top, bottom, _, _) = ui.zoombox_rect();
+                    zoombox_scrollbar_state(retained_seq_ndx(ui).len(), top, bottom)
+                }
+            };
             let v_scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
                 .thumb_style(zoombox_color)
                 .begin_symbol(None)
@@ -520,10 +747,16 @@ fn render_alignment_pane(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
         if (AlnWRTSeqPane::TooWide == (ui.aln_wrt_seq_pane() & AlnWRTSeqPane::TooWide))
             && ui.max_nb_col_shown() > 2
         {
-            let mut h_scrollbar_state = ScrollbarState::default()
-                .content_length((ui.app.aln_len() - ui.max_nb_col_shown()).into())
-                .viewport_content_length((ui.max_nb_col_shown() - 2).into())
-                .position(ui.leftmost_col.into());
+            let mut h_scrollbar_state = match ui.zoom_level {
+                ZoomLevel::ZoomedIn => ScrollbarState::default()
+                    .content_length((ui.app.aln_len() - ui.max_nb_col_shown()).into())
+                    .viewport_content_length((ui.max_nb_col_shown() - 2).into())
+                    .position(ui.leftmost_col.into()),
+                ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => {
+                    let (_, _, left, right) = ui.zoombox_rect();
+                    zoombox_scrollbar_state(retained_col_ndx(ui).len(), left, right)
+                }
+            };
             let h_scrollbar = Scrollbar::new(ScrollbarOrientation::HorizontalBottom)
                 .begin_symbol(None)
                 .thumb_style(zoombox_color)
@@ -538,6 +771,271 @@ fn render_alignment_pane(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
                 &mut h_scrollbar_state,
             );
         }
+        render_scrollbar_ticks(f, aln_chunk, ui);
+    }
+}
+
+// Marks on the scrollbars, in the color of the current search's matches: where sequences match
+// the current header or sequence search (vertical scrollbar) and where the sequence matches start
+// (horizontal scrollbar), across the whole alignment, so as to show off-screen matches.
+fn render_scrollbar_ticks(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
+    let (r, g, b) = ui.app.next_search_color();
+    let color = ui.map_color(Color::Rgb(r, g, b));
+    if (AlnWRTSeqPane::TooTall == (ui.aln_wrt_seq_pane() & AlnWRTSeqPane::TooTall))
+        && ui.max_nb_seq_shown() > 2
+    {
+        let track = aln_chunk.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        });
+        let x = track.x + track.width - 1;
+        for tick in scrollbar_ticks(
+            match_lines(ui),
+            ui.app.num_visible_seq() as usize,
+            track.height,
+        ) {
+            if let Some(cell) = f.buffer_mut().cell_mut(Position::from((x, track.y + tick))) {
+                cell.set_symbol(SCROLLBAR_TICK).set_fg(color);
+            }
+        }
+    }
+    if (AlnWRTSeqPane::TooWide == (ui.aln_wrt_seq_pane() & AlnWRTSeqPane::TooWide))
+        && ui.max_nb_col_shown() > 2
+    {
+        let track = aln_chunk.inner(Margin {
+            vertical: 0,
+            horizontal: 1,
+        });
+        let y = track.y + track.height - 1;
+        let cols = ui
+            .app
+            .seq_search_spans()
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|&(start, _)| start);
+        for tick in scrollbar_ticks(cols, ui.app.aln_len() as usize, track.width) {
+            if let Some(cell) = f.buffer_mut().cell_mut(Position::from((track.x + tick, y))) {
+                cell.set_symbol(SCROLLBAR_TICK).set_fg(color);
+            }
+        }
+    }
+}
+
+const SCROLLBAR_TICK: &str = "•";
+
+// Screen lines of the sequences that match the current header search or sequence search.
+fn match_lines(ui: &UI) -> Vec<usize> {
+    let label_matches = ui
+        .app
+        .search_state
+        .iter()
+        .flat_map(|state| state.match_linenums.iter().copied());
+    let seq_matches = ui
+        .app
+        .seq_search_spans()
+        .into_iter()
+        .flat_map(|spans| spans.iter().enumerate())
+        .filter(|(_, spans)| !spans.is_empty())
+        .map(|(rank, _)| rank);
+    label_matches
+        .chain(seq_matches)
+        .filter_map(|rank| ui.app.reverse_ordering.get(rank).copied())
+        .collect()
+}
+
+// Offsets along a scrollbar track `track` cells long of `positions` (lines or columns) out of
+// `total`, one per cell, in increasing order.
+fn scrollbar_ticks(
+    positions: impl IntoIterator<Item = usize>,
+    total: usize,
+    track: u16,
+) -> Vec<u16> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let mut ticks: Vec<u16> = positions
+        .into_iter()
+        .filter(|&pos| pos < total)
+        .map(|pos| (pos * track as usize / total) as u16)
+        .collect();
+    ticks.sort_unstable();
+    ticks.dedup();
+    ticks
+}
+
+// When zoomed out, the thumb spans the zoom box: `start`..`end` out of the `shown` rows (or
+// columns) of the zoomed-out alignment. The scrollbar maps `position`..`position` + viewport onto
+// a track of (content length - 1 + viewport).
+fn zoombox_scrollbar_state(shown: usize, start: usize, end: usize) -> ScrollbarState {
+    let extent = end.saturating_sub(start).max(1);
+    ScrollbarState::default()
+        .content_length(shown.saturating_sub(extent) + 1)
+        .viewport_content_length(extent)
+        .position(start)
+}
+
+// Column numbers of a wrap-mode block: first column on the left, last one on the right.
+fn wrap_block_header(first_col: i64, last_col: i64, width: usize) -> String {
+    let first = first_col.to_string();
+    let last = last_col.to_string();
+    if first.len() + last.len() < width {
+        format!("{}{:>2$}", first, last, width - first.len())
+    } else {
+        first
+    }
+}
+
+fn render_split_panes(f: &mut Frame, lbl_chunk: Rect, aln_chunk: Rect, ui: &UI) {
+    let Some(split) = ui.split() else {
+        return;
+    };
+    let top = split.top_line as usize;
+    let rows = lbl_chunk.height.saturating_sub(2) as usize;
+    let labels: Vec<Line> = split
+        .alignment
+        .headers()
+        .iter()
+        .skip(top)
+        .take(rows)
+        .map(|header| Line::from(header.clone()))
+        .collect();
+    let lbl_block = Block::default().borders(Borders::ALL);
+    f.render_widget(Paragraph::new(labels).block(lbl_block), lbl_chunk);
+
+    let link = if split.linked { "linked" } else { "unlinked" };
+    let title = format!(
+        " {} - {} sequences x {} columns ({}) ",
+        split.name,
+        split.alignment.num_seq(),
+        split.alignment.aln_len(),
+        link
+    );
+    let aln_block = Block::default().title(title).borders(Borders::ALL);
+    let inner_aln_block = aln_block.inner(aln_chunk);
+    f.render_widget(aln_block, aln_chunk);
+
+    let style_lut = build_style_lut(ui);
+    let conservation = conservation_shading(ui, &split.alignment);
+    let ordering: Vec<usize> = (0..split.alignment.num_seq()).collect();
+    let (_, highlight_config) = ui.search_highlights();
+    let pane = SeqPane {
+        sequences: split.alignment.sequences(),
+        ordering: &ordering,
+        top_i: top,
+        left_j: ui.split_leftmost_col() as usize,
+        style_lut: &style_lut,
+        highlights: &[],
+        highlight_config: SearchHighlightConfig {
+            current_match: None,
+            ..highlight_config
+        },
+        underline_seq_index: None,
+        guide_col: None,
+        occupancy: &[],
+        min_occupancy: None,
+        conservation,
+        gap_char: ui.gap_char(),
+        case_fold: ui.case_fold(),
+        base_style: Style::default().bg(Color::Black),
+    };
+    f.render_widget(pane, inner_aln_block);
+}
+
+// Column conservation (kept by the alignment), if residues are shaded by it.
+fn conservation_shading<'a>(ui: &UI, alignment: &'a Alignment) -> Option<&'a [f64]> {
+    ui.conservation_shading()
+        .then(|| alignment.column_conservation())
+}
+
+fn render_pinned_panes(f: &mut Frame, lbl_chunk: Rect, aln_chunk: Rect, ui: &UI) {
+    let pinned = ui.app.pinned_ranks();
+    let top = ui.pinned_top_line() as usize;
+    let num_cols = ui.seq_num_max_len() as usize;
+    let labels: Vec<Line> = pinned
+        .iter()
+        .skip(top)
+        .map(|rank| {
+            let style = if ui.app.is_cursor_rank(*rank) {
+                Style::default().bg(Color::Red).fg(Color::Black)
+            } else if ui.app.is_label_selected(*rank) {
+                Style::default().bg(Color::White).fg(Color::Black)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::raw(format!("{:1$} ", rank + 1, num_cols)),
+                Span::styled(ui.app.alignment.headers()[*rank].clone(), style),
+            ])
+        })
+        .collect();
+    let lbl_block = Block::default().borders(Borders::ALL);
+    f.render_widget(Paragraph::new(labels).block(lbl_block), lbl_chunk);
+
+    let title = format!(" Pinned ({}) ", pinned.len());
+    let aln_block = Block::default().title(title).borders(Borders::ALL);
+    let inner_aln_block = aln_block.inner(aln_chunk);
+    f.render_widget(aln_block, aln_chunk);
+
+    let style_lut = build_style_lut(ui);
+    let conservation = conservation_shading(ui, &ui.app.alignment);
+    let (highlights, highlight_config) = ui.search_highlights();
+    let underline_seq_index = ui.app.cursor_rank();
+    let base_style = Style::default().bg(Color::Black);
+    match ui.zoom_level {
+        ZoomLevel::ZoomedIn => {
+            let pane = SeqPane {
+                sequences: ui.app.alignment.sequences(),
+                ordering: &pinned,
+                top_i: top,
+                left_j: ui.leftmost_col as usize,
+                style_lut: &style_lut,
+                highlights: &highlights,
+                highlight_config,
+                underline_seq_index,
+                guide_col: ui.guide_col(),
+                occupancy: ui.app.alignment.densities(),
+                min_occupancy: ui.min_occupancy(),
+                conservation,
+                gap_char: ui.gap_char(),
+                case_fold: ui.case_fold(),
+                base_style,
+            };
+            // As wide as the main pane's residues, so that the columns line up.
+            let gutter_width = ui.end_coords_width().min(inner_aln_block.width);
+            let residues_area = Rect {
+                width: inner_aln_block.width - gutter_width,
+                ..inner_aln_block
+            };
+            f.render_widget(pane, residues_area);
+        }
+        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => {
+            // Same columns as the main pane, but every pinned row.
+            let pane = SeqPaneZoomedOut {
+                sequences: ui.app.alignment.sequences(),
+                ordering: &pinned,
+                retained_rows: &(top..pinned.len()).collect::<Vec<usize>>(),
+                retained_cols: &retained_col_ndx(ui),
+                style_lut: &style_lut,
+                highlights: &highlights,
+                highlight_config,
+                underline_seq_index,
+                guide_col: ui.guide_col(),
+                occupancy: ui.app.alignment.densities(),
+                min_occupancy: ui.min_occupancy(),
+                conservation,
+                gap_char: ui.gap_char(),
+                case_fold: ui.case_fold(),
+                base_style,
+                show_zoombox: false,
+                zb_top: 0,
+                zb_bottom: 0,
+                zb_left: 0,
+                zb_right: 0,
+                zb_style: Style::default(),
+            };
+            f.render_widget(pane, inner_aln_block);
+        }
     }
 }
 
@@ -565,11 +1063,12 @@ fn render_corner_pane(f: &mut Frame, corner_chunk: Rect, ui: &UI) {
     .right_aligned();
     f.render_widget(metric_para, metric_chunk);
 
-    let cons_text = Text::from(vec![
-        "Position".into(),
-        "Consensus".into(),
-        "Conservation".into(),
-    ]);
+    let mut cons_lines: Vec<Line> =
+        vec!["Position".into(), "Consensus".into(), "Conservation".into()];
+    if ui.has_search_legend() {
+        cons_lines.push("Searches".into());
+    }
+    let cons_text = Text::from(cons_lines);
     let cons_para = Paragraph::new(cons_text).block(cons_block);
     f.render_widget(cons_para, cons_chunk);
 }
@@ -586,6 +1085,23 @@ fn mark_consensus_zb_pos(consensus: &mut [Span], ui: &UI) {
     }
 }
 
+// Each shown sequence's coordinate at the rightmost column shown, right-aligned.
+fn render_end_coords(f: &mut Frame, area: Rect, ui: &UI) {
+    let width = area.width as usize;
+    let lines: Vec<Line> = ui
+        .app
+        .ordering
+        .iter()
+        .skip(ui.top_line as usize)
+        .take(area.height as usize)
+        .map(|&rank| Line::from(format!("{:>width$}", ui.end_coord(rank))))
+        .collect();
+    f.render_widget(
+        Paragraph::new(lines).style(Style::default().bg(Color::Black)),
+        area,
+    );
+}
+
 fn render_bottom_pane(f: &mut Frame, bottom_chunk: Rect, ui: &UI) {
     let colormap = ui.color_scheme().current_residue_colormap();
     let btm_block = Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM);
@@ -593,7 +1109,7 @@ fn render_bottom_pane(f: &mut Frame, bottom_chunk: Rect, ui: &UI) {
     let mut colored_consensus: Vec<Span> = ui
         .app
         .alignment
-        .consensus
+        .consensus()
         .chars()
         .map(|c| {
             Span::styled(
@@ -620,25 +1136,80 @@ fn render_bottom_pane(f: &mut Frame, bottom_chunk: Rect, ui: &UI) {
 
     let btm_text: Vec<Line> = vec![
         Line::from(Span::styled(
-            tick_marks(ui.app.aln_len() as usize, None, Some(':')),
+            tick_marks(
+                ui.app.aln_len() as usize,
+                ui.app.column_origin(),
+                None,
+                Some(':'),
+            ),
             Style::default().fg(pos_color).bg(Color::Reset),
         )),
         Line::from(Span::styled(
-            tick_position(ui.app.aln_len() as usize),
+            tick_position(ui.app.aln_len() as usize, ui.app.column_origin()),
             Style::default().fg(pos_color).bg(Color::Reset),
         )),
         Line::from(colored_consensus),
-        Line::from(values_barchart(&product(
-            &ui.app.alignment.densities,
-            &ones_complement(&normalize(&ui.app.alignment.entropies)),
-        )))
-        .style(conservation_color),
+        Line::from(values_barchart(ui.app.alignment.column_conservation()))
+            .style(conservation_color),
     ];
 
     let btm_para = Paragraph::new(btm_text)
         .scroll((0, ui.leftmost_col))
         .block(btm_block);
     f.render_widget(btm_para, bottom_chunk);
+    // Columns under the end coordinates gutter are not shown above, so they are not shown here.
+    let gutter_width = ui.end_coords_width();
+    if gutter_width > 0 {
+        let inner = Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM)
+            .inner(bottom_chunk);
+        let width = gutter_width.min(inner.width);
+        f.render_widget(
+            Clear,
+            Rect {
+                x: inner.x + inner.width - width,
+                width,
+                height: inner.height.min(4),
+                ..inner
+            },
+        );
+    }
+
+    // The legend does not scroll with the alignment, so it goes below the paragraph's lines.
+    if ui.has_search_legend() && bottom_chunk.height > 5 {
+        let legend_rect = Rect {
+            x: bottom_chunk.x + 1,
+            y: bottom_chunk.y + 4,
+            width: bottom_chunk.width.saturating_sub(2),
+            height: 1,
+        };
+        f.render_widget(Line::from(ui.search_legend_spans()), legend_rect);
+    }
+
+    // The gap map summarizes the whole cursor sequence at pane width, in the last row.
+    if ui.show_gap_map() && bottom_chunk.height > 2 {
+        let gap_map_rect = Rect {
+            x: bottom_chunk.x + 1,
+            y: bottom_chunk.y + bottom_chunk.height - 2,
+            width: bottom_chunk.width.saturating_sub(2),
+            height: 1,
+        };
+        let residues: Vec<f64> = ui
+            .cursor_rank()
+            .map(|rank| {
+                ui.app
+                    .alignment
+                    .gap_profile(rank, gap_map_rect.width as usize)
+            })
+            .unwrap_or_default()
+            .iter()
+            .map(|gaps| 1.0 - gaps)
+            .collect();
+        f.render_widget(
+            Paragraph::new(values_barchart(&residues)).style(conservation_color),
+            gap_map_rect,
+        );
+    }
 }
 
 fn render_modeline(f: &mut Frame, last_content_line: u16, ui: &mut UI) {
@@ -654,11 +1225,21 @@ fn render_modeline(f: &mut Frame, last_content_line: u16, ui: &mut UI) {
         ))
     };
     let mut spans = vec![Span::raw(" ")];
-    if let Some(base) = base_msg {
+    if let Some(label) = ui.buffer_label() {
         spans.push(Span::styled(
-            base,
-            style_for(&ui.app.current_message().kind),
+            label,
+            Style::new().add_modifier(Modifier::BOLD),
         ));
+        spans.push(Span::raw(" | "));
+    }
+    if let Some(base) = base_msg {
+        let mut style = style_for(&ui.app.current_message().kind);
+        if ui.app.current_message().is_flashing() {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(base, style));
+    } else {
+        spans.push(Span::raw(ui.composition_summary()));
     }
     let search_spans = ui.search_status_line_spans();
     if !search_spans.is_empty() {
@@ -676,6 +1257,23 @@ fn render_modeline(f: &mut Frame, last_content_line: u16, ui: &mut UI) {
         spans.push(Span::raw(" | "));
         spans.push(Span::styled("T", Style::new().fg(Color::Green)));
     }
+    if !ui.app.view_notes().trim().is_empty() {
+        spans.push(Span::raw(" | "));
+        spans.push(Span::styled("[N]", Style::new().fg(Color::Yellow)));
+    }
+    if ui.app.scroll_reference_rank().is_some() {
+        spans.push(Span::raw(" | [ref-scroll]"));
+    }
+    if ui.app.has_unsaved_changes() {
+        spans.push(Span::raw(" | [+]"));
+    }
+    if ui.app.read_only() {
+        spans.push(Span::raw(" | [RO]"));
+    }
+    let num_all_gap = ui.app.num_hidden_all_gap();
+    if num_all_gap > 0 {
+        spans.push(Span::raw(format!(" | {} all-gap hidden", num_all_gap)));
+    }
     if spans.len() == 1 {
         return;
     }
@@ -690,6 +1288,246 @@ fn render_modeline(f: &mut Frame, last_content_line: u16, ui: &mut UI) {
     f.render_widget(modeline, modeline_rect);
 }
 
+const AMINO_ACIDS: &str = "ACDEFGHIKLMNPQRSTVWY";
+const NUCLEOTIDES: &str = "ACGTU";
+
+// Residues of the current colormap and their colors, in the top right corner of the sequence
+// pane: amino acids grouped by color, nucleotides one per line, then gaps. The monochrome theme
+// has no colors, so only the residues are listed.
+fn render_legend(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
+    let colormap = ui.color_scheme().current_residue_colormap();
+    let groups = match ui.app.macromolecule_type() {
+        SeqType::Protein => colormap.residue_groups(AMINO_ACIDS),
+        SeqType::Nucleic | SeqType::Rna => NUCLEOTIDES
+            .chars()
+            .map(|residue| (colormap.get(residue), residue.to_string()))
+            .collect(),
+    };
+    let theme = ui.theme();
+    let swatch = |color: Color| match theme {
+        Theme::Monochrome => Span::raw(""),
+        Theme::Dark | Theme::Light => Span::styled("  ", Style::default().bg(color)),
+    };
+    let mut lines: Vec<Line> = groups
+        .into_iter()
+        .map(|(color, residues)| {
+            Line::from(vec![
+                swatch(color),
+                Span::raw(" "),
+                Span::styled(residues, get_residue_style(ui.video_mode, theme, color)),
+            ])
+        })
+        .collect();
+    let gap_color = ui.color_scheme().gap_style.fg.unwrap_or(Color::Reset);
+    lines.push(Line::from(vec![
+        swatch(gap_color),
+        Span::raw(" "),
+        Span::styled("-", ui.color_scheme().gap_style),
+        Span::raw(" gap"),
+    ]));
+
+    let title = format!(" {} ", colormap.name);
+    let content_width = lines.iter().map(Line::width).max().unwrap_or(0);
+    let area = aln_chunk.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    // The legend may extend below a short sequence pane, down to the bottom of the frame.
+    let width = (content_width.max(title.len()) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(f.area().bottom().saturating_sub(area.y));
+    let legend_chunk = Rect::new(area.x + area.width - width, area.y, width, height);
+    let legend_block = Block::default().borders(Borders::ALL).title(title);
+    f.render_widget(Clear, legend_chunk);
+    f.render_widget(Paragraph::new(lines).block(legend_block), legend_chunk);
+}
+
+// Screen column (within the sequence pane) of alignment column `col`, and screen row of the
+// sequence at ordering line `line`, if shown.
+fn seq_pane_position(ui: &UI, col: usize, line: Option<usize>) -> (Option<usize>, Option<usize>) {
+    match ui.zoom_level {
+        ZoomLevel::ZoomedIn if ui.wrap_mode() => (None, None),
+        ZoomLevel::ZoomedIn => (
+            col.checked_sub(ui.leftmost_col as usize),
+            line.and_then(|line| line.checked_sub(ui.top_line as usize)),
+        ),
+        ZoomLevel::ZoomedOut | ZoomLevel::ZoomedOutAR => {
+            let retained =
+                |ndx: Vec<usize>, i: usize| ndx.partition_point(|&j| j <= i).checked_sub(1);
+            (
+                retained(retained_col_ndx(ui), col),
+                line.and_then(|line| retained(retained_seq_ndx(ui), line)),
+            )
+        }
+    }
+}
+
+// Shade of an overview map cell, from blank (variable or gapped) to full block (conserved).
+const OVERVIEW_GLYPHS: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+// Conservation map of the whole alignment over the alignment pane (`:overview`): one shaded,
+// colored cell per character, from blue (variable) to red (conserved); the selected cell is in
+// reverse video.
+fn render_overview(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
+    let InputMode::Overview { row, col } = ui.input_mode else {
+        return;
+    };
+    let area = aln_chunk.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let grid = ui
+        .app
+        .alignment
+        .conservation_grid(area.height as usize, area.width as usize);
+    let colored = ui.color_scheme().theme != Theme::Monochrome;
+    let lines: Vec<Line> = grid
+        .iter()
+        .enumerate()
+        .map(|(r, cells)| {
+            Line::from(
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(c, &value)| {
+                        let value = value.clamp(0.0, 1.0);
+                        let glyph = OVERVIEW_GLYPHS
+                            [(value * (OVERVIEW_GLYPHS.len() - 1) as f64).round() as usize];
+                        let mut style = Style::default();
+                        if colored {
+                            let red = (255.0 * value) as u8;
+                            style = style.fg(ui.map_color(Color::Rgb(red, 64, 255 - red)));
+                        }
+                        if (r, c) == (row, col) {
+                            style = style.add_modifier(Modifier::REVERSED);
+                        }
+                        Span::styled(glyph.to_string(), style)
+                    })
+                    .collect::<Vec<Span>>(),
+            )
+        })
+        .collect();
+    let block = Block::default().borders(Borders::ALL).title(" Overview ");
+    f.render_widget(Clear, aln_chunk);
+    f.render_widget(Paragraph::new(lines).block(block), aln_chunk);
+}
+
+// Floating box with the composition of the guide column, next to the guide on the cursor
+// sequence's row, moved so as to stay within the sequence pane.
+fn render_column_popup(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
+    let (Some(col), Some(composition)) = (ui.guide_col(), ui.column_composition()) else {
+        return;
+    };
+    let area = aln_chunk.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    if area.width < 3 || area.height < 3 {
+        return;
+    }
+    let title = format!(" col {} ", ui.app.display_col(col));
+    // One line if possible, else as many entries per line as fit.
+    let max_content = area.width as usize - 2;
+    let mut lines: Vec<String> = Vec::new();
+    for entry in composition.split(' ') {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + entry.len() <= max_content => {
+                line.push(' ');
+                line.push_str(entry);
+            }
+            _ => lines.push(entry.to_string()),
+        }
+    }
+    let content_width = lines.iter().map(String::len).max().unwrap_or(0);
+    let width = (content_width.max(title.len()) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+
+    let cursor_line = ui.cursor_rank().map(|rank| ui.app.rank_to_screenline(rank));
+    let (x, y) = seq_pane_position(ui, col, cursor_line);
+    let x = x.filter(|&x| x < area.width as usize).map(|x| x as u16);
+    let y = y.filter(|&y| y < area.height as usize).map(|y| y as u16);
+    // Right of the guide if there is room, else left of it; below the cursor row, else above.
+    let popup_x = match x {
+        Some(x) if x + 1 + width <= area.width => x + 1,
+        Some(x) if x >= width => x - width,
+        _ => area.width - width,
+    };
+    let popup_y = match y {
+        Some(y) if y + 1 + height <= area.height => y + 1,
+        Some(y) if y >= height => y - height,
+        _ => 0,
+    };
+    let popup_chunk = Rect::new(area.x + popup_x, area.y + popup_y, width, height);
+    let popup_block = Block::default().borders(Borders::ALL).title(title);
+    f.render_widget(Clear, popup_chunk);
+    f.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(popup_block)
+            .style(Style::new().white().on_black()),
+        popup_chunk,
+    );
+}
+
+// Details of a sequence (Enter) in a box centered across the sequence pane, just below the
+// sequence's row if there is room, else just above it.
+fn render_inspect_popup(f: &mut Frame, aln_chunk: Rect, ui: &UI) {
+    let InputMode::Inspect { rank } = ui.input_mode else {
+        return;
+    };
+    let Some(details) = ui.app.seq_details(rank) else {
+        return;
+    };
+    let area = aln_chunk.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    if area.width < 3 || area.height < 3 {
+        return;
+    }
+    let max_content = area.width as usize - 2;
+    // The full header, over several lines if need be.
+    let header: Vec<char> = details.header.chars().collect();
+    let mut lines: Vec<String> = header
+        .chunks(max_content)
+        .map(|chunk| chunk.iter().collect())
+        .collect();
+    lines.push(format!("Length: {} (ungapped)", details.ungapped_len));
+    lines.push(format!(
+        "%id to consensus: {:.1}",
+        100.0 * details.id_wrt_consensus
+    ));
+    lines.push(format!("Gaps: {}", details.num_gaps));
+    lines.push(match details.residue_span {
+        Some((first, last)) => format!("Residues: columns {} to {}", first, last),
+        None => String::from("Residues: none"),
+    });
+    let title = format!(" seq {} ", rank + 1);
+    let content_width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let width = (content_width.max(title.len()) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+
+    let line = ui.app.rank_to_screenline(rank);
+    let (_, y) = seq_pane_position(ui, ui.leftmost_col as usize, Some(line));
+    let y = y.filter(|&y| y < area.height as usize).map(|y| y as u16);
+    let popup_x = (area.width - width) / 2;
+    let popup_y = match y {
+        Some(y) if y + 1 + height <= area.height => y + 1,
+        Some(y) if y >= height => y - height,
+        _ => (area.height - height) / 2,
+    };
+    let popup_chunk = Rect::new(area.x + popup_x, area.y + popup_y, width, height);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(" any key to close ");
+    f.render_widget(Clear, popup_chunk);
+    f.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(popup_block)
+            .style(Style::new().white().on_black()),
+        popup_chunk,
+    );
+}
+
 fn render_help_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &mut UI) {
     let dialog_block = Block::default().borders(Borders::ALL).title("Help");
     let mut lines: Vec<Line> = crate::ui::USER_GUIDE.lines().map(Line::from).collect();
@@ -711,6 +1549,49 @@ fn render_help_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &mut UI) {
     f.render_widget(dialog_para, dialog_chunk);
 }
 
+fn render_tree_check_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &mut UI) {
+    let InputMode::TreeCheck { mismatch, scroll } = &mut ui.input_mode else {
+        return;
+    };
+    let dialog_block = Block::default().borders(Borders::ALL).title("Tree Check");
+    let mut lines: Vec<Line> = Vec::new();
+    lines.push(Line::from(format!(
+        "Tree leaves without a matching header ({}):",
+        mismatch.unmatched_leaves.len()
+    )));
+    lines.extend(
+        mismatch
+            .unmatched_leaves
+            .iter()
+            .map(|name| Line::from(format!("  {}", name))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!(
+        "Headers without a matching leaf ({}):",
+        mismatch.missing_headers.len()
+    )));
+    lines.extend(
+        mismatch
+            .missing_headers
+            .iter()
+            .map(|name| Line::from(format!("  {}", name))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from("Up/Down/PgUp/PgDn to scroll, Esc to close."));
+    let visible_height = (dialog_chunk.height.saturating_sub(2) as usize).max(1);
+    let max_scroll = lines.len().saturating_sub(visible_height);
+    if *scroll > max_scroll {
+        *scroll = max_scroll;
+    }
+    let dialog_para = Paragraph::new(Text::from(lines))
+        .block(dialog_block)
+        .scroll((*scroll as u16, 0))
+        .style(Style::new().white().on_black());
+    ui.help_page_height = visible_height;
+    f.render_widget(Clear, dialog_chunk);
+    f.render_widget(dialog_para, dialog_chunk);
+}
+
 fn render_search_list_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &UI) {
     let dialog_block = Block::default().borders(Borders::ALL).title("Search List");
     let entries = ui.app.saved_searches();
@@ -718,30 +1599,45 @@ fn render_search_list_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &UI) {
 
     let mut lines: Vec<Line> = Vec::new();
     lines.push(Line::from(format!(
-        "{:>2}  {:<3} {:<4} {:<16} {}",
-        "ID", "On", "Type", "Name", "Query"
+        "{:>2}    {:<3} {:<4} {:>7} {:<16} {}",
+        "ID", "On", "Type", "Matches", "Name", "Query"
     )));
     if entries.is_empty() {
-        lines.push(Line::from("No saved searches."));
+        lines.push(Line::from(
+            "No saved searches. Run a search (/ or \\), then press a to add it here.",
+        ));
     } else {
         for (idx, entry) in entries.iter().enumerate() {
             let on = if entry.enabled { "*" } else { " " };
             let kind = match entry.kind {
                 crate::app::SearchKind::Regex => "R",
                 crate::app::SearchKind::Emboss => "E",
+                crate::app::SearchKind::Gff => "G",
             };
-            let line = format!(
-                "{:>2}  {:<3} {:<4} {:<16} {}",
-                entry.id, on, kind, entry.name, entry.query
-            );
+            let num_matches: usize = entry.spans_by_seq.iter().map(Vec::len).sum();
             let style = if idx == selected {
                 Style::default().add_modifier(Modifier::REVERSED)
             } else {
                 Style::default()
             };
-            lines.push(Line::styled(line, style));
+            let color = ui.map_color(Color::Rgb(entry.color.0, entry.color.1, entry.color.2));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:>2} ", entry.id), style),
+                Span::styled("  ", Style::default().bg(color)),
+                Span::styled(
+                    format!(
+                        " {:<3} {:<4} {:>7} {:<16} {}",
+                        on, kind, num_matches, entry.name, entry.query
+                    ),
+                    style,
+                ),
+            ]));
         }
     }
+    lines.push(Line::from(""));
+    lines.push(Line::from(
+        "Space: toggle  d: delete  Enter: make current  a: add current  Esc: close",
+    ));
 
     let dialog_para = Paragraph::new(Text::from(lines))
         .block(dialog_block)
@@ -908,13 +1804,13 @@ fn render_view_move_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &UI) {
     f.render_widget(dialog_para, dialog_chunk);
 }
 
-fn render_notes_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &UI) {
-    let Some((editor, target)) = ui.notes_state() else {
+fn render_notes_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &mut UI) {
+    let Some((_, target)) = ui.notes_state() else {
         return;
     };
     let title = match target {
-        super::NotesTarget::Global => "Notes",
-        super::NotesTarget::View => "View Notes",
+        super::NotesTarget::Global => "Notes (Esc: save, Ctrl-C: discard)",
+        super::NotesTarget::View => "View Notes (Esc: save, Ctrl-C: discard)",
     };
     let dialog_block = Block::default().borders(Borders::ALL).title(title);
 
@@ -931,18 +1827,21 @@ fn render_notes_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &UI) {
         height: height + 2,
     };
 
-    let mut editor = editor.clone();
+    // Long lines wrap to the overlay; the editor keeps its scroll position between draws.
+    let Some(editor) = ui.notes_editor_mut() else {
+        return;
+    };
+    editor.set_wrap_width(Some(width as usize));
     editor.ensure_visible(height as usize);
     let start = editor.scroll();
 
-    let mut lines: Vec<Line> = Vec::new();
-    for line in editor.lines().iter().skip(start).take(height as usize) {
-        let mut text = line.clone();
-        if text.len() > width as usize {
-            text.truncate(width as usize);
-        }
-        lines.push(Line::from(text));
-    }
+    let mut lines: Vec<Line> = editor
+        .wrapped_lines(width as usize)
+        .into_iter()
+        .skip(start)
+        .take(height as usize)
+        .map(Line::from)
+        .collect();
     while lines.len() < height as usize {
         lines.push(Line::from(""));
     }
@@ -951,8 +1850,9 @@ fn render_notes_dialog(f: &mut Frame, dialog_chunk: Rect, ui: &UI) {
     f.render_widget(Clear, notes_chunk);
     f.render_widget(para, notes_chunk);
 
-    let cursor_x = editor.col().min(width as usize) as u16;
-    let cursor_y = editor.row().saturating_sub(start).min(height as usize - 1) as u16;
+    let (cursor_row, cursor_col) = editor.visual_cursor();
+    let cursor_x = cursor_col.min(width as usize) as u16;
+    let cursor_y = cursor_row.saturating_sub(start).min(height as usize - 1) as u16;
     f.set_cursor_position((notes_chunk.x + 1 + cursor_x, notes_chunk.y + 1 + cursor_y));
 }
 
@@ -967,6 +1867,15 @@ pub fn render_ui(f: &mut Frame, ui: &mut UI) {
      */
 
     ui.aln_pane_size = Some(layout_panes.sequence.as_size());
+    ui.aln_pane_area = Some(layout_panes.sequence);
+    if let Some((_, split_seq)) = layout_panes.split {
+        ui.set_split_pane_size(
+            Block::default()
+                .borders(Borders::ALL)
+                .inner(split_seq)
+                .as_size(),
+        );
+    }
 
     // Handle resizing
     ui.adjust_seq_pane_position();
@@ -998,11 +1907,23 @@ pub fn render_ui(f: &mut Frame, ui: &mut UI) {
     render_labels_pane(f, layout_panes.labels, ui);
     render_seq_metrics_pane(f, layout_panes.seq_metrics, ui);
     render_alignment_pane(f, layout_panes.sequence, ui);
+    if let Some((pinned_lbl, pinned_seq)) = layout_panes.pinned {
+        render_pinned_panes(f, pinned_lbl, pinned_seq, ui);
+    }
+    if let Some((split_lbl, split_seq)) = layout_panes.split {
+        render_split_panes(f, split_lbl, split_seq, ui);
+    }
+    if ui.show_legend() {
+        render_legend(f, layout_panes.sequence, ui);
+    }
+    if ui.show_column_popup() {
+        render_column_popup(f, layout_panes.sequence, ui);
+    }
     render_corner_pane(f, layout_panes.corner, ui);
     render_bottom_pane(f, layout_panes.bottom, ui);
     render_modeline(
         f,
-        layout_panes.lbl_num.height + layout_panes.corner.height - 1,
+        layout_panes.corner.y + layout_panes.corner.height - 1,
         ui,
     );
 
@@ -1012,6 +1933,18 @@ pub fn render_ui(f: &mut Frame, ui: &mut UI) {
         ui.app.clear_msg();
     }
 
+    if let InputMode::TreeCheck { .. } = ui.input_mode {
+        render_tree_check_dialog(f, layout_panes.dialog, ui);
+    }
+
+    if let InputMode::Overview { .. } = ui.input_mode {
+        render_overview(f, layout_panes.sequence, ui);
+    }
+
+    if let InputMode::Inspect { .. } = ui.input_mode {
+        render_inspect_popup(f, layout_panes.sequence, ui);
+    }
+
     if let InputMode::SearchList { .. } = ui.input_mode {
         render_search_list_dialog(f, layout_panes.dialog, ui);
     }
@@ -1059,7 +1992,19 @@ pub fn every_nth(l: usize, n: usize) -> Vec<usize> {
 #[cfg(test)]
 mod tests {
 
-    use crate::ui::render::{every_nth, tick_marks};
+    use crate::ui::render::{
+        every_nth, scrollbar_ticks, tick_marks, tick_position, truncate_middle,
+    };
+
+    #[test]
+    fn test_scrollbar_ticks() {
+        assert_eq!(
+            scrollbar_ticks([0, 99, 50, 51, 100], 100, 10),
+            vec![0, 5, 9]
+        );
+        assert_eq!(scrollbar_ticks([3, 1], 4, 8), vec![2, 6]);
+        assert_eq!(scrollbar_ticks([0], 0, 8), Vec::<u16>::new());
+    }
 
     #[test]
     fn test_every_nth_1() {
@@ -1083,13 +2028,39 @@ mod tests {
 
     #[test]
     fn test_tick_marks_01() {
-        let tm = tick_marks(21, None, None);
+        let tm = tick_marks(21, 1, None, None);
         assert_eq!(tm, "    :    |         |");
     }
 
     #[test]
     fn test_tick_marks_02() {
-        let tm = tick_marks(21, Some(':'), Some('.'));
+        let tm = tick_marks(21, 1, Some(':'), Some('.'));
         assert_eq!(tm, "    :    :    .    :");
     }
+
+    #[test]
+    fn test_tick_position() {
+        assert_eq!(tick_position(25, 1), "1       10        20");
+        assert_eq!(tick_position(25, 10001), "10001          10020");
+        assert_eq!(tick_position(12, 10009), "10009");
+        assert_eq!(tick_position(12, -4), "-4  0");
+        assert_eq!(tick_marks(12, 10001, None, Some(':')), "    :    | ");
+    }
+
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!("Homo_…GAPDH", truncate_middle("Homo_sapiens_GAPDH", 11));
+        assert_eq!("Homo_…APDH", truncate_middle("Homo_sapiens_GAPDH", 10));
+        assert_eq!("Homo_sapiens", truncate_middle("Homo_sapiens", 12));
+        assert_eq!("Ho…H", truncate_middle("Homo_sapiens_GAPDH", 4));
+        assert_eq!("…", truncate_middle("Homo", 1));
+        assert_eq!("", truncate_middle("Homo", 0));
+    }
+
+    #[test]
+    fn test_truncate_middle_multibyte() {
+        // Cuts between characters, never inside one.
+        assert_eq!("αβγ…ψω", truncate_middle("αβγδεζηθικλμνξοπρστυφχψω", 6));
+        assert_eq!("é…è", truncate_middle("éàüè", 3));
+    }
 }
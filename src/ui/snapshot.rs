@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+//! Rendering an alignment without the interactive TUI, e.g. to take snapshots from another tool.
+
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    prelude::{Position, Rect, Terminal},
+    TerminalOptions, Viewport,
+};
+
+use crate::{
+    alignment::Alignment,
+    app::{App, StartupScheme, StartupVideoMode},
+    ui::{render::render_ui, ZoomLevel, UI},
+};
+
+pub use crate::ui::svg::buffer_to_svg;
+
+/// Display options for `render_to_buffer()`. The default is what msafara shows at startup,
+/// scrolled to the top left corner, without the startup message.
+#[derive(Clone, Copy, Debug)]
+pub struct RenderOpts {
+    /// Color scheme (`None`: the UI's default).
+    pub scheme: Option<StartupScheme>,
+    /// Video mode (`None`: the UI's default).
+    pub video_mode: Option<StartupVideoMode>,
+    pub zoom: ZoomLevel,
+    /// First sequence line shown (0-based; clamped like scrolling in the UI).
+    pub top_line: u16,
+    /// First alignment column shown (0-based; clamped like scrolling in the UI).
+    pub leftmost_col: u16,
+}
+
+impl Default for RenderOpts {
+    fn default() -> Self {
+        RenderOpts {
+            scheme: None,
+            video_mode: None,
+            zoom: ZoomLevel::ZoomedIn,
+            top_line: 0,
+            leftmost_col: 0,
+        }
+    }
+}
+
+/// Renders `aln` once, as msafara would show it in a `width` x `height` terminal, and returns the
+/// screen's cells.
+///
+/// ```
+/// use msafara::{alignment::Alignment, buffer_to_text, render_to_buffer, RenderOpts};
+///
+/// let aln = Alignment::from_vecs(
+///     vec![String::from("seq1"), String::from("seq2")],
+///     vec![String::from("ACGTAC"), String::from("ACGAAC")],
+/// );
+/// let buffer = render_to_buffer(&aln, 60, 12, RenderOpts::default());
+/// let text = buffer_to_text(&buffer);
+/// assert!(text.contains("seq1"));
+/// assert!(text.contains("ACGAAC"));
+/// ```
+pub fn render_to_buffer(aln: &Alignment, width: u16, height: u16, opts: RenderOpts) -> Buffer {
+    let mut copy = Alignment::from_vecs(aln.headers().to_vec(), aln.sequences().to_vec());
+    copy.set_consensus_threshold(aln.consensus_threshold());
+    copy.set_ut_equivalence(aln.ut_equivalence());
+    let mut app = App::new("", copy, None);
+    let mut ui = UI::new(&mut app);
+    if let Some(scheme) = opts.scheme {
+        ui.set_color_scheme(scheme);
+    }
+    if let Some(video_mode) = opts.video_mode {
+        ui.set_video_mode(video_mode);
+    }
+    ui.app.clear_msg();
+
+    let backend = TestBackend::new(width, height);
+    let viewport = Viewport::Fixed(Rect::new(0, 0, width, height));
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })
+        .expect("test backend terminal");
+    // The first draw lays out the panes, which bound the zoom and scrolling.
+    terminal
+        .draw(|f| render_ui(f, &mut ui))
+        .expect("test backend draw");
+    if opts.zoom != ZoomLevel::ZoomedIn {
+        ui.zoom_level = opts.zoom;
+        terminal
+            .draw(|f| render_ui(f, &mut ui))
+            .expect("test backend draw");
+    }
+    ui.top_line = opts.top_line.min(ui.max_top_line());
+    ui.leftmost_col = opts.leftmost_col.min(ui.max_leftmost_col());
+    terminal
+        .draw(|f| render_ui(f, &mut ui))
+        .expect("test backend draw");
+    terminal.backend().buffer().clone()
+}
+
+/// The characters of `buf`, one line per row (wide symbols are cut to their first character).
+pub fn buffer_to_text(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut out = String::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            out.push(
+                buf.cell(Position::from((x, y)))
+                    .and_then(|cell| cell.symbol().chars().next())
+                    .unwrap_or(' '),
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -7,6 +7,7 @@ use ratatui::style::{Color, Modifier, Style};
 use super::{
     UI, {Theme, VideoMode},
 };
+use crate::alignment::is_gap;
 
 pub fn get_residue_style(video_mode: VideoMode, theme: Theme, color: Color) -> Style {
     let mut style = Style::default();
@@ -30,11 +31,29 @@ pub fn get_residue_style(video_mode: VideoMode, theme: Theme, color: Color) -> S
     style
 }
 
+// Gaps use the color scheme's gap style, even in inverse video (they are not residues). Residues
+// take the color of their case-folded form, and lower-case (soft-masked) ones are dimmed in
+// mask-aware mode. With conservation shading, colors are left in RGB: the panes shade them by
+// column and map them to the terminal's palette themselves.
 pub fn build_style_lut(ui: &UI) -> [Style; 256] {
     let colormap = ui.color_scheme().current_residue_colormap();
+    let map_color = |color| {
+        if ui.conservation_shading() {
+            color
+        } else {
+            ui.map_color(color)
+        }
+    };
     std::array::from_fn(|b| {
         let ch = b as u8 as char;
-        let color = ui.map_color(colormap.get(ch));
+        if is_gap(ch) {
+            return ui.color_scheme().gap_style;
+        }
+        if ui.mask_aware() && ch.is_ascii_lowercase() {
+            let color = map_color(colormap.get(ch.to_ascii_uppercase()));
+            return get_residue_style(ui.video_mode, ui.theme(), color).add_modifier(Modifier::DIM);
+        }
+        let color = map_color(colormap.get(ui.case_fold().apply(ch)));
         get_residue_style(ui.video_mode, ui.theme(), color)
     })
 }
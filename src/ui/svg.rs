@@ -63,7 +63,9 @@ pub fn export_full_view(ui: &mut UI, path: &Path) -> Result<(), TermalError> {
     Ok(())
 }
 
-fn buffer_to_svg(buf: &Buffer, seq_rect: Rect) -> String {
+/// An SVG image of `buf`'s characters; those on a colored background take its color, in bold
+/// within `seq_rect` (the sequence pane, or an empty `Rect` for none).
+pub fn buffer_to_svg(buf: &Buffer, seq_rect: Rect) -> String {
     let area = buf.area;
     let width_px = area.width.saturating_mul(CELL_WIDTH) as u32;
     let height_px = area.height.saturating_mul(CELL_HEIGHT) as u32;
@@ -158,7 +160,7 @@ fn is_within(rect: Rect, x: u16, y: u16) -> bool {
 
 fn max_num_seq(area: Rect, ui: &UI) -> u16 {
     match ui.zoom_level {
-        super::ZoomLevel::ZoomedOut | super::ZoomLevel::ZoomedIn => ui.app.num_seq(),
+        super::ZoomLevel::ZoomedOut | super::ZoomLevel::ZoomedIn => ui.app.num_visible_seq(),
         super::ZoomLevel::ZoomedOutAR => {
             let v_constraints = vec![Constraint::Fill(1), Constraint::Max(ui.bottom_pane_height)];
             let top_chunk = Layout::new(Direction::Vertical, v_constraints).split(area)[0];
@@ -168,11 +170,11 @@ fn max_num_seq(area: Rect, ui: &UI) -> u16 {
             )
             .split(top_chunk)[1];
 
-            let v_ratio = (aln_pane.height - 2) as f64 / ui.app.num_seq() as f64;
+            let v_ratio = (aln_pane.height - 2) as f64 / ui.app.num_visible_seq() as f64;
             let h_ratio = (aln_pane.width - 2) as f64 / ui.app.aln_len() as f64;
             let ratio = h_ratio.min(v_ratio);
 
-            (ui.app.num_seq() as f64 * ratio).round() as u16
+            (ui.app.num_visible_seq() as f64 * ratio).round() as u16
         }
     }
 }
@@ -202,7 +204,7 @@ fn sequence_pane_rect(ui: &UI, area: Rect) -> Rect {
 }
 
 fn full_frame_size(ui: &UI) -> Result<ratatui::layout::Size, TermalError> {
-    let seq_rows = ui.app.num_seq() as u32 + 2;
+    let seq_rows = ui.app.num_visible_seq() as u32 + 2;
     let seq_cols = ui.app.aln_len() as u32 + 2;
     let seq_pane_height = seq_rows.min(u16::MAX as u32) as u16;
     let seq_pane_width = seq_cols.min(u16::MAX as u32) as u16;
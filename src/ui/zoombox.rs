@@ -4,6 +4,22 @@
 
 use ratatui::{buffer::Buffer, layout::Rect, prelude::Position, style::Style};
 
+// Span (start, exclusive end) of the zoom box along one axis of the zoomed-out pane: the positions
+// of the `retained` rows (or columns) that fall in the `shown` ones from `first` on. Never empty,
+// unless nothing is retained.
+pub fn zoombox_span(retained: &[usize], first: usize, shown: usize) -> (usize, usize) {
+    if retained.is_empty() {
+        return (0, 0);
+    }
+    let start = retained
+        .partition_point(|&ndx| ndx < first)
+        .min(retained.len() - 1);
+    let end = retained
+        .partition_point(|&ndx| ndx < first + shown)
+        .max(start + 1);
+    (start, end)
+}
+
 pub fn draw_zoombox_border(
     buf: &mut Buffer,
     area: Rect,
@@ -160,3 +176,20 @@ fn draw_zoombox_border_zero_height(
         .set_char('╼')
         .set_style(style);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::zoombox_span;
+
+    #[test]
+    fn test_zoombox_span() {
+        let retained = [0, 4, 8, 12, 16];
+        assert_eq!(zoombox_span(&retained, 0, 5), (0, 2));
+        assert_eq!(zoombox_span(&retained, 3, 5), (1, 2));
+        // Between two retained rows: still one row tall.
+        assert_eq!(zoombox_span(&retained, 5, 2), (2, 3));
+        assert_eq!(zoombox_span(&retained, 14, 5), (4, 5));
+        assert_eq!(zoombox_span(&retained, 0, 100), (0, 5));
+        assert_eq!(zoombox_span(&[], 0, 5), (0, 0));
+    }
+}
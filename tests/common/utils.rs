@@ -15,7 +15,7 @@ use msafara::{
     alignment::Alignment,
     app::App,
     seq::fasta,
-    ui::{render, render::render_ui, UI},
+    ui::{key_handling, render, render::render_ui, resolve_quit, UI},
 };
 
 #[allow(dead_code)]
@@ -29,22 +29,7 @@ pub fn render(app: &mut App, w: u16, h: u16) -> Buffer {
 
 #[allow(dead_code)]
 pub fn buffer_text(buf: &Buffer) -> String {
-    let area = buf.area;
-    let mut out = String::new();
-    for y in 0..area.height {
-        for x in 0..area.width {
-            out.push(
-                buf.cell(Position::from((x, y)))
-                    .expect("Wrong position")
-                    .symbol()
-                    .chars()
-                    .next()
-                    .unwrap_or(' '),
-            );
-        }
-        out.push('\n');
-    }
-    out
+    msafara::buffer_to_text(buf)
 }
 
 #[allow(dead_code)]
@@ -57,6 +42,47 @@ pub fn keypress(c: char) -> KeyEvent {
     }
 }
 
+/// `code` with the Control key held (e.g. Ctrl-N, Ctrl-Right).
+#[allow(dead_code)]
+pub fn ctrl(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::CONTROL)
+}
+
+/// Presses the keys for the characters of `keys`, in order; returns what the last press returned
+/// (true to quit).
+#[allow(dead_code)]
+pub fn type_keys(ui: &mut UI, keys: &str) -> bool {
+    keys.chars()
+        .map(|c| key_handling::handle_key_press(ui, keypress(c)))
+        .last()
+        .unwrap_or(false)
+}
+
+/// Types `:` and `cmd`, then Enter; returns what Enter returned (true to quit).
+#[allow(dead_code)]
+pub fn ex_command(ui: &mut UI, cmd: &str) -> bool {
+    type_keys(ui, ":");
+    type_keys(ui, cmd);
+    key_handling::handle_key_press(ui, KeyCode::Enter.into())
+}
+
+/// Opens a search prompt with `open` (e.g. '/', '"', Ctrl-/), types `pattern`, then Enter.
+#[allow(dead_code)]
+pub fn search(ui: &mut UI, open: KeyEvent, pattern: &str) {
+    key_handling::handle_key_press(ui, open);
+    type_keys(ui, pattern);
+    key_handling::handle_key_press(ui, KeyCode::Enter.into());
+}
+
+/// Whether the runner quits after a key press that returned `done`, `ui` being the only
+/// alignment open (a quit request only goes through if there are no unsaved changes).
+#[allow(dead_code)]
+pub fn quits(ui: &mut UI, done: bool) -> bool {
+    done || ui
+        .take_quit_request()
+        .is_some_and(|request| resolve_quit(std::slice::from_mut(ui), &mut 0, request))
+}
+
 #[allow(dead_code)]
 pub fn with_rig<F>(path: &str, term_width: u16, term_height: u16, mut f: F)
 where
@@ -32,3 +32,18 @@ fn renders_without_panic() {
 
     assert!(!screen.trim().is_empty());
 }
+
+#[test]
+fn renders_saved_search_legend() {
+    let hdrs = vec![String::from("R1"), String::from("R2")];
+    let seqs = vec![String::from("catgcatatg"), String::from("caGgAaCaAg")];
+    let aln = Alignment::from_vecs(hdrs, seqs);
+    let mut app = App::new("TEST", aln, None);
+    app.add_saved_search(String::from("motif"), String::from("cat"))
+        .unwrap();
+    let buf = utils::render(&mut app, 60, 20);
+    let screen = utils::buffer_text(&buf);
+
+    assert!(screen.contains("Searches"));
+    assert!(screen.contains("   motif"));
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+use ratatui::buffer::Buffer;
+
+// Row of the lower border of the sequence pane, i.e. just above the bottom pane.
+fn seq_pane_bottom_row(buffer: &Buffer) -> u16 {
+    (0..buffer.area.height)
+        .find(|&row| utils::screen_line(buffer, row).starts_with('└'))
+        .expect("no lower border")
+}
+
+#[test]
+/// `+` and `_` (with counts) grow and shrink the bottom pane; hiding and showing it keeps the
+/// height.
+fn test_resize_bottom_pane() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        let initial = seq_pane_bottom_row(terminal.backend().buffer());
+
+        key_handling::handle_key_press(ui, utils::keypress('3'));
+        key_handling::handle_key_press(ui, utils::keypress('+'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(
+            seq_pane_bottom_row(terminal.backend().buffer()),
+            initial - 3
+        );
+
+        key_handling::handle_key_press(ui, utils::keypress('_'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(
+            seq_pane_bottom_row(terminal.backend().buffer()),
+            initial - 2
+        );
+
+        key_handling::handle_key_press(ui, utils::keypress('c'));
+        key_handling::handle_key_press(ui, utils::keypress('c'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(
+            seq_pane_bottom_row(terminal.backend().buffer()),
+            initial - 2
+        );
+
+        // The sequence pane keeps at least one row.
+        key_handling::handle_key_press(ui, utils::keypress('9'));
+        key_handling::handle_key_press(ui, utils::keypress('9'));
+        key_handling::handle_key_press(ui, utils::keypress('+'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(seq_pane_bottom_row(terminal.backend().buffer()), 2);
+    });
+}
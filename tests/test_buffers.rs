@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, BufferSwitch};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+#[test]
+/// With a single alignment, Ctrl-N does not request a switch and says why.
+fn test_single_buffer_no_switch() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('n')));
+            assert_eq!(ui.take_buffer_switch(), None);
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(
+                last_line.contains("Only one alignment file is open"),
+                "{}",
+                last_line
+            );
+            assert!(!last_line.contains("[1/1]"), "{}", last_line);
+        },
+    );
+}
+
+#[test]
+/// With several alignments, Ctrl-N / Ctrl-P request a switch and the modeline shows the position.
+fn test_buffer_switch_and_label() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            ui.set_buffer_position(1, 3);
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('n')));
+            assert_eq!(ui.take_buffer_switch(), Some(BufferSwitch::Next));
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('p')));
+            assert_eq!(ui.take_buffer_switch(), Some(BufferSwitch::Previous));
+            // Plain n/p still navigate matches.
+            key_handling::handle_key_press(ui, utils::keypress('n'));
+            assert_eq!(ui.take_buffer_switch(), None);
+
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(last_line.contains("TEST [2/3]"), "{}", last_line);
+        },
+    );
+}
+
+#[test]
+fn test_buffer_switch_target_wraps() {
+    assert_eq!(BufferSwitch::Next.target(2, 3), 0);
+    assert_eq!(BufferSwitch::Previous.target(0, 3), 2);
+    assert_eq!(BufferSwitch::Next.target(0, 3), 1);
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    app::StartupScheme,
+    ui::{key_handling, render},
+};
+use ratatui::{buffer::Buffer, style::Modifier};
+
+// Screen position of the first residue of the first sequence, whose text is `seq`.
+fn seq_start(buffer: &Buffer, seq: &str) -> (u16, u16) {
+    let line = utils::screen_line(buffer, 1);
+    let byte = line.find(seq).expect("sequence on screen");
+    (line[..byte].chars().count() as u16, 1)
+}
+
+#[test]
+/// `~` draws all residues in upper case (then lower case), with the colors of that case; the data
+/// is not changed.
+fn test_case_fold_display() {
+    utils::with_rig("tests/data/soft-masked.fas", 80, 20, |ui, terminal| {
+        ui.set_color_scheme(StartupScheme::Dark);
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let (x, y) = seq_start(terminal.backend().buffer(), "ACGTacgtAC");
+
+        key_handling::handle_key_press(ui, utils::keypress('~'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        assert!(utils::screen_line(buffer, y).contains("ACGTACGTAC"));
+        // 'a' (column 5) now looks like 'A' (column 1).
+        assert_eq!(buffer[(x, y)].fg, buffer[(x + 4, y)].fg);
+
+        key_handling::handle_key_press(ui, utils::keypress('~'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(utils::screen_line(terminal.backend().buffer(), y).contains("acgtacgtac"));
+
+        key_handling::handle_key_press(ui, utils::keypress('~'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(utils::screen_line(terminal.backend().buffer(), y).contains("ACGTacgtAC"));
+    });
+}
+
+#[test]
+/// With `:mask`, lower-case residues keep their letters but are drawn as dimmed upper-case ones.
+fn test_mask_aware_display() {
+    utils::with_rig("tests/data/soft-masked.fas", 80, 20, |ui, terminal| {
+        ui.set_color_scheme(StartupScheme::Dark);
+        utils::ex_command(ui, "mask");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let (x, y) = seq_start(buffer, "ACGTacgtAC");
+        let (upper, lower) = (&buffer[(x, y)], &buffer[(x + 4, y)]);
+        assert_eq!("a", lower.symbol());
+        assert_eq!(upper.fg, lower.fg);
+        assert!(lower.modifier.contains(Modifier::DIM));
+        assert!(!upper.modifier.contains(Modifier::DIM));
+    });
+}
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 160;
+const SCREEN_HEIGHT: u16 = 30;
+
+#[test]
+/// `:colormap <name>` selects a colormap by name, Tab completes the name, and unknown names are
+/// reported along with the available ones.
+fn test_colormap_by_name() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            ui.add_user_colormap(&String::from("tests/data/hydrophobic.json"));
+            ui.add_user_colormap(&String::from("tests/data/charge.json"));
+            // The dark scheme has the user maps and the built-in ones.
+            key_handling::handle_key_press(ui, utils::keypress('s'));
+            let names = ui.color_scheme().colormap_names();
+            assert!(names.contains(&"Hydrophobic") && names.contains(&"Charge"));
+
+            utils::ex_command(ui, "colormap Charge");
+            assert_eq!(
+                ui.color_scheme().current_residue_colormap().name(),
+                "Charge"
+            );
+
+            utils::type_keys(ui, ":colormap hyd");
+            key_handling::handle_key_press(ui, KeyCode::Tab.into());
+            assert_eq!(ui.command_text(), "colormap Hydrophobic");
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+            assert_eq!(
+                ui.color_scheme().current_residue_colormap().name(),
+                "Hydrophobic"
+            );
+
+            utils::ex_command(ui, "colormap nosuchmap");
+            assert_eq!(
+                ui.color_scheme().current_residue_colormap().name(),
+                "Hydrophobic"
+            );
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(
+                screen.contains("No colormap named nosuchmap (have: Charge, Hydrophobic"),
+                "{}",
+                screen
+            );
+        },
+    );
+}
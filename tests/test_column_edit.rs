@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, UI};
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 30;
+
+fn shift_arrow(ui: &mut UI, code: KeyCode) {
+    key_handling::handle_key_press(ui, KeyEvent::new(code, KeyModifiers::SHIFT));
+}
+
+#[test]
+/// With the guide shown, `i` inserts gap columns at it, `x` deletes columns from it on, and `u`
+/// undoes them; without the guide, `i` and `x` keep their usual meaning.
+fn test_column_edit() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, _terminal| {
+            utils::type_keys(ui, "V");
+            let guide = ui.guide_col().expect("guide shown");
+            let here = ui.column_composition().expect("composition");
+            utils::type_keys(ui, "3.");
+            let three_further = ui.column_composition().expect("composition");
+            utils::type_keys(ui, "3,");
+            assert_eq!(ui.guide_col(), Some(guide));
+
+            // Two gap columns: the guide shows the first one, the next but one is the old column.
+            utils::type_keys(ui, "2i");
+            let gaps = ui.column_composition().expect("composition");
+            assert!(gaps.starts_with("-:"), "{}", gaps);
+            utils::type_keys(ui, "2.");
+            assert_eq!(ui.column_composition().as_deref(), Some(here.as_str()));
+            utils::type_keys(ui, "2,u");
+            assert_eq!(ui.column_composition().as_deref(), Some(here.as_str()));
+
+            // Three columns deleted: the guide shows the one that was three columns further.
+            utils::type_keys(ui, "3x");
+            assert_eq!(
+                ui.column_composition().as_deref(),
+                Some(three_further.as_str())
+            );
+            utils::type_keys(ui, "u");
+            assert_eq!(ui.guide_col(), Some(guide));
+            assert_eq!(ui.column_composition().as_deref(), Some(here.as_str()));
+        },
+    );
+}
+
+#[test]
+/// With the guide shown, Shift-Left/Right move the cursor sequence's residue under the guide
+/// through gaps, the guide following it; `u` moves it back.
+fn test_shift_residue() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, _terminal| {
+            // The first sequence starts with ------------MSTT----, the T at column 15.
+            utils::ex_command(ui, "n 1");
+            utils::type_keys(ui, "V");
+            let guide = ui.guide_col().expect("guide shown");
+            if guide > 15 {
+                utils::type_keys(ui, &format!("{},", guide - 15));
+            } else if guide < 15 {
+                utils::type_keys(ui, &format!("{}.", 15 - guide));
+            }
+            assert_eq!(ui.guide_col(), Some(15));
+
+            shift_arrow(ui, KeyCode::Right);
+            assert_eq!(ui.guide_col(), Some(16));
+            // The S is blocked by the T next to it.
+            utils::type_keys(ui, "3,");
+            shift_arrow(ui, KeyCode::Right);
+            assert_eq!(ui.guide_col(), Some(13));
+            utils::type_keys(ui, "3.");
+
+            // Three more to the right in one go, then back.
+            utils::type_keys(ui, "3");
+            shift_arrow(ui, KeyCode::Right);
+            assert_eq!(ui.guide_col(), Some(19));
+            utils::type_keys(ui, "u");
+            assert_eq!(ui.guide_col(), Some(16));
+            utils::type_keys(ui, "u");
+            assert_eq!(ui.guide_col(), Some(15));
+        },
+    );
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::render;
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 40;
+
+#[test]
+/// With `:origin 10001`, the ruler numbers the first column 10001, and `:col` and `|` take
+/// numbers as shown.
+fn test_column_origin() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("1       10        20"), "{}", screen);
+
+            utils::ex_command(ui, "origin 10001");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("10001          10020"), "{}", screen);
+
+            utils::ex_command(ui, "col 10005");
+            assert_eq!(ui.leftmost_col(), 4);
+
+            utils::type_keys(ui, "10010|");
+            assert_eq!(ui.leftmost_col(), 9);
+
+            // Out of the alignment: clamped, with a warning.
+            utils::ex_command(ui, "col 5");
+            assert_eq!(ui.leftmost_col(), 0);
+        },
+    );
+}
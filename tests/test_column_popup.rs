@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use crossterm::event::KeyCode;
+use msafara::ui::{key_handling, render};
+
+#[test]
+/// With the guide on the last column (the guide starts in the middle of the visible columns,
+/// i.e. past the end of this short alignment), `=` shows its composition, including nucleotides
+/// that do not occur; a motion closes it, and so does Esc, which leaves the guide.
+fn test_column_popup() {
+    utils::with_rig("tests/data/snp.fas", 80, 20, |ui, terminal| {
+        utils::type_keys(ui, "=");
+        assert!(!ui.show_column_popup());
+
+        utils::type_keys(ui, "V=");
+        assert_eq!(Some(9), ui.guide_col());
+        assert!(ui.show_column_popup());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("A:1 C:0 G:2 T:1 -:1"));
+        assert!(screen.contains(" col 10 "));
+
+        // The popup follows the guide.
+        utils::type_keys(ui, ",");
+        assert!(ui.show_column_popup());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("A:5 C:0 G:0 T:0"));
+
+        utils::type_keys(ui, "j");
+        assert!(!ui.show_column_popup());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(!screen.contains("A:5 C:0"));
+
+        utils::type_keys(ui, "=");
+        key_handling::handle_key_press(ui, KeyCode::Esc.into());
+        assert!(!ui.show_column_popup());
+        assert!(ui.guide_col().is_some());
+    });
+}
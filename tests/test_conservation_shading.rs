@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    app::StartupScheme,
+    ui::{key_handling, render},
+};
+
+const SCREEN_WIDTH: u16 = 120;
+const SCREEN_HEIGHT: u16 = 20;
+
+#[test]
+/// With "`", residues in variable columns fade while those in fully conserved ones (the block of
+/// Ws from column 41) keep their color.
+fn test_conservation_shading() {
+    utils::with_rig(
+        "tests/data/conserved-blocks.fas",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            ui.set_color_scheme(StartupScheme::Dark);
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let buffer = terminal.backend().buffer().clone();
+            let (x, y) = (0..SCREEN_HEIGHT)
+                .find_map(|y| {
+                    let line = utils::screen_line(&buffer, y);
+                    line.find("ACDEFGHIKLMNPQRSTVWY")
+                        .map(|x| (line[..x].chars().count() as u16, y))
+                })
+                .expect("first sequence on screen");
+            let variable = buffer[(x, y)].fg;
+            let conserved = buffer[(x + 40, y)].fg;
+            assert_eq!(buffer[(x + 40, y)].symbol(), "W");
+
+            key_handling::handle_key_press(ui, utils::keypress('`'));
+            assert!(ui.conservation_shading());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let buffer = terminal.backend().buffer();
+            let screen = utils::buffer_text(buffer);
+            assert!(
+                screen.contains("Residues shaded by column conservation"),
+                "{}",
+                screen
+            );
+            assert_ne!(buffer[(x, y)].fg, variable);
+            assert_eq!(buffer[(x + 40, y)].fg, conserved);
+
+            key_handling::handle_key_press(ui, utils::keypress('`'));
+            assert!(!ui.conservation_shading());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            assert_eq!(terminal.backend().buffer()[(x, y)].fg, variable);
+        },
+    );
+}
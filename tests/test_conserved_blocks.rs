@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::render;
+
+#[test]
+/// The alignment has two conserved blocks, at columns 41-50 and 101-110: `}` scrolls to each in
+/// turn and reports it in the modeline, `{` goes back.
+fn test_conserved_block_navigation() {
+    utils::with_rig("tests/data/conserved-blocks.fas", 80, 20, |ui, terminal| {
+        utils::type_keys(ui, "}");
+        assert_eq!(40, ui.leftmost_col());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("block 1/2"));
+
+        utils::type_keys(ui, "}");
+        assert_eq!(ui.max_leftmost_col().min(100), ui.leftmost_col());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("block 2/2"));
+
+        // No block further right: the view stays.
+        let left = ui.leftmost_col();
+        utils::type_keys(ui, "}");
+        assert_eq!(left, ui.leftmost_col());
+
+        utils::type_keys(ui, "{");
+        assert_eq!(40, ui.leftmost_col());
+        utils::type_keys(ui, "{");
+        assert_eq!(40, ui.leftmost_col());
+
+        // With a count.
+        utils::type_keys(ui, "2}");
+        assert_eq!(left, ui.leftmost_col());
+        ui.assert_invariants();
+    });
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use std::collections::HashSet;
+
+use crossterm::event::KeyCode;
+use ratatui::{buffer::Buffer, style::Color, style::Modifier};
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+// Background colors of the highlighted cells, split into those of the current match (which is
+// underlined) and those of the other matches.
+fn highlight_colors(buf: &Buffer) -> (HashSet<Color>, HashSet<Color>) {
+    let mut current = HashSet::new();
+    let mut others = HashSet::new();
+    for cell in buf.content() {
+        let bg = cell.style().bg.unwrap_or(Color::Reset);
+        if matches!(bg, Color::Rgb(..) | Color::Indexed(..)) {
+            if cell.style().add_modifier.contains(Modifier::UNDERLINED) {
+                current.insert(bg);
+            } else {
+                others.insert(bg);
+            }
+        }
+    }
+    (current, others)
+}
+
+#[test]
+/// After a search, the current match is drawn in its own color, and `]` moves that color on.
+fn test_current_match_color() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            key_handling::handle_key_press(ui, KeyCode::Esc.into());
+            key_handling::handle_key_press(ui, utils::keypress('/'));
+            utils::type_keys(ui, "MSTT");
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let (current, others) = highlight_colors(terminal.backend().buffer());
+            assert_eq!(current.len(), 1, "{:?}", current);
+            assert!(!others.is_empty());
+            assert!(current.is_disjoint(&others), "{:?} {:?}", current, others);
+
+            key_handling::handle_key_press(ui, utils::keypress(']'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let (next_current, next_others) = highlight_colors(terminal.backend().buffer());
+            assert_eq!(next_current, current);
+            assert!(next_current.is_disjoint(&next_others));
+        },
+    );
+}
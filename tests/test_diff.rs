@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+#[test]
+/// `:diff A B` scrolls to the first disagreement; n/N cycle through the others.
+fn test_diff_command_and_cycling() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            utils::ex_command(ui, "diff 1 2");
+            let columns = ui.diff_columns().expect("diff state").to_vec();
+            assert!(columns.len() > 2);
+            assert_eq!(ui.leftmost_col() as usize, columns[0]);
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(
+                last_line.contains(&format!("difference 1 of {}", columns.len())),
+                "{}",
+                last_line
+            );
+
+            key_handling::handle_key_press(ui, utils::keypress('n'));
+            assert_eq!(ui.leftmost_col() as usize, columns[1]);
+            key_handling::handle_key_press(ui, utils::keypress('N'));
+            key_handling::handle_key_press(ui, utils::keypress('N'));
+            assert_eq!(ui.leftmost_col() as usize, columns[columns.len() - 1]);
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(last_line.contains("(wrapped)"), "{}", last_line);
+
+            utils::ex_command(ui, "diff");
+            assert!(ui.diff_columns().is_none());
+        },
+    );
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    alignment::Alignment,
+    app::App,
+    ui::{key_handling, render, UI},
+};
+use ratatui::{backend::TestBackend, Terminal};
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 24;
+
+// Renders (with a warning in the modeline), then tries zooming, scrolling and jumping; none of this may panic.
+fn exercise(headers: Vec<String>, sequences: Vec<String>) {
+    let mut app = App::new("TEST", Alignment::from_vecs(headers, sequences), None);
+    let mut ui = UI::new(&mut app);
+    let mut terminal = Terminal::new(TestBackend::new(SCREEN_WIDTH, SCREEN_HEIGHT)).unwrap();
+    terminal.draw(|f| render::render_ui(f, &mut ui)).unwrap();
+    let buffer = terminal.backend().buffer();
+    assert!(utils::buffer_text(buffer).contains("Empty alignment"));
+
+    for key in [
+        'z', 'z', 'z', 'j', 'l', 'G', '$', 'g', '^', '5', '0', '%', '5', '0', '#',
+    ] {
+        key_handling::handle_key_press(&mut ui, utils::keypress(key));
+        terminal.draw(|f| render::render_ui(f, &mut ui)).unwrap();
+    }
+    assert_eq!((0, 0), (ui.top_line(), ui.leftmost_col()));
+    assert_eq!(1.0, ui.h_ratio());
+    assert_eq!(1.0, ui.v_ratio());
+}
+
+#[test]
+fn test_alignment_of_empty_sequences() {
+    exercise(
+        vec![String::from("s1"), String::from("s2")],
+        vec![String::new(), String::new()],
+    );
+}
+
+#[test]
+fn test_alignment_without_sequences() {
+    exercise(Vec::new(), Vec::new());
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    alignment::is_gap,
+    seq::fasta,
+    ui::{key_handling, render},
+};
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 30;
+
+// Residues of `seq` up to column `col` included.
+fn residues_through(seq: &str, col: usize) -> usize {
+    seq.chars().take(col + 1).filter(|c| !is_gap(*c)).count()
+}
+
+#[test]
+/// `;` shows each sequence's coordinate at the rightmost column shown, which follows scrolling.
+fn test_end_coords() {
+    let first_seq = fasta::read_fasta_file("tests/data/test-motion.msa")
+        .expect("read")
+        .into_iter()
+        .next()
+        .expect("first record")
+        .sequence;
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            let cols_before = ui.visible_cols();
+            key_handling::handle_key_press(ui, utils::keypress(';'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            // The alignment has 1054 columns: the gutter takes a space and four digits.
+            assert_eq!(ui.visible_cols(), cols_before - 5);
+
+            for _ in 0..3 {
+                let right = (ui.leftmost_col() + ui.visible_cols() - 1) as usize;
+                let expected = residues_through(&first_seq, right);
+                let line = utils::screen_line(terminal.backend().buffer(), 1);
+                let gutter: String = line.chars().rev().skip(1).take(5).collect();
+                let gutter: String = gutter.chars().rev().collect();
+                assert_eq!(gutter, format!("{:>5}", expected), "{}", line);
+
+                // Scrolls right by a screenful.
+                key_handling::handle_key_press(ui, utils::keypress('L'));
+                terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            }
+
+            // At the end, the number is the sequence's length.
+            key_handling::handle_key_press(ui, utils::keypress('$'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let line = utils::screen_line(terminal.backend().buffer(), 1);
+            let total = residues_through(&first_seq, usize::MAX - 1);
+            assert!(line.contains(&format!(" {}", total)), "{}", line);
+
+            key_handling::handle_key_press(ui, utils::keypress(';'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            assert_eq!(ui.visible_cols(), cols_before);
+        },
+    );
+}
+
+#[test]
+/// With the gutter shown, pinned rows stop where the main pane's residues do, so that their
+/// columns line up.
+fn test_end_coords_pinned_rows() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            key_handling::handle_key_press(ui, utils::keypress(';'));
+            key_handling::handle_key_press(ui, utils::keypress('.'));
+            utils::ex_command(ui, "pin");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("Pinned (1)"), "{}", screen);
+            let line = utils::screen_line(terminal.backend().buffer(), 1);
+            let gutter: String = line.chars().rev().skip(1).take(5).collect();
+            assert_eq!(gutter, "     ", "{}", line);
+            let last_residue = line.chars().rev().nth(6).expect("residue");
+            assert_ne!(last_residue, ' ', "{}", line);
+        },
+    );
+}
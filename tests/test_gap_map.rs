@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+use ratatui::buffer::Buffer;
+
+// Text of the gap map row, i.e. the last row inside the bottom pane, between its borders.
+fn gap_map_row(buffer: &Buffer) -> String {
+    let bottom_border = (0..buffer.area.height)
+        .rev()
+        .find(|&row| utils::screen_line(buffer, row).starts_with('└'))
+        .expect("no lower border");
+    let line = utils::screen_line(buffer, bottom_border - 1);
+    let line = line.trim_end().trim_end_matches('│');
+    let (_, inner) = line.rsplit_once('│').expect("no left border");
+    inner.to_string()
+}
+
+#[test]
+/// The first sequence has residues in its first and last thirds and gaps in between, the second
+/// none: the gap map follows the cursor, and `*` adds and removes its row.
+fn test_gap_map_follows_cursor() {
+    utils::with_rig("tests/data/gap-map.fas", 80, 20, |ui, terminal| {
+        utils::ex_command(ui, "n 1");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let without = gap_map_row(terminal.backend().buffer());
+
+        key_handling::handle_key_press(ui, utils::keypress('*'));
+        assert!(ui.show_gap_map());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let row = gap_map_row(terminal.backend().buffer());
+        let width = row.chars().count();
+        let third = width / 3;
+        assert!(row.chars().take(third - 1).all(|c| c == '█'));
+        assert!(row
+            .chars()
+            .skip(third + 1)
+            .take(third - 2)
+            .all(|c| c == ' '));
+        assert!(row.chars().skip(2 * third + 2).all(|c| c == '█'));
+
+        utils::ex_command(ui, "n 2");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let row = gap_map_row(terminal.backend().buffer());
+        assert!(row.chars().all(|c| c == '█'));
+
+        key_handling::handle_key_press(ui, utils::keypress('*'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(without, gap_map_row(terminal.backend().buffer()));
+    });
+}
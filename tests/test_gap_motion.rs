@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use crossterm::event::KeyCode;
+use msafara::ui::key_handling;
+
+#[test]
+/// s1 has gap runs at columns 11-15 and 76-78 (1-based); `)` and `(` scroll to their starts and to
+/// the residues after them, wrapping around, and move the guide instead when it is shown.
+fn test_gap_motions_follow_cursor_sequence() {
+    utils::with_rig("tests/data/gap-runs.fas", 80, 20, |ui, _terminal| {
+        utils::ex_command(ui, "n 1");
+        utils::type_keys(ui, ")");
+        assert_eq!(ui.leftmost_col(), 10);
+        utils::type_keys(ui, ")");
+        let second = 75.min(ui.max_leftmost_col());
+        assert_eq!(ui.leftmost_col(), second);
+        // Continues from the gap run even if the view could not be scrolled to it, and wraps.
+        utils::type_keys(ui, ")");
+        assert_eq!(ui.leftmost_col(), 10);
+        utils::type_keys(ui, "(");
+        assert_eq!(ui.leftmost_col(), 15);
+        utils::type_keys(ui, "2(");
+        assert_eq!(ui.leftmost_col(), 15);
+
+        utils::type_keys(ui, "^V");
+        let guide = ui.guide_col().expect("no guide");
+        assert!((15..75).contains(&guide));
+        utils::type_keys(ui, ")");
+        assert_eq!(ui.guide_col(), Some(75));
+        utils::type_keys(ui, "(");
+        assert_eq!(ui.guide_col(), Some(78));
+        utils::type_keys(ui, "(");
+        assert_eq!(ui.guide_col(), Some(15));
+        key_handling::handle_key_press(ui, KeyCode::Esc.into());
+
+        // s2 has no gaps: nothing moves.
+        utils::ex_command(ui, "n 2");
+        utils::type_keys(ui, "^");
+        utils::type_keys(ui, ")(");
+        assert_eq!(ui.leftmost_col(), 0);
+    });
+}
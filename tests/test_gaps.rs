@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use ratatui::style::Color;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+#[test]
+/// Gaps use the scheme's dim gap style, and `D` draws them as middle dots.
+fn test_gap_style_and_dots() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 40, |ui, terminal| {
+        // Monochrome is the default; switch to the dark scheme.
+        key_handling::handle_key_press(ui, utils::keypress('s'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let gap_cell = buffer
+            .content()
+            .iter()
+            .find(|cell| cell.symbol() == "-" && cell.bg == Color::Black)
+            .expect("a gap in the alignment pane");
+        assert_eq!(gap_cell.fg, Color::DarkGray);
+
+        key_handling::handle_key_press(ui, utils::keypress('D'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains('·'), "{}", screen);
+        key_handling::handle_key_press(ui, utils::keypress('D'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(!screen.contains('·'), "{}", screen);
+    });
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use crossterm::event::KeyCode;
+use msafara::ui::{key_handling, render};
+use ratatui::{buffer::Buffer, style::Color};
+
+const GUIDE_BG: Color = Color::Indexed(238);
+
+// Screen x coordinates of the cells drawn with the guide's background.
+fn guide_xs(buffer: &Buffer) -> Vec<u16> {
+    let mut xs: Vec<u16> = buffer
+        .content
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell.bg == GUIDE_BG)
+        .map(|(i, _)| buffer.pos_of(i).0)
+        .collect();
+    xs.sort_unstable();
+    xs.dedup();
+    xs
+}
+
+#[test]
+/// V shows the guide as a single highlighted screen column, '.' / ',' move it (with a count) and
+/// Esc hides it.
+fn test_guide_toggle_and_move() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 30, |ui, terminal| {
+        assert!(ui.guide_col().is_none());
+        utils::type_keys(ui, "V");
+        let col = ui.guide_col().expect("guide shown");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let xs = guide_xs(terminal.backend().buffer());
+        assert_eq!(1, xs.len());
+
+        utils::type_keys(ui, "3.");
+        assert_eq!(Some(col + 3), ui.guide_col());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(vec![xs[0] + 3], guide_xs(terminal.backend().buffer()));
+
+        utils::type_keys(ui, ",");
+        assert_eq!(Some(col + 2), ui.guide_col());
+
+        key_handling::handle_key_press(ui, KeyCode::Esc.into());
+        assert!(ui.guide_col().is_none());
+    });
+}
+
+#[test]
+/// Moving the guide past the right edge of the pane scrolls the view along, and the guide stops
+/// at the last column.
+fn test_guide_scrolls_into_view() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 30, |ui, _terminal| {
+        utils::type_keys(ui, "V");
+        utils::type_keys(ui, "5000.");
+        let last = ui.max_leftmost_col() as usize + ui.visible_cols() as usize - 1;
+        assert_eq!(Some(last), ui.guide_col());
+        assert_eq!(ui.max_leftmost_col(), ui.leftmost_col());
+        ui.assert_invariants();
+    });
+}
+
+#[test]
+/// Zoomed out, the guide is drawn on one of the retained columns.
+fn test_guide_zoomed_out() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 30, |ui, terminal| {
+        utils::type_keys(ui, "Vz");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(1, guide_xs(terminal.backend().buffer()).len());
+    });
+}
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 40;
+
+#[test]
+/// Up/Down in the command prompt recall earlier commands, then the text being typed.
+fn test_command_history_recall() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            utils::ex_command(ui, "hg");
+            utils::ex_command(ui, "cm");
+
+            utils::type_keys(ui, ":wr");
+            key_handling::handle_key_press(ui, KeyCode::Up.into());
+            assert_eq!(ui.command_text(), "cm");
+            key_handling::handle_key_press(ui, KeyCode::Up.into());
+            assert_eq!(ui.command_text(), "hg");
+            // Already at the oldest entry
+            key_handling::handle_key_press(ui, KeyCode::Up.into());
+            assert_eq!(ui.command_text(), "hg");
+            key_handling::handle_key_press(ui, KeyCode::Down.into());
+            assert_eq!(ui.command_text(), "cm");
+            key_handling::handle_key_press(ui, KeyCode::Down.into());
+            assert_eq!(ui.command_text(), "wr");
+
+            key_handling::handle_key_press(ui, KeyCode::Up.into());
+            key_handling::handle_key_press(ui, KeyCode::Up.into());
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            // `:hg` again: all-gap sequences shown again.
+            assert!(
+                last_line.contains("Showing all-gap sequences"),
+                "{}",
+                last_line
+            );
+        },
+    );
+}
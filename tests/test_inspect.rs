@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+#[test]
+/// Enter shows the cursor sequence's full header and ungapped length in a popup; any key closes
+/// it.
+fn test_inspect_popup() {
+    let fasta = std::fs::read_to_string("tests/data/test-motion.msa").expect("read");
+    let mut records = fasta.split('>').skip(1);
+    let first = records.next().expect("record");
+    let (header_line, seq) = first.split_once('\n').expect("header");
+    // The header is the record's ID, without its description.
+    let header = header_line.split_whitespace().next().expect("ID");
+    let length = seq.chars().filter(|c| c.is_ascii_alphabetic()).count();
+
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        utils::ex_command(ui, "n 1");
+        key_handling::handle_key_press(ui, KeyCode::Enter.into());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains(header), "{}", screen);
+        assert!(
+            screen.contains(&format!("Length: {} (ungapped)", length)),
+            "{}",
+            screen
+        );
+
+        key_handling::handle_key_press(ui, utils::keypress('j'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(!screen.contains("(ungapped)"), "{}", screen);
+        assert_eq!(ui.cursor_rank(), Some(0));
+    });
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+// Sequence 150 of tests/data/test-motion.msa is the only one whose header contains "Q88D".
+const RANK: u16 = 149;
+
+#[test]
+/// Typing a label search pattern scrolls to its first match before Enter; the match shows on
+/// screen, and the selection is left alone until Enter.
+fn test_label_search_preview() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        assert_eq!(ui.top_line(), 0);
+        utils::type_keys(ui, "\"Q88D");
+        assert!(ui.top_line() > 0);
+        assert!(ui.top_line() <= RANK);
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains(&format!("│{}│", RANK + 1)), "{}", screen);
+        assert_eq!(ui.selection_len(), 0);
+
+        key_handling::handle_key_press(ui, KeyCode::Enter.into());
+        assert!(ui.top_line() <= RANK);
+        assert_eq!(ui.selection_len(), 1);
+        assert_eq!(ui.cursor_rank(), Some(RANK as usize));
+    });
+}
+
+#[test]
+/// Esc scrolls back to where the label search started; a pattern matching nothing (or not yet a
+/// valid regex) shows that position too.
+fn test_label_search_preview_canceled() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, _terminal| {
+        utils::type_keys(ui, "\"Q88D");
+        assert!(ui.top_line() > 0);
+        utils::type_keys(ui, "(");
+        assert_eq!(ui.top_line(), 0);
+        key_handling::handle_key_press(ui, KeyCode::Backspace.into());
+        assert!(ui.top_line() > 0);
+        key_handling::handle_key_press(ui, KeyCode::Esc.into());
+        assert_eq!(ui.top_line(), 0);
+        assert_eq!(ui.selection_len(), 0);
+    });
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    alignment::Alignment,
+    app::App,
+    ui::{key_handling, render, UI},
+};
+use ratatui::{backend::TestBackend, Terminal};
+
+#[test]
+/// Alignments wider than u16::MAX columns are displayed up to that limit, with a warning, instead
+/// of panicking.
+fn test_70000_columns() {
+    let headers = vec![String::from("s1"), String::from("s2")];
+    let sequences = vec!["ACGT".repeat(17_500), "AC-T".repeat(17_500)];
+    let mut app = App::new("TEST", Alignment::from_vecs(headers, sequences), None);
+    assert_eq!(app.aln_len_usize(), 70_000);
+    assert_eq!(app.aln_len(), u16::MAX);
+
+    let mut ui = UI::new(&mut app);
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| render::render_ui(f, &mut ui)).unwrap();
+    let screen = utils::buffer_text(terminal.backend().buffer());
+    assert!(screen.contains("/70000c"));
+    assert!(screen.contains("only the first 65535"));
+
+    for key in ['$', 'z', 'z', 'z', '^'] {
+        key_handling::handle_key_press(&mut ui, utils::keypress(key));
+        terminal.draw(|f| render::render_ui(f, &mut ui)).unwrap();
+    }
+}
@@ -355,3 +355,101 @@ fn test_label_search_malformed() {
         },
     );
 }
+
+#[test]
+/// Tests jumping to the Nth header match with a count (PendingCount followed by n).
+fn test_label_search_goto_nth_match() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            let last_line_y = SCREEN_HEIGHT - 1;
+            utils::type_keys(ui, "\"KFJ");
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+
+            key_handling::handle_key_press(ui, utils::keypress('3'));
+            key_handling::handle_key_press(ui, utils::keypress('n'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(
+                last_line.contains("match #3/8"),
+                "\"match #3/8\" not found on last line: {}",
+                last_line
+            );
+            let third = ui.cursor_rank();
+
+            // Counts past the last match are clamped.
+            key_handling::handle_key_press(ui, utils::keypress('9'));
+            key_handling::handle_key_press(ui, utils::keypress('9'));
+            key_handling::handle_key_press(ui, utils::keypress('n'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(
+                last_line.contains("match #8/8"),
+                "\"match #8/8\" not found on last line: {}",
+                last_line
+            );
+            assert_ne!(third, ui.cursor_rank());
+        },
+    );
+}
+
+#[test]
+/// Tests jumping to the Nth sequence match with a count (PendingCount followed by ]).
+fn test_seq_search_goto_nth_match() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            let last_line_y = SCREEN_HEIGHT - 1;
+            utils::type_keys(ui, "/MSTT");
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+
+            key_handling::handle_key_press(ui, utils::keypress('1'));
+            key_handling::handle_key_press(ui, utils::keypress('2'));
+            key_handling::handle_key_press(ui, utils::keypress(']'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(
+                last_line.contains("match 12 of 13"),
+                "\"match 12 of 13\" not found on last line: {}",
+                last_line
+            );
+        },
+    );
+}
+
+#[test]
+/// Tests that cycling past the last sequence match is signalled in the modeline.
+fn test_seq_search_wrap_indicator() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            let last_line_y = SCREEN_HEIGHT - 1;
+            utils::type_keys(ui, "/MSTT");
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+
+            // 12 steps reach the last of the 13 matches without wrapping...
+            for _ in 0..12 {
+                key_handling::handle_key_press(ui, utils::keypress(']'));
+            }
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(last_line.contains("match 13 of 13 |"), "{}", last_line);
+
+            // ...and one more wraps around to the first.
+            key_handling::handle_key_press(ui, utils::keypress(']'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(
+                last_line.contains("match 1 of 13 (wrapped)"),
+                "\"match 1 of 13 (wrapped)\" not found on last line: {}",
+                last_line
+            );
+        },
+    );
+}
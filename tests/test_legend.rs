@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    app::StartupScheme,
+    ui::{key_handling, render},
+};
+use ratatui::{buffer::Buffer, style::Color};
+
+// Position of the first cell holding `text`, searching rows top to bottom.
+fn find(buffer: &Buffer, text: &str) -> Option<(u16, u16)> {
+    (0..buffer.area.height).find_map(|y| {
+        let line = utils::screen_line(buffer, y);
+        line.find(text)
+            .map(|byte| (line[..byte].chars().count() as u16, y))
+    })
+}
+
+#[test]
+/// E shows a legend of the nucleotide colormap (A, C, G, T, U, then gaps), each residue after a
+/// swatch of its color.
+fn test_legend_nucleotides() {
+    utils::with_rig("data/test2.fas", 80, 30, |ui, terminal| {
+        ui.set_color_scheme(StartupScheme::Dark);
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(find(terminal.backend().buffer(), "- gap").is_none());
+
+        key_handling::handle_key_press(ui, utils::keypress('E'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let (gap_x, gap_y) = find(buffer, "- gap").expect("legend");
+        let (x, y) = (gap_x - 3, gap_y - 5);
+        assert_eq!(Color::Rgb(0x64, 0xf7, 0x3f), buffer[(x, y)].bg);
+        assert_eq!(Color::Rgb(0x64, 0xf7, 0x3f), buffer[(x + 1, y)].bg);
+        assert_eq!("A", buffer[(x + 3, y)].symbol());
+        assert_eq!("U", buffer[(x + 3, y + 4)].symbol());
+
+        key_handling::handle_key_press(ui, utils::keypress('E'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(find(terminal.backend().buffer(), "- gap").is_none());
+    });
+}
+
+#[test]
+/// In the monochrome theme the legend only lists the residues.
+fn test_legend_monochrome() {
+    utils::with_rig("data/test2.fas", 80, 30, |ui, terminal| {
+        ui.set_color_scheme(StartupScheme::Monochrome);
+        key_handling::handle_key_press(ui, utils::keypress('E'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let (gap_x, gap_y) = find(buffer, "- gap").expect("legend");
+        let line: String = utils::screen_line(buffer, gap_y - 5)
+            .chars()
+            .skip(gap_x as usize - 1)
+            .take(2)
+            .collect();
+        assert_eq!(" A", line);
+    });
+}
+
+#[test]
+/// For proteins, residues that share a color are listed together.
+fn test_legend_protein_groups() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 40, |ui, terminal| {
+        ui.set_color_scheme(StartupScheme::Dark);
+        key_handling::handle_key_press(ui, utils::keypress('E'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let text = utils::buffer_text(terminal.backend().buffer());
+        // ClustalX colors K and R alike.
+        assert!(text.contains("- gap"));
+        assert!(text.contains("KR"));
+    });
+}
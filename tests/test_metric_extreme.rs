@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+#[test]
+/// `:min` (or Ctrl-B) puts the cursor on the sequence with the lowest %id to the consensus, `:max`
+/// (or Ctrl-T) on the one with the highest, whatever the ordering.
+fn test_jump_to_metric_extreme() {
+    utils::with_rig("tests/data/outlier.fas", 100, 20, |ui, terminal| {
+        utils::ex_command(ui, "min");
+        assert_eq!(Some(3), ui.cursor_rank());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Lowest %id (cons)"), "{}", screen);
+        assert!(screen.contains("sequence 4 (outlier)"), "{}", screen);
+
+        key_handling::handle_key_press(ui, utils::keypress('o'));
+        utils::ex_command(ui, "max");
+        assert_eq!(Some(0), ui.cursor_rank());
+        utils::ex_command(ui, "min");
+        assert_eq!(Some(3), ui.cursor_rank());
+
+        key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('t')));
+        assert_eq!(Some(0), ui.cursor_rank());
+        key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('b')));
+        assert_eq!(Some(3), ui.cursor_rank());
+        ui.assert_invariants();
+    });
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+#[test]
+/// With no message, the modeline shows the alignment's composition.
+fn test_composition_summary() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            let last_line_y = SCREEN_HEIGHT - 1;
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(!last_line.contains("seqs |"), "{}", last_line);
+
+            key_handling::handle_key_press(ui, KeyCode::Esc.into());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), last_line_y);
+            assert!(
+                last_line.contains("protein | 226 seqs | 1054 cols | 46% gaps"),
+                "composition summary not found on last line: {}",
+                last_line
+            );
+        },
+    );
+}
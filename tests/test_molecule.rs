@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    alignment::{Alignment, SeqType},
+    app::{App, StartupScheme},
+    ui::UI,
+};
+
+// Short peptides made only of A, C, G and T: detected as nucleic.
+fn ambiguous_app() -> App {
+    let hdrs = vec![String::from("p1"), String::from("p2")];
+    let seqs = vec![String::from("GATTACA"), String::from("CAT-ACA")];
+    App::new("TEST", Alignment::from_vecs(hdrs, seqs), None)
+}
+
+#[test]
+/// `:mol protein` on an ambiguous alignment selects the protein colormap in the color schemes,
+/// and the modeline reports the forced type.
+fn test_forced_protein_selects_protein_colormap() {
+    let mut app = ambiguous_app();
+    let mut ui = UI::new(&mut app);
+    ui.set_color_scheme(StartupScheme::Dark);
+    assert!(ui.composition_summary().starts_with("nucleic |"));
+    assert_eq!(
+        "JalView-nt",
+        ui.color_scheme().current_residue_colormap().name
+    );
+
+    utils::ex_command(&mut ui, "mol protein");
+    assert_eq!(
+        "ClustalX",
+        ui.color_scheme().current_residue_colormap().name
+    );
+    ui.set_color_scheme(StartupScheme::Light);
+    assert_eq!(
+        "ClustalX",
+        ui.color_scheme().current_residue_colormap().name
+    );
+    assert!(ui.composition_summary().starts_with("protein (forced)"));
+}
+
+#[test]
+/// A type forced before the UI is created (as with --molecule) picks the initial colormap.
+fn test_forced_type_before_ui() {
+    let mut app = ambiguous_app();
+    app.force_molecule_type(SeqType::Protein);
+    let mut ui = UI::new(&mut app);
+    ui.set_color_scheme(StartupScheme::Dark);
+    assert_eq!(
+        "ClustalX",
+        ui.color_scheme().current_residue_colormap().name
+    );
+}
+
+#[test]
+/// The RNA colormap, the default for RNA, gives U a color of its own.
+fn test_forced_rna_selects_rna_colormap() {
+    let mut app = ambiguous_app();
+    app.force_molecule_type(SeqType::Rna);
+    let mut ui = UI::new(&mut app);
+    ui.set_color_scheme(StartupScheme::Dark);
+    let colormap = ui.color_scheme().current_residue_colormap();
+    assert_eq!("RNA", colormap.name);
+    assert_ne!(colormap.get('T'), colormap.get('U'));
+}
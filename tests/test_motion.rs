@@ -35,3 +35,62 @@ fn g_moves_to_top() {
         assert_eq!(0, ui.top_line());
     });
 }
+
+#[test]
+fn cap_c_centers_cursor_line() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 50, |ui, _terminal| {
+        let half = ui.visible_seq_rows() / 2;
+        key_handling::handle_key_press(ui, utils::keypress('.'));
+        utils::type_keys(ui, "40n");
+        assert_eq!(Some(40), ui.cursor_rank());
+        key_handling::handle_key_press(ui, utils::keypress('C'));
+        assert_eq!(40 - half, ui.top_line());
+        // Clamped at the top
+        key_handling::handle_key_press(ui, utils::keypress('g'));
+        utils::type_keys(ui, "40p");
+        key_handling::handle_key_press(ui, utils::keypress('C'));
+        assert_eq!(0, ui.top_line());
+    });
+}
+
+#[test]
+fn jumps_stay_in_range_on_one_column_alignment() {
+    utils::with_rig("tests/data/one-column.fas", 80, 30, |ui, _terminal| {
+        ui.jump_to_col(0);
+        assert_eq!(0, ui.leftmost_col());
+        for key in ['1', '|', '1', '0', '0', '%', '1', '0', '0', '#'] {
+            key_handling::handle_key_press(ui, utils::keypress(key));
+        }
+        assert_eq!(0, ui.top_line());
+        assert_eq!(0, ui.leftmost_col());
+        ui.assert_invariants();
+    });
+}
+
+#[test]
+fn pct_jumps_clamp_to_last_screenful() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 30, |ui, _terminal| {
+        for key in ['1', '0', '0', '%', '1', '0', '0', '#'] {
+            key_handling::handle_key_press(ui, utils::keypress(key));
+        }
+        assert_eq!(ui.max_top_line(), ui.top_line());
+        assert_eq!(ui.max_leftmost_col(), ui.leftmost_col());
+        ui.assert_invariants();
+    });
+}
+
+#[test]
+fn ampersand_puts_cursor_at_pct_line_and_centers_it() {
+    // test-motion.msa has 226 sequences.
+    utils::with_rig("tests/data/test-motion.msa", 80, 50, |ui, _terminal| {
+        let half = ui.visible_seq_rows() / 2;
+        utils::type_keys(ui, "50&");
+        assert_eq!(Some(113), ui.cursor_rank());
+        assert_eq!(113 - half, ui.top_line());
+        // Clamped to the last sequence
+        utils::type_keys(ui, "250&");
+        assert_eq!(Some(225), ui.cursor_rank());
+        assert_eq!(ui.max_top_line(), ui.top_line());
+        ui.assert_invariants();
+    });
+}
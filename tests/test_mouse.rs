@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 40;
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+#[test]
+/// The wheel scrolls by lines; a left click puts the cursor on the clicked sequence.
+fn test_mouse_scroll_and_click() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            key_handling::handle_mouse(ui, mouse(MouseEventKind::ScrollDown, 50, 10));
+            key_handling::handle_mouse(ui, mouse(MouseEventKind::ScrollDown, 50, 10));
+            assert_eq!(ui.top_line(), 2);
+            key_handling::handle_mouse(ui, mouse(MouseEventKind::ScrollUp, 50, 10));
+            assert_eq!(ui.top_line(), 1);
+
+            // The alignment pane starts at column 18 (after the left pane), row 0; its first
+            // residue is at (19, 1). Row 3 shows display line 1 + 2 = 3, i.e. sequence #4.
+            key_handling::handle_mouse(ui, mouse(MouseEventKind::Down(MouseButton::Left), 25, 3));
+            assert_eq!(ui.cursor_rank(), Some(3));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(last_line.contains("Sequence 4"), "{}", last_line);
+            assert!(last_line.contains("column 7"), "{}", last_line);
+
+            // Clicks on the left pane are ignored.
+            key_handling::handle_mouse(ui, mouse(MouseEventKind::Down(MouseButton::Left), 5, 5));
+            assert_eq!(ui.cursor_rank(), Some(3));
+        },
+    );
+}
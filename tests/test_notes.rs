@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 40;
+
+#[test]
+/// View notes are saved on Esc (the modeline then shows [N]) and discarded on Ctrl-C.
+fn test_view_notes_editor() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            key_handling::handle_key_press(ui, KeyCode::Esc.into());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(!last_line.contains("[N]"), "{}", last_line);
+
+            key_handling::handle_key_press(ui, utils::keypress('|'));
+            utils::type_keys(ui, "hello");
+            key_handling::handle_key_press(ui, KeyCode::Esc.into());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(last_line.contains("[N]"), "{}", last_line);
+
+            key_handling::handle_key_press(ui, utils::keypress('|'));
+            utils::type_keys(ui, " world");
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('c')));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(last_line.contains("Notes not saved"), "{}", last_line);
+
+            // Reopening shows the saved text only.
+            key_handling::handle_key_press(ui, utils::keypress('|'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("hello"), "{}", screen);
+            assert!(!screen.contains("world"), "{}", screen);
+        },
+    );
+}
+
+#[test]
+/// A line longer than the notes overlay wraps at word boundaries instead of running off its edge.
+fn test_notes_editor_wraps_long_lines() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            key_handling::handle_key_press(ui, utils::keypress('@'));
+            let text = "alpha beta gamma delta epsilon zeta eta theta iota kappa lambda";
+            utils::type_keys(ui, text);
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("alpha beta gamma"), "{}", screen);
+            assert!(screen.contains("kappa lambda"), "{}", screen);
+            let rows = screen.lines().filter(|l| l.contains("alpha beta")).count()
+                + screen
+                    .lines()
+                    .filter(|l| l.contains("kappa lambda"))
+                    .count();
+            assert_eq!(rows, 2, "{}", screen);
+
+            // The overlay is 40 wide: "theta iota kappa lambda" (23 characters) is the second
+            // visual line, and Up from its end lands on column 23 of the first one.
+            key_handling::handle_key_press(ui, KeyCode::Up.into());
+            key_handling::handle_key_press(ui, utils::keypress('>'));
+            key_handling::handle_key_press(ui, KeyCode::Esc.into());
+            key_handling::handle_key_press(ui, utils::keypress('@'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("gamma delta >epsilon"), "{}", screen);
+        },
+    );
+}
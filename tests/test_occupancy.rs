@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::render;
+use ratatui::style::Color;
+
+const SPARSE_BG: Color = Color::Indexed(235);
+
+#[test]
+/// Column occupancies are 1, 0.5, 0, 0.75, 0.25 and 1: `:occupancy 0.5` dims the third and fifth
+/// columns, and `:occupancy off` stops.
+fn test_occupancy_marks_sparse_columns() {
+    utils::with_rig("tests/data/sparse-columns.fas", 80, 20, |ui, terminal| {
+        utils::ex_command(ui, "occupancy 0.5");
+        assert_eq!(Some(0.5), ui.min_occupancy());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let line = utils::screen_line(buffer, 1);
+        let x = line[..line.find("AC-G-T").expect("first sequence")]
+            .chars()
+            .count() as u16;
+        let sparse: Vec<bool> = (0..6).map(|c| buffer[(x + c, 1)].bg == SPARSE_BG).collect();
+        assert_eq!(vec![false, false, true, false, true, false], sparse);
+
+        utils::ex_command(ui, "occupancy off");
+        assert_eq!(None, ui.min_occupancy());
+    });
+}
+
+#[test]
+/// `:trim 0.5` removes the columns below half occupancy and the metrics follow; `:trim` alone only
+/// removes all-gap columns.
+fn test_trim_by_occupancy() {
+    utils::with_rig("tests/data/sparse-columns.fas", 80, 20, |ui, terminal| {
+        utils::ex_command(ui, "trim");
+        assert!(ui.composition_summary().contains("| 5 cols |"));
+
+        utils::ex_command(ui, "trim 0.5");
+        assert!(ui.composition_summary().contains("| 4 cols |"));
+        assert!(ui.composition_summary().ends_with("| 19% gaps"));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(utils::screen_line(terminal.backend().buffer(), 1).contains("ACGT"));
+        ui.assert_invariants();
+    });
+}
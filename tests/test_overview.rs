@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 30;
+
+#[test]
+/// `:overview` maps the whole alignment onto the alignment pane; Enter on a cell jumps the
+/// zoomed-in view there.
+fn test_overview() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            utils::ex_command(ui, "overview");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains(" Overview "), "{}", screen);
+            assert!(screen.contains('█'), "{}", screen);
+
+            // 226 sequences and 1054 columns over the pane's cells.
+            let (rows, cols) = ui.overview_size();
+            assert!(rows > 0 && cols > 0);
+            utils::type_keys(ui, "jjjlllll");
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(!screen.contains(" Overview "), "{}", screen);
+            assert_eq!(ui.cursor_rank(), Some(3 * 226 / rows));
+            assert_eq!(ui.leftmost_col() as usize, 5 * 1054 / cols);
+        },
+    );
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+#[test]
+/// `:pin` moves the cursor row into a sub-pane above the scrolling rows, which shrink accordingly.
+fn test_pin_cursor_row() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let rows = ui.visible_seq_rows();
+            let num_seq = ui.num_visible_sequences();
+
+            key_handling::handle_key_press(ui, utils::keypress('.'));
+            utils::ex_command(ui, "pin");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("Pinned (1)"), "{}", screen);
+            // One pinned row plus the sub-pane's two borders.
+            assert_eq!(ui.visible_seq_rows(), rows - 3);
+            assert_eq!(ui.num_visible_sequences(), num_seq - 1);
+
+            utils::ex_command(ui, "pin");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(!screen.contains("Pinned ("), "{}", screen);
+            assert_eq!(ui.visible_seq_rows(), rows);
+            assert_eq!(ui.num_visible_sequences(), num_seq);
+        },
+    );
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{app::StartupScheme, ui::render};
+
+#[test]
+/// `:export print` writes the alignment in black and white, whatever the scheme: the fully
+/// conserved block of Ws (columns 41-50) is in bold, and residues identical to the consensus are
+/// dots.
+fn test_export_print() {
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.print.html", std::process::id()));
+    utils::with_rig(
+        "tests/data/conserved-blocks.fas",
+        100,
+        20,
+        |ui, terminal| {
+            ui.set_color_scheme(StartupScheme::Dark);
+            utils::ex_command(ui, &format!("export print {}", path.display()));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(
+                screen.contains("Wrote print-friendly alignment"),
+                "{}",
+                screen
+            );
+        },
+    );
+    let html = std::fs::read_to_string(&path).expect("print export");
+    let _ = std::fs::remove_file(&path);
+    // Two blocks of Ws, in the consensus and (as dots) in each of the four sequences.
+    assert_eq!(html.matches(&"<b>W</b>".repeat(10)).count(), 2, "{}", html);
+    assert_eq!(
+        html.matches(&"<b>.</b>".repeat(10)).count(),
+        4 * 2,
+        "{}",
+        html
+    );
+}
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+use msafara::{
+    alignment::Alignment,
+    buffer_to_text,
+    errors::TermalError,
+    render_to_buffer,
+    seq::{
+        file::{check_aligned, is_ragged},
+        stockholm::read_stockholm_file,
+    },
+    ui::ZoomLevel,
+    RenderOpts,
+};
+
+#[test]
+/// Ragged Stockholm input is refused, unless padding is asked for (`--pad`): the sequences are
+/// then padded to the longest one, and the alignment renders, zoomed out too.
+fn test_ragged_stockholm() {
+    let seq_file = read_stockholm_file("data/PF00571-jagged.sto").expect("read");
+    assert!(is_ragged(&seq_file));
+    match check_aligned(&seq_file, false) {
+        Err(TermalError::Format(msg)) => {
+            assert!(
+                msg.starts_with("Sequences have unequal length; not an alignment"),
+                "{}",
+                msg
+            )
+        }
+        _ => panic!("ragged input accepted"),
+    }
+    assert!(check_aligned(&seq_file, true).expect("padded"));
+
+    let max_len = seq_file.iter().map(|rec| rec.sequence.len()).max().unwrap();
+    let aln = Alignment::from_file(seq_file);
+    assert!(aln.sequences().iter().all(|seq| seq.len() == max_len));
+    let opts = RenderOpts {
+        zoom: ZoomLevel::ZoomedOut,
+        ..RenderOpts::default()
+    };
+    let text = buffer_to_text(&render_to_buffer(&aln, 40, 20, opts));
+    assert!(text.contains("O83071"), "{}", text);
+
+    let aligned = read_stockholm_file("data/PF00571.sto").expect("read");
+    assert!(!check_aligned(&aligned, false).expect("aligned"));
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+#[test]
+/// In read-only mode, rejecting the selected sequences (`!`, which in a custom view removes them
+/// from it) and inserting a gap column leave the alignment alone, with a warning.
+fn test_read_only() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        utils::ex_command(ui, "vc");
+        utils::type_keys(ui, "mine");
+        key_handling::handle_key_press(ui, KeyCode::Enter.into());
+        utils::ex_command(ui, "vs");
+        key_handling::handle_key_press(ui, KeyCode::Down.into());
+        key_handling::handle_key_press(ui, KeyCode::Enter.into());
+        ui.clear_unsaved_changes();
+        ui.set_read_only(true);
+        let num_seq = ui.num_sequences();
+        utils::ex_command(ui, "sn 1");
+        utils::type_keys(ui, "!");
+        assert_eq!(ui.num_sequences(), num_seq);
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Read-only mode"), "{}", screen);
+        assert!(screen.contains("view: mine"), "{}", screen);
+        assert!(screen.contains("[RO]"), "{}", screen);
+
+        utils::type_keys(ui, "Vi");
+        assert!(!ui.has_unsaved_changes());
+    });
+}
+
+#[test]
+/// In read-only mode, moving sequences to the rejected view (:mv) is refused too.
+fn test_read_only_move_to_rejected() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        utils::ex_command(ui, "rn 1");
+        ui.set_read_only(true);
+        let num_seq = ui.num_sequences();
+        utils::ex_command(ui, "mv 1");
+        utils::type_keys(ui, "j");
+        key_handling::handle_key_press(ui, KeyCode::Enter.into());
+        assert_eq!(ui.num_sequences(), num_seq);
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Read-only mode"), "{}", screen);
+    });
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, UI};
+
+const SCREEN_WIDTH: u16 = 120;
+const SCREEN_HEIGHT: u16 = 20;
+
+fn right(ui: &mut UI) {
+    key_handling::handle_key_press(ui, KeyCode::Right.into());
+}
+
+#[test]
+/// With `'`, Left/Right step through the residues of the cursor sequence (`A--C...`), skipping
+/// its gap columns.
+fn test_ref_scroll() {
+    utils::with_rig(
+        "tests/data/ref-scroll.fa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            utils::ex_command(ui, "n 1");
+            utils::type_keys(ui, "'");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("[ref-scroll]"), "{}", screen);
+
+            assert_eq!(ui.leftmost_col(), 0);
+            right(ui);
+            assert_eq!(ui.leftmost_col(), 3);
+            utils::type_keys(ui, "h");
+            assert_eq!(ui.leftmost_col(), 0);
+            utils::type_keys(ui, "2l");
+            assert_eq!(ui.leftmost_col(), 4);
+
+            // From a gap column, Left goes to the residue before it.
+            utils::type_keys(ui, "'4h2l'");
+            assert_eq!(ui.leftmost_col(), 2);
+            utils::type_keys(ui, "h");
+            assert_eq!(ui.leftmost_col(), 0);
+
+            // Off again: by columns.
+            utils::type_keys(ui, "'");
+            right(ui);
+            assert_eq!(ui.leftmost_col(), 1);
+        },
+    );
+}
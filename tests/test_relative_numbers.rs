@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+#[test]
+/// With `R`, the cursor line keeps its absolute number and the others show their distance to it.
+fn test_relative_line_numbers() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        key_handling::handle_key_press(ui, utils::keypress('R'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        // No cursor: absolute numbers.
+        let buffer = terminal.backend().buffer();
+        assert!(utils::screen_line(buffer, 1).starts_with("│  1│"));
+        assert!(utils::screen_line(buffer, 4).starts_with("│  4│"));
+
+        // Cursor on the third line.
+        key_handling::handle_key_press(ui, utils::keypress('.'));
+        key_handling::handle_key_press(ui, utils::keypress('2'));
+        key_handling::handle_key_press(ui, utils::keypress('n'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        assert!(utils::screen_line(buffer, 1).starts_with("│  2│"));
+        assert!(utils::screen_line(buffer, 3).starts_with("│  3│"));
+        assert!(utils::screen_line(buffer, 6).starts_with("│  3│"));
+
+        key_handling::handle_key_press(ui, utils::keypress('R'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        assert!(utils::screen_line(buffer, 6).starts_with("│  6│"));
+    });
+}
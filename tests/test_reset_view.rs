@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::{
+    app::SeqOrdering,
+    ui::{key_handling, render, ZoomLevel},
+};
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 40;
+
+#[test]
+/// Ctrl-R drops the search, the ordering, the zoom and the scrolling, but not the saved searches.
+fn test_reset_view() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            // Saves a search (which then stops being the current one), then runs another.
+            utils::search(ui, utils::keypress('/'), "MSTT");
+            key_handling::handle_key_press(ui, utils::keypress('P'));
+            key_handling::handle_key_press(ui, KeyCode::Enter.into());
+            utils::search(ui, utils::keypress('/'), "GMYIL");
+            assert!(ui.has_seq_search());
+
+            key_handling::handle_key_press(ui, utils::keypress('o'));
+            key_handling::handle_key_press(ui, utils::keypress('G'));
+            key_handling::handle_key_press(ui, utils::keypress('$'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            key_handling::handle_key_press(ui, utils::keypress('z'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            assert_ne!(ui.seq_ordering(), SeqOrdering::SourceFile);
+            assert_ne!(ui.zoom_level(), ZoomLevel::ZoomedIn);
+
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('r')));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            assert_eq!(ui.seq_ordering(), SeqOrdering::SourceFile);
+            assert!(!ui.has_seq_search());
+            assert_eq!(ui.zoom_level(), ZoomLevel::ZoomedIn);
+            assert_eq!(ui.top_line(), 0);
+            assert_eq!(ui.leftmost_col(), 0);
+            let last_line = utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1);
+            assert!(last_line.contains("View reset"), "{}", last_line);
+
+            key_handling::handle_key_press(ui, utils::keypress('F'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("MSTT"), "{}", screen);
+        },
+    );
+}
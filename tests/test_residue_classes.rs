@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    app::{ResidueClassMap, StartupScheme},
+    ui::color_map::{color_map_hydrophobicity, color_map_taylor_classes},
+};
+use ratatui::style::Color;
+
+#[test]
+/// Class colormaps give all the residues of a class (in either case) the same color.
+fn test_hydrophobic_class_colors() {
+    let cmap = color_map_hydrophobicity();
+    let hydrophobic = cmap.get('L');
+    assert_eq!(cmap.get('I'), hydrophobic);
+    assert_eq!(cmap.get('V'), hydrophobic);
+    assert_eq!(cmap.get('v'), hydrophobic);
+    assert_ne!(cmap.get('K'), hydrophobic);
+
+    let cmap = color_map_taylor_classes();
+    assert_eq!(cmap.get('L'), cmap.get('I'));
+    assert_eq!(cmap.get('D'), cmap.get('E'));
+    assert_ne!(cmap.get('D'), cmap.get('K'));
+}
+
+#[test]
+/// Class maps from the config are added to the colormaps without changing the current one, and
+/// the built-in class maps can be selected like the others.
+fn test_config_class_colormap() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 30, |ui, _terminal| {
+        ui.set_color_scheme(StartupScheme::Dark);
+        let current = ui
+            .color_scheme()
+            .current_residue_colormap()
+            .name()
+            .to_string();
+        ui.add_residue_class_colormap(&ResidueClassMap {
+            name: String::from("Charge"),
+            classes: vec![
+                (String::from("acidic"), String::from("DE"), (214, 39, 40)),
+                (String::from("basic"), String::from("KR"), (31, 119, 180)),
+            ],
+        });
+        assert_eq!(ui.color_scheme().current_residue_colormap().name(), current);
+
+        ui.select_colormap_by_name("Charge").expect("class map");
+        let cmap = ui.color_scheme().current_residue_colormap();
+        assert_eq!(cmap.get('e'), Color::Rgb(214, 39, 40));
+        assert_eq!(cmap.get('K'), cmap.get('R'));
+
+        ui.select_colormap_by_name("hydrophobicity")
+            .expect("built-in class map");
+        let cmap = ui.color_scheme().current_residue_colormap();
+        assert_eq!(cmap.get('L'), cmap.get('V'));
+    });
+}
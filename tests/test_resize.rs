@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, ZoomLevel};
+use ratatui::{buffer::Buffer, layout::Rect};
+
+// Top left corner of the zoom box, whatever its shape.
+const ZOOMBOX_CORNERS: [char; 4] = ['┌', '▯', '╿', '╾'];
+
+// Character at the zoom box's top left corner; the sequence pane's own corner is the last '┌' of
+// the first line.
+fn zoombox_corner(buffer: &Buffer, (top, left): (usize, usize)) -> char {
+    let first_line: Vec<char> = utils::screen_line(buffer, 0).chars().collect();
+    let pane_x = first_line.iter().rposition(|c| *c == '┌').unwrap();
+    let row: Vec<char> = utils::screen_line(buffer, 1 + top as u16).chars().collect();
+    row[pane_x + 1 + left]
+}
+
+#[test]
+/// Shrinking the terminal while zoomed out at the end of the alignment keeps the zoom box in the
+/// pane.
+fn test_zoombox_visible_after_shrink() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 50, |ui, terminal| {
+        utils::type_keys(ui, "zG$");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+
+        terminal.backend_mut().resize(30, 15);
+        terminal.resize(Rect::new(0, 0, 30, 15)).expect("resize");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let (top, bottom, left, right) = ui.zoombox_rect();
+        assert!(top < bottom && bottom <= ui.visible_seq_rows() as usize);
+        assert!(left < right && right <= ui.visible_cols() as usize);
+        let corner = zoombox_corner(terminal.backend().buffer(), (top, left));
+        assert!(
+            ZOOMBOX_CORNERS.contains(&corner),
+            "no zoom box at {:?}",
+            (top, left)
+        );
+    });
+}
+
+#[test]
+/// Growing the terminal until the alignment fits leaves zoomed-out mode.
+fn test_zoom_out_ends_when_alignment_fits() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 50, |ui, terminal| {
+        key_handling::handle_key_press(ui, utils::keypress('z'));
+        terminal.backend_mut().resize(1200, 300);
+        terminal.resize(Rect::new(0, 0, 1200, 300)).expect("resize");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert_eq!(ui.zoom_level(), ZoomLevel::ZoomedIn);
+    });
+}
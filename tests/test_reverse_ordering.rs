@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, UI};
+
+// Headers in the order they are shown, top to bottom.
+fn shown_headers(ui: &mut UI, terminal: &mut Terminal<TestBackend>) -> Vec<String> {
+    terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .filter_map(|y| {
+            let line = utils::screen_line(buffer, y);
+            (1..=5)
+                .map(|i| format!("s{}", i))
+                .find(|hdr| line.contains(&format!("│{} ", hdr)))
+        })
+        .collect()
+}
+
+#[test]
+/// Ctrl-O flips a metric ordering top to bottom (keeping the criterion), and back.
+fn test_reverse_ordering() {
+    utils::with_rig("tests/data/snp.fas", 80, 20, |ui, terminal| {
+        key_handling::handle_key_press(ui, utils::keypress('o'));
+        let ordered = shown_headers(ui, terminal);
+        assert_eq!(ordered.len(), 5, "{:?}", ordered);
+
+        let ctrl_o = utils::ctrl(KeyCode::Char('o'));
+        key_handling::handle_key_press(ui, ctrl_o);
+        let reversed = shown_headers(ui, terminal);
+        assert_eq!(reversed, ordered.iter().rev().cloned().collect::<Vec<_>>());
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("o:%id↑(rev)"), "{}", screen);
+
+        key_handling::handle_key_press(ui, ctrl_o);
+        assert_eq!(shown_headers(ui, terminal), ordered);
+    });
+}
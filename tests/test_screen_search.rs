@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, UI};
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 20;
+
+#[test]
+/// Ctrl-/ only reports the matches on screen, and re-scopes after scrolling.
+fn test_search_on_screen() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            let ctrl_slash = utils::ctrl(KeyCode::Char('/'));
+            let last_line = |terminal: &mut ratatui::Terminal<ratatui::backend::TestBackend>,
+                             ui: &mut UI| {
+                terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+                utils::screen_line(terminal.backend().buffer(), SCREEN_HEIGHT - 1)
+            };
+
+            utils::search(ui, utils::keypress('/'), "MSTT");
+            let line = last_line(terminal, ui);
+            assert!(line.contains("13 matches in"), "{}", line);
+
+            // In this file, MSTT is found at columns 12-15 of 13 sequences, of which three are on
+            // the first screen.
+            utils::search(ui, ctrl_slash, "MSTT");
+            let line = last_line(terminal, ui);
+            assert!(
+                line.contains("3 matches in 3 sequences on screen"),
+                "{}",
+                line
+            );
+
+            // The last sequences have no match.
+            key_handling::handle_key_press(ui, utils::keypress('G'));
+            utils::search(ui, ctrl_slash, "MSTT");
+            let line = last_line(terminal, ui);
+            assert!(
+                line.contains("0 matches in 0 sequences on screen"),
+                "{}",
+                line
+            );
+
+            // Back at the top but scrolled past column 15.
+            key_handling::handle_key_press(ui, utils::keypress('g'));
+            for _ in 0..20 {
+                key_handling::handle_key_press(ui, utils::keypress('l'));
+            }
+            utils::search(ui, ctrl_slash, "MSTT");
+            let line = last_line(terminal, ui);
+            assert!(
+                line.contains("0 matches in 0 sequences on screen"),
+                "{}",
+                line
+            );
+        },
+    );
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::render;
+
+// Rows that hold a tick in column `x` (e.g. the vertical scrollbar's).
+fn tick_rows(screen: &[Vec<char>], x: usize) -> Vec<usize> {
+    (0..screen.len()).filter(|&y| screen[y][x] == '•').collect()
+}
+
+#[test]
+/// The only header matching "Q88D" is that of sequence 150 of 226: with 23 rows of scrollbar
+/// (rows 1 to 23 of the 100x30 screen), its tick is on row 1 + 149 * 23 / 226 = 16. The first
+/// matches of "MSTT" start at column 12 of 1054, so their tick is at 12 / 1054 of the horizontal
+/// track, i.e. on its first cell.
+fn test_scrollbar_ticks() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let screen: Vec<Vec<char>> = (0..30)
+            .map(|y| utils::screen_line(buffer, y).chars().collect())
+            .collect();
+        assert!(tick_rows(&screen, 99).is_empty());
+
+        utils::search(ui, utils::keypress('"'), "Q88D");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let screen: Vec<Vec<char>> = (0..30)
+            .map(|y| utils::screen_line(buffer, y).chars().collect())
+            .collect();
+        assert_eq!(tick_rows(&screen, 99), vec![16]);
+
+        utils::search(ui, utils::keypress('/'), "MSTT");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let buffer = terminal.backend().buffer();
+        let bottom: Vec<char> = utils::screen_line(buffer, 24).chars().collect();
+        // The alignment pane's bottom left corner.
+        let left = bottom.iter().rposition(|&c| c == '└').expect("corner");
+        assert_eq!(
+            bottom[left + 1],
+            '•',
+            "{}",
+            bottom.iter().collect::<String>()
+        );
+    });
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+use ratatui::buffer::Buffer;
+
+const SCREEN_WIDTH: u16 = 80;
+const SCREEN_HEIGHT: u16 = 24;
+// Lower border of the sequence pane (where the horizontal scrollbar is drawn)
+const H_SCROLLBAR_ROW: u16 = 18;
+
+// Rows of the vertical scrollbar's thumb (in the rightmost column).
+fn v_thumb_rows(buffer: &Buffer) -> Vec<u16> {
+    (1..H_SCROLLBAR_ROW)
+        .filter(|&row| utils::screen_line(buffer, row).ends_with('█'))
+        .collect()
+}
+
+// Columns of the horizontal scrollbar's thumb.
+fn h_thumb_cols(buffer: &Buffer) -> Vec<usize> {
+    utils::screen_line(buffer, H_SCROLLBAR_ROW)
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| *c == '🬹')
+        .map(|(col, _)| col)
+        .collect()
+}
+
+#[test]
+/// When zoomed out, the scrollbar thumbs follow the zoom box.
+fn test_zoomed_out_thumbs_follow_zoombox() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            key_handling::handle_key_press(ui, utils::keypress('z'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let buffer = terminal.backend().buffer();
+            let (rows, cols) = (v_thumb_rows(buffer), h_thumb_cols(buffer));
+            assert_eq!(rows.first(), Some(&1));
+            assert!(!cols.is_empty());
+
+            utils::type_keys(ui, "50j99l");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let buffer = terminal.backend().buffer();
+            // The zoom box is now in the lower right corner, and so are the thumbs.
+            assert_eq!(v_thumb_rows(buffer).last(), Some(&(H_SCROLLBAR_ROW - 1)));
+            assert!(h_thumb_cols(buffer)[0] > cols[cols.len() - 1]);
+            assert!(utils::screen_line(buffer, H_SCROLLBAR_ROW - 1).contains("└──┘"));
+        },
+    );
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent {
+        code,
+        modifiers: KeyModifiers::NONE,
+        kind: KeyEventKind::Press,
+        state: KeyEventState::NONE,
+    }
+}
+
+fn screen(
+    ui: &mut msafara::ui::UI,
+    terminal: &mut ratatui::Terminal<ratatui::backend::TestBackend>,
+) -> String {
+    terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+    let buffer = terminal.backend().buffer();
+    (0..SCREEN_HEIGHT)
+        .map(|y| utils::screen_line(buffer, y))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Runs a sequence search for `pattern` and saves it as `name` (P prompts for a name; an empty one
+// defaults to the pattern).
+fn save_search(ui: &mut msafara::ui::UI, pattern: &str, name: &str) {
+    key_handling::handle_key_press(ui, utils::keypress('/'));
+    utils::type_keys(ui, pattern);
+    key_handling::handle_key_press(ui, key(KeyCode::Enter));
+    key_handling::handle_key_press(ui, utils::keypress('P'));
+    utils::type_keys(ui, name);
+    key_handling::handle_key_press(ui, key(KeyCode::Enter));
+}
+
+#[test]
+/// Opens the saved-search list with F, moves down and toggles the second search.
+fn test_search_list_toggle() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            save_search(ui, "MSTT", "");
+            save_search(ui, "GMYIL", "");
+
+            key_handling::handle_key_press(ui, utils::keypress('F'));
+            let text = screen(ui, terminal);
+            assert!(text.contains("Search List"), "list not shown:\n{}", text);
+            assert!(text.contains("*   R         13 MSTT"), "{}", text);
+            assert!(text.contains("*   R         39 GMYIL"), "{}", text);
+
+            key_handling::handle_key_press(ui, key(KeyCode::Down));
+            key_handling::handle_key_press(ui, utils::keypress(' '));
+            let text = screen(ui, terminal);
+            assert!(text.contains("*   R         13 MSTT"), "{}", text);
+            assert!(text.contains("    R         39 GMYIL"), "{}", text);
+
+            key_handling::handle_key_press(ui, key(KeyCode::Esc));
+            let text = screen(ui, terminal);
+            assert!(!text.contains("Search List"));
+        },
+    );
+}
+
+#[test]
+/// Saves the current search under a typed name.
+fn test_save_named_search() {
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            save_search(ui, "MSTT", "motif");
+            let text = screen(ui, terminal);
+            assert!(text.contains("Saved search 'motif'"), "{}", text);
+
+            key_handling::handle_key_press(ui, utils::keypress('F'));
+            let text = screen(ui, terminal);
+            assert!(text.contains("13 motif            MSTT"), "{}", text);
+        },
+    );
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+// Screen row (within the sequence pane) whose label number is `n`.
+fn row_of_number(buffer: &ratatui::buffer::Buffer, rows: u16, n: usize) -> Option<u16> {
+    let label = format!("│{:3}│", n);
+    (1..=rows).find(|y| utils::screen_line(buffer, *y).starts_with(&label))
+}
+
+#[test]
+/// Under a metric ordering, `:n 42` puts the cursor on sequence 42 and brings its screen line
+/// into view; numbers past the end go to the last sequence.
+fn test_jump_to_seq_number_with_ordering() {
+    utils::with_rig("tests/data/test-motion.msa", 80, 30, |ui, terminal| {
+        // Order by %id to the consensus: sequence 42 is no longer on line 42.
+        key_handling::handle_key_press(ui, utils::keypress('o'));
+        let rows = ui.visible_seq_rows();
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(row_of_number(terminal.backend().buffer(), rows, 42).is_none());
+
+        utils::ex_command(ui, "n 42");
+        assert_eq!(Some(41), ui.cursor_rank());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(row_of_number(terminal.backend().buffer(), rows, 42).is_some());
+
+        utils::ex_command(ui, "n 1000");
+        assert_eq!(Some(225), ui.cursor_rank());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        assert!(row_of_number(terminal.backend().buffer(), rows, 226).is_some());
+        ui.assert_invariants();
+    });
+}
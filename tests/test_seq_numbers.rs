@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+use msafara::{alignment::Alignment, app::App, ui::UI};
+
+fn app_with(num_seq: usize) -> App {
+    let headers = (1..=num_seq).map(|i| format!("s{}", i)).collect();
+    let sequences = vec![String::from("ACGT"); num_seq];
+    App::new("TEST", Alignment::from_vecs(headers, sequences), None)
+}
+
+#[test]
+/// The number column is as wide as the highest sequence number, and one column wide when there
+/// are no sequences at all.
+fn test_seq_num_max_len() {
+    for (num_seq, width) in [
+        (0, 1),
+        (1, 1),
+        (9, 1),
+        (10, 2),
+        (99, 2),
+        (100, 3),
+        (1000, 4),
+    ] {
+        let mut app = app_with(num_seq);
+        let ui = UI::new(&mut app);
+        assert_eq!(ui.seq_num_max_len(), width, "{} sequences", num_seq);
+    }
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::{
+    app::App,
+    ui::{render, UI},
+};
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+// The pane borders on the whole screen but the modeline (whose message differs after loading a
+// session). The metric bars are left out, as ties in the consensus can change them.
+fn pane_borders(buffer: &Buffer) -> Vec<String> {
+    (0..buffer.area.height - 1)
+        .map(|row| {
+            utils::screen_line(buffer, row)
+                .chars()
+                .map(|c| {
+                    if "│┌┐└┘─".contains(c) {
+                        c
+                    } else {
+                        ' '
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+/// Pane sizes and display toggles are saved in the session and restored when it is reopened.
+fn test_session_restores_layout() {
+    let path =
+        std::env::temp_dir().join(format!("msafara-test-layout-{}.msfr", std::process::id()));
+    let mut saved_screen = Vec::new();
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        utils::type_keys(ui, "3+4>v");
+        ui.save_session(&path).expect("save session");
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        saved_screen = pane_borders(terminal.backend().buffer());
+    });
+
+    let mut app = App::from_session_file(&path).expect("load session");
+    let _ = std::fs::remove_file(&path);
+    let mut ui = UI::new(&mut app);
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).expect("terminal");
+    terminal
+        .draw(|f| render::render_ui(f, &mut ui))
+        .expect("draw");
+    assert_eq!(pane_borders(terminal.backend().buffer()), saved_screen);
+}
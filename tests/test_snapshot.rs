@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+use msafara::{
+    alignment::Alignment, buffer_to_svg, buffer_to_text, render_to_buffer, seq::fasta,
+    ui::ZoomLevel, RenderOpts,
+};
+use ratatui::prelude::Rect;
+
+fn motion_alignment() -> Alignment {
+    Alignment::from_file(fasta::read_fasta_file("tests/data/test-motion.msa").expect("read"))
+}
+
+#[test]
+fn snapshot_scrolls_and_zooms() {
+    let aln = motion_alignment();
+    let top = buffer_to_text(&render_to_buffer(&aln, 80, 20, RenderOpts::default()));
+    assert!(top.contains("JPNFFBMG_0"));
+    assert!(!top.contains("Press '?' for help"));
+
+    let scrolled = buffer_to_text(&render_to_buffer(
+        &aln,
+        80,
+        20,
+        RenderOpts {
+            top_line: 100,
+            leftmost_col: 40,
+            ..RenderOpts::default()
+        },
+    ));
+    assert_ne!(top, scrolled);
+    assert!(!scrolled.contains("JPNFFBMG_0"));
+
+    // Out-of-range positions are clamped, as when scrolling.
+    let bottom = render_to_buffer(
+        &aln,
+        80,
+        20,
+        RenderOpts {
+            top_line: u16::MAX,
+            ..RenderOpts::default()
+        },
+    );
+    assert!(buffer_to_text(&bottom).contains(&aln.headers()[aln.num_seq() - 1][..10]));
+
+    let zoomed = buffer_to_text(&render_to_buffer(
+        &aln,
+        80,
+        20,
+        RenderOpts {
+            zoom: ZoomLevel::ZoomedOut,
+            ..RenderOpts::default()
+        },
+    ));
+    assert_ne!(top, zoomed);
+}
+
+#[test]
+fn snapshot_to_svg() {
+    let buffer = render_to_buffer(&motion_alignment(), 40, 10, RenderOpts::default());
+    let svg = buffer_to_svg(&buffer, Rect::default());
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.contains("width=\"320\" height=\"160\""));
+}
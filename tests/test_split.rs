@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 50;
+
+// A 60 x 200 alignment, i.e. shorter (in both directions) than test-motion.msa.
+fn write_short_alignment(tag: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("msafara-split-{}-{}.fa", tag, std::process::id()));
+    let mut fasta = String::new();
+    for i in 0..60 {
+        fasta.push_str(&format!(">short{}\n{}\n", i, "ACDEFGHIKL".repeat(20)));
+    }
+    fs::write(&path, fasta).expect("write test alignment");
+    path
+}
+
+#[test]
+/// `:split <file>` shows the second alignment, whose linked scrolling is clamped to its length.
+fn test_split_linked_scroll_clamped() {
+    let path = write_short_alignment("linked");
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            utils::ex_command(ui, &format!("split {}", path.display()));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(
+                screen.contains("60 sequences x 200 columns (linked)"),
+                "{}",
+                screen
+            );
+            assert!(screen.contains("short0"), "{}", screen);
+
+            key_handling::handle_key_press(ui, utils::keypress('$'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            let split_max = ui.split().expect("split").max_leftmost_col();
+            assert!(split_max > 0);
+            assert!(ui.leftmost_col() > split_max);
+            assert_eq!(ui.split_leftmost_col(), split_max);
+
+            key_handling::handle_key_press(ui, utils::keypress('^'));
+            assert_eq!(ui.split_leftmost_col(), 0);
+        },
+    );
+    fs::remove_file(&path).ok();
+}
+
+#[test]
+/// Unlinked, Ctrl-arrows scroll the split without moving the main alignment; `:split` closes it.
+fn test_split_unlinked_scroll_and_close() {
+    let path = write_short_alignment("unlinked");
+    utils::with_rig(
+        "tests/data/test-motion.msa",
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            ui.open_split(&path).expect("open split");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Char('l')));
+            assert!(!ui.split().expect("split").linked);
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Right));
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Right));
+            key_handling::handle_key_press(ui, utils::ctrl(KeyCode::Down));
+            assert_eq!(ui.split_leftmost_col(), 2);
+            assert_eq!(ui.split().expect("split").top_line, 1);
+            assert_eq!(ui.leftmost_col(), 0);
+            assert_eq!(ui.top_line(), 0);
+
+            utils::ex_command(ui, "split");
+            assert!(ui.split().is_none());
+        },
+    );
+    fs::remove_file(&path).ok();
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::common::utils;
+
+use msafara::{
+    alignment::Alignment,
+    app::App,
+    seq::fasta,
+    ui::{key_handling, render, resolve_quit, QuitRequest, UI},
+};
+
+// Types `keys` one at a time, each handled as the runner would; whether the last one quits.
+fn keys(ui: &mut UI, keys: &str) -> bool {
+    keys.chars()
+        .map(|c| {
+            let done = utils::type_keys(ui, &c.to_string());
+            utils::quits(ui, done)
+        })
+        .last()
+        .unwrap_or(false)
+}
+
+#[test]
+/// An edit shows `[+]` in the modeline, and `q` then asks before quitting (`y` quits, `n` and Esc
+/// do not); saving the session clears it, after which `q` quits at once.
+fn test_unsaved_changes() {
+    let path =
+        std::env::temp_dir().join(format!("msafara-test-{}.unsaved.msfr", std::process::id()));
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        assert!(!ui.has_unsaved_changes());
+        keys(ui, "Vi");
+        assert!(ui.has_unsaved_changes());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("[+]"), "{}", screen);
+
+        assert!(!keys(ui, "q"));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Unsaved changes"), "{}", screen);
+        assert!(!key_handling::handle_key_press(ui, KeyCode::Esc.into()));
+        assert!(!keys(ui, "Qn"));
+
+        ui.save_session(&path).expect("save");
+        assert!(!ui.has_unsaved_changes());
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(!screen.contains("[+]"), "{}", screen);
+        assert!(keys(ui, "q"));
+
+        keys(ui, "x");
+        assert!(ui.has_unsaved_changes());
+        assert!(!keys(ui, "Q"));
+        assert!(keys(ui, "y"));
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+/// Quitting from an alignment without unsaved changes still asks first if another open alignment
+/// has some, and shows that one.
+fn test_quit_checks_every_buffer() {
+    let path =
+        std::env::temp_dir().join(format!("msafara-test-{}.buffers.msfr", std::process::id()));
+    let mut apps: Vec<App> = (0..2)
+        .map(|_| {
+            let seq_file = fasta::read_fasta_file("tests/data/test-motion.msa").expect("read");
+            App::new("TEST", Alignment::from_file(seq_file), None)
+        })
+        .collect();
+    let mut uis: Vec<UI> = apps.iter_mut().map(UI::new).collect();
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).expect("terminal");
+    for ui in uis.iter_mut() {
+        terminal.draw(|f| render::render_ui(f, ui)).expect("draw");
+    }
+    let mut current = 0;
+    keys(&mut uis[1], "Vi");
+    assert!(!uis[0].has_unsaved_changes());
+    assert!(uis[1].has_unsaved_changes());
+
+    assert!(!key_handling::handle_key_press(
+        &mut uis[0],
+        utils::keypress('q')
+    ));
+    assert_eq!(uis[0].take_quit_request(), Some(QuitRequest::Confirm));
+    assert!(!resolve_quit(&mut uis, &mut current, QuitRequest::Confirm));
+    assert_eq!(current, 1);
+    terminal
+        .draw(|f| render::render_ui(f, &mut uis[current]))
+        .expect("update");
+    let screen = utils::buffer_text(terminal.backend().buffer());
+    assert!(screen.contains("quit anyway?"), "{}", screen);
+    assert!(!keys(&mut uis[1], "n"));
+
+    uis[1].save_session(&path).expect("save");
+    assert!(!key_handling::handle_key_press(
+        &mut uis[1],
+        utils::keypress('q')
+    ));
+    assert_eq!(uis[1].take_quit_request(), Some(QuitRequest::Confirm));
+    assert!(resolve_quit(&mut uis, &mut current, QuitRequest::Confirm));
+    let _ = std::fs::remove_file(&path);
+}
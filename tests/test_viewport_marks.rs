@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crossterm::event::KeyCode;
+
+use crate::common::utils;
+
+use msafara::ui::key_handling;
+
+#[test]
+/// Canceling a label search with Esc returns to the scroll position and cursor it started from,
+/// however far the preview scrolled; completing it with Enter keeps the new position.
+fn test_viewport_restored_on_esc() {
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, _terminal| {
+        utils::ex_command(ui, "n 40");
+        utils::type_keys(ui, "lll");
+        let (top_line, leftmost_col) = (ui.top_line(), ui.leftmost_col());
+        assert!(leftmost_col > 0);
+        assert_eq!(ui.cursor_rank(), Some(39));
+
+        // Sequence 150 is the only one whose header contains "Q88D".
+        utils::type_keys(ui, "\"Q88D");
+        assert_ne!(ui.top_line(), top_line);
+        key_handling::handle_key_press(ui, KeyCode::Esc.into());
+        assert_eq!((ui.top_line(), ui.leftmost_col()), (top_line, leftmost_col));
+        assert_eq!(ui.cursor_rank(), Some(39));
+
+        utils::type_keys(ui, "/MSTT");
+        key_handling::handle_key_press(ui, KeyCode::Esc.into());
+        assert_eq!((ui.top_line(), ui.leftmost_col()), (top_line, leftmost_col));
+
+        utils::type_keys(ui, "\"Q88D");
+        key_handling::handle_key_press(ui, KeyCode::Enter.into());
+        assert_ne!(ui.top_line(), top_line);
+        assert_eq!(ui.cursor_rank(), Some(149));
+    });
+}
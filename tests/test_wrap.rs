@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render};
+
+const SCREEN_WIDTH: u16 = 100;
+const SCREEN_HEIGHT: u16 = 30;
+
+// Three sequences of 250 columns: several blocks in wrap mode.
+fn write_long_alignment() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("msafara-wrap-{}.fa", std::process::id()));
+    let mut fasta = String::new();
+    for i in 0..3 {
+        fasta.push_str(&format!(">long{}\n{}\n", i, "ACDEFGHIKL".repeat(25)));
+    }
+    fs::write(&path, fasta).expect("write test alignment");
+    path
+}
+
+#[test]
+/// `:wrap` stacks the alignment in blocks headed by column numbers; `j` moves down one block.
+fn test_wrap_mode_blocks() {
+    let path = write_long_alignment();
+    utils::with_rig(
+        path.to_str().expect("path"),
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        |ui, terminal| {
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            utils::ex_command(ui, "wrap");
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            assert!(ui.wrap_mode());
+            let width = ui.wrap_width();
+            assert_eq!(ui.num_wrap_blocks(), 250usize.div_ceil(width));
+            let screen = utils::buffer_text(terminal.backend().buffer());
+            assert!(screen.contains("Wrapped"), "{}", screen);
+            assert!(screen.contains(&format!("{}", width)), "{}", screen);
+            assert!(screen.contains("250"), "{}", screen);
+            // Each block repeats the labels.
+            assert!(screen.matches("long0").count() > 1, "{}", screen);
+
+            key_handling::handle_key_press(ui, utils::keypress('j'));
+            terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+            assert_eq!(ui.wrap_top_block(), 1);
+            assert_eq!(ui.leftmost_col() as usize, width);
+
+            key_handling::handle_key_press(ui, utils::keypress('z'));
+            assert!(!ui.wrap_mode());
+        },
+    );
+    let _ = fs::remove_file(path);
+}
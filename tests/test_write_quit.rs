@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, UI};
+
+// Whether `:cmd` quits.
+fn command(ui: &mut UI, cmd: &str) -> bool {
+    let done = utils::ex_command(ui, cmd);
+    utils::quits(ui, done)
+}
+
+#[test]
+/// `:q` refuses to quit while there are unsaved changes (without asking, unlike `q`), `:q!` quits
+/// anyway; `:w` needs a file until one has been saved to, and `:wq` saves before quitting.
+fn test_write_quit() {
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.wq.msfr", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        assert!(command(ui, "q"));
+
+        utils::type_keys(ui, "Vi");
+        assert!(ui.has_unsaved_changes());
+        assert!(!command(ui, "q"));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Unsaved changes"), "{}", screen);
+        assert!(!screen.contains("quit anyway?"), "{}", screen);
+        assert!(command(ui, "q!"));
+
+        assert!(!command(ui, "w"));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("No session file"), "{}", screen);
+        assert!(ui.has_unsaved_changes());
+
+        assert!(!command(ui, &format!("w {}", path.display())));
+        assert!(path.exists());
+        assert!(!ui.has_unsaved_changes());
+        assert!(command(ui, "q"));
+
+        key_handling::handle_key_press(ui, utils::keypress('x'));
+        assert!(ui.has_unsaved_changes());
+        assert!(command(ui, "wq"));
+        assert!(!ui.has_unsaved_changes());
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+/// `:w <file>` and `:wq <file>` ask before overwriting an existing file other than the session
+/// file; any answer but `y` cancels the write.
+fn test_write_confirms_overwrite() {
+    let path = std::env::temp_dir().join(format!("msafara-test-{}.ow.msfr", std::process::id()));
+    let other = std::env::temp_dir().join(format!("msafara-test-{}.other", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    std::fs::write(&other, "keep me").unwrap();
+    utils::with_rig("tests/data/test-motion.msa", 100, 30, |ui, terminal| {
+        utils::type_keys(ui, "Vi");
+        assert!(!command(ui, &format!("w {}", other.display())));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("File exists. Overwrite?"), "{}", screen);
+        assert!(!key_handling::handle_key_press(ui, utils::keypress('n')));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let screen = utils::buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Write canceled"), "{}", screen);
+        assert!(!screen.contains("Session:"), "{}", screen);
+        assert_eq!(std::fs::read_to_string(&other).unwrap(), "keep me");
+        assert!(ui.has_unsaved_changes());
+
+        // The session file itself is overwritten without asking.
+        assert!(!command(ui, &format!("w {}", path.display())));
+        key_handling::handle_key_press(ui, utils::keypress('x'));
+        assert!(ui.has_unsaved_changes());
+        assert!(!command(ui, &format!("w {}", path.display())));
+        assert!(!ui.has_unsaved_changes());
+
+        assert!(!command(ui, &format!("wq {}", other.display())));
+        let done = key_handling::handle_key_press(ui, utils::keypress('y'));
+        assert!(utils::quits(ui, done));
+        assert_ne!(std::fs::read_to_string(&other).unwrap(), "keep me");
+    });
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&other);
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Peter Carlton
+
+mod common;
+
+use crate::common::utils;
+
+use msafara::ui::{key_handling, render, UI};
+
+// Zoom box edges, checked against the pane: within bounds and at least one cell in each direction.
+fn checked_zoombox(ui: &UI, size: (u16, u16)) -> (usize, usize, usize, usize) {
+    let (top, bottom, left, right) = ui.zoombox_rect();
+    assert!(
+        top < bottom,
+        "degenerate box {:?} at {:?}",
+        (top, bottom),
+        size
+    );
+    assert!(
+        left < right,
+        "degenerate box {:?} at {:?}",
+        (left, right),
+        size
+    );
+    assert!(
+        bottom <= ui.visible_seq_rows() as usize,
+        "box too low at {:?}",
+        size
+    );
+    assert!(
+        right <= ui.visible_cols() as usize,
+        "box too wide at {:?}",
+        size
+    );
+    (top, bottom, left, right)
+}
+
+#[test]
+/// In both zoomed-out modes and for a range of terminal sizes, the zoom box stays within the pane
+/// and is never empty, at the top left as well as at the bottom right of the alignment.
+fn test_zoombox_within_bounds() {
+    for width in (40..=160).step_by(7) {
+        for height in (12..=60).step_by(5) {
+            utils::with_rig(
+                "tests/data/test-motion.msa",
+                width,
+                height,
+                |ui, terminal| {
+                    for zoom_presses in 1..=2 {
+                        for _ in 0..zoom_presses {
+                            key_handling::handle_key_press(ui, utils::keypress('z'));
+                        }
+                        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+                        let (top, _, left, _) = checked_zoombox(ui, (width, height));
+                        assert_eq!((top, left), (0, 0));
+
+                        key_handling::handle_key_press(ui, utils::keypress('G'));
+                        key_handling::handle_key_press(ui, utils::keypress('$'));
+                        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+                        checked_zoombox(ui, (width, height));
+
+                        key_handling::handle_key_press(ui, utils::keypress('g'));
+                        key_handling::handle_key_press(ui, utils::keypress('^'));
+                        // Back to zoomed in
+                        for _ in zoom_presses..=2 {
+                            key_handling::handle_key_press(ui, utils::keypress('z'));
+                        }
+                    }
+                },
+            );
+        }
+    }
+}
+
+#[test]
+/// Zoomed out (all rows and columns of the pane used), the box reaches the pane's lower right
+/// corner when the view is at the end of the alignment.
+fn test_zoombox_reaches_corner() {
+    utils::with_rig("tests/data/test-motion.msa", 97, 31, |ui, terminal| {
+        key_handling::handle_key_press(ui, utils::keypress('z'));
+        key_handling::handle_key_press(ui, utils::keypress('G'));
+        key_handling::handle_key_press(ui, utils::keypress('$'));
+        terminal.draw(|f| render::render_ui(f, ui)).expect("update");
+        let (_, bottom, _, right) = ui.zoombox_rect();
+        assert_eq!(bottom, ui.visible_seq_rows() as usize);
+        assert_eq!(right, ui.visible_cols() as usize);
+    });
+}